@@ -0,0 +1,313 @@
+use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Not, Rem, Shl, Shr, Sub};
+
+use php_parser_rs::lexer::byte_string::ByteString;
+
+use crate::conversion::parse_numeric_string;
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+/// The number of bits of an int, PHP_INT_SIZE is this divided by 8.
+const INT_BITS: i64 = i64::BITS as i64;
+
+/// An operand of an arithmetic operation.
+#[derive(Debug, Clone, Copy)]
+enum Number {
+    Int(i64),
+    Float(f64),
+}
+
+impl Number {
+    fn to_float(self) -> f64 {
+        match self {
+            Number::Int(i) => i as f64,
+            Number::Float(f) => f,
+        }
+    }
+
+    /// Floats are truncated, NaN and the infinities are 0.
+    fn to_int(self) -> i64 {
+        match self {
+            Number::Int(i) => i,
+            Number::Float(f) if !f.is_finite() => 0,
+            Number::Float(f) => f as i64,
+        }
+    }
+
+    fn is_zero(self) -> bool {
+        match self {
+            Number::Int(i) => i == 0,
+            Number::Float(f) => f == 0.0,
+        }
+    }
+}
+
+impl From<Number> for PhpValue {
+    fn from(number: Number) -> Self {
+        match number {
+            Number::Int(i) => PhpValue::Int(i),
+            Number::Float(f) => PhpValue::Float(f),
+        }
+    }
+}
+
+impl PhpValue {
+    /// Performs `self ** value`. An int result that does not fit in an int, or a
+    /// negative exponent, gives a float.
+    pub fn pow(self, value: PhpValue) -> Result<PhpValue, PhpError> {
+        let (base, exponent) = get_operands(&self, "**", &value)?;
+
+        let result = match (base, exponent) {
+            (Number::Int(base), Number::Int(exponent)) if exponent >= 0 => u32::try_from(exponent)
+                .ok()
+                .and_then(|exponent| base.checked_pow(exponent))
+                .map(Number::Int)
+                .unwrap_or(Number::Float((base as f64).powf(exponent as f64))),
+            (base, exponent) => Number::Float(base.to_float().powf(exponent.to_float())),
+        };
+
+        Ok(result.into())
+    }
+
+    /// Performs `+`, `-` or `*`. Two ints give an int, unless the result does not fit
+    /// in an int, then it is a float like in PHP.
+    fn perform_arithmetic_operation(
+        &self,
+        operation_sign: &str,
+        rhs: &PhpValue,
+        int_operation: fn(i64, i64) -> Option<i64>,
+        float_operation: fn(f64, f64) -> f64,
+    ) -> Result<PhpValue, PhpError> {
+        let (left, right) = get_operands(self, operation_sign, rhs)?;
+
+        let result = match (left, right) {
+            (Number::Int(left), Number::Int(right)) => match int_operation(left, right) {
+                Some(result) => Number::Int(result),
+                None => Number::Float(float_operation(left as f64, right as f64)),
+            },
+            (left, right) => Number::Float(float_operation(left.to_float(), right.to_float())),
+        };
+
+        Ok(result.into())
+    }
+
+    /// Performs `&`, `|` or `^`, floats are truncated to ints.
+    fn perform_bitwise_operation(
+        &self,
+        operation_sign: &str,
+        rhs: &PhpValue,
+        operation: fn(i64, i64) -> i64,
+    ) -> Result<PhpValue, PhpError> {
+        let (left, right) = get_operands(self, operation_sign, rhs)?;
+
+        Ok(PhpValue::Int(operation(left.to_int(), right.to_int())))
+    }
+
+    /// The operands of `<<` and `>>` as ints, the shift cannot be negative.
+    fn get_shift_operands(
+        &self,
+        operation_sign: &str,
+        rhs: &PhpValue,
+    ) -> Result<(i64, i64), PhpError> {
+        let (left, right) = get_operands(self, operation_sign, rhs)?;
+
+        let shift = right.to_int();
+
+        if shift < 0 {
+            return Err(uncaught_error(
+                "ArithmeticError",
+                "Bit shift by negative number",
+            ));
+        }
+
+        Ok((left.to_int(), shift))
+    }
+}
+
+/// The operands of an arithmetic operation as numbers, numeric strings are converted.
+fn get_operands(
+    left: &PhpValue,
+    operation_sign: &str,
+    right: &PhpValue,
+) -> Result<(Number, Number), PhpError> {
+    let to_number = |value: &PhpValue| match value {
+        PhpValue::Int(i) => Some(Number::Int(*i)),
+        PhpValue::Float(f) => Some(Number::Float(*f)),
+        PhpValue::String(s) => match parse_numeric_string(s)? {
+            PhpValue::Int(i) => Some(Number::Int(i)),
+            PhpValue::Float(f) => Some(Number::Float(f)),
+            _ => None,
+        },
+        _ => None,
+    };
+
+    match (to_number(left), to_number(right)) {
+        (Some(left), Some(right)) => Ok((left, right)),
+        _ => Err(unsupported_operand_types(left, operation_sign, right)),
+    }
+}
+
+/// The TypeError of an arithmetic operation with an operand that is not a number.
+pub(crate) fn unsupported_operand_types(
+    left: &PhpValue,
+    operation_sign: &str,
+    right: &PhpValue,
+) -> PhpError {
+    uncaught_error(
+        "TypeError",
+        &format!(
+            "Unsupported operand types: {} {} {}",
+            left.type_name().error_name(),
+            operation_sign,
+            right.type_name().error_name()
+        ),
+    )
+}
+
+/// The error of an Error that PHP throws, like the DivisionByZeroError of `1 % 0`, the
+/// evaluator throws it as an object of the class.
+///
+/// The operators do not know where they are written, the evaluator sets the line of
+/// the operator, see `Evaluator::eval_arithmetic()`.
+fn uncaught_error(class: &'static str, message: &str) -> PhpError {
+    PhpError {
+        level: ErrorLevel::EngineError(class),
+        message: message.to_string(),
+        line: 0,
+    }
+}
+
+impl Add for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.perform_arithmetic_operation("+", &rhs, i64::checked_add, |left, right| left + right)
+    }
+}
+
+impl Sub for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self.perform_arithmetic_operation("-", &rhs, i64::checked_sub, |left, right| left - right)
+    }
+}
+
+impl Mul for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        self.perform_arithmetic_operation("*", &rhs, i64::checked_mul, |left, right| left * right)
+    }
+}
+
+impl Div for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    /// Two ints give an int only when the division is exact, 7 / 2 is 3.5.
+    fn div(self, rhs: Self) -> Self::Output {
+        let (left, right) = get_operands(&self, "/", &rhs)?;
+
+        if right.is_zero() {
+            return Err(uncaught_error("DivisionByZeroError", "Division by zero"));
+        }
+
+        let result = match (left, right) {
+            // i64::MIN / -1 does not fit in an int, it is a float
+            (Number::Int(left), Number::Int(right)) if left.checked_rem(right) == Some(0) => {
+                Number::Int(left / right)
+            }
+            (left, right) => Number::Float(left.to_float() / right.to_float()),
+        };
+
+        Ok(result.into())
+    }
+}
+
+impl Rem for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    /// The operands are converted to ints, the result has the sign of the left one.
+    fn rem(self, rhs: Self) -> Self::Output {
+        let (left, right) = get_operands(&self, "%", &rhs)?;
+
+        let (left, right) = (left.to_int(), right.to_int());
+
+        if right == 0 {
+            return Err(uncaught_error("DivisionByZeroError", "Modulo by zero"));
+        }
+
+        // i64::MIN % -1 overflows, the result is 0
+        Ok(PhpValue::Int(left.checked_rem(right).unwrap_or(0)))
+    }
+}
+
+impl BitAnd for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.perform_bitwise_operation("&", &rhs, |left, right| left & right)
+    }
+}
+
+impl BitOr for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.perform_bitwise_operation("|", &rhs, |left, right| left | right)
+    }
+}
+
+impl BitXor for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    fn bitxor(self, rhs: Self) -> Self::Output {
+        self.perform_bitwise_operation("^", &rhs, |left, right| left ^ right)
+    }
+}
+
+impl Shl for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    /// The bits shifted past the sign are lost, a shift by the size of an int gives 0.
+    fn shl(self, rhs: Self) -> Self::Output {
+        let (value, shift) = self.get_shift_operands("<<", &rhs)?;
+
+        if shift >= INT_BITS {
+            return Ok(PhpValue::Int(0));
+        }
+
+        Ok(PhpValue::Int(value << shift))
+    }
+}
+
+impl Shr for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    /// The sign is kept, a shift by the size of an int gives 0 or -1.
+    fn shr(self, rhs: Self) -> Self::Output {
+        let (value, shift) = self.get_shift_operands(">>", &rhs)?;
+
+        Ok(PhpValue::Int(value >> shift.min(INT_BITS - 1)))
+    }
+}
+
+impl Not for PhpValue {
+    type Output = Result<PhpValue, PhpError>;
+
+    /// Performs `~`, floats are truncated to ints and every byte of a string is inverted.
+    fn not(self) -> Self::Output {
+        match self {
+            PhpValue::Int(i) => Ok(PhpValue::Int(!i)),
+            PhpValue::Float(f) => Ok(PhpValue::Int(!Number::Float(f).to_int())),
+            PhpValue::String(s) => Ok(PhpValue::String(ByteString::new(
+                s.bytes.iter().map(|byte| !byte).collect(),
+            ))),
+            value => Err(uncaught_error(
+                "TypeError",
+                &format!(
+                    "Cannot perform bitwise not on {}",
+                    value.type_name().error_name()
+                ),
+            )),
+        }
+    }
+}
@@ -0,0 +1,224 @@
+use std::cell::RefCell;
+use std::cmp::Ordering;
+use std::rc::Rc;
+
+use crate::conversion::{parse_numeric_string, scalar_to_string, DEFAULT_PRECISION};
+use crate::php_array::PhpArray;
+use crate::php_value::{ErrorLevel, PhpError, PhpObject, PhpValue};
+
+/// How deep arrays can be nested when they are compared.
+const MAX_COMPARISON_DEPTH: usize = 256;
+
+impl PhpValue {
+    /// Compares two values loosely, like `==`, `<` and `<=>` do.
+    ///
+    /// None means that the values cannot be compared, e.g. two arrays with the same
+    /// size and different keys.
+    pub fn compare(&self, other: &PhpValue) -> Result<Option<Ordering>, PhpError> {
+        self.compare_with_depth(other, 0)
+    }
+
+    /// Whether two values are equal for `==`, in_array(), array_search() and the cases of a switch.
+    pub fn loosely_equals(&self, other: &PhpValue) -> Result<bool, PhpError> {
+        Ok(self.compare(other)? == Some(Ordering::Equal))
+    }
+
+    /// Compares two values strictly, like `===` does.
+    ///
+    /// Both values must have the same type, arrays must also have their elements in the same order.
+    pub fn is_identical(&self, other: &PhpValue) -> Result<bool, PhpError> {
+        self.is_identical_with_depth(other, 0)
+    }
+
+    fn compare_with_depth(
+        &self,
+        other: &PhpValue,
+        depth: usize,
+    ) -> Result<Option<Ordering>, PhpError> {
+        match (self, other) {
+            (PhpValue::Array(left), PhpValue::Array(right)) => compare_arrays(left, right, depth),
+            // null and booleans are compared as booleans
+            (PhpValue::Array(_), PhpValue::Null | PhpValue::Bool(_))
+            | (PhpValue::Null | PhpValue::Bool(_), PhpValue::Array(_)) => {
+                let left = self.clone().is_true();
+                let right = other.clone().is_true();
+
+                Ok(Some(left.cmp(&right)))
+            }
+            // an array is always greater than any other value
+            (PhpValue::Array(_), _) => Ok(Some(Ordering::Greater)),
+            (_, PhpValue::Array(_)) => Ok(Some(Ordering::Less)),
+            _ => Ok(compare_scalars(self, other)),
+        }
+    }
+
+    fn is_identical_with_depth(&self, other: &PhpValue, depth: usize) -> Result<bool, PhpError> {
+        match (self, other) {
+            (PhpValue::Null, PhpValue::Null) => Ok(true),
+            (PhpValue::Bool(left), PhpValue::Bool(right)) => Ok(left == right),
+            (PhpValue::Int(left), PhpValue::Int(right)) => Ok(left == right),
+            (PhpValue::Float(left), PhpValue::Float(right)) => Ok(left == right),
+            (PhpValue::String(left), PhpValue::String(right)) => Ok(left.bytes == right.bytes),
+            // objects are identical only if they are the same instance
+            (PhpValue::Object(left), PhpValue::Object(right)) => Ok(Rc::ptr_eq(left, right)),
+            (PhpValue::Array(left), PhpValue::Array(right)) => {
+                if depth > MAX_COMPARISON_DEPTH {
+                    return Err(nesting_level_too_deep());
+                }
+
+                if left.len() != right.len() {
+                    return Ok(false);
+                }
+
+                for ((left_key, left_value), (right_key, right_value)) in
+                    left.iter().zip(right.iter())
+                {
+                    if left_key != right_key
+                        || !left_value.is_identical_with_depth(&right_value, depth + 1)?
+                    {
+                        return Ok(false);
+                    }
+                }
+
+                Ok(true)
+            }
+            _ if self.type_name() == other.type_name() => {
+                Ok(self.compare(other)? == Some(Ordering::Equal))
+            }
+            _ => Ok(false),
+        }
+    }
+}
+
+/// Compares two values that are not arrays, with the rules of PHP 8:
+/// a number and a numeric string are compared as numbers, a number and any
+/// other string are compared as strings.
+fn compare_scalars(left: &PhpValue, right: &PhpValue) -> Option<Ordering> {
+    match (left, right) {
+        (PhpValue::Null, PhpValue::Null) => Some(Ordering::Equal),
+        // null is the empty string when it is compared with a string
+        (PhpValue::Null, PhpValue::String(string)) => compare_strings(b"", string),
+        (PhpValue::String(string), PhpValue::Null) => compare_strings(string, b""),
+        (PhpValue::Null | PhpValue::Bool(_), _) | (_, PhpValue::Null | PhpValue::Bool(_)) => {
+            let left = left.clone().is_true();
+            let right = right.clone().is_true();
+
+            Some(left.cmp(&right))
+        }
+        (PhpValue::String(left), PhpValue::String(right)) => compare_strings(left, right),
+        (PhpValue::Int(_) | PhpValue::Float(_), PhpValue::String(string)) => {
+            match parse_numeric_string(string) {
+                Some(number) => compare_numbers(left, &number),
+                None => compare_strings(number_to_string(left).as_bytes(), string),
+            }
+        }
+        (PhpValue::String(string), PhpValue::Int(_) | PhpValue::Float(_)) => {
+            match parse_numeric_string(string) {
+                Some(number) => compare_numbers(&number, right),
+                None => compare_strings(string, number_to_string(right).as_bytes()),
+            }
+        }
+        (PhpValue::Int(_) | PhpValue::Float(_), PhpValue::Int(_) | PhpValue::Float(_)) => {
+            compare_numbers(left, right)
+        }
+        (PhpValue::Object(left), PhpValue::Object(right)) => compare_objects(left, right),
+        // an object is greater than any other value
+        (PhpValue::Object(_), _) => Some(Ordering::Greater),
+        (_, PhpValue::Object(_)) => Some(Ordering::Less),
+        _ => None,
+    }
+}
+
+/// Two numeric strings are compared as numbers, any other strings byte by byte.
+fn compare_strings(left: &[u8], right: &[u8]) -> Option<Ordering> {
+    match (parse_numeric_string(left), parse_numeric_string(right)) {
+        (Some(left), Some(right)) => compare_numbers(&left, &right),
+        _ => Some(left.cmp(right)),
+    }
+}
+
+/// None if one of the numbers is NAN.
+fn compare_numbers(left: &PhpValue, right: &PhpValue) -> Option<Ordering> {
+    match (left, right) {
+        (PhpValue::Int(left), PhpValue::Int(right)) => Some(left.cmp(right)),
+        _ => {
+            let as_float = |value: &PhpValue| match value {
+                PhpValue::Int(i) => *i as f64,
+                PhpValue::Float(f) => *f,
+                _ => 0.0,
+            };
+
+            as_float(left).partial_cmp(&as_float(right))
+        }
+    }
+}
+
+fn number_to_string(number: &PhpValue) -> String {
+    scalar_to_string(number, DEFAULT_PRECISION).unwrap_or_default()
+}
+
+/// Objects of the same class are compared property by property, objects of
+/// different classes cannot be compared.
+fn compare_objects(
+    left: &Rc<RefCell<PhpObject>>,
+    right: &Rc<RefCell<PhpObject>>,
+) -> Option<Ordering> {
+    if Rc::ptr_eq(left, right) {
+        return Some(Ordering::Equal);
+    }
+
+    let left = left.borrow();
+    let right = right.borrow();
+
+    if !Rc::ptr_eq(&left.class, &right.class) || left.properties.len() != right.properties.len() {
+        return None;
+    }
+
+    for (name, left_value) in &left.properties {
+        let right_value = right.get_property(&name.bytes)?;
+
+        match left_value.compare(right_value).ok()? {
+            Some(Ordering::Equal) => continue,
+            ordering => return ordering,
+        }
+    }
+
+    Some(Ordering::Equal)
+}
+
+/// Arrays with fewer elements are smaller, arrays of the same size are compared
+/// element by element, following the order of the left array.
+fn compare_arrays(
+    left: &PhpArray,
+    right: &PhpArray,
+    depth: usize,
+) -> Result<Option<Ordering>, PhpError> {
+    if depth > MAX_COMPARISON_DEPTH {
+        return Err(nesting_level_too_deep());
+    }
+
+    if left.len() != right.len() {
+        return Ok(Some(left.len().cmp(&right.len())));
+    }
+
+    for (key, left_value) in left.iter() {
+        let Some(right_value) = right.get(key) else {
+            return Ok(None);
+        };
+
+        match left_value.compare_with_depth(&right_value, depth + 1)? {
+            Some(Ordering::Equal) => continue,
+            ordering => return Ok(ordering),
+        }
+    }
+
+    Ok(Some(Ordering::Equal))
+}
+
+fn nesting_level_too_deep() -> PhpError {
+    PhpError {
+        level: ErrorLevel::Fatal,
+        message: "Nesting level too deep - recursive dependency?".to_string(),
+        line: 0,
+    }
+}
@@ -0,0 +1,546 @@
+use crate::helpers::get_string_from_bytes;
+use crate::php_value::PhpValue;
+
+/// The default of the "precision" ini setting.
+pub const DEFAULT_PRECISION: i64 = 14;
+
+/// Converts a value to a string like PHP does, floats have `precision` significant digits.
+///
+/// This is the conversion used by echo, print, concatenation and string interpolation,
+/// any other place that turns a value into a string must use it too so they all agree.
+/// Only var_dump() and var_export() have their own formats.
+///
+/// Arrays and objects cannot be converted, the caller decides what to do with them.
+pub fn scalar_to_string(value: &PhpValue, precision: i64) -> Option<String> {
+    match value {
+        PhpValue::Null => Some(String::new()),
+        PhpValue::Bool(true) => Some("1".to_string()),
+        PhpValue::Bool(false) => Some(String::new()),
+        PhpValue::Int(i) => Some(i.to_string()),
+        PhpValue::Float(f) => Some(format_float(*f, precision)),
+        PhpValue::String(s) => Some(String::from_utf8_lossy(s).to_string()),
+        PhpValue::Array(_) => None,
+        PhpValue::Object(_) => None,
+        PhpValue::Callable(c) => Some(get_string_from_bytes(&c.name.bytes)),
+        PhpValue::Resource(_) => Some("Resource".to_string()),
+    }
+}
+
+/// Converts a float to an int like PHP does, the fractional part is dropped, the numbers
+/// out of the range of the ints wrap around modulo 2^64, and NAN and INF are 0.
+pub fn float_to_int(value: f64) -> i64 {
+    if !value.is_finite() {
+        return 0;
+    }
+
+    // -2^63 is an int, 2^63 is not
+    if value >= i64::MIN as f64 && value < -(i64::MIN as f64) {
+        return value as i64;
+    }
+
+    let modulus = 2f64.powi(64);
+
+    let mut wrapped = value.trunc() % modulus;
+
+    if wrapped < 0.0 {
+        wrapped += modulus;
+    }
+
+    wrapped as u64 as i64
+}
+
+/// Converts a float to a string like PHP does, with `precision` significant digits.
+///
+/// The digits are the binary value correctly rounded, like the "%.*G" of C that PHP uses,
+/// so 0.123456789012345 is 0.12345678901234. A negative precision keeps all the digits of
+/// the shortest representation. Big and small numbers use the scientific notation (1.0E+25).
+pub fn format_float(value: f64, precision: i64) -> String {
+    if value.is_nan() {
+        return "NAN".to_string();
+    }
+
+    if value.is_infinite() {
+        return if value > 0.0 { "INF" } else { "-INF" }.to_string();
+    }
+
+    if value == 0.0 {
+        return if value.is_sign_negative() { "-0" } else { "0" }.to_string();
+    }
+
+    // like PHP, the notation of the shortest representation depends on 17 digits
+    let notation_threshold = if precision < 0 { 17 } else { precision.max(1) };
+
+    let (digits, exponent) = match precision {
+        precision if precision < 0 => decimal_digits(value),
+        precision => significant_digits(value, precision.max(1) as usize),
+    };
+
+    let digits: String = digits.iter().map(|digit| (digit + b'0') as char).collect();
+
+    let sign = if value < 0.0 { "-" } else { "" };
+
+    if exponent < -4 || exponent >= notation_threshold {
+        let fraction = if digits.len() > 1 { &digits[1..] } else { "0" };
+
+        let exponent_sign = if exponent < 0 { '-' } else { '+' };
+
+        return format!(
+            "{}{}.{}E{}{}",
+            sign,
+            &digits[..1],
+            fraction,
+            exponent_sign,
+            exponent.abs()
+        );
+    }
+
+    if exponent < 0 {
+        let zeros = "0".repeat((-exponent - 1) as usize);
+
+        return format!("{}0.{}{}", sign, zeros, digits);
+    }
+
+    let integer_length = exponent as usize + 1;
+
+    if digits.len() <= integer_length {
+        let zeros = "0".repeat(integer_length - digits.len());
+
+        return format!("{}{}{}", sign, digits, zeros);
+    }
+
+    format!(
+        "{}{}.{}",
+        sign,
+        &digits[..integer_length],
+        &digits[integer_length..]
+    )
+}
+
+/// The digits of the shortest representation of a float, without its sign, and the
+/// exponent of the first one: 1234.5 is [1, 2, 3, 4, 5] and 3.
+///
+/// number_format() and sprintf() round these digits, not the binary value, so 2.675 is
+/// rounded to 2.68 like PHP does although the nearest float is a bit smaller.
+fn decimal_digits(value: f64) -> (Vec<u8>, i64) {
+    // e.g. "1.2345e3"
+    let shortest = format!("{:e}", value.abs());
+
+    let (mantissa, exponent) = shortest.split_once('e').unwrap();
+
+    let digits = mantissa
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .map(|digit| digit - b'0')
+        .collect();
+
+    (digits, exponent.parse().unwrap())
+}
+
+/// The first `length` significant digits of the binary value of a float, correctly rounded,
+/// without its sign and the trailing zeros, and the exponent of the first one.
+fn significant_digits(value: f64, length: usize) -> (Vec<u8>, i64) {
+    // e.g. "1.2340e3", already rounded by the formatter
+    let rounded = format!("{:.*e}", length - 1, value.abs());
+
+    let (mantissa, exponent) = rounded.split_once('e').unwrap();
+
+    let mut digits: Vec<u8> = mantissa
+        .bytes()
+        .filter(u8::is_ascii_digit)
+        .map(|digit| digit - b'0')
+        .collect();
+
+    while digits.len() > 1 && digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    (digits, exponent.parse().unwrap())
+}
+
+/// Keeps `length` digits, rounding half up, and removes the trailing zeros.
+fn round_digits(digits: &mut Vec<u8>, exponent: &mut i64, length: usize) {
+    if digits.len() <= length {
+        return;
+    }
+
+    let round_up = digits[length] >= 5;
+
+    digits.truncate(length);
+
+    if round_up {
+        let mut position = length;
+
+        loop {
+            if position == 0 {
+                // all the digits were 9
+                digits.insert(0, 1);
+                digits.truncate(length.max(1));
+
+                *exponent += 1;
+
+                break;
+            }
+
+            position -= 1;
+
+            if digits[position] == 9 {
+                digits[position] = 0;
+            } else {
+                digits[position] += 1;
+
+                break;
+            }
+        }
+    }
+
+    while digits.len() > 1 && digits.last() == Some(&0) {
+        digits.pop();
+    }
+
+    if digits.is_empty() {
+        digits.push(0);
+    }
+}
+
+/// A number rounded to a number of decimals, see `round_to_decimals()`.
+#[derive(Debug, PartialEq)]
+pub struct FixedDecimal {
+    /// Whether the sign of the number is negative, -0.0 is negative too.
+    pub negative: bool,
+
+    /// The digits before the decimal point, at least one.
+    pub integer: String,
+
+    /// The digits after the decimal point, as many as the decimals.
+    pub fraction: String,
+}
+
+impl FixedDecimal {
+    /// Whether all the digits are zero, e.g. after rounding -0.001 to 2 decimals.
+    pub fn is_zero(&self) -> bool {
+        self.integer
+            .bytes()
+            .chain(self.fraction.bytes())
+            .all(|digit| digit == b'0')
+    }
+}
+
+/// Rounds a finite number half up to `decimals` decimals, or to a multiple of 10^-decimals
+/// when they are negative, without the float arithmetic that turns 2.675 into 2.67.
+///
+/// The rounding is done on the shortest representation of a float, then the rounded
+/// number is written with the digits of its binary value like PHP does, so 1e30 has
+/// the digits 1000000000000000019884624838656.
+pub fn round_to_decimals(value: &PhpValue, decimals: i64) -> FixedDecimal {
+    let (negative, mut digits, mut exponent) = match value {
+        PhpValue::Int(i) => {
+            let digits: Vec<u8> = i
+                .unsigned_abs()
+                .to_string()
+                .bytes()
+                .map(|digit| digit - b'0')
+                .collect();
+
+            let exponent = digits.len() as i64 - 1;
+
+            (*i < 0, digits, exponent)
+        }
+        value => {
+            let float = value.to_float().unwrap_or(0.0);
+
+            let (digits, exponent) = decimal_digits(float);
+
+            (float.is_sign_negative(), digits, exponent)
+        }
+    };
+
+    // a leading zero receives the carry of the rounding, like 9.99 to 10.0
+    digits.insert(0, 0);
+    exponent += 1;
+
+    let length = exponent + 1 + decimals;
+
+    if length <= 0 {
+        digits = vec![0];
+    } else {
+        round_digits(&mut digits, &mut exponent, length as usize);
+    }
+
+    let digit_at = |power: i64| {
+        let index = exponent - power;
+
+        if index >= 0 && (index as usize) < digits.len() {
+            (digits[index as usize] + b'0') as char
+        } else {
+            '0'
+        }
+    };
+
+    let integer: String = (0..=exponent.max(0)).rev().map(digit_at).collect();
+    let integer = integer.trim_start_matches('0');
+    let integer = if integer.is_empty() { "0" } else { integer };
+
+    let fraction: String = (1..=decimals.max(0))
+        .map(|power| digit_at(-power))
+        .collect();
+
+    if let PhpValue::Int(_) = value {
+        return FixedDecimal {
+            negative,
+            integer: integer.to_string(),
+            fraction,
+        };
+    }
+
+    let rounded: f64 = format!("{}.{}", integer, fraction).parse().unwrap_or(0.0);
+
+    let exact = format!("{:.*}", decimals.max(0) as usize, rounded);
+
+    let (integer, fraction) = exact.split_once('.').unwrap_or((&exact, ""));
+
+    FixedDecimal {
+        negative,
+        integer: integer.to_string(),
+        fraction: fraction.to_string(),
+    }
+}
+
+/// Formats a finite float in scientific notation with `precision` decimals, like the
+/// "%e" specifier of sprintf(): 1.234500e+3.
+pub fn format_scientific(value: f64, precision: usize, exponent_character: char) -> String {
+    let (mut digits, mut exponent) = decimal_digits(value);
+
+    round_digits(&mut digits, &mut exponent, precision + 1);
+
+    digits.resize(precision + 1, 0);
+
+    let digits: String = digits.iter().map(|digit| (digit + b'0') as char).collect();
+
+    let mut formatted = digits[..1].to_string();
+
+    if precision > 0 {
+        formatted.push('.');
+        formatted.push_str(&digits[1..]);
+    }
+
+    // a zero has the exponent 0, whatever the shortest representation says
+    if value == 0.0 {
+        exponent = 0;
+    }
+
+    format!(
+        "{}{}{}{}",
+        formatted,
+        exponent_character,
+        if exponent < 0 { '-' } else { '+' },
+        exponent.abs()
+    )
+}
+
+/// Formats a finite float with `precision` significant digits, like the "%g" specifier
+/// of sprintf(). Numbers with more integer digits than that or less than 0.0001
+/// use the scientific notation, with at least one decimal: 1.0e+25.
+pub fn format_general(value: f64, precision: usize, exponent_character: char) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+
+    let (mut digits, mut exponent) = decimal_digits(value);
+
+    round_digits(&mut digits, &mut exponent, precision.max(1));
+
+    let digits: String = digits.iter().map(|digit| (digit + b'0') as char).collect();
+
+    // the position of the decimal point from the first digit
+    let point = exponent + 1;
+
+    if point < -3 || point > precision.max(1) as i64 {
+        let fraction = if digits.len() > 1 { &digits[1..] } else { "0" };
+
+        return format!(
+            "{}.{}{}{}{}",
+            &digits[..1],
+            fraction,
+            exponent_character,
+            if exponent < 0 { '-' } else { '+' },
+            exponent.abs()
+        );
+    }
+
+    if point <= 0 {
+        return format!("0.{}{}", "0".repeat(-point as usize), digits);
+    }
+
+    let point = point as usize;
+
+    if digits.len() <= point {
+        return format!("{}{}", digits, "0".repeat(point - digits.len()));
+    }
+
+    format!("{}.{}", &digits[..point], &digits[point..])
+}
+
+/// Converts a numeric string to an int or a float, like "5" or "1.5".
+pub fn parse_numeric_string(string: &[u8]) -> Option<PhpValue> {
+    let string = std::str::from_utf8(string).ok()?.trim();
+
+    if let Ok(i) = string.parse::<i64>() {
+        return Some(PhpValue::Int(i));
+    }
+
+    let is_number = string.bytes().any(|byte| byte.is_ascii_digit())
+        && string
+            .bytes()
+            .all(|byte| byte.is_ascii_digit() || b".eE+-".contains(&byte));
+
+    if !is_number {
+        return None;
+    }
+
+    string.parse::<f64>().ok().map(PhpValue::Float)
+}
+
+/// The value of an integer literal of the source, like 42, 0x2A, 0o52, 052 or 0b101010.
+/// A literal too big for an int is a float, like in PHP.
+pub fn parse_integer_literal(literal: &str) -> PhpValue {
+    let literal = literal.to_ascii_lowercase();
+
+    let (digits, base) = match literal.as_bytes() {
+        [b'0', b'x', ..] => (&literal[2..], 16),
+        [b'0', b'o', ..] => (&literal[2..], 8),
+        [b'0', b'b', ..] => (&literal[2..], 2),
+        [b'0', _, ..] => (&literal[1..], 8),
+        _ => (&literal[..], 10),
+    };
+
+    if let Ok(value) = i64::from_str_radix(digits, base) {
+        return PhpValue::Int(value);
+    }
+
+    if base == 10 {
+        return PhpValue::Float(digits.parse().unwrap_or(0.0));
+    }
+
+    let value = digits.chars().fold(0.0, |value, digit| {
+        value * base as f64 + digit.to_digit(base).unwrap_or(0) as f64
+    });
+
+    PhpValue::Float(value)
+}
+
+/// The number at the start of a string, like the conversion of "12abc" to 12 that
+/// happens when a string is used as a number. It is 0 if the string has none.
+pub fn leading_numeric_value(string: &[u8]) -> PhpValue {
+    parse_leading_number(string).unwrap_or(PhpValue::Int(0))
+}
+
+/// The number at the start of a string, after the whitespace, None if the string
+/// does not start with one.
+pub fn parse_leading_number(string: &[u8]) -> Option<PhpValue> {
+    let start = string
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(string.len());
+
+    let string = &string[start..];
+
+    let skip_digits = |mut position: usize| {
+        while string.get(position).is_some_and(u8::is_ascii_digit) {
+            position += 1;
+        }
+
+        position
+    };
+
+    let mut end = 0;
+
+    if matches!(string.first(), Some(b'+' | b'-')) {
+        end += 1;
+    }
+
+    let integer_end = skip_digits(end);
+    let mut number_end = integer_end;
+
+    if string.get(number_end) == Some(&b'.') {
+        number_end = skip_digits(number_end + 1);
+    }
+
+    // at least one digit, before or after the decimal point
+    if integer_end == end && number_end <= integer_end + 1 {
+        return None;
+    }
+
+    if matches!(string.get(number_end), Some(b'e' | b'E')) {
+        let mut exponent_start = number_end + 1;
+
+        if matches!(string.get(exponent_start), Some(b'+' | b'-')) {
+            exponent_start += 1;
+        }
+
+        let exponent_end = skip_digits(exponent_start);
+
+        if exponent_end > exponent_start {
+            number_end = exponent_end;
+        }
+    }
+
+    parse_numeric_string(&string[..number_end])
+}
+
+/// The int written at the start of a string in `base`, like intval() reads it with a
+/// base other than 10. Base 16 can start with "0x", base 8 with "0o" or "0" and base 2
+/// with "0b", and base 0 takes the base from that prefix. The result saturates.
+pub fn parse_int_with_base(string: &[u8], mut base: u32) -> i64 {
+    let start = string
+        .iter()
+        .position(|byte| !byte.is_ascii_whitespace())
+        .unwrap_or(string.len());
+
+    let mut string = &string[start..];
+
+    let negative = string.first() == Some(&b'-');
+
+    if matches!(string.first(), Some(b'+' | b'-')) {
+        string = &string[1..];
+    }
+
+    let prefix = string.get(..2).map(|prefix| prefix.to_ascii_lowercase());
+
+    let prefix_base = match prefix.as_deref() {
+        Some(b"0x") => Some(16),
+        Some(b"0o") => Some(8),
+        Some(b"0b") => Some(2),
+        _ => None,
+    };
+
+    if let Some(prefix_base) = prefix_base {
+        if base == 0 || base == prefix_base {
+            base = prefix_base;
+            string = &string[2..];
+        }
+    }
+
+    if base == 0 {
+        base = if string.first() == Some(&b'0') { 8 } else { 10 };
+    }
+
+    if !(2..=36).contains(&base) {
+        return 0;
+    }
+
+    let digits = string
+        .iter()
+        .map_while(|byte| (*byte as char).to_digit(base));
+
+    let mut value: i128 = 0;
+
+    for digit in digits {
+        value = (value * base as i128 + digit as i128).min(i64::MAX as i128 + 1);
+    }
+
+    if negative {
+        (-value).max(i64::MIN as i128) as i64
+    } else {
+        value.min(i64::MAX as i128) as i64
+    }
+}
@@ -1,18 +1,162 @@
 use std::{cell::RefCell, collections::HashMap, rc::Rc};
 
-use crate::php_value::PhpValue;
+use crate::native::LANGINFO_ITEMS;
+use crate::ordered_map::OrderedMap;
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_class::{PhpClass, PhpInterface};
+use crate::php_value::{
+    PhpObject, PhpValue, E_ALL, E_COMPILE_ERROR, E_COMPILE_WARNING, E_CORE_ERROR, E_CORE_WARNING,
+    E_DEPRECATED, E_ERROR, E_NOTICE, E_PARSE, E_RECOVERABLE_ERROR, E_STRICT, E_USER_DEPRECATED,
+    E_USER_ERROR, E_USER_NOTICE, E_USER_WARNING, E_WARNING,
+};
+
+/// The variables of a scope, in the order they were defined, by their name without
+/// the `$`, see `helpers::variable_key()`.
+pub type Vars = OrderedMap<Vec<u8>, Rc<RefCell<PhpValue>>>;
+
+/// The objects of the enum cases that were used, by the lowercase name of their enum
+/// and the name of the case. There is one object per case.
+type EnumCaseObjects = HashMap<(Vec<u8>, Vec<u8>), Rc<RefCell<PhpObject>>>;
+
+/// The constants defined before the script starts.
+fn predefined_constants() -> HashMap<Vec<u8>, PhpValue> {
+    let directory_separator = std::path::MAIN_SEPARATOR.to_string();
+    let path_separator = if cfg!(windows) { ";" } else { ":" };
+
+    let mut constants = HashMap::from([
+        (
+            b"DIRECTORY_SEPARATOR".to_vec(),
+            PhpValue::String(directory_separator.into()),
+        ),
+        (
+            b"PATH_SEPARATOR".to_vec(),
+            PhpValue::String(path_separator.into()),
+        ),
+        (b"PHP_EOL".to_vec(), PhpValue::String("\n".into())),
+        (b"COUNT_NORMAL".to_vec(), PhpValue::Int(0)),
+        (b"COUNT_RECURSIVE".to_vec(), PhpValue::Int(1)),
+        (b"ARRAY_FILTER_USE_BOTH".to_vec(), PhpValue::Int(1)),
+        (b"ARRAY_FILTER_USE_KEY".to_vec(), PhpValue::Int(2)),
+        (b"SORT_REGULAR".to_vec(), PhpValue::Int(0)),
+        (b"SORT_NUMERIC".to_vec(), PhpValue::Int(1)),
+        (b"SORT_STRING".to_vec(), PhpValue::Int(2)),
+        (b"SORT_LOCALE_STRING".to_vec(), PhpValue::Int(5)),
+        // ints have 64 bits, like in the 64-bit builds of PHP
+        (b"PHP_INT_MAX".to_vec(), PhpValue::Int(i64::MAX)),
+        (b"PHP_INT_MIN".to_vec(), PhpValue::Int(i64::MIN)),
+        (b"PHP_INT_SIZE".to_vec(), PhpValue::Int(8)),
+        (b"PHP_FLOAT_EPSILON".to_vec(), PhpValue::Float(f64::EPSILON)),
+        (b"M_PI".to_vec(), PhpValue::Float(std::f64::consts::PI)),
+        (b"E_ERROR".to_vec(), PhpValue::Int(E_ERROR)),
+        (b"E_WARNING".to_vec(), PhpValue::Int(E_WARNING)),
+        (b"E_PARSE".to_vec(), PhpValue::Int(E_PARSE)),
+        (b"E_NOTICE".to_vec(), PhpValue::Int(E_NOTICE)),
+        (b"E_CORE_ERROR".to_vec(), PhpValue::Int(E_CORE_ERROR)),
+        (b"E_CORE_WARNING".to_vec(), PhpValue::Int(E_CORE_WARNING)),
+        (b"E_COMPILE_ERROR".to_vec(), PhpValue::Int(E_COMPILE_ERROR)),
+        (
+            b"E_COMPILE_WARNING".to_vec(),
+            PhpValue::Int(E_COMPILE_WARNING),
+        ),
+        (b"E_USER_ERROR".to_vec(), PhpValue::Int(E_USER_ERROR)),
+        (b"E_USER_WARNING".to_vec(), PhpValue::Int(E_USER_WARNING)),
+        (b"E_USER_NOTICE".to_vec(), PhpValue::Int(E_USER_NOTICE)),
+        (b"E_STRICT".to_vec(), PhpValue::Int(E_STRICT)),
+        (
+            b"E_RECOVERABLE_ERROR".to_vec(),
+            PhpValue::Int(E_RECOVERABLE_ERROR),
+        ),
+        (b"E_DEPRECATED".to_vec(), PhpValue::Int(E_DEPRECATED)),
+        (
+            b"E_USER_DEPRECATED".to_vec(),
+            PhpValue::Int(E_USER_DEPRECATED),
+        ),
+        (b"E_ALL".to_vec(), PhpValue::Int(E_ALL)),
+    ]);
+
+    // the items of nl_langinfo()
+    for (name, number, _) in LANGINFO_ITEMS {
+        constants.insert(name.as_bytes().to_vec(), PhpValue::Int(*number));
+    }
+
+    constants
+}
+
+/// The interfaces of PHP that the classes can implement, with the interfaces they extend.
+const PREDEFINED_INTERFACES: &[(&str, &[&str])] = &[
+    ("ArrayAccess", &[]),
+    ("Countable", &[]),
+    ("JsonSerializable", &[]),
+    ("Stringable", &[]),
+    ("Throwable", &[]),
+    ("Traversable", &[]),
+    ("Iterator", &["Traversable"]),
+    ("IteratorAggregate", &["Traversable"]),
+    ("UnitEnum", &[]),
+    ("BackedEnum", &["UnitEnum"]),
+];
+
+/// The interfaces declared before the script starts.
+///
+/// They have no methods, so the classes that implement them are not checked against them.
+fn predefined_interfaces() -> HashMap<Vec<u8>, Rc<PhpInterface>> {
+    let mut interfaces: HashMap<Vec<u8>, Rc<PhpInterface>> = HashMap::new();
+
+    for (name, parents) in PREDEFINED_INTERFACES {
+        let parents = parents
+            .iter()
+            .map(|parent| Rc::clone(&interfaces[&parent.to_ascii_lowercase().into_bytes()]))
+            .collect();
+
+        let interface = PhpInterface {
+            name: (*name).into(),
+            parents,
+            constants: vec![],
+            methods: HashMap::new(),
+        };
+
+        interfaces.insert(name.to_ascii_lowercase().into_bytes(), Rc::new(interface));
+    }
+
+    interfaces
+}
 
 #[derive(Clone)]
 pub struct Environment {
-    vars: HashMap<Vec<u8>, Rc<RefCell<PhpValue>>>,
+    vars: Vars,
+
+    /// The variables of the global scope while a function runs, see enter_scope().
+    globals: Vars,
+
+    /// How many scopes were entered, 0 in the global scope.
+    depth: usize,
+
+    /// $_SERVER and the other superglobals, they are seen from every scope.
+    superglobals: HashMap<Vec<u8>, Rc<RefCell<PhpValue>>>,
+
     identifiers: HashMap<Vec<u8>, PhpValue>,
+
+    /// The declared classes, by their lowercase name.
+    classes: HashMap<Vec<u8>, Rc<PhpClass>>,
+
+    /// The declared interfaces, by their lowercase name. A class and an interface
+    /// cannot have the same name.
+    interfaces: HashMap<Vec<u8>, Rc<PhpInterface>>,
+
+    enum_cases: EnumCaseObjects,
 }
 
 impl Environment {
     pub fn new() -> Environment {
         Environment {
-            vars: HashMap::new(),
-            identifiers: HashMap::new(),
+            vars: OrderedMap::new(),
+            globals: OrderedMap::new(),
+            depth: 0,
+            superglobals: HashMap::new(),
+            identifiers: predefined_constants(),
+            classes: HashMap::new(),
+            interfaces: predefined_interfaces(),
+            enum_cases: HashMap::new(),
         }
     }
 
@@ -30,17 +174,7 @@ impl Environment {
     }
 
     pub fn get_var(&self, key: &[u8]) -> Option<PhpValue> {
-        let key = if key.is_empty() || key[0] != b'$' {
-            let mut new_key: Vec<u8> = vec![b'$'];
-
-            new_key.extend(key);
-
-            new_key
-        } else {
-            key.to_vec()
-        };
-
-        let value = self.vars.get(&key);
+        let value = self.get_var_with_rc(key);
 
         match value {
             Some(value) => Some(value.borrow().clone()),
@@ -48,29 +182,204 @@ impl Environment {
         }
     }
 
+    /// Defines a superglobal, it is also a variable of the global scope.
+    pub fn set_superglobal(&mut self, key: &[u8], value: PhpValue) {
+        let value = Rc::new(RefCell::new(value));
+
+        self.superglobals.insert(key.to_vec(), Rc::clone(&value));
+        self.global_vars_mut().insert(key.to_vec(), value);
+    }
+
+    /// Returns a new handle to a variable of the global scope, it is created as null
+    /// if it does not exist.
+    pub fn get_global_var_with_rc(&mut self, key: &[u8]) -> Rc<RefCell<PhpValue>> {
+        let globals = self.global_vars_mut();
+
+        if let Some(value) = globals.get(key) {
+            return Rc::clone(value);
+        }
+
+        let value = Rc::new(RefCell::new(PhpValue::Null));
+
+        globals.insert(key.to_vec(), Rc::clone(&value));
+
+        value
+    }
+
+    /// The value of $GLOBALS, the variables of the global scope.
+    ///
+    /// It is a copy, writing to it does not change the variables.
+    fn globals_array(&self) -> PhpArray {
+        let mut array = PhpArray::new();
+
+        for (name, value) in self.global_vars().iter() {
+            array.insert(
+                ArrayKey::String(name.clone().into()),
+                value.borrow().clone(),
+            );
+        }
+
+        array
+    }
+
+    /// The variables of the global scope, whatever the current scope is.
+    fn global_vars(&self) -> &Vars {
+        match self.depth {
+            0 => &self.vars,
+            _ => &self.globals,
+        }
+    }
+
+    fn global_vars_mut(&mut self) -> &mut Vars {
+        match self.depth {
+            0 => &mut self.vars,
+            _ => &mut self.globals,
+        }
+    }
+
+    /// Returns the variables of the current scope in the order they were defined,
+    /// including the ones set to null.
+    ///
+    /// A variable that was never assigned or that was unset is not in the scope.
+    pub fn get_vars(&self) -> impl Iterator<Item = (&Vec<u8>, PhpValue)> {
+        self.vars
+            .iter()
+            .map(|(name, value)| (name, value.borrow().clone()))
+    }
+
     pub fn var_exists(&self, key: &[u8]) -> bool {
-        self.vars.contains_key(key)
+        self.get_var_with_rc(key).is_some()
     }
 
-    pub fn get_var_with_rc(&self, key: &[u8]) -> Option<&Rc<RefCell<PhpValue>>> {
-        self.vars.get(key)
+    /// Returns a new handle to the variable, the environment is not borrowed while it is used.
+    ///
+    /// The superglobals are found from every scope.
+    pub fn get_var_with_rc(&self, key: &[u8]) -> Option<Rc<RefCell<PhpValue>>> {
+        if let Some(value) = self.vars.get(key).or_else(|| self.superglobals.get(key)) {
+            return Some(Rc::clone(value));
+        }
+
+        if key == b"GLOBALS" {
+            return Some(Rc::new(RefCell::new(PhpValue::Array(self.globals_array()))));
+        }
+
+        None
+    }
+
+    /// Writes the value of a variable, creating the variable if it does not exist.
+    ///
+    /// An existing variable is written in place so that every reference to it sees the new value.
+    /// The variable is only borrowed during the write, never while PHP code is evaluated.
+    pub fn update_var(&mut self, key: &[u8], value: PhpValue) {
+        match self.vars.get(key).or_else(|| self.superglobals.get(key)) {
+            Some(old_value) => *old_value.borrow_mut() = value,
+            None => self.set_var(key, &value),
+        }
+    }
+
+    /// Whether any variable is currently borrowed.
+    ///
+    /// A borrow held while PHP code is evaluated would panic as soon as that code touches the variable.
+    pub fn has_borrowed_vars(&self) -> bool {
+        self.vars.values().any(|value| value.try_borrow_mut().is_err())
     }
 
     pub fn get_identifier(&self, key: &[u8]) -> Option<PhpValue> {
         self.identifiers.get(key).cloned()
     }
 
-    /// Merges differences from another environment, adding missing values.
-    pub fn get_and_set_diff(&mut self, other_env: Environment) {
-        for (key, value) in other_env.vars {
-            self.vars.entry(key).or_insert(value);
+    /// The names of the user defined functions, in no particular order.
+    pub fn function_names(&self) -> impl Iterator<Item = &[u8]> {
+        // order: suggest_name() breaks the ties by name, the order cannot be seen
+        self.identifiers
+            .iter()
+            .filter(|(_, value)| matches!(value, PhpValue::Callable(_)))
+            .map(|(name, _)| name.as_slice())
+    }
+
+    /// Returns the class with the given name, class names are case insensitive.
+    pub fn get_class(&self, name: &[u8]) -> Option<Rc<PhpClass>> {
+        self.classes.get(&name.to_ascii_lowercase()).cloned()
+    }
+
+    /// Adds a class, it returns false if a class or an interface with the same name already exists.
+    pub fn add_class(&mut self, class: PhpClass) -> bool {
+        let key = class.name.bytes.to_ascii_lowercase();
+
+        if self.interfaces.contains_key(&key) {
+            return false;
         }
 
-        for (key, value) in other_env.identifiers {
-            self.identifiers.entry(key).or_insert(value);
+        match self.classes.entry(key) {
+            std::collections::hash_map::Entry::Occupied(_) => false,
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(Rc::new(class));
+
+                true
+            }
         }
     }
 
+    /// Returns the object of an enum case, if it was already created.
+    pub fn get_enum_case(&self, class: &[u8], case: &[u8]) -> Option<Rc<RefCell<PhpObject>>> {
+        self.enum_cases
+            .get(&(class.to_ascii_lowercase(), case.to_vec()))
+            .cloned()
+    }
+
+    pub fn add_enum_case(&mut self, class: &[u8], case: &[u8], object: Rc<RefCell<PhpObject>>) {
+        self.enum_cases
+            .insert((class.to_ascii_lowercase(), case.to_vec()), object);
+    }
+
+    /// Returns the interface with the given name, interface names are case insensitive.
+    pub fn get_interface(&self, name: &[u8]) -> Option<Rc<PhpInterface>> {
+        self.interfaces.get(&name.to_ascii_lowercase()).cloned()
+    }
+
+    /// Adds an interface, it returns false if a class or an interface with the same name
+    /// already exists.
+    pub fn add_interface(&mut self, interface: PhpInterface) -> bool {
+        let key = interface.name.bytes.to_ascii_lowercase();
+
+        if self.classes.contains_key(&key) || self.interfaces.contains_key(&key) {
+            return false;
+        }
+
+        self.interfaces.insert(key, Rc::new(interface));
+
+        true
+    }
+
+    /// Starts a new scope without variables and returns what leave_scope() needs to
+    /// go back to the old one.
+    ///
+    /// Identifiers are shared by all the scopes.
+    pub fn enter_scope(&mut self) -> Vars {
+        let vars = std::mem::take(&mut self.vars);
+
+        self.depth += 1;
+
+        // the global variables stay here, so they are found from the functions
+        if self.depth == 1 {
+            self.globals = vars;
+
+            return Vars::new();
+        }
+
+        vars
+    }
+
+    /// Goes back to the scope returned by enter_scope().
+    pub fn leave_scope(&mut self, vars: Vars) {
+        self.depth -= 1;
+
+        self.vars = match self.depth {
+            0 => std::mem::take(&mut self.globals),
+            _ => vars,
+        };
+    }
+
     pub fn identifier_entry(
         &mut self,
         key: Vec<u8>,
@@ -78,3 +387,42 @@ impl Environment {
         self.identifiers.entry(key)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::php_value::PhpValue;
+
+    use super::Environment;
+
+    #[test]
+    fn a_handle_to_a_variable_does_not_borrow_it() {
+        let mut env = Environment::new();
+
+        env.set_var(b"a", &PhpValue::Int(1));
+
+        let handle = env.get_var_with_rc(b"a").unwrap();
+
+        assert!(!env.has_borrowed_vars());
+
+        // written in place, the handle sees the new value
+        env.update_var(b"a", PhpValue::Int(2));
+
+        assert_eq!(*handle.borrow(), PhpValue::Int(2));
+    }
+
+    #[test]
+    fn a_live_borrow_is_detected() {
+        let mut env = Environment::new();
+
+        env.set_var(b"a", &PhpValue::Int(1));
+
+        let handle = env.get_var_with_rc(b"a").unwrap();
+        let borrow = handle.borrow();
+
+        assert!(env.has_borrowed_vars());
+
+        drop(borrow);
+
+        assert!(!env.has_borrowed_vars());
+    }
+}
@@ -0,0 +1,906 @@
+use std::rc::Rc;
+use std::str;
+
+use php_parser_rs::lexer::byte_string::ByteString;
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::operators::AssignmentOperationExpression;
+use php_parser_rs::parser::ast::{ArrayIndexExpression, ArrayItem, Expression, ListEntry};
+
+use crate::conversion::parse_numeric_string;
+use crate::helpers::{display_name, display_variable_name, increment_string};
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::references::{is_reference_path, ReferencePath};
+use super::{undefined_array_key, Evaluator, NULL};
+
+impl<'w> Evaluator<'w> {
+    pub(super) fn eval_assignment_operation(
+        &mut self,
+        operation: &AssignmentOperationExpression,
+    ) -> Result<PhpValue, PhpError> {
+        match operation {
+            AssignmentOperationExpression::Assign {
+                left,
+                equals,
+                right,
+            } => {
+                if let Expression::Reference(reference) = &**right {
+                    let value = self.get_reference(&reference.right, reference.ampersand)?;
+
+                    self.assign_reference(left, Rc::clone(&value), *equals)?;
+
+                    let value = value.borrow().clone();
+
+                    return Ok(value);
+                }
+
+                if let Expression::PropertyFetch(fetch) = &**left {
+                    return self
+                        .assign_property(fetch, |evaluator| evaluator.eval_expression(right));
+                }
+
+                if let Expression::ShortArray(_) | Expression::List(_) = &**left {
+                    // the entries taken by reference share the elements of the array
+                    if has_reference_entries(left) {
+                        if !is_reference_path(right) {
+                            return Err(PhpError {
+                                level: ErrorLevel::Fatal,
+                                message: "Cannot assign reference to non referenceable value"
+                                    .to_string(),
+                                line: equals.line,
+                            });
+                        }
+
+                        let source = self.get_reference_path(right, *equals)?;
+
+                        let value = self.get_path_reference(&source)?.borrow().clone();
+
+                        self.destructure(left, &value, Some(&source), *equals)?;
+
+                        return Ok(value);
+                    }
+
+                    let value = self.eval_expression(right)?;
+
+                    self.destructure(left, &value, None, *equals)?;
+
+                    return Ok(value);
+                }
+
+                if let Expression::StaticPropertyFetch(fetch) = &**left {
+                    let property = self.get_static_property(fetch)?;
+
+                    let value = self.eval_expression(right)?;
+
+                    *property.borrow_mut() = value.clone();
+
+                    return Ok(value);
+                }
+
+                if let Expression::ArrayIndex(index) = &**left {
+                    return self.assign_array_element(
+                        index,
+                        |evaluator| evaluator.eval_expression(right),
+                        *equals,
+                    );
+                }
+
+                let Expression::Variable(ref left_var) = **left else {
+                    return Err(PhpError {
+                        level: ErrorLevel::ParseError,
+                        message: "Only variables can be assigned".to_string(),
+                        line: equals.line,
+                    });
+                };
+
+                let left_var_name = self.get_variable_name(&left_var)?;
+
+                if left_var_name == b"this" {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "Cannot re-assign $this".to_string(),
+                        line: equals.line,
+                    });
+                }
+
+                // The right side is fully evaluated before the variable is borrowed,
+                // it may read or write the same variable.
+                let right_value = self.eval_expression(right)?;
+
+                self.env.update_var(&left_var_name, right_value.clone());
+
+                Ok(right_value)
+            }
+            AssignmentOperationExpression::Addition {
+                left,
+                plus_equals,
+                right,
+            } => self.change_var_value(left, plus_equals, right, "+"),
+            AssignmentOperationExpression::Subtraction {
+                left,
+                minus_equals,
+                right,
+            } => self.change_var_value(left, minus_equals, right, "-"),
+            AssignmentOperationExpression::Multiplication {
+                left,
+                asterisk_equals,
+                right,
+            } => self.change_var_value(left, asterisk_equals, right, "*"),
+            AssignmentOperationExpression::Division {
+                left,
+                slash_equals,
+                right,
+            } => self.change_var_value(left, slash_equals, right, "/"),
+            AssignmentOperationExpression::Modulo {
+                left,
+                percent_equals,
+                right,
+            } => self.change_var_value(left, percent_equals, right, "%"),
+            AssignmentOperationExpression::Exponentiation {
+                left,
+                pow_equals,
+                right,
+            } => self.change_var_value(left, pow_equals, right, "**"),
+            AssignmentOperationExpression::Concat {
+                left,
+                dot_equals,
+                right,
+            } => self.change_var_value(left, dot_equals, right, "."),
+            AssignmentOperationExpression::BitwiseAnd {
+                left,
+                ampersand_equals,
+                right,
+            } => self.change_var_value(left, ampersand_equals, right, "&"),
+            AssignmentOperationExpression::BitwiseOr {
+                left,
+                pipe_equals,
+                right,
+            } => self.change_var_value(left, pipe_equals, right, "|"),
+            AssignmentOperationExpression::BitwiseXor {
+                left,
+                caret_equals,
+                right,
+            } => self.change_var_value(left, caret_equals, right, "^"),
+            AssignmentOperationExpression::LeftShift {
+                left,
+                left_shift_equals,
+                right,
+            } => self.change_var_value(left, left_shift_equals, right, "<<"),
+            AssignmentOperationExpression::RightShift {
+                left,
+                right_shift_equals,
+                right,
+            } => self.change_var_value(left, right_shift_equals, right, ">>"),
+            AssignmentOperationExpression::Coalesce {
+                left,
+                coalesce_equals,
+                right,
+            } => self.change_var_value(left, coalesce_equals, &right, "??"),
+        }
+    }
+
+    /// Assigns a value to an element of an array or to a character of a string,
+    /// like `$array['key'][] = $value`.
+    ///
+    /// The indexes are evaluated from left to right before `value` gives the value. A variable,
+    /// a property or an element that is null or undefined becomes an empty array.
+    fn assign_array_element(
+        &mut self,
+        target: &ArrayIndexExpression,
+        value: impl FnOnce(&mut Self) -> Result<PhpValue, PhpError>,
+        equals: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let mut dimensions = vec![target];
+        let mut base = target.array.as_ref();
+
+        while let Expression::ArrayIndex(index) = base {
+            dimensions.push(index);
+            base = index.array.as_ref();
+        }
+
+        dimensions.reverse();
+
+        // the object of a property is evaluated before the indexes
+        let object = match base {
+            Expression::PropertyFetch(fetch) => {
+                let object = self.eval_expression(&fetch.target)?;
+                let property_name = self.get_member_name(&fetch.property)?;
+
+                let PhpValue::Object(object) = object else {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: format!(
+                            "Attempt to modify property \"{}\" on {}",
+                            display_name(&property_name),
+                            object.type_name().error_name()
+                        ),
+                        line: fetch.arrow.line,
+                    });
+                };
+
+                Some((object, property_name))
+            }
+            _ => None,
+        };
+
+        let mut indexes = vec![];
+
+        for dimension in dimensions {
+            let index = match &dimension.index {
+                Some(index) => Some(self.eval_expression(index)?),
+                None => None,
+            };
+
+            indexes.push((index, dimension.left_bracket));
+        }
+
+        let value = value(self)?;
+
+        let container = match base {
+            Expression::Variable(variable) => {
+                let name = self.get_variable_name(variable)?;
+
+                if !self.env.var_exists(&name) {
+                    self.env.set_var(&name, &NULL);
+                }
+
+                self.env.get_var_with_rc(&name).unwrap()
+            }
+            Expression::StaticPropertyFetch(fetch) => self.get_static_property(fetch)?,
+            Expression::PropertyFetch(fetch) => {
+                let (object, property_name) = object.unwrap();
+
+                self.check_property_write(&object.borrow(), &property_name, fetch.arrow)?;
+
+                let mut property = object
+                    .borrow()
+                    .get_property(&property_name)
+                    .cloned()
+                    .unwrap_or(NULL);
+
+                let result = self.assign_element(&mut property, &indexes, value);
+
+                object.borrow_mut().set_property(&property_name, property);
+
+                return result;
+            }
+            _ => {
+                return Err(PhpError {
+                    level: ErrorLevel::ParseError,
+                    message: "Only variables can be assigned".to_string(),
+                    line: equals.line,
+                })
+            }
+        };
+
+        // the value is taken out of the variable, so that the errors raised while it is
+        // changed can run an error handler
+        let mut current = std::mem::replace(&mut *container.borrow_mut(), NULL);
+
+        let result = self.assign_element(&mut current, &indexes, value);
+
+        *container.borrow_mut() = current;
+
+        result
+    }
+
+    /// Assigns the elements of an array to the targets of `[$a, $b]` or `list($a, $b)`,
+    /// like `['x' => $x, 'y' => [$y, $z]] = $point`.
+    ///
+    /// The value was fully evaluated before, so `[$a, $b] = [$b, $a]` swaps the variables
+    /// and a target that changes the array does not change the elements given to the
+    /// next targets. The targets are assigned from left to right, a nested pattern
+    /// is destructured before the targets that come after it. A value that is not an
+    /// array gives null to every target.
+    ///
+    /// The entries like `&$a` share the element of the array at `source` instead, the
+    /// element is created with null when it does not exist, like `$a = &$array[0]`.
+    fn destructure(
+        &mut self,
+        pattern: &Expression,
+        value: &PhpValue,
+        source: Option<&ReferencePath>,
+        equals: Span,
+    ) -> Result<(), PhpError> {
+        let error = |message: &str| PhpError {
+            level: ErrorLevel::Fatal,
+            message: message.to_string(),
+            line: equals.line,
+        };
+
+        // the key, the target and whether it is taken by reference of every entry, the
+        // skipped entries only take a position
+        let entries: Vec<Option<(Option<&Expression>, &Expression, bool)>> = match pattern {
+            Expression::ShortArray(array) => array
+                .items
+                .inner
+                .iter()
+                .map(|item| match item {
+                    ArrayItem::Skipped => Ok(None),
+                    ArrayItem::Value { value } => Ok(Some((None, value, false))),
+                    ArrayItem::KeyValue { key, value, .. } => Ok(Some((Some(key), value, false))),
+                    ArrayItem::ReferencedValue { value, .. } => Ok(Some((None, value, true))),
+                    ArrayItem::ReferencedKeyValue { key, value, .. } => {
+                        Ok(Some((Some(key), value, true)))
+                    }
+                    ArrayItem::SpreadValue { .. } => {
+                        Err(error("Spread operator is not supported in assignments"))
+                    }
+                })
+                .collect::<Result<_, _>>()?,
+            Expression::List(list) => list
+                .items
+                .iter()
+                .map(|entry| match entry {
+                    ListEntry::Skipped => None,
+                    ListEntry::Value { value } => Some((None, value, false)),
+                    ListEntry::KeyValue { key, value, .. } => Some((Some(key), value, false)),
+                })
+                .collect(),
+            _ => return Err(error("Only arrays can be destructured")),
+        };
+
+        // a trailing comma is a skipped entry too
+        if entries.iter().all(Option::is_none) {
+            return Err(error("Cannot use empty list"));
+        }
+
+        let has_keyed = entries
+            .iter()
+            .any(|entry| matches!(entry, Some((Some(_), ..))));
+        let has_unkeyed = entries
+            .iter()
+            .any(|entry| matches!(entry, Some((None, ..))));
+
+        if has_keyed && has_unkeyed {
+            return Err(error(
+                "Cannot mix keyed and unkeyed array entries in assignments",
+            ));
+        }
+
+        for (position, entry) in entries.into_iter().enumerate() {
+            let Some((key, target, by_reference)) = entry else {
+                continue;
+            };
+
+            let key = match key {
+                Some(key) => {
+                    let key = self.eval_expression(key)?;
+
+                    self.get_array_key(&key, equals)?
+                }
+                None => ArrayKey::Int(position as i64),
+            };
+
+            // the entries by reference are only allowed when there is a source
+            let element_source = source.map(|source| source.element(&key, equals));
+
+            if let (true, Some(element_source)) = (by_reference, &element_source) {
+                let reference = self.get_path_reference(element_source)?;
+
+                self.assign_reference(target, reference, equals)?;
+
+                continue;
+            }
+
+            let nested_source = element_source.filter(|_| has_reference_entries(target));
+
+            let element = match value {
+                PhpValue::Array(array) => match array.get(&key) {
+                    Some(element) => element.into_owned(),
+                    // the elements of a nested pattern by reference are created
+                    None if nested_source.is_some() => NULL,
+                    None => {
+                        self.raise_error(undefined_array_key(&key, equals))?;
+
+                        NULL
+                    }
+                },
+                _ => NULL,
+            };
+
+            match nested_source {
+                Some(nested_source) => {
+                    self.destructure(target, &element, Some(&nested_source), equals)?
+                }
+                None => self.assign_value(target, element, equals)?,
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Assigns an already evaluated value to a variable, an array element, a property,
+    /// a static property or a nested destructuring pattern.
+    fn assign_value(
+        &mut self,
+        target: &Expression,
+        value: PhpValue,
+        equals: Span,
+    ) -> Result<(), PhpError> {
+        match target {
+            Expression::Variable(variable) => {
+                let name = self.get_variable_name(variable)?;
+
+                self.env.update_var(&name, value);
+            }
+            Expression::ArrayIndex(index) => {
+                self.assign_array_element(index, |_| Ok(value), equals)?;
+            }
+            Expression::PropertyFetch(fetch) => {
+                self.assign_property(fetch, |_| Ok(value))?;
+            }
+            Expression::StaticPropertyFetch(fetch) => {
+                *self.get_static_property(fetch)?.borrow_mut() = value;
+            }
+            Expression::ShortArray(_) | Expression::List(_) => {
+                self.destructure(target, &value, None, equals)?;
+            }
+            _ => {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Assignments can only happen to writable values".to_string(),
+                    line: equals.line,
+                })
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Writes `value` at the end of a path of indexes, None is the index of `[]`.
+    fn assign_element(
+        &mut self,
+        container: &mut PhpValue,
+        indexes: &[(Option<PhpValue>, Span)],
+        value: PhpValue,
+    ) -> Result<PhpValue, PhpError> {
+        let Some(((index, span), remaining_indexes)) = indexes.split_first() else {
+            *container = value.clone();
+
+            return Ok(value);
+        };
+
+        self.convert_to_array(container, *span)?;
+
+        match container {
+            PhpValue::Array(array) => {
+                let key = match index {
+                    Some(index) => self.get_array_key(index, *span)?,
+                    None => array.next_key().map_err(|error| PhpError {
+                        line: span.line,
+                        ..error
+                    })?,
+                };
+
+                // an element assigned by reference changes the value it shares
+                if let Some(reference) = array.get_reference(&key) {
+                    let mut current = std::mem::replace(&mut *reference.borrow_mut(), NULL);
+
+                    let result = self.assign_element(&mut current, remaining_indexes, value);
+
+                    *reference.borrow_mut() = current;
+
+                    return result;
+                }
+
+                if array.get(&key).is_none() {
+                    array.insert(key.clone(), NULL);
+                }
+
+                let element = array.get_mut(&key).unwrap();
+
+                self.assign_element(element, remaining_indexes, value)
+            }
+            PhpValue::String(string) => {
+                let Some(index) = index else {
+                    return Err(PhpError {
+                        level: ErrorLevel::EngineError("Error"),
+                        message: "[] operator not supported for strings".to_string(),
+                        line: span.line,
+                    });
+                };
+
+                if !remaining_indexes.is_empty() {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "Cannot use string offset as an array".to_string(),
+                        line: span.line,
+                    });
+                }
+
+                self.assign_string_offset(string, index, value, *span)
+            }
+            PhpValue::Object(object) => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Cannot use object of type {} as array",
+                    object.borrow().class.name
+                ),
+                line: span.line,
+            }),
+            _ => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "Cannot use a scalar value as an array".to_string(),
+                line: span.line,
+            }),
+        }
+    }
+
+    /// Turns null and false into an empty array before one of its elements is written,
+    /// false is deprecated.
+    pub(super) fn convert_to_array(
+        &mut self,
+        container: &mut PhpValue,
+        span: Span,
+    ) -> Result<(), PhpError> {
+        match container {
+            PhpValue::Null => *container = PhpValue::Array(PhpArray::new()),
+            PhpValue::Bool(false) => {
+                self.raise_error(PhpError {
+                    level: ErrorLevel::Deprecated,
+                    message: "Automatic conversion of false to array is deprecated".to_string(),
+                    line: span.line,
+                })?;
+
+                *container = PhpValue::Array(PhpArray::new());
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// Replaces a character of a string, like `$string[0] = 'a'`, it returns the new character.
+    ///
+    /// Only the first byte of the value is used, a string shorter than the offset is
+    /// padded with spaces.
+    fn assign_string_offset(
+        &mut self,
+        string: &mut ByteString,
+        index: &PhpValue,
+        value: PhpValue,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let offset = match index {
+            PhpValue::Int(i) => Some(*i),
+            PhpValue::String(s) => str::from_utf8(s).ok().and_then(|s| s.parse().ok()),
+            _ => None,
+        };
+
+        let Some(offset) = offset else {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Cannot access offset of type {} on string",
+                    index.type_name().error_name()
+                ),
+                line: span.line,
+            });
+        };
+
+        // negative offsets are counted from the end of the string
+        let position = if offset < 0 {
+            string.len() as i64 + offset
+        } else {
+            offset
+        };
+
+        if position < 0 {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: format!("Illegal string offset {}", offset),
+                line: span.line,
+            })?;
+
+            return Ok(NULL);
+        }
+
+        let replacement = match value {
+            PhpValue::String(value) => value.bytes,
+            value => self.value_to_output_string(value, span)?.into_bytes(),
+        };
+
+        let Some(character) = replacement.first() else {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "Cannot assign an empty string to a string offset".to_string(),
+                line: span.line,
+            });
+        };
+
+        if replacement.len() > 1 {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: "Only the first byte will be assigned to the string offset".to_string(),
+                line: span.line,
+            })?;
+        }
+
+        let position = position as usize;
+
+        if position >= string.len() {
+            string.bytes.resize(position + 1, b' ');
+        }
+
+        string.bytes[position] = *character;
+
+        Ok(PhpValue::String(ByteString::new(vec![*character])))
+    }
+
+    pub(super) fn change_var_value(
+        &mut self,
+        left_expr: &Box<Expression>,
+        span: &Span,
+        right_expr: &Box<Expression>,
+        operation: &str,
+    ) -> Result<PhpValue, PhpError> {
+        let left = left_expr;
+        let right = right_expr;
+
+        if let Expression::StaticPropertyFetch(fetch) = &**left {
+            let property = self.get_static_property(fetch)?;
+
+            let right_value = self.eval_expression(right)?;
+
+            let current_value = property.borrow().clone();
+
+            let new_value = self.compound_value(current_value, operation, right_value, span)?;
+
+            *property.borrow_mut() = new_value.clone();
+
+            return Ok(new_value);
+        }
+
+        let right_value = self.eval_expression(&right)?;
+
+        let Expression::Variable(ref var) = **left else {
+            return Err(PhpError {
+                level: ErrorLevel::ParseError,
+                message: "Only variables can be assigned".to_string(),
+                line: span.line,
+            });
+        };
+
+        let var_name = self.get_variable_name(&var)?;
+
+        let current_var_value = self.env.get_var(&var_name);
+
+        if current_var_value.is_none() {
+            let error = format!("Undefined variable {}", display_variable_name(&var_name));
+
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: error,
+                line: span.line,
+            });
+        }
+
+        let current_var_value = current_var_value.unwrap();
+
+        let new_value = self.compound_value(current_var_value, operation, right_value, span)?;
+
+        self.env.update_var(&var_name, new_value.clone());
+
+        Ok(new_value)
+    }
+
+    /// The new value of a compound assignment like `$a += 2`, from the current one.
+    fn compound_value(
+        &mut self,
+        current_value: PhpValue,
+        operation: &str,
+        right_value: PhpValue,
+        span: &Span,
+    ) -> Result<PhpValue, PhpError> {
+        match operation {
+            "+" | "-" | "*" | "/" | "%" | "**" | "&" | "|" | "^" | "<<" | ">>" => {
+                self.eval_arithmetic(current_value, operation, right_value, span)
+            }
+            "." => {
+                let left_string = self.value_to_output_string(current_value, *span)?;
+                let right_string = self.value_to_output_string(right_value, *span)?;
+
+                Ok(PhpValue::String((left_string + &right_string).into()))
+            }
+            "??" => {
+                if current_value.is_null() {
+                    Ok(right_value)
+                } else {
+                    Ok(current_value)
+                }
+            }
+            _ => Ok(NULL),
+        }
+    }
+
+    /// Applies `++` or `--` to a variable or a property, it returns its old and its new value.
+    pub(super) fn increment_or_decrement(
+        &mut self,
+        target: &Expression,
+        span: Span,
+        increment: bool,
+    ) -> Result<(PhpValue, PhpValue), PhpError> {
+        match target {
+            Expression::Variable(variable) => {
+                let old_value = self.get_var(variable)?;
+
+                let new_value =
+                    self.increment_or_decrement_value(old_value.clone(), span, increment)?;
+
+                let var_name = self.get_variable_name(variable)?;
+
+                self.env.update_var(&var_name, new_value.clone());
+
+                Ok((old_value, new_value))
+            }
+            Expression::PropertyFetch(fetch) => {
+                let object = self.eval_expression(&fetch.target)?;
+
+                let property_name = self.get_member_name(&fetch.property)?;
+
+                let PhpValue::Object(object) = object else {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: format!(
+                            "Attempt to increment/decrement property \"{}\" on {}",
+                            display_name(&property_name),
+                            object.type_name().error_name()
+                        ),
+                        line: fetch.arrow.line,
+                    });
+                };
+
+                self.check_property_write(&object.borrow(), &property_name, fetch.arrow)?;
+
+                let old_value = object
+                    .borrow()
+                    .get_property(&property_name)
+                    .cloned()
+                    .unwrap_or(NULL);
+
+                let new_value =
+                    self.increment_or_decrement_value(old_value.clone(), span, increment)?;
+
+                object
+                    .borrow_mut()
+                    .set_property(&property_name, new_value.clone());
+
+                Ok((old_value, new_value))
+            }
+            Expression::StaticPropertyFetch(fetch) => {
+                let property = self.get_static_property(fetch)?;
+
+                let old_value = property.borrow().clone();
+
+                let new_value =
+                    self.increment_or_decrement_value(old_value.clone(), span, increment)?;
+
+                *property.borrow_mut() = new_value.clone();
+
+                Ok((old_value, new_value))
+            }
+            _ => Err(PhpError {
+                level: ErrorLevel::ParseError,
+                message: format!(
+                    "Only variables can be {}",
+                    if increment {
+                        "incremented"
+                    } else {
+                        "decremented"
+                    }
+                ),
+                line: span.line,
+            }),
+        }
+    }
+
+    /// The result of `++` or `--` on a value, with the warnings and deprecations of PHP 8.3.
+    pub(super) fn increment_or_decrement_value(
+        &mut self,
+        value: PhpValue,
+        span: Span,
+        increment: bool,
+    ) -> Result<PhpValue, PhpError> {
+        let (operation, step) = if increment {
+            ("Increment", 1)
+        } else {
+            ("Decrement", -1)
+        };
+
+        let deprecation = |message: &str| PhpError {
+            level: ErrorLevel::Deprecated,
+            message: message.to_string(),
+            line: span.line,
+        };
+
+        match value {
+            PhpValue::Int(i) => Ok(i
+                .checked_add(step)
+                .map_or(PhpValue::Float(i as f64 + step as f64), PhpValue::Int)),
+            PhpValue::Float(f) => Ok(PhpValue::Float(f + step as f64)),
+            PhpValue::Null if increment => Ok(PhpValue::Int(1)),
+            PhpValue::Null | PhpValue::Bool(_) => {
+                self.raise_error(PhpError {
+                    level: ErrorLevel::Warning,
+                    message: format!(
+                        "{} on type {} has no effect, this will change in the next major version of PHP",
+                        operation,
+                        value.type_name().error_name()
+                    ),
+                    line: span.line,
+                })?;
+
+                Ok(value)
+            }
+            PhpValue::String(string) => {
+                if let Some(number) = parse_numeric_string(&string) {
+                    return self.increment_or_decrement_value(number, span, increment);
+                }
+
+                if string.is_empty() {
+                    if increment {
+                        return Ok(PhpValue::String("1".into()));
+                    }
+
+                    self.raise_error(deprecation(
+                        "Decrement on empty string is deprecated as non-numeric",
+                    ))?;
+
+                    return Ok(PhpValue::Int(-1));
+                }
+
+                if !increment {
+                    self.raise_error(deprecation(
+                        "Decrement on non-numeric string has no effect and is deprecated",
+                    ))?;
+
+                    return Ok(PhpValue::String(string));
+                }
+
+                if !string.bytes.iter().all(u8::is_ascii_alphanumeric) {
+                    self.raise_error(deprecation(
+                        "Increment on non-alphanumeric string is deprecated",
+                    ))?;
+                }
+
+                Ok(PhpValue::String(increment_string(&string).into()))
+            }
+            PhpValue::Object(object) => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Cannot {} {}",
+                    operation.to_lowercase(),
+                    display_name(&object.borrow().class.name)
+                ),
+                line: span.line,
+            }),
+            value => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Cannot {} {}",
+                    operation.to_lowercase(),
+                    value.type_name().error_name()
+                ),
+                line: span.line,
+            }),
+        }
+    }
+}
+
+/// Whether a destructuring pattern takes one of its entries by reference, like
+/// `[$a, [&$b]]`.
+fn has_reference_entries(pattern: &Expression) -> bool {
+    let Expression::ShortArray(array) = pattern else {
+        return false;
+    };
+
+    array.items.inner.iter().any(|item| match item {
+        ArrayItem::ReferencedValue { .. } | ArrayItem::ReferencedKeyValue { .. } => true,
+        ArrayItem::Value { value } | ArrayItem::KeyValue { value, .. } => {
+            has_reference_entries(value)
+        }
+        ArrayItem::Skipped | ArrayItem::SpreadValue { .. } => false,
+    })
+}
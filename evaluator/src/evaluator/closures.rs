@@ -0,0 +1,352 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use php_parser_rs::parser::ast::functions::{ArrowFunctionExpression, ClosureExpression};
+use php_parser_rs::parser::ast::identifiers::Identifier;
+use php_parser_rs::parser::ast::{
+    Ending, Expression, FunctionClosureCreationExpression, MethodClosureCreationExpression,
+    ReturnStatement, Statement, StaticMethodClosureCreationExpression,
+};
+
+use crate::helpers::{display_name, display_variable_name, variable_key};
+use crate::native::get_native_function;
+use crate::php_class::{PhpClass, PhpMethod};
+use crate::php_value::{
+    CapturedVariable, ClosureScope, ErrorLevel, PhpCallable, PhpError, PhpObject, PhpValue,
+};
+
+use super::declarations::get_callable_arguments;
+use super::types::check_return_statements;
+use super::{Evaluator, NULL};
+
+/// The name of the functions that have no name, like PHP gives them in errors.
+const CLOSURE_NAME: &str = "{closure}";
+
+impl<'w> Evaluator<'w> {
+    /// Evaluates `function (...) use (...) { ... }`.
+    ///
+    /// The variables of `use` are read now: the ones used by value are copied, the ones
+    /// used by reference are shared with the current scope, and created if needed.
+    pub(super) fn eval_closure(
+        &mut self,
+        closure: &ClosureExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let mut parameters = get_callable_arguments(closure.parameters.clone());
+
+        self.check_parameters(&mut parameters)?;
+
+        check_return_statements(
+            closure
+                .return_type
+                .as_ref()
+                .map(|return_type| &return_type.data_type),
+            &closure.body.statements,
+        )?;
+
+        let mut scope = self.closure_scope(closure.r#static.is_some());
+
+        for used in closure.uses.iter().flat_map(|uses| uses.variables.iter()) {
+            let name = variable_key(&used.variable);
+            let line = used.variable.span.line;
+
+            let error = |message: String| PhpError {
+                level: ErrorLevel::Fatal,
+                message,
+                line,
+            };
+
+            if name == b"this" {
+                return Err(error("Cannot use $this as lexical variable".to_string()));
+            }
+
+            if parameters
+                .iter()
+                .any(|parameter| variable_key(&parameter.name) == name)
+            {
+                return Err(error(format!(
+                    "Cannot use lexical variable {} as a parameter name",
+                    display_variable_name(name)
+                )));
+            }
+
+            let value = match used.ampersand {
+                Some(_) => {
+                    if !self.env.var_exists(name) {
+                        self.env.set_var(name, &NULL);
+                    }
+
+                    self.env.get_var_with_rc(name).unwrap()
+                }
+                None => {
+                    let value = match self.env.get_var(name) {
+                        Some(value) => value,
+                        None => {
+                            self.raise_error(PhpError {
+                                level: ErrorLevel::Warning,
+                                message: format!(
+                                    "Undefined variable {}",
+                                    display_variable_name(name)
+                                ),
+                                line,
+                            })?;
+
+                            NULL
+                        }
+                    };
+
+                    Rc::new(RefCell::new(value))
+                }
+            };
+
+            scope.variables.push(CapturedVariable {
+                name: name.to_vec(),
+                value,
+                by_reference: used.ampersand.is_some(),
+            });
+        }
+
+        Ok(PhpValue::Callable(PhpCallable {
+            attributes: closure.attributes.clone(),
+            span: closure.function,
+            return_by_reference: closure.ampersand.is_some(),
+            name: CLOSURE_NAME.into(),
+            parameters,
+            return_type: closure.return_type.clone(),
+            body: closure.body.statements.clone(),
+            file: self.current_file.clone(),
+            closure: Some(self.new_closure_scope(scope)),
+        }))
+    }
+
+    /// Evaluates `fn (...) => expression`, it captures every variable of the current
+    /// scope by value, so it reads the outer variables but cannot change them.
+    pub(super) fn eval_arrow_function(
+        &mut self,
+        function: &ArrowFunctionExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let mut parameters = get_callable_arguments(function.parameters.clone());
+
+        self.check_parameters(&mut parameters)?;
+
+        let body = vec![Statement::Return(ReturnStatement {
+            r#return: function.double_arrow,
+            value: Some(*function.body.clone()),
+            ending: Ending::Semicolon(function.double_arrow),
+        })];
+
+        let mut scope = self.closure_scope(function.r#static.is_some());
+
+        scope.variables = self
+            .env
+            .get_vars()
+            .filter(|(name, _)| name.as_slice() != b"this")
+            .map(|(name, value)| CapturedVariable {
+                name: name.clone(),
+                value: Rc::new(RefCell::new(value)),
+                by_reference: false,
+            })
+            .collect();
+
+        Ok(PhpValue::Callable(PhpCallable {
+            attributes: function.attributes.clone(),
+            span: function.r#fn,
+            return_by_reference: function.ampersand.is_some(),
+            name: CLOSURE_NAME.into(),
+            parameters,
+            return_type: function.return_type.clone(),
+            body,
+            file: self.current_file.clone(),
+            closure: Some(self.new_closure_scope(scope)),
+        }))
+    }
+
+    /// Evaluates `name(...)`, which gives the function as a closure.
+    pub(super) fn eval_function_closure_creation(
+        &mut self,
+        creation: &FunctionClosureCreationExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let span = creation.placeholder.left_parenthesis;
+
+        let name = match creation.target.as_ref() {
+            Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => {
+                identifier.value.bytes.clone()
+            }
+            target => match self.eval_expression(target)? {
+                PhpValue::Callable(function) => return Ok(PhpValue::Callable(function)),
+                target => self.get_function_name(target, span)?,
+            },
+        };
+
+        // every closure of a function is a new object
+        if let Some(PhpValue::Callable(function)) = self.env.get_identifier(&name) {
+            return Ok(PhpValue::Callable(PhpCallable {
+                closure: Some(self.new_closure_scope(ClosureScope::default())),
+                ..function
+            }));
+        }
+
+        let Some(native_function) = get_native_function(&name) else {
+            return Err(self.undefined_function(&name, span));
+        };
+
+        let scope = self.new_closure_scope(ClosureScope {
+            native_function: Some(native_function),
+            ..ClosureScope::default()
+        });
+
+        Ok(PhpValue::Callable(PhpCallable {
+            attributes: vec![],
+            span,
+            return_by_reference: false,
+            name: native_function.name.into(),
+            parameters: vec![],
+            return_type: None,
+            body: vec![],
+            file: self.current_file.clone(),
+            closure: Some(scope),
+        }))
+    }
+
+    /// Evaluates `$object->method(...)`, which gives the method as a closure bound to
+    /// the object.
+    pub(super) fn eval_method_closure_creation(
+        &mut self,
+        creation: &MethodClosureCreationExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let span = creation.placeholder.left_parenthesis;
+
+        let target = self.eval_expression(&creation.target)?;
+
+        let method_name = self.get_member_name(&creation.method)?;
+
+        let PhpValue::Object(object) = target else {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Call to a member function {}() on {}",
+                    display_name(&method_name),
+                    target.type_name().error_name()
+                ),
+                line: span.line,
+            });
+        };
+
+        let class = Rc::clone(&object.borrow().class);
+
+        let Some(resolved) = class.resolve_method(&method_name) else {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Call to undefined method {}::{}()",
+                    class.name,
+                    display_name(&method_name)
+                ),
+                line: span.line,
+            });
+        };
+
+        self.check_method_visibility(&resolved, span)?;
+
+        let method = resolved.method.clone();
+
+        let this = (!resolved.is_static).then_some(object);
+
+        Ok(self.method_closure(method, this, class))
+    }
+
+    /// Evaluates `Class::method(...)`, a method that is not static is bound to `$this`.
+    pub(super) fn eval_static_method_closure_creation(
+        &mut self,
+        creation: &StaticMethodClosureCreationExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let span = creation.placeholder.left_parenthesis;
+
+        let class = self.resolve_class(&creation.target, creation.double_colon)?;
+
+        let Identifier::SimpleIdentifier(method_name) = &creation.method else {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "Dynamic static method names are not supported".to_string(),
+                line: span.line,
+            });
+        };
+
+        let Some(resolved) = class.resolve_method(&method_name.value) else {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Call to undefined method {}::{}()",
+                    class.name,
+                    display_name(&method_name.value)
+                ),
+                line: span.line,
+            });
+        };
+
+        self.check_method_visibility(&resolved, span)?;
+
+        let method = resolved.method.clone();
+
+        if resolved.is_static {
+            let static_class = match *creation.target {
+                Expression::Self_ | Expression::Parent => self.static_class.clone(),
+                _ => None,
+            };
+
+            return Ok(self.method_closure(method, None, static_class.unwrap_or(class)));
+        }
+
+        let this = match self.env.get_var(b"this") {
+            Some(PhpValue::Object(object)) if object.borrow().is_instance_of(&class.name) => object,
+            _ => {
+                return Err(PhpError {
+                    level: ErrorLevel::EngineError("Error"),
+                    message: format!(
+                        "Non-static method {}::{}() cannot be called statically",
+                        method.class_name, method.function.name
+                    ),
+                    line: span.line,
+                })
+            }
+        };
+
+        let static_class = Rc::clone(&this.borrow().class);
+
+        Ok(self.method_closure(method, Some(this), static_class))
+    }
+
+    /// A closure that calls the method with the class scope of the class that declares it.
+    fn method_closure(
+        &mut self,
+        method: PhpMethod,
+        this: Option<Rc<RefCell<PhpObject>>>,
+        static_class: Rc<PhpClass>,
+    ) -> PhpValue {
+        let scope = self.new_closure_scope(ClosureScope {
+            this,
+            class: self.env.get_class(&method.class_name.bytes),
+            static_class: Some(static_class),
+            ..ClosureScope::default()
+        });
+
+        PhpValue::Callable(PhpCallable {
+            closure: Some(scope),
+            ..method.function
+        })
+    }
+
+    /// The object and the classes of the current method, kept by a closure created in it.
+    fn closure_scope(&self, is_static: bool) -> ClosureScope {
+        let this = match self.env.get_var(b"this") {
+            Some(PhpValue::Object(object)) if !is_static => Some(object),
+            _ => None,
+        };
+
+        ClosureScope {
+            this,
+            class: self.current_class.clone(),
+            static_class: self.static_class.clone(),
+            ..ClosureScope::default()
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::path::Path;
+
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::constant::ConstantStatement;
+use php_parser_rs::parser::ast::identifiers::{Identifier, SimpleIdentifier};
+use php_parser_rs::parser::ast::{ConstantFetchExpression, Expression};
+
+use crate::helpers::display_name;
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::includes::included_file_key;
+use super::{get_keyword_literal, Evaluator, NULL};
+
+impl<'w> Evaluator<'w> {
+    /// Evaluates `const NAME = value, ...;`, the constants are defined in order, so an
+    /// entry can use the ones before it.
+    pub(super) fn eval_constant_statement(
+        &mut self,
+        statement: ConstantStatement,
+    ) -> Result<PhpValue, PhpError> {
+        for entry in statement.entries {
+            let value = self.eval_expression(&entry.value)?;
+
+            self.define_constant(&entry.name.value, value, entry.name.span)?;
+        }
+
+        Ok(NULL)
+    }
+
+    /// Defines a global constant, it returns false with a warning if the constant
+    /// already exists. Constant names are case sensitive.
+    ///
+    /// The constants are kept with the functions, so a closure cannot be the value
+    /// of one.
+    pub(crate) fn define_constant(
+        &mut self,
+        name: &[u8],
+        value: PhpValue,
+        span: Span,
+    ) -> Result<bool, PhpError> {
+        let name = name.strip_prefix(b"\\").unwrap_or(name);
+
+        if self.get_constant(name).is_some() {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: format!("Constant {} already defined", display_name(name)),
+                line: span.line,
+            })?;
+
+            return Ok(false);
+        }
+
+        match self.set_identifier(name, value, span) {
+            Some(error) => Err(error),
+            None => Ok(true),
+        }
+    }
+
+    /// The value of a global constant, None if it is not defined.
+    ///
+    /// `true`, `false` and `null` are constants too, written in any case.
+    pub(crate) fn get_constant(&self, name: &[u8]) -> Option<PhpValue> {
+        if let Some(value) = get_keyword_literal(name) {
+            return Some(value);
+        }
+
+        let name = name.strip_prefix(b"\\").unwrap_or(name);
+
+        match self.env.get_identifier(name)? {
+            PhpValue::Callable(_) => None,
+            value => Some(value),
+        }
+    }
+
+    /// The value of `__FILE__`, the canonical path of the current file like the one
+    /// include_once looks for, the path as it was given when the file does not exist.
+    pub(super) fn get_file_constant(&self) -> String {
+        included_file_key(&self.current_file)
+    }
+
+    /// The value of `__DIR__`, the directory of `__FILE__`, "." for a file given without one.
+    pub(super) fn get_directory_constant(&self) -> String {
+        let file = self.get_file_constant();
+
+        match Path::new(&file).parent() {
+            Some(directory) if !directory.as_os_str().is_empty() => {
+                directory.to_string_lossy().to_string()
+            }
+            _ => ".".to_string(),
+        }
+    }
+
+    /// The error of a constant that is not defined.
+    pub(crate) fn undefined_constant(&self, name: &[u8], span: Span) -> PhpError {
+        PhpError {
+            level: ErrorLevel::EngineError("Error"),
+            message: format!("Undefined constant \"{}\"", display_name(name)),
+            line: span.line,
+        }
+    }
+
+    /// The value of a class constant or an enum case given by its names, like
+    /// constant("Suit::Hearts"). `self`, `static` and `parent` are the classes of the
+    /// current scope, like in the code.
+    pub(crate) fn get_class_constant(
+        &mut self,
+        class: &[u8],
+        constant: &[u8],
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let identifier = |name: &[u8]| SimpleIdentifier {
+            span,
+            value: name.into(),
+        };
+
+        let target = match class.to_ascii_lowercase().as_slice() {
+            b"self" => Expression::Self_,
+            b"static" => Expression::Static,
+            b"parent" => Expression::Parent,
+            _ => Expression::Identifier(Identifier::SimpleIdentifier(identifier(class))),
+        };
+
+        self.eval_constant_fetch(&ConstantFetchExpression {
+            target: Box::new(target),
+            double_colon: span,
+            constant: Identifier::SimpleIdentifier(identifier(constant)),
+        })
+    }
+}
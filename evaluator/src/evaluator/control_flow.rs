@@ -0,0 +1,304 @@
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::arguments::SinglePositionalArgument;
+use php_parser_rs::parser::ast::control_flow::{IfStatement, IfStatementBody};
+use php_parser_rs::parser::ast::loops::{BreakStatement, ContinueStatement, Level};
+use php_parser_rs::parser::ast::{MatchExpression, ReturnStatement, Statement, SwitchStatement};
+
+use crate::helpers::display_string;
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::{Evaluator, NULL};
+
+/// A `break` or a `continue` with the number of switches it still has to leave.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum LoopJump {
+    Break(usize),
+    Continue(usize),
+}
+
+impl<'w> Evaluator<'w> {
+    pub(super) fn eval_return(&mut self, statement: ReturnStatement) -> Result<PhpValue, PhpError> {
+        let value = match statement.value {
+            Some(value) => self.eval_expression(&value)?,
+            None => NULL,
+        };
+
+        self.return_value = Some(value);
+
+        Ok(NULL)
+    }
+
+    /// Evaluates `exit` and `die`, which stop the whole program.
+    ///
+    /// An int argument is the exit status of the program, any other argument is output
+    /// like in PHP before 8.4.
+    pub(super) fn eval_exit(
+        &mut self,
+        argument: Option<&SinglePositionalArgument>,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        if let Some(argument) = argument {
+            match self.eval_expression(&argument.argument.value)? {
+                PhpValue::Int(code) => self.exit_code = code as i32,
+                value => {
+                    let text = self.value_to_output_string(value, span)?;
+
+                    self.write_output(&text, span.line)?;
+                }
+            }
+        }
+
+        self.die = true;
+
+        Ok(NULL)
+    }
+
+    /// Evaluates a list of statements until one of them fails, returns or the program dies.
+    pub(super) fn eval_statements(
+        &mut self,
+        statements: Vec<Statement>,
+    ) -> Result<PhpValue, PhpError> {
+        for statement in statements {
+            self.eval_statement(statement)?;
+
+            if self.die || self.return_value.is_some() || self.loop_jump.is_some() {
+                break;
+            }
+        }
+
+        Ok(NULL)
+    }
+
+    pub(super) fn eval_if_statement(
+        &mut self,
+        statement: IfStatement,
+    ) -> Result<PhpValue, PhpError> {
+        let condition = self.eval_expression(&statement.condition)?.is_true();
+
+        match statement.body {
+            IfStatementBody::Statement {
+                statement: body,
+                elseifs,
+                r#else,
+            } => {
+                if condition {
+                    return self.eval_statement(*body);
+                }
+
+                for elseif in elseifs {
+                    if self.eval_expression(&elseif.condition)?.is_true() {
+                        return self.eval_statement(*elseif.statement);
+                    }
+                }
+
+                if let Some(r#else) = r#else {
+                    return self.eval_statement(*r#else.statement);
+                }
+
+                Ok(NULL)
+            }
+            IfStatementBody::Block {
+                statements,
+                elseifs,
+                r#else,
+                ..
+            } => {
+                if condition {
+                    return self.eval_statements(statements);
+                }
+
+                for elseif in elseifs {
+                    if self.eval_expression(&elseif.condition)?.is_true() {
+                        return self.eval_statements(elseif.statements);
+                    }
+                }
+
+                if let Some(r#else) = r#else {
+                    return self.eval_statements(r#else.statements);
+                }
+
+                Ok(NULL)
+            }
+        }
+    }
+
+    /// Evaluates `match (subject) { conditions => expression, default => expression }`.
+    ///
+    /// The conditions are compared with the subject by `===` in order, and only the
+    /// expression of the first arm that matches is evaluated. Without a matching arm
+    /// nor a default one, the program stops with the error of the UnhandledMatchError
+    /// that PHP throws.
+    pub(super) fn eval_match(&mut self, r#match: &MatchExpression) -> Result<PhpValue, PhpError> {
+        let subject = self.eval_expression(&r#match.condition)?;
+
+        for arm in &r#match.arms {
+            for condition in &arm.conditions {
+                let value = self.eval_expression(condition)?;
+
+                if subject.is_identical(&value).unwrap_or(false) {
+                    return self.eval_expression(&arm.body);
+                }
+            }
+        }
+
+        if let Some(default) = &r#match.default {
+            return self.eval_expression(&default.body);
+        }
+
+        let subject = match subject {
+            PhpValue::Null => "NULL".to_string(),
+            PhpValue::Bool(b) => b.to_string(),
+            PhpValue::String(string) => display_string(&string),
+            value @ (PhpValue::Int(_) | PhpValue::Float(_)) => {
+                value.to_string().unwrap_or_default()
+            }
+            value => format!("of type {}", value.type_name().error_name()),
+        };
+
+        Err(PhpError {
+            level: ErrorLevel::EngineError("UnhandledMatchError"),
+            message: format!("Unhandled match case {}", subject),
+            line: r#match.keyword.line,
+        })
+    }
+
+    /// Evaluates `switch (subject) { case value: ... default: ... }`.
+    ///
+    /// The cases are compared with the subject by `==` in order, the default case is only
+    /// taken when no case matches, wherever it is. The statements are executed from the
+    /// taken case to the end of the switch, through the next cases, until a `break`.
+    pub(super) fn eval_switch_statement(
+        &mut self,
+        statement: SwitchStatement,
+    ) -> Result<PhpValue, PhpError> {
+        let subject = self.eval_expression(&statement.condition)?;
+
+        let mut taken = None;
+
+        for (position, case) in statement.cases.iter().enumerate() {
+            let Some(condition) = &case.condition else {
+                continue;
+            };
+
+            let value = self.eval_expression(condition)?;
+
+            if self.values_loosely_equal(&subject, &value, &statement.switch)? {
+                taken = Some(position);
+
+                break;
+            }
+        }
+
+        let taken = taken.or_else(|| {
+            statement
+                .cases
+                .iter()
+                .position(|case| case.condition.is_none())
+        });
+
+        let Some(taken) = taken else {
+            return Ok(NULL);
+        };
+
+        self.loop_depth += 1;
+
+        let mut result = Ok(NULL);
+
+        for case in statement.cases.into_iter().skip(taken) {
+            result = self.eval_statements(case.body);
+
+            if result.is_err()
+                || self.die
+                || self.return_value.is_some()
+                || self.loop_jump.is_some()
+            {
+                break;
+            }
+        }
+
+        self.loop_depth -= 1;
+
+        // the switch is the first level that a break or a continue leaves
+        self.loop_jump = match self.loop_jump.take() {
+            Some(LoopJump::Break(levels)) if levels > 1 => Some(LoopJump::Break(levels - 1)),
+            Some(LoopJump::Continue(levels)) if levels > 1 => Some(LoopJump::Continue(levels - 1)),
+            _ => None,
+        };
+
+        result
+    }
+
+    /// Evaluates `break`, `break 2` also leaves the switch around the current one.
+    pub(super) fn eval_break(&mut self, statement: BreakStatement) -> Result<PhpValue, PhpError> {
+        let levels = get_levels(statement.level.as_ref());
+
+        self.jump(LoopJump::Break(levels), statement.r#break)
+    }
+
+    /// A `continue` in a switch does the same as a `break`, with a warning like PHP.
+    pub(super) fn eval_continue(
+        &mut self,
+        statement: ContinueStatement,
+    ) -> Result<PhpValue, PhpError> {
+        let levels = get_levels(statement.level.as_ref());
+
+        self.jump(LoopJump::Continue(levels), statement.r#continue)
+    }
+
+    /// Starts a `break` or a `continue`, the switches that it leaves stop.
+    fn jump(&mut self, jump: LoopJump, span: Span) -> Result<PhpValue, PhpError> {
+        let (keyword, levels) = match jump {
+            LoopJump::Break(levels) => ("break", levels),
+            LoopJump::Continue(levels) => ("continue", levels),
+        };
+
+        let error = |message: String| PhpError {
+            level: ErrorLevel::Fatal,
+            message,
+            line: span.line,
+        };
+
+        if levels == 0 {
+            return Err(error(format!(
+                "'{}' operator accepts only positive integers",
+                keyword
+            )));
+        }
+
+        if self.loop_depth == 0 {
+            return Err(error(format!(
+                "'{}' not in the 'loop' or 'switch' context",
+                keyword
+            )));
+        }
+
+        if levels > self.loop_depth {
+            return Err(error(format!("Cannot '{}' {} levels", keyword, levels)));
+        }
+
+        // there are no loops, so a continue always targets a switch
+        if let LoopJump::Continue(_) = jump {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: "\"continue\" targeting switch is equivalent to \"break\"".to_string(),
+                line: span.line,
+            })?;
+        }
+
+        self.loop_jump = Some(jump);
+
+        Ok(NULL)
+    }
+}
+
+/// The number of levels of `break 2`, 1 without a number.
+fn get_levels(level: Option<&Level>) -> usize {
+    match level {
+        None => 1,
+        Some(Level::Literal(literal)) => std::str::from_utf8(&literal.value)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(0),
+        Some(Level::Parenthesized { level, .. }) => get_levels(Some(level)),
+    }
+}
@@ -0,0 +1,1248 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use std::str;
+
+use php_parser_rs::lexer::byte_string::ByteString;
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::classes::{ClassMember, ClassStatement};
+use php_parser_rs::parser::ast::comments::CommentGroup;
+use php_parser_rs::parser::ast::constant::{ClassishConstant, ConstantEntry};
+use php_parser_rs::parser::ast::enums::{
+    BackedEnumMember, BackedEnumStatement, BackedEnumType, UnitEnumMember, UnitEnumStatement,
+};
+use php_parser_rs::parser::ast::functions::{
+    ConcreteMethod, FunctionParameterList, FunctionStatement,
+};
+use php_parser_rs::parser::ast::identifiers::SimpleIdentifier;
+use php_parser_rs::parser::ast::interfaces::{InterfaceMember, InterfaceStatement};
+use php_parser_rs::parser::ast::modifiers::{
+    ClassModifier, ClassModifierGroup, PromotedPropertyModifier, PromotedPropertyModifierGroup,
+    PropertyModifier, PropertyModifierGroup, Visibility,
+};
+use php_parser_rs::parser::ast::Expression;
+
+use crate::helpers::{contains_new_expression, describe_parameters, display_name, variable_key};
+use crate::php_class::{ClassProperty, EnumCases, PhpClass, PhpInterface, PhpMethod};
+use crate::php_value::{CallableArgument, ErrorLevel, PhpCallable, PhpError, PhpValue, TypeName};
+
+use super::types::check_return_statements;
+use super::{Evaluator, NULL};
+
+impl<'w> Evaluator<'w> {
+    pub(super) fn declare_function(
+        &mut self,
+        function: FunctionStatement,
+    ) -> Result<PhpValue, PhpError> {
+        let mut callable_args = get_callable_arguments(function.parameters);
+
+        self.check_parameters(&mut callable_args)?;
+
+        check_return_statements(
+            function
+                .return_type
+                .as_ref()
+                .map(|return_type| &return_type.data_type),
+            &function.body.statements,
+        )?;
+
+        let php_callable = PhpCallable {
+            attributes: function.attributes,
+            span: function.function,
+            return_by_reference: function.ampersand.is_some(),
+            name: function.name.value.clone(),
+            parameters: callable_args,
+            return_type: function.return_type,
+            body: function.body.statements,
+            file: self.current_file.clone(),
+            closure: None,
+        };
+
+        let set_identifier = self.set_identifier(
+            &function.name.value.bytes,
+            PhpValue::Callable(php_callable),
+            function.function,
+        );
+
+        if set_identifier.is_some() {
+            return Err(set_identifier.unwrap());
+        }
+
+        Ok(NULL)
+    }
+
+    pub(super) fn declare_class(&mut self, class: ClassStatement) -> Result<PhpValue, PhpError> {
+        check_class_name("class", &class.name.value, class.name.span)?;
+
+        // before the parent, a redeclared class could otherwise look like a cycle
+        let name_in_use = self.env.get_class(&class.name.value.bytes).is_some()
+            || self.env.get_interface(&class.name.value).is_some();
+
+        if name_in_use {
+            return Err(redeclared_class(&class.name.value, class.class));
+        }
+
+        let parent = match &class.extends {
+            Some(extends) => {
+                check_self_inheritance(
+                    &class.name.value,
+                    &extends.parent.value,
+                    extends.parent.span,
+                )?;
+
+                let parent = self.env.get_class(&extends.parent.value.bytes);
+
+                let Some(parent) = parent else {
+                    let (level, message) = match self.env.get_interface(&extends.parent.value) {
+                        Some(interface) => (
+                            ErrorLevel::Fatal,
+                            format!(
+                                "Class {} cannot extend interface {}",
+                                class.name.value, interface.name
+                            ),
+                        ),
+                        None => (
+                            ErrorLevel::EngineError("Error"),
+                            format!("Class \"{}\" not found", extends.parent.value),
+                        ),
+                    };
+
+                    return Err(PhpError {
+                        level,
+                        message,
+                        line: extends.parent.span.line,
+                    });
+                };
+
+                Some(parent)
+            }
+            None => None,
+        };
+
+        let interfaces = match &class.implements {
+            Some(implements) => self.resolve_interfaces(&class.name.value, implements.iter())?,
+            None => vec![],
+        };
+
+        // only the enums are UnitEnum
+        if let Some(interface) = interfaces
+            .iter()
+            .find(|interface| interface.extends(b"UnitEnum"))
+        {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Non-enum class {} cannot implement interface {}",
+                    class.name.value, interface.name
+                ),
+                line: class.class.line,
+            });
+        }
+
+        let mut php_class = PhpClass {
+            name: class.name.value.clone(),
+            modifiers: class.modifiers,
+            parent,
+            interfaces,
+            doc_comment: get_doc_comment(&class.comments),
+            constants: vec![],
+            properties: vec![],
+            methods: HashMap::new(),
+            static_properties: HashMap::new(),
+            static_property_declarations: vec![],
+            enum_cases: None,
+        };
+
+        for member in class.body.members {
+            match member {
+                ClassMember::Constant(constant) => {
+                    self.declare_class_constant(&mut php_class, constant)?;
+                }
+                ClassMember::Property(property) => {
+                    let doc_comment = get_doc_comment(&property.comments);
+
+                    // static properties are not stored in the objects
+                    if property.modifiers.has_static() {
+                        for entry in property.entries {
+                            let class_property =
+                                self.with_class_scope(&php_class, |evaluator| {
+                                    evaluator.get_class_property(
+                                        entry,
+                                        property.modifiers.clone(),
+                                        property.r#type.clone(),
+                                        doc_comment.clone(),
+                                    )
+                                })?;
+
+                            if property.modifiers.has_readonly() {
+                                return Err(PhpError {
+                                    level: ErrorLevel::Fatal,
+                                    message: format!(
+                                        "Static property {}::${} cannot be readonly",
+                                        php_class.name, class_property.name
+                                    ),
+                                    line: property.end.line,
+                                });
+                            }
+
+                            php_class.static_properties.insert(
+                                class_property.name.bytes.clone(),
+                                Rc::new(RefCell::new(class_property.default_value.clone())),
+                            );
+
+                            php_class.static_property_declarations.push(class_property);
+                        }
+
+                        continue;
+                    }
+
+                    for entry in property.entries {
+                        let class_property = self.with_class_scope(&php_class, |evaluator| {
+                            evaluator.get_class_property(
+                                entry,
+                                property.modifiers.clone(),
+                                property.r#type.clone(),
+                                doc_comment.clone(),
+                            )
+                        })?;
+
+                        php_class.properties.push(class_property);
+                    }
+                }
+                ClassMember::VariableProperty(property) => {
+                    let doc_comment = get_doc_comment(&property.comments);
+
+                    for entry in property.entries {
+                        let class_property = self.with_class_scope(&php_class, |evaluator| {
+                            evaluator.get_class_property(
+                                entry,
+                                PropertyModifierGroup { modifiers: vec![] },
+                                property.r#type.clone(),
+                                doc_comment.clone(),
+                            )
+                        })?;
+
+                        php_class.properties.push(class_property);
+                    }
+                }
+                ClassMember::ConcreteMethod(method) => {
+                    let span = method.function;
+                    let php_method = self.get_concrete_method(&php_class.name, method)?;
+
+                    add_method(&mut php_class, php_method, span)?;
+                }
+                ClassMember::ConcreteConstructor(constructor) => {
+                    let mut parameters = vec![];
+                    let mut promoted_parameters = vec![];
+
+                    for parameter in constructor.parameters.parameters.inner {
+                        if !parameter.modifiers.is_empty() {
+                            let name = ByteString::new(variable_key(&parameter.name).to_vec());
+
+                            php_class.properties.push(ClassProperty {
+                                name: name.clone(),
+                                modifiers: get_promoted_property_modifiers(&parameter.modifiers),
+                                data_type: parameter.data_type.clone(),
+                                default_value: NULL,
+                                doc_comment: None,
+                            });
+
+                            promoted_parameters.push(name);
+                        }
+
+                        parameters.push(CallableArgument {
+                            name: parameter.name,
+                            data_type: parameter.data_type,
+                            by_reference: parameter.ampersand.is_some(),
+                            default_value: parameter.default,
+                            ellipsis: parameter.ellipsis.is_some(),
+                        });
+                    }
+
+                    self.check_parameters(&mut parameters)?;
+
+                    let php_method = PhpMethod {
+                        function: PhpCallable {
+                            attributes: constructor.attributes,
+                            span: constructor.function,
+                            return_by_reference: constructor.ampersand.is_some(),
+                            name: constructor.name.value.clone(),
+                            parameters,
+                            return_type: None,
+                            body: constructor.body.statements,
+                            file: self.current_file.clone(),
+                            closure: None,
+                        },
+                        modifiers: constructor.modifiers,
+                        class_name: php_class.name.clone(),
+                        promoted_parameters,
+                        doc_comment: get_doc_comment(&constructor.comments),
+                    };
+
+                    add_method(&mut php_class, php_method, constructor.function)?;
+                }
+                _ => {}
+            }
+        }
+
+        check_interface_methods(&php_class, class.class)?;
+        check_interface_constants(
+            "Class",
+            &php_class.name,
+            &php_class.constants,
+            php_class.ancestors().flat_map(|class| &class.interfaces),
+            class.class.line,
+        )?;
+
+        let class_name = php_class.name.clone();
+
+        if !self.env.add_class(php_class) {
+            return Err(redeclared_class(&class_name, class.class));
+        }
+
+        Ok(NULL)
+    }
+
+    pub(super) fn declare_interface(
+        &mut self,
+        interface: InterfaceStatement,
+    ) -> Result<PhpValue, PhpError> {
+        check_class_name("interface", &interface.name.value, interface.name.span)?;
+
+        let parents = match &interface.extends {
+            Some(extends) => {
+                self.resolve_interfaces(&interface.name.value, extends.parents.iter())?
+            }
+            None => vec![],
+        };
+
+        let mut php_interface = PhpInterface {
+            name: interface.name.value.clone(),
+            parents,
+            constants: vec![],
+            methods: HashMap::new(),
+        };
+
+        for member in interface.body.members {
+            let (method, comments) = match member {
+                InterfaceMember::Constant(constant) => {
+                    for entry in constant.entries {
+                        check_constant_expression(&entry)?;
+
+                        let value = self.eval_expression(&entry.value)?;
+
+                        if php_interface
+                            .constants
+                            .iter()
+                            .any(|(name, _)| *name == entry.name.value)
+                        {
+                            return Err(PhpError {
+                                level: ErrorLevel::Fatal,
+                                message: format!(
+                                    "Cannot redefine class constant {}::{}",
+                                    php_interface.name, entry.name.value
+                                ),
+                                line: entry.name.span.line,
+                            });
+                        }
+
+                        php_interface.constants.push((entry.name.value, value));
+                    }
+
+                    continue;
+                }
+                InterfaceMember::Method(method) => {
+                    let mut parameters = get_callable_arguments(method.parameters);
+
+                    self.check_parameters(&mut parameters)?;
+
+                    let function = PhpCallable {
+                        attributes: method.attributes,
+                        span: method.function,
+                        return_by_reference: method.ampersand.is_some(),
+                        name: method.name.value,
+                        parameters,
+                        return_type: method.return_type,
+                        body: vec![],
+                        file: self.current_file.clone(),
+                        closure: None,
+                    };
+
+                    ((function, method.modifiers), method.comments)
+                }
+                InterfaceMember::Constructor(constructor) => {
+                    let mut parameters = get_callable_arguments(constructor.parameters);
+
+                    self.check_parameters(&mut parameters)?;
+
+                    let function = PhpCallable {
+                        attributes: constructor.attributes,
+                        span: constructor.function,
+                        return_by_reference: constructor.ampersand.is_some(),
+                        name: constructor.name.value,
+                        parameters,
+                        return_type: None,
+                        body: vec![],
+                        file: self.current_file.clone(),
+                        closure: None,
+                    };
+
+                    ((function, constructor.modifiers), constructor.comments)
+                }
+                InterfaceMember::ConcreteMethod(method) => {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: format!(
+                            "Interface function {}::{}() cannot contain body",
+                            php_interface.name, method.name.value
+                        ),
+                        line: method.function.line,
+                    });
+                }
+            };
+
+            let (function, modifiers) = method;
+
+            if modifiers.visibility() != Visibility::Public {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: format!(
+                        "Access type for interface method {}::{}() must be public",
+                        php_interface.name, function.name
+                    ),
+                    line: function.span.line,
+                });
+            }
+
+            let key = function.name.bytes.to_ascii_lowercase();
+
+            if php_interface.methods.contains_key(&key) {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: format!(
+                        "Cannot redeclare {}::{}()",
+                        php_interface.name, function.name
+                    ),
+                    line: function.span.line,
+                });
+            }
+
+            php_interface.methods.insert(
+                key,
+                PhpMethod {
+                    function,
+                    modifiers,
+                    class_name: php_interface.name.clone(),
+                    promoted_parameters: vec![],
+                    doc_comment: get_doc_comment(&comments),
+                },
+            );
+        }
+
+        check_interface_constants(
+            "Interface",
+            &php_interface.name,
+            &php_interface.constants,
+            php_interface.parents.iter(),
+            interface.interface.line,
+        )?;
+
+        if !self.env.add_interface(php_interface) {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Cannot declare interface {}, because the name is already in use",
+                    interface.name.value
+                ),
+                line: interface.interface.line,
+            });
+        }
+
+        Ok(NULL)
+    }
+
+    pub(super) fn declare_unit_enum(
+        &mut self,
+        statement: UnitEnumStatement,
+    ) -> Result<PhpValue, PhpError> {
+        let members = statement
+            .body
+            .members
+            .into_iter()
+            .filter_map(|member| match member {
+                UnitEnumMember::Case(case) => Some(EnumMember::Case(case.name, None)),
+                UnitEnumMember::Method(method) => Some(EnumMember::Method(method)),
+                UnitEnumMember::Constant(constant) => Some(EnumMember::Constant(constant)),
+                UnitEnumMember::TraitUsage(_) => None,
+            })
+            .collect();
+
+        self.declare_enum(
+            statement.name,
+            statement.r#enum,
+            None,
+            &statement.implements,
+            members,
+        )
+    }
+
+    pub(super) fn declare_backed_enum(
+        &mut self,
+        statement: BackedEnumStatement,
+    ) -> Result<PhpValue, PhpError> {
+        let backing_type = match statement.backed_type {
+            BackedEnumType::Int(..) => TypeName::Int,
+            BackedEnumType::String(..) => TypeName::String,
+        };
+
+        let members = statement
+            .body
+            .members
+            .into_iter()
+            .filter_map(|member| match member {
+                BackedEnumMember::Case(case) => Some(EnumMember::Case(case.name, Some(case.value))),
+                BackedEnumMember::Method(method) => Some(EnumMember::Method(method)),
+                BackedEnumMember::Constant(constant) => Some(EnumMember::Constant(constant)),
+                BackedEnumMember::TraitUsage(_) => None,
+            })
+            .collect();
+
+        self.declare_enum(
+            statement.name,
+            statement.r#enum,
+            Some(backing_type),
+            &statement.implements,
+            members,
+        )
+    }
+
+    /// Declares an enum as a final class whose objects are its cases, with the readonly
+    /// properties `name` and, for a backed enum, `value`.
+    ///
+    /// It implements UnitEnum, or BackedEnum for a backed enum, and the methods of
+    /// these interfaces, `cases()`, `from()` and `tryFrom()`, are given by `call_enum_method()`.
+    fn declare_enum(
+        &mut self,
+        name: SimpleIdentifier,
+        span: Span,
+        backing_type: Option<TypeName>,
+        implements: &[SimpleIdentifier],
+        members: Vec<EnumMember>,
+    ) -> Result<PhpValue, PhpError> {
+        check_class_name("enum", &name.value, name.span)?;
+
+        let mut interfaces = self.resolve_interfaces(&name.value, implements.iter())?;
+
+        let implicit_interface: &[u8] = match backing_type {
+            Some(_) => b"BackedEnum",
+            None => b"UnitEnum",
+        };
+
+        if !interfaces
+            .iter()
+            .any(|interface| interface.extends(implicit_interface))
+        {
+            interfaces.extend(self.env.get_interface(implicit_interface));
+        }
+
+        let readonly = PropertyModifierGroup {
+            modifiers: vec![
+                PropertyModifier::Public(span),
+                PropertyModifier::Readonly(span),
+            ],
+        };
+
+        let mut properties = vec![ClassProperty {
+            name: "name".into(),
+            modifiers: readonly.clone(),
+            data_type: None,
+            default_value: NULL,
+            doc_comment: None,
+        }];
+
+        if backing_type.is_some() {
+            properties.push(ClassProperty {
+                name: "value".into(),
+                modifiers: readonly,
+                data_type: None,
+                default_value: NULL,
+                doc_comment: None,
+            });
+        }
+
+        let mut php_class = PhpClass {
+            name: name.value.clone(),
+            modifiers: ClassModifierGroup {
+                modifiers: vec![ClassModifier::Final(span)],
+            },
+            parent: None,
+            interfaces,
+            doc_comment: None,
+            constants: vec![],
+            properties,
+            methods: HashMap::new(),
+            static_properties: HashMap::new(),
+            static_property_declarations: vec![],
+            enum_cases: Some(EnumCases {
+                backing_type,
+                cases: vec![],
+            }),
+        };
+
+        for member in members {
+            match member {
+                EnumMember::Case(case, value) => {
+                    let value = match value {
+                        Some(value) => self.with_class_scope(&php_class, |evaluator| {
+                            evaluator.eval_expression(&value)
+                        })?,
+                        None => NULL,
+                    };
+
+                    add_enum_case(&mut php_class, case, value)?;
+                }
+                EnumMember::Constant(constant) => {
+                    self.declare_class_constant(&mut php_class, constant)?;
+                }
+                EnumMember::Method(method) => {
+                    let method_span = method.function;
+                    let method = self.get_concrete_method(&php_class.name, method)?;
+
+                    check_enum_method(&php_class, &method)?;
+
+                    add_method(&mut php_class, method, method_span)?;
+                }
+            }
+        }
+
+        check_interface_methods(&php_class, span)?;
+        check_interface_constants(
+            "Enum",
+            &php_class.name,
+            &php_class.constants,
+            php_class.interfaces.iter(),
+            span.line,
+        )?;
+
+        if !self.env.add_class(php_class) {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Cannot declare enum {}, because the name is already in use",
+                    name.value
+                ),
+                line: span.line,
+            });
+        }
+
+        let php_class = self.env.get_class(&name.value).unwrap();
+
+        self.complete_enum_cases(&php_class);
+
+        Ok(NULL)
+    }
+
+    /// Evaluates the constants of a `const` declaration and adds them to the class.
+    pub(super) fn declare_class_constant(
+        &mut self,
+        php_class: &mut PhpClass,
+        constant: ClassishConstant,
+    ) -> Result<(), PhpError> {
+        for entry in constant.entries {
+            check_constant_expression(&entry)?;
+
+            let value = self.with_class_scope(php_class, |evaluator| {
+                evaluator.eval_expression(&entry.value)
+            })?;
+
+            let is_case = php_class.enum_cases.as_ref().is_some_and(|enum_cases| {
+                enum_cases
+                    .cases
+                    .iter()
+                    .any(|(name, _)| *name == entry.name.value)
+            });
+
+            if is_case
+                || php_class
+                    .constants
+                    .iter()
+                    .any(|(name, _)| *name == entry.name.value)
+            {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: format!(
+                        "Cannot redefine class constant {}::{}",
+                        php_class.name, entry.name.value
+                    ),
+                    line: entry.name.span.line,
+                });
+            }
+
+            php_class.constants.push((entry.name.value, value));
+        }
+
+        Ok(())
+    }
+
+    /// Converts a method that has a body, declared by the class `class_name`.
+    pub(super) fn get_concrete_method(
+        &mut self,
+        class_name: &ByteString,
+        method: ConcreteMethod,
+    ) -> Result<PhpMethod, PhpError> {
+        let mut parameters = get_callable_arguments(method.parameters);
+
+        self.check_parameters(&mut parameters)?;
+
+        check_return_statements(
+            method
+                .return_type
+                .as_ref()
+                .map(|return_type| &return_type.data_type),
+            &method.body.statements,
+        )?;
+
+        Ok(PhpMethod {
+            function: PhpCallable {
+                attributes: method.attributes,
+                span: method.function,
+                return_by_reference: method.ampersand.is_some(),
+                name: method.name.value.clone(),
+                parameters,
+                return_type: method.return_type,
+                body: method.body.statements,
+                file: self.current_file.clone(),
+                closure: None,
+            },
+            modifiers: method.modifiers,
+            class_name: class_name.clone(),
+            promoted_parameters: vec![],
+            doc_comment: get_doc_comment(&method.comments),
+        })
+    }
+
+    /// Finds the interfaces that a class implements or that an interface extends.
+    fn resolve_interfaces<'a>(
+        &self,
+        declaring_name: &ByteString,
+        names: impl Iterator<Item = &'a SimpleIdentifier>,
+    ) -> Result<Vec<Rc<PhpInterface>>, PhpError> {
+        let mut interfaces: Vec<Rc<PhpInterface>> = vec![];
+
+        for name in names {
+            let error = |message: String| PhpError {
+                level: ErrorLevel::Fatal,
+                message,
+                line: name.span.line,
+            };
+
+            let Some(interface) = self.env.get_interface(&name.value) else {
+                return Err(match self.env.get_class(&name.value) {
+                    Some(class) => error(format!(
+                        "{} cannot implement {} - it is not an interface",
+                        declaring_name, class.name
+                    )),
+                    None => PhpError {
+                        level: ErrorLevel::EngineError("Error"),
+                        message: format!("Interface \"{}\" not found", name.value),
+                        line: name.span.line,
+                    },
+                });
+            };
+
+            if interfaces.iter().any(|other| Rc::ptr_eq(other, &interface)) {
+                return Err(error(format!(
+                    "{} cannot implement previously implemented interface {}",
+                    declaring_name, interface.name
+                )));
+            }
+
+            interfaces.push(interface);
+        }
+
+        Ok(interfaces)
+    }
+
+    /// Evaluates the constants and the default values of the properties of a class being
+    /// declared, `self::` refers to what the class declared so far.
+    pub(super) fn with_class_scope<T>(
+        &mut self,
+        class: &PhpClass,
+        evaluate: impl FnOnce(&mut Self) -> Result<T, PhpError>,
+    ) -> Result<T, PhpError> {
+        let class = Rc::new(class.clone());
+
+        let old_current_class = self.current_class.replace(Rc::clone(&class));
+        let old_static_class = self.static_class.replace(class);
+
+        let result = evaluate(self);
+
+        self.current_class = old_current_class;
+        self.static_class = old_static_class;
+
+        result
+    }
+
+    /// Checks the parameters of a function when it is declared, like PHP does when it compiles it.
+    ///
+    /// An optional parameter followed by a required one is required too, so its default is removed.
+    pub(super) fn check_parameters(
+        &mut self,
+        parameters: &mut [CallableArgument],
+    ) -> Result<(), PhpError> {
+        for (position, parameter) in parameters.iter().enumerate() {
+            let line = parameter.name.span.line;
+
+            if parameter.ellipsis && parameter.default_value.is_some() {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Variadic parameter cannot have a default value".to_string(),
+                    line,
+                });
+            }
+
+            if parameter.ellipsis && position + 1 != parameters.len() {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Only the last parameter can be variadic".to_string(),
+                    line,
+                });
+            }
+
+            if parameters[..position]
+                .iter()
+                .any(|previous| previous.name.name == parameter.name.name)
+            {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: format!("Redefinition of parameter {}", parameter.name.name),
+                    line,
+                });
+            }
+        }
+
+        let Some(last_required) = parameters
+            .iter()
+            .rposition(|parameter| parameter.default_value.is_none() && !parameter.ellipsis)
+        else {
+            return Ok(());
+        };
+
+        let required_name = parameters[last_required].name.name.clone();
+
+        for parameter in &mut parameters[..last_required] {
+            let Some(default_value) = &parameter.default_value else {
+                continue;
+            };
+
+            // a typed parameter with a null default is only a nullable parameter
+            let implicitly_nullable =
+                parameter.data_type.is_some() && matches!(default_value, Expression::Null);
+
+            if !implicitly_nullable {
+                self.raise_error(PhpError {
+                    level: ErrorLevel::Deprecated,
+                    message: format!(
+                        "Optional parameter {} declared before required parameter {} is implicitly treated as a required parameter",
+                        parameter.name.name, required_name
+                    ),
+                    line: parameter.name.span.line,
+                })?;
+            }
+
+            parameter.default_value = None;
+        }
+
+        Ok(())
+    }
+}
+
+/// Checks that a class declares, or inherits, the methods of the interfaces it implements,
+/// with a signature that accepts the calls the interface allows.
+///
+/// An abstract class can leave the methods to its children, which are checked when they
+/// are declared.
+fn check_interface_methods(class: &PhpClass, span: Span) -> Result<(), PhpError> {
+    let mut checked_methods = vec![];
+    let mut missing_methods = vec![];
+
+    for interface in class.ancestors().flat_map(|class| &class.interfaces) {
+        for required in interface.all_methods() {
+            let key = required.function.name.bytes.to_ascii_lowercase();
+
+            if checked_methods.contains(&key) {
+                continue;
+            }
+
+            match class.get_method(&key) {
+                Some(method) => check_method_compatibility(method, required)?,
+                None => missing_methods.push(format!(
+                    "{}::{}",
+                    required.class_name, required.function.name
+                )),
+            }
+
+            checked_methods.push(key);
+        }
+    }
+
+    if missing_methods.is_empty() || class.modifiers.has_abstract() {
+        return Ok(());
+    }
+
+    // like PHP, only the first 3 methods are listed
+    let mut list = missing_methods
+        .iter()
+        .take(3)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+
+    if missing_methods.len() > 3 {
+        list.push_str(", ...");
+    }
+
+    Err(PhpError {
+        level: ErrorLevel::Fatal,
+        message: format!(
+            "Class {} contains {} abstract method{} and must therefore be declared abstract or implement the remaining methods ({})",
+            class.name,
+            missing_methods.len(),
+            if missing_methods.len() == 1 { "" } else { "s" },
+            list
+        ),
+        line: span.line,
+    })
+}
+
+/// Checks that a class, an enum or an interface does not get the same constant from two
+/// interfaces, which PHP finds ambiguous, unless it declares the constant itself.
+fn check_interface_constants<'a>(
+    kind: &str,
+    name: &ByteString,
+    declared: &[(ByteString, PhpValue)],
+    interfaces: impl Iterator<Item = &'a Rc<PhpInterface>>,
+    line: usize,
+) -> Result<(), PhpError> {
+    let mut inherited: Vec<(&PhpInterface, &ByteString)> = vec![];
+
+    for (interface, constant) in interfaces.flat_map(|interface| interface.all_constants()) {
+        if declared.iter().any(|(name, _)| name == constant) {
+            continue;
+        }
+
+        match inherited.iter().find(|(_, other)| *other == constant) {
+            // the same interface can be reached twice, like through two parents
+            Some((other, _)) if std::ptr::eq(*other, interface) => {}
+            Some((other, _)) => {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: format!(
+                        "{} {} inherits both {}::{} and {}::{}, which is ambiguous",
+                        kind, name, other.name, constant, interface.name, constant
+                    ),
+                    line,
+                })
+            }
+            None => inherited.push((interface, constant)),
+        }
+    }
+
+    Ok(())
+}
+
+/// A class or interface constant cannot create an object, like a property default.
+fn check_constant_expression(entry: &ConstantEntry) -> Result<(), PhpError> {
+    if contains_new_expression(&entry.value) {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: "New expressions are not supported in this context".to_string(),
+            line: entry.name.span.line,
+        });
+    }
+
+    Ok(())
+}
+
+/// Checks that a method can be called everywhere the interface method it implements can:
+/// it is public, static if the other one is, and it accepts the same number of arguments.
+fn check_method_compatibility(method: &PhpMethod, required: &PhpMethod) -> Result<(), PhpError> {
+    let error = |message: String| PhpError {
+        level: ErrorLevel::Fatal,
+        message,
+        line: method.function.span.line,
+    };
+
+    if method.modifiers.visibility() != Visibility::Public {
+        return Err(error(format!(
+            "Access level to {}::{}() must be public (as in class {})",
+            method.class_name, method.function.name, required.class_name
+        )));
+    }
+
+    match (
+        method.modifiers.has_static(),
+        required.modifiers.has_static(),
+    ) {
+        (true, false) => {
+            return Err(error(format!(
+                "Cannot make non static method {}::{}() static in class {}",
+                required.class_name, required.function.name, method.class_name
+            )))
+        }
+        (false, true) => {
+            return Err(error(format!(
+                "Cannot make static method {}::{}() non static in class {}",
+                required.class_name, required.function.name, method.class_name
+            )))
+        }
+        _ => {}
+    }
+
+    let parameters = &method.function.parameters;
+    let required_parameters = &required.function.parameters;
+
+    let required_count = parameters
+        .iter()
+        .filter(|parameter| parameter.default_value.is_none() && !parameter.ellipsis)
+        .count();
+
+    let is_variadic = parameters
+        .last()
+        .is_some_and(|parameter| parameter.ellipsis);
+
+    if required_count <= required_parameters.len()
+        && (is_variadic || parameters.len() >= required_parameters.len())
+    {
+        return Ok(());
+    }
+
+    Err(error(format!(
+        "Declaration of {} must be compatible with {}",
+        describe_method(method),
+        describe_method(required)
+    )))
+}
+
+/// The name and the parameters of a method, like `Foo::bar(int $a = 1): int`.
+fn describe_method(method: &PhpMethod) -> String {
+    let mut description = format!(
+        "{}::{}({})",
+        method.class_name,
+        method.function.name,
+        describe_parameters(&method.function.parameters)
+    );
+
+    if let Some(return_type) = &method.function.return_type {
+        description.push_str(&format!(": {}", return_type.data_type));
+    }
+
+    description
+}
+
+/// A member of a pure or a backed enum, the value of a case is None in a pure enum.
+enum EnumMember {
+    Case(SimpleIdentifier, Option<Expression>),
+    Method(ConcreteMethod),
+    Constant(ClassishConstant),
+}
+
+fn add_enum_case(
+    class: &mut PhpClass,
+    name: SimpleIdentifier,
+    value: PhpValue,
+) -> Result<(), PhpError> {
+    let error = |message: String| PhpError {
+        level: ErrorLevel::Fatal,
+        message,
+        line: name.span.line,
+    };
+
+    let enum_cases = class.enum_cases.as_mut().unwrap();
+
+    // the cases and the constants have the same names
+    let is_redefined = enum_cases.cases.iter().any(|(case, _)| *case == name.value)
+        || class
+            .constants
+            .iter()
+            .any(|(constant, _)| *constant == name.value);
+
+    if is_redefined {
+        return Err(error(format!(
+            "Cannot redefine class constant {}::{}",
+            class.name, name.value
+        )));
+    }
+
+    if let Some(backing_type) = enum_cases.backing_type {
+        if value.type_name() != backing_type {
+            return Err(error(format!(
+                "Enum case type {} does not match enum backing type {}",
+                value.type_name().error_name(),
+                backing_type.error_name()
+            )));
+        }
+
+        let duplicate = enum_cases
+            .cases
+            .iter()
+            .find(|(_, other)| other.is_identical(&value).unwrap_or(false));
+
+        if let Some((duplicate, _)) = duplicate {
+            return Err(error(format!(
+                "Duplicate value in enum {} for cases {} and {}",
+                class.name, duplicate, name.value
+            )));
+        }
+    }
+
+    enum_cases.cases.push((name.value, value));
+
+    Ok(())
+}
+
+/// The methods of UnitEnum and BackedEnum cannot be redeclared, the magic methods that
+/// an enum cannot have are already rejected by the parser.
+fn check_enum_method(class: &PhpClass, method: &PhpMethod) -> Result<(), PhpError> {
+    let name = &method.function.name;
+
+    let is_backed = class
+        .enum_cases
+        .as_ref()
+        .is_some_and(|enum_cases| enum_cases.backing_type.is_some());
+
+    let is_interface_method = name.bytes.eq_ignore_ascii_case(b"cases")
+        || (is_backed
+            && (name.bytes.eq_ignore_ascii_case(b"from")
+                || name.bytes.eq_ignore_ascii_case(b"tryFrom")));
+
+    if is_interface_method {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!("Cannot redeclare {}::{}()", class.name, name),
+            line: method.function.span.line,
+        });
+    }
+
+    Ok(())
+}
+
+/// A class cannot extend itself. A longer cycle needs a class that is declared again,
+/// which is reported as a redeclaration before the parent is looked up.
+fn check_self_inheritance(
+    name: &ByteString,
+    parent_name: &ByteString,
+    span: Span,
+) -> Result<(), PhpError> {
+    if !parent_name.bytes.eq_ignore_ascii_case(&name.bytes) {
+        return Ok(());
+    }
+
+    Err(PhpError {
+        level: ErrorLevel::Fatal,
+        message: format!("Circular inheritance detected: {} -> {}", name, parent_name),
+        line: span.line,
+    })
+}
+
+fn redeclared_class(name: &ByteString, span: Span) -> PhpError {
+    PhpError {
+        level: ErrorLevel::Fatal,
+        message: format!(
+            "Cannot declare class {}, because the name is already in use",
+            name
+        ),
+        line: span.line,
+    }
+}
+
+/// The names of types, that classes, interfaces, traits and enums cannot have,
+/// whatever their namespace is. The parser already rejects the other reserved words.
+const RESERVED_CLASS_NAMES: &[&str] = &[
+    "bool", "false", "float", "int", "iterable", "mixed", "never", "null", "object", "parent",
+    "self", "static", "string", "true", "void",
+];
+
+/// Checks the name of a class-like declaration, `kind` is "class", "interface", "trait" or "enum".
+fn check_class_name(kind: &str, name: &ByteString, span: Span) -> Result<(), PhpError> {
+    let unqualified_name = match name.bytes.iter().rposition(|byte| *byte == b'\\') {
+        Some(separator) => &name.bytes[separator + 1..],
+        None => &name.bytes[..],
+    };
+
+    let is_reserved = RESERVED_CLASS_NAMES
+        .iter()
+        .any(|reserved| unqualified_name.eq_ignore_ascii_case(reserved.as_bytes()));
+
+    if !is_reserved {
+        return Ok(());
+    }
+
+    Err(PhpError {
+        level: ErrorLevel::Fatal,
+        message: format!(
+            "Cannot use '{}' as {} name as it is reserved",
+            display_name(unqualified_name),
+            kind
+        ),
+        line: span.line,
+    })
+}
+
+/// Converts the parameters of a function declaration.
+pub(super) fn get_callable_arguments(parameters: FunctionParameterList) -> Vec<CallableArgument> {
+    parameters
+        .into_iter()
+        .map(|parameter| CallableArgument {
+            name: parameter.name,
+            data_type: parameter.data_type,
+            by_reference: parameter.ampersand.is_some(),
+            default_value: parameter.default,
+            ellipsis: parameter.ellipsis.is_some(),
+        })
+        .collect()
+}
+
+/// The content of the doc comment of a declaration, with `/**` and `*/`.
+fn get_doc_comment(comments: &CommentGroup) -> Option<ByteString> {
+    comments
+        .doc_comment()
+        .map(|comment| comment.content.clone())
+}
+
+/// Promoted constructor parameters become properties with the same visibility.
+fn get_promoted_property_modifiers(
+    modifiers: &PromotedPropertyModifierGroup,
+) -> PropertyModifierGroup {
+    let modifiers = modifiers
+        .modifiers
+        .iter()
+        .map(|modifier| match modifier {
+            PromotedPropertyModifier::Public(span) => PropertyModifier::Public(*span),
+            PromotedPropertyModifier::Protected(span) => PropertyModifier::Protected(*span),
+            PromotedPropertyModifier::Private(span) => PropertyModifier::Private(*span),
+            PromotedPropertyModifier::Readonly(span) => PropertyModifier::Readonly(*span),
+        })
+        .collect();
+
+    PropertyModifierGroup { modifiers }
+}
+
+fn add_method(class: &mut PhpClass, method: PhpMethod, span: Span) -> Result<(), PhpError> {
+    let key = method.function.name.bytes.to_ascii_lowercase();
+
+    if class.methods.contains_key(&key) {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "Cannot redeclare {}::{}()",
+                class.name, method.function.name
+            ),
+            line: span.line,
+        });
+    }
+
+    class.methods.insert(key, method);
+
+    Ok(())
+}
@@ -0,0 +1,191 @@
+use std::rc::Rc;
+
+use php_parser_rs::lexer::token::Span;
+
+use crate::helpers::{display_name, display_string};
+use crate::native::{check_arguments_count, get_int_argument, get_string_argument};
+use crate::php_class::PhpClass;
+use crate::php_value::{ErrorLevel, PhpError, PhpObject, PhpValue, TypeName};
+
+use super::{CallArguments, Evaluator};
+
+impl<'w> Evaluator<'w> {
+    /// The object of the case `name` of an enum, None if the class is not an enum
+    /// or has no such case.
+    ///
+    /// The object is created the first time the case is used, then the same object is
+    /// given, so `Suit::Hearts === Suit::Hearts`.
+    pub(super) fn get_enum_case(&mut self, class: &Rc<PhpClass>, name: &[u8]) -> Option<PhpValue> {
+        let enum_cases = class.enum_cases.as_ref()?;
+
+        let (case, value) = enum_cases
+            .cases
+            .iter()
+            .find(|(case, _)| case.bytes == name)?;
+
+        if let Some(object) = self.env.get_enum_case(&class.name, name) {
+            return Some(PhpValue::Object(object));
+        }
+
+        let object = self.new_object(class);
+
+        object
+            .borrow_mut()
+            .set_property(b"name", PhpValue::String(case.clone()));
+
+        if enum_cases.backing_type.is_some() {
+            object.borrow_mut().set_property(b"value", value.clone());
+        }
+
+        self.env
+            .add_enum_case(&class.name, name, Rc::clone(&object));
+
+        Some(PhpValue::Object(object))
+    }
+
+    /// A case used while the enum is declared, by a constant or another case, is an
+    /// object of the incomplete class, it is given the declared class.
+    pub(super) fn complete_enum_cases(&mut self, class: &Rc<PhpClass>) {
+        for (case, _) in &class.enum_cases.as_ref().unwrap().cases {
+            if let Some(object) = self.env.get_enum_case(&class.name, case) {
+                object.borrow_mut().class = Rc::clone(class);
+            }
+        }
+    }
+
+    /// Whether `name` is one of the static methods that an enum has without declaring
+    /// them, `cases()` and, for a backed enum, `from()` and `tryFrom()`.
+    pub(super) fn is_enum_method(class: &PhpClass, name: &[u8]) -> bool {
+        let Some(enum_cases) = &class.enum_cases else {
+            return false;
+        };
+
+        name.eq_ignore_ascii_case(b"cases")
+            || (enum_cases.backing_type.is_some()
+                && (name.eq_ignore_ascii_case(b"from") || name.eq_ignore_ascii_case(b"tryFrom")))
+    }
+
+    /// Calls one of the methods given by `is_enum_method()`.
+    ///
+    /// `from()` gives the case that has the value, or stops the program with the error
+    /// of the ValueError that PHP throws. `tryFrom()` gives null instead.
+    pub(super) fn call_enum_method(
+        &mut self,
+        class: &Rc<PhpClass>,
+        name: &[u8],
+        arguments: CallArguments,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let enum_cases = class.enum_cases.as_ref().unwrap();
+
+        let method_name = if name.eq_ignore_ascii_case(b"cases") {
+            "cases"
+        } else if name.eq_ignore_ascii_case(b"from") {
+            "from"
+        } else {
+            "tryFrom"
+        };
+
+        let function_name = format!("{}::{}", class.name, method_name);
+
+        let mut values = arguments.positional;
+
+        for (parameter, value) in arguments.named {
+            if method_name == "cases" || parameter.bytes != b"value" || !values.is_empty() {
+                return Err(PhpError {
+                    level: ErrorLevel::EngineError("Error"),
+                    message: format!("Unknown named parameter ${}", display_name(&parameter)),
+                    line: span.line,
+                });
+            }
+
+            values.push(value);
+        }
+
+        if method_name == "cases" {
+            check_arguments_count(&function_name, &values, 0, 0, span)?;
+
+            let cases = enum_cases
+                .cases
+                .iter()
+                .map(|(case, _)| self.get_enum_case(class, case).unwrap())
+                .collect();
+
+            return Ok(PhpValue::Array(cases));
+        }
+
+        check_arguments_count(&function_name, &values, 1, 1, span)?;
+
+        let value = match enum_cases.backing_type {
+            Some(TypeName::Int) => PhpValue::Int(get_int_argument(
+                self,
+                &function_name,
+                &values,
+                0,
+                "value",
+                span,
+            )?),
+            _ => PhpValue::String(
+                get_string_argument(self, &function_name, &values, 0, "value", span)?.into(),
+            ),
+        };
+
+        let case = enum_cases
+            .cases
+            .iter()
+            .find(|(_, case_value)| case_value.is_identical(&value).unwrap_or(false));
+
+        match case {
+            Some((case, _)) => Ok(self.get_enum_case(class, case).unwrap()),
+            None if method_name == "tryFrom" => Ok(PhpValue::Null),
+            None => {
+                let value = match value {
+                    PhpValue::String(string) => display_string(&string),
+                    value => value.to_string().unwrap_or_default(),
+                };
+
+                Err(PhpError {
+                    level: ErrorLevel::EngineError("ValueError"),
+                    message: format!(
+                        "{} is not a valid backing value for enum {}",
+                        value, class.name
+                    ),
+                    line: span.line,
+                })
+            }
+        }
+    }
+
+    /// The cases of an enum cannot be changed: `name` and `value` are readonly and
+    /// no other property can be added.
+    pub(super) fn check_property_write(
+        &self,
+        object: &PhpObject,
+        property_name: &[u8],
+        span: Span,
+    ) -> Result<(), PhpError> {
+        if object.class.enum_cases.is_none() {
+            return Ok(());
+        }
+
+        let message = if object.get_property(property_name).is_some() {
+            format!(
+                "Cannot modify readonly property {}::${}",
+                object.class.name,
+                display_name(property_name)
+            )
+        } else {
+            format!(
+                "Cannot create dynamic property {}::${}",
+                object.class.name,
+                display_name(property_name)
+            )
+        };
+
+        Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message,
+            line: span.line,
+        })
+    }
+}
@@ -0,0 +1,247 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use php_parser_rs::parser;
+use php_parser_rs::parser::ast::try_block::{CatchType, TryStatement};
+use php_parser_rs::parser::ast::{Statement, ThrowExpression};
+
+use crate::helpers::variable_key;
+use crate::php_value::{ErrorLevel, PhpError, PhpObject, PhpValue};
+
+use super::{Evaluator, NULL};
+
+/// The throwable classes declared before the script starts, with the class they extend.
+/// A class without a parent implements Throwable itself.
+const THROWABLE_CLASSES: &[(&str, Option<&str>)] = &[
+    ("Exception", None),
+    ("Error", None),
+    ("TypeError", Some("Error")),
+    ("ValueError", Some("Error")),
+    ("ArgumentCountError", Some("TypeError")),
+    ("ArithmeticError", Some("Error")),
+    ("DivisionByZeroError", Some("ArithmeticError")),
+    ("UnhandledMatchError", Some("Error")),
+];
+
+/// The members of Exception and Error, `{class}` is replaced by the name of the class.
+///
+/// `file` and `line` are set when the object is created, by `new`.
+const THROWABLE_BODY: &str = "
+class {class} implements Throwable {
+    protected $message = '';
+    protected $code = 0;
+    protected string $file = '';
+    protected int $line = 0;
+    protected ?Throwable $previous = null;
+
+    public function __construct(string $message = '', int $code = 0, ?Throwable $previous = null) {
+        $this->message = $message;
+        $this->code = $code;
+        $this->previous = $previous;
+    }
+
+    final public function getMessage(): string { return $this->message; }
+    final public function getCode() { return $this->code; }
+    final public function getPrevious(): ?Throwable { return $this->previous; }
+    final public function getFile(): string { return $this->file; }
+    final public function getLine(): int { return $this->line; }
+}
+";
+
+impl<'w> Evaluator<'w> {
+    /// Declares the classes of THROWABLE_CLASSES, they are written in PHP.
+    pub(super) fn declare_throwable_classes(&mut self) {
+        let mut source = String::from("<?php");
+
+        for (name, parent) in THROWABLE_CLASSES {
+            match parent {
+                Some(parent) => source += &format!("\nclass {} extends {} {{}}", name, parent),
+                None => source += &THROWABLE_BODY.replace("{class}", name),
+            }
+        }
+
+        for statement in parser::parse(&source).unwrap() {
+            if let Statement::Class(class) = statement {
+                self.declare_class(class).unwrap();
+            }
+        }
+    }
+
+    /// Evaluates `throw expression`, the object goes up as an error until a catch
+    /// takes it, or the program stops with it.
+    pub(super) fn eval_throw(&mut self, throw: &ThrowExpression) -> Result<PhpValue, PhpError> {
+        let line = throw.value.first_span().map_or(0, |span| span.line);
+
+        let object = match self.eval_expression(&throw.value)? {
+            PhpValue::Object(object) if object.borrow().is_instance_of(b"Throwable") => object,
+            PhpValue::Object(_) => {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Cannot throw objects that do not implement Throwable".to_string(),
+                    line,
+                })
+            }
+            _ => {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Can only throw objects".to_string(),
+                    line,
+                })
+            }
+        };
+
+        Err(self.throw(object))
+    }
+
+    /// Throws an Error of the engine, like a DivisionByZeroError, as an object of its
+    /// class created where the error was raised. The other errors are given back.
+    pub(super) fn throw_engine_error(&mut self, error: PhpError) -> PhpError {
+        let ErrorLevel::EngineError(class_name) = error.level else {
+            return error;
+        };
+
+        let class = self.env.get_class(class_name.as_bytes()).unwrap();
+
+        let object = self.new_object(&class);
+
+        {
+            let mut exception = object.borrow_mut();
+
+            exception.set_property(b"message", PhpValue::String(error.message.as_str().into()));
+            exception.set_property(b"file", PhpValue::String(self.current_file.as_str().into()));
+            exception.set_property(b"line", PhpValue::Int(error.line as i64));
+        }
+
+        self.throw(object)
+    }
+
+    /// The error that carries a thrown object, its message is the one PHP gives when
+    /// the exception is not caught:
+    ///
+    /// ```text
+    /// PHP Fatal error: Uncaught Exception: message in test.php:3
+    /// Stack trace:
+    /// #0 {main}
+    ///   thrown in test.php on line 3
+    /// ```
+    fn throw(&mut self, object: Rc<RefCell<PhpObject>>) -> PhpError {
+        let exception = object.borrow();
+
+        let property = |name: &[u8]| {
+            exception
+                .get_property(name)
+                .and_then(|value| value.to_string())
+                .unwrap_or_default()
+        };
+
+        let message = property(b"message");
+        let file = property(b"file");
+        let line = property(b"line").parse().unwrap_or(0);
+
+        let mut uncaught = format!("Uncaught {}", exception.class.name);
+
+        if !message.is_empty() {
+            uncaught += &format!(": {}", message);
+        }
+
+        uncaught += &format!(" in {}:{}\nStack trace:", file, line);
+
+        for (depth, site) in self.include_stack.iter().rev().enumerate() {
+            uncaught += &format!(
+                "\n#{} {}({}): {}()",
+                depth, site.file, site.line, site.function
+            );
+        }
+
+        uncaught += &format!(
+            "\n#{} {{main}}\n  thrown in {} on line {}",
+            self.include_stack.len(),
+            file,
+            line
+        );
+
+        drop(exception);
+
+        self.thrown = Some(object);
+
+        PhpError {
+            level: ErrorLevel::Exception,
+            message: uncaught,
+            line,
+        }
+    }
+
+    /// Evaluates `try { ... } catch (...) { ... } finally { ... }`.
+    ///
+    /// The first catch whose class the thrown object is an instance of takes the
+    /// exception. The finally block always runs, after a return, a break or an
+    /// exception, which go on after it unless the block has its own.
+    pub(super) fn eval_try_statement(
+        &mut self,
+        statement: TryStatement,
+    ) -> Result<PhpValue, PhpError> {
+        let mut result = self.eval_statements(statement.body);
+
+        if let Err(PhpError {
+            level: ErrorLevel::Exception,
+            ..
+        }) = result
+        {
+            let exception = self.thrown.take().unwrap();
+
+            let catch = statement.catches.into_iter().find(|catch| {
+                let types = match &catch.types {
+                    CatchType::Identifier { identifier } => std::slice::from_ref(identifier),
+                    CatchType::Union { identifiers } => identifiers.as_slice(),
+                };
+
+                types.iter().any(|class| {
+                    let name = class
+                        .value
+                        .bytes
+                        .strip_prefix(b"\\")
+                        .unwrap_or(&class.value.bytes);
+
+                    exception.borrow().is_instance_of(name)
+                })
+            });
+
+            match catch {
+                Some(catch) => {
+                    if let Some(var) = &catch.var {
+                        self.env
+                            .set_var(variable_key(var), &PhpValue::Object(exception));
+                    }
+
+                    result = self.eval_statements(catch.body);
+                }
+                None => self.thrown = Some(exception),
+            }
+        }
+
+        let Some(finally) = statement.finally else {
+            return result;
+        };
+
+        // exit() does not run the finally blocks
+        if self.die {
+            return result;
+        }
+
+        let thrown = self.thrown.take();
+        let return_value = self.return_value.take();
+        let loop_jump = self.loop_jump.take();
+
+        self.eval_statements(finally.body)?;
+
+        if self.die || self.return_value.is_some() || self.loop_jump.is_some() {
+            return Ok(NULL);
+        }
+
+        self.thrown = thrown;
+        self.return_value = return_value;
+        self.loop_jump = loop_jump;
+
+        result
+    }
+}
@@ -0,0 +1,347 @@
+use std::path::{Path, MAIN_SEPARATOR};
+use std::{fs, io, str};
+
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::{Expression, Statement};
+
+use crate::helpers::parse_php_file;
+use crate::php_value::{ErrorLevel, IncludeSite, PhpError, PhpValue};
+
+use super::Evaluator;
+
+/// The separator of the directories of the "include_path" setting.
+const INCLUDE_PATH_SEPARATOR: char = if cfg!(windows) { ';' } else { ':' };
+
+impl<'w> Evaluator<'w> {
+    /// Warns about the whitespace before the opening tag of an included file when
+    /// `warn_output_before_code` is set. It is output, so it is usually the reason
+    /// why headers cannot be sent anymore.
+    pub(crate) fn check_output_before_code(&mut self, ast: &[Statement]) -> Result<(), PhpError> {
+        if !self.warn_output_before_code {
+            return Ok(());
+        }
+
+        let Some(Statement::InlineHtml(html)) = ast.first() else {
+            return Ok(());
+        };
+
+        if !html.html.bytes.iter().all(u8::is_ascii_whitespace) {
+            return Ok(());
+        }
+
+        self.raise_error(PhpError {
+            level: ErrorLevel::Warning,
+            message: format!(
+                "{} bytes of whitespace are output before the opening tag",
+                html.html.bytes.len()
+            ),
+            line: 1,
+        })
+    }
+
+    /// Looks for a relative path in the directories of the "include_path" setting, then
+    /// in the directory of the current file, like PHP.
+    ///
+    /// Paths starting with "./" or "../" are only relative to the working directory,
+    /// on Windows `.\` and `..\` too. The directories are separated with ":", or ";" on Windows.
+    pub(super) fn resolve_include_path(&self, path: String) -> String {
+        let file_path = Path::new(&path);
+
+        let is_relative_to_working_directory = path.starts_with("./")
+            || path.starts_with("../")
+            || (cfg!(windows) && (path.starts_with(".\\") || path.starts_with("..\\")));
+
+        if file_path.is_absolute() || is_relative_to_working_directory {
+            return path;
+        }
+
+        let include_path = self.ini.get("include_path").unwrap_or(".");
+
+        for directory in include_path
+            .split(INCLUDE_PATH_SEPARATOR)
+            .filter(|directory| !directory.is_empty())
+        {
+            let candidate = Path::new(directory).join(&path);
+
+            if candidate.is_file() {
+                return candidate.to_string_lossy().to_string();
+            }
+        }
+
+        if let Some(directory) = Path::new(&self.current_file).parent() {
+            let candidate = directory.join(&path);
+
+            if candidate.is_file() {
+                return candidate.to_string_lossy().to_string();
+            }
+        }
+
+        path
+    }
+
+    /// Stops the program if an included file is larger than `max_source_size`, it is checked
+    /// before the file is read since a huge file and its AST may not fit in memory.
+    pub(super) fn check_source_size(
+        &self,
+        fn_name: &str,
+        path: &str,
+        span: Span,
+    ) -> Result<(), PhpError> {
+        if self.max_source_size == 0 {
+            return Ok(());
+        }
+
+        // a file that cannot be read fails later with its own error
+        let Ok(metadata) = fs::metadata(path) else {
+            return Ok(());
+        };
+
+        if metadata.len() <= self.max_source_size {
+            return Ok(());
+        }
+
+        Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "{}(): The file \"{}\" is {} bytes, more than the maximum source size of {} bytes",
+                fn_name,
+                path,
+                metadata.len(),
+                self.max_source_size
+            ),
+            line: span.line,
+        })
+    }
+
+    /// Warns that the file of an include or a require cannot be read, the reason is
+    /// written like PHP writes it, e.g. "No such file or directory", without the number
+    /// of the error of the system.
+    fn warn_failed_to_open_stream(
+        &mut self,
+        fn_name: &str,
+        path: &str,
+        error: &io::Error,
+        span: Span,
+    ) -> Result<(), PhpError> {
+        let reason = error.to_string();
+
+        let reason = match reason.find(" (os error ") {
+            Some(position) => &reason[..position],
+            None => &reason,
+        };
+
+        self.raise_error(PhpError {
+            level: ErrorLevel::Warning,
+            message: format!("{}({}): Failed to open stream: {}", fn_name, path, reason),
+            line: span.line,
+        })
+    }
+
+    pub(super) fn handle_include(
+        &mut self,
+        path: &Expression,
+        once: bool,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let path = self.eval_expression(&path)?;
+
+        let path_as_string = path.to_string();
+
+        if path_as_string.is_none() {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: format!(
+                    "{} to string conversion failed",
+                    path.type_name().error_name(),
+                ),
+                line: span.line,
+            })?;
+        }
+
+        let real_path = path_as_string.unwrap_or("".to_string());
+
+        if real_path.is_empty() {
+            let error = format!("Path cannot be empty");
+
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: error,
+                line: span.line,
+            });
+        }
+
+        let real_path = self.resolve_include_path(real_path);
+
+        let registry_key = included_file_key(&real_path);
+
+        if once && self.included_files.contains(&registry_key) {
+            return Ok(PhpValue::Bool(true));
+        }
+
+        let fn_name = if once { "include_once" } else { "include" };
+
+        self.check_source_size(fn_name, &real_path, span)?;
+
+        let content = fs::read_to_string(&real_path);
+
+        if let Err(error) = &content {
+            self.warn_failed_to_open_stream(fn_name, &real_path, error, span)?;
+
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: format!(
+                    "{}(): Failed opening '{}' for inclusion (include_path='{}')",
+                    fn_name,
+                    real_path,
+                    self.ini.get("include_path").unwrap_or_default()
+                ),
+                line: span.line,
+            })?;
+
+            return Ok(PhpValue::Bool(false));
+        }
+
+        self.included_files.push(registry_key);
+
+        let site = IncludeSite {
+            file: self.current_file.clone(),
+            line: span.line,
+            function: fn_name,
+        };
+
+        parse_php_file(self, &real_path, &content.unwrap(), site)
+    }
+
+    pub(super) fn handle_require(
+        &mut self,
+        path: &Expression,
+        once: bool,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let path = self.eval_expression(&path)?;
+
+        let path_as_string = path.to_string();
+
+        if path_as_string.is_none() {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: format!(
+                    "{} to string conversion failed",
+                    path.type_name().error_name(),
+                ),
+                line: span.line,
+            })?;
+        }
+
+        let real_path = path_as_string.unwrap_or("".to_string());
+
+        if real_path.is_empty() {
+            let error = format!("Path cannot be empty");
+
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: error,
+                line: span.line,
+            });
+        }
+
+        let real_path = self.resolve_include_path(real_path);
+
+        let registry_key = included_file_key(&real_path);
+
+        if once && self.included_files.contains(&registry_key) {
+            return Ok(PhpValue::Bool(true));
+        }
+
+        let fn_name = if once { "require_once" } else { "require" };
+
+        self.check_source_size(fn_name, &real_path, span)?;
+
+        let content = fs::read_to_string(&real_path);
+
+        if let Err(error) = &content {
+            self.warn_failed_to_open_stream(fn_name, &real_path, error, span)?;
+
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "{}(): Failed opening required '{}' (include_path='{}')",
+                    fn_name,
+                    real_path,
+                    self.ini.get("include_path").unwrap_or_default()
+                ),
+                line: span.line,
+            });
+        }
+
+        self.included_files.push(registry_key);
+
+        let site = IncludeSite {
+            file: self.current_file.clone(),
+            line: span.line,
+            function: fn_name,
+        };
+
+        parse_php_file(self, &real_path, &content.unwrap(), site)
+    }
+}
+
+/// The key of a file in the registry of the included files, that include_once and
+/// require_once look at. It is the canonical path of the file, so "a.php", "./a.php" and
+/// "dir/../a.php" are the same file, and on Windows `dir/a.php` and `dir\a.php` too.
+pub(super) fn included_file_key(path: &str) -> String {
+    match fs::canonicalize(path) {
+        Ok(canonical_path) => normalize_path(&canonical_path.to_string_lossy(), MAIN_SEPARATOR),
+        Err(_) => normalize_path(path, MAIN_SEPARATOR),
+    }
+}
+
+/// Removes the `\\?\` prefix that canonical paths have on Windows, `\\?\UNC\server`
+/// becomes `\\server`, and uses the same separator everywhere when it is a backslash.
+/// Slashes are kept when the separator is a slash, since a backslash is a valid character
+/// in the file names of the other platforms.
+fn normalize_path(path: &str, separator: char) -> String {
+    let path = if let Some(network_path) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", network_path)
+    } else {
+        path.strip_prefix(r"\\?\").unwrap_or(path).to_string()
+    };
+
+    if separator == '\\' {
+        path.replace('/', "\\")
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::normalize_path;
+
+    #[test]
+    fn the_verbatim_prefix_is_removed() {
+        assert_eq!(normalize_path(r"\\?\C:\www\a.php", '\\'), r"C:\www\a.php");
+        assert_eq!(
+            normalize_path(r"\\?\UNC\server\share\a.php", '\\'),
+            r"\\server\share\a.php"
+        );
+    }
+
+    #[test]
+    fn windows_paths_use_backslashes() {
+        assert_eq!(
+            normalize_path("C:/www/lib\\a.php", '\\'),
+            r"C:\www\lib\a.php"
+        );
+        assert_eq!(normalize_path(r"\\?\C:/www/a.php", '\\'), r"C:\www\a.php");
+    }
+
+    #[test]
+    fn other_paths_are_kept() {
+        assert_eq!(normalize_path("/var/www/a.php", '/'), "/var/www/a.php");
+        assert_eq!(
+            normalize_path("/var/www/a\\b.php", '/'),
+            "/var/www/a\\b.php"
+        );
+    }
+}
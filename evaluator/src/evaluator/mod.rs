@@ -0,0 +1,3050 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::io;
+use std::rc::{Rc, Weak};
+use std::str;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Instant, SystemTime};
+
+use php_parser_rs::lexer::byte_string::ByteString;
+use php_parser_rs::parser::ast::arguments::{Argument, ArgumentList, PositionalArgument};
+use php_parser_rs::parser::ast::data_type::Type;
+use php_parser_rs::parser::ast::declares::DeclareBody;
+use php_parser_rs::parser::ast::identifiers::Identifier;
+use php_parser_rs::parser::ast::modifiers::{PropertyModifierGroup, Visibility};
+
+use php_parser_rs::{
+    lexer::token::Span,
+    parser::ast::{
+        literals::Literal,
+        properties::PropertyEntry,
+        variables::{SimpleVariable, Variable},
+        ArrayIndexExpression, ArrayItem, ConstantFetchExpression, Expression,
+        MagicConstantExpression, NewExpression, NullsafePropertyFetchExpression,
+        PropertyFetchExpression, Statement, StaticMethodCallExpression,
+        StaticPropertyFetchExpression, StringPart,
+    },
+};
+
+use crate::conversion::{float_to_int, format_float, parse_integer_literal};
+use crate::helpers::{
+    contains_new_expression, display_name, display_string, display_variable_name,
+    get_span_from_expression, get_string_from_bytes, suggest_name, variable_key, variant_name,
+};
+use crate::ini::IniSettings;
+use crate::native::{get_native_function, native_function_names, NativeFunction};
+use crate::output::Output;
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_class::{ClassProperty, PhpClass, PhpInterface, PhpMethod, ResolvedMethod};
+use crate::php_value::{ClosureScope, IncludeSite, PhpCallable, PhpObject, ReportedError};
+use crate::program::{Clock, DiagnosticsSink, DEFAULT_MAX_SOURCE_SIZE};
+use crate::trace::{summarize_value, TraceEvent, TraceSink};
+use crate::{
+    environment::Environment,
+    helpers::get_span_from_var,
+    php_value::{ErrorLevel, PhpError, PhpValue},
+};
+
+use self::control_flow::LoopJump;
+
+mod assignments;
+mod closures;
+mod constants;
+mod control_flow;
+mod declarations;
+mod enums;
+mod exceptions;
+mod includes;
+mod operations;
+mod output;
+mod references;
+mod scopes;
+mod superglobals;
+mod types;
+
+const NULL: PhpValue = PhpValue::Null;
+
+pub struct Evaluator<'w> {
+    /// The output of the evaluated code and where it goes
+    pub output: Output<'w>,
+
+    /// Whether the PHP code is currently "open"
+    php_open: bool,
+
+    /// Whether the PHP code must die
+    pub die: bool,
+
+    /// The exit status given to exit() or die()
+    pub exit_code: i32,
+
+    /// The environment of the code
+    pub env: Environment,
+
+    /// The reported errors, only kept when there is no diagnostics sink or when it is asked for
+    pub warnings: Vec<ReportedError>,
+
+    /// Receives the errors as soon as they are reported
+    diagnostics: Option<Rc<RefCell<dyn DiagnosticsSink>>>,
+
+    /// Whether the reported errors are kept in `warnings` even if there is a diagnostics sink
+    collect_warnings: bool,
+
+    /// How many `@` operators are being evaluated, the errors are not reported while it is not 0
+    silenced: usize,
+
+    /// The canonical paths of the files read by include and require, that the `_once`
+    /// variants skip. It is shared with the included files.
+    pub included_files: Vec<String>,
+
+    /// The files that declare strict_types=1, the calls made from them do not convert
+    /// their arguments nor the return value. It is shared with the included files.
+    pub strict_files: Vec<String>,
+
+    /// The objects and the closures that were created, the one with the id `n` is at `n - 1`.
+    /// The id of a destroyed object is given to the next new object, like PHP
+    /// reuses the handles. It is shared with the included files.
+    pub objects: Vec<Weak<dyn Any>>,
+
+    /// The variables of the static statements, by the file and the position of their
+    /// declaration, they keep their value between the calls. It is shared with the
+    /// included files.
+    pub static_variables: HashMap<(String, usize), Rc<RefCell<PhpValue>>>,
+
+    /// The functions registered with register_shutdown_function()
+    pub shutdown_functions: Vec<ShutdownFunction>,
+
+    /// Whether the shutdown functions are being executed
+    shutting_down: bool,
+
+    /// The file being evaluated
+    pub current_file: String,
+
+    /// The include and require expressions that led to the file being evaluated, the
+    /// outermost first, it is empty for the main script
+    pub include_stack: Vec<IncludeSite>,
+
+    /// The fatal error that stopped an included file, it goes up to the main script
+    /// with the file where it was raised
+    pub included_fatal: Option<ReportedError>,
+
+    /// The file and the line of the first output, returned by headers_sent().
+    /// It is shared with the included files.
+    pub output_start: Option<(String, usize)>,
+
+    /// The line where the inline HTML that comes next starts, the one of the last closing tag
+    html_line: usize,
+
+    /// Whether included files that start with whitespace before their opening tag get a warning
+    pub warn_output_before_code: bool,
+
+    /// Whether the messages of the fatal errors go to the diagnostics sink instead of the output
+    pub fatal_errors_to_diagnostics: bool,
+
+    /// The value of `__COMPILER_HALT_OFFSET__`, set when the file has a `__halt_compiler()`
+    halt_compiler_offset: Option<usize>,
+
+    /// The handlers set with set_error_handler(), the last one is the active one
+    pub error_handlers: Vec<ErrorHandler>,
+
+    /// Whether an error handler is being executed
+    handling_error: bool,
+
+    /// The value of the last executed return statement, until the function that returns takes it
+    pub return_value: Option<PhpValue>,
+
+    /// The `break` or `continue` being executed, until the switch that it leaves takes it
+    loop_jump: Option<LoopJump>,
+
+    /// How many switches the statement being executed is in, inside the current function
+    loop_depth: usize,
+
+    /// The object of the exception being thrown, until a catch takes it
+    pub thrown: Option<Rc<RefCell<PhpObject>>>,
+
+    /// The class that declares the method being executed, it is the class of `self::`
+    current_class: Option<Rc<PhpClass>>,
+
+    /// The class of the object the method was called on, it is the class of `static::`
+    static_class: Option<Rc<PhpClass>>,
+
+    /// The settings read with ini_get() and changed with ini_set()
+    pub ini: IniSettings,
+
+    /// The largest file that include and require read, in bytes, 0 means no limit
+    pub max_source_size: u64,
+
+    /// Receives every executed statement, call and included file
+    tracer: Option<Rc<RefCell<dyn TraceSink>>>,
+
+    /// How many calls and included files the code being executed is nested in
+    trace_depth: usize,
+
+    /// The wall clock of time() and microtime(), the system clock when there is none
+    pub clock: Option<Rc<dyn Clock>>,
+
+    /// When the evaluation started, hrtime() gives the time elapsed since then.
+    /// It is shared with the included files.
+    pub started_at: Instant,
+
+    /// When it is set, the program stops before its next statement
+    pub interrupted: Option<Arc<AtomicBool>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ErrorHandler {
+    pub callback: PhpValue,
+    pub error_levels: i64,
+}
+
+#[derive(Debug, Clone)]
+pub struct ShutdownFunction {
+    pub callback: PhpValue,
+    pub arguments: Vec<PhpValue>,
+    pub span: Span,
+}
+
+/// The evaluated arguments of a call to a user defined function or method.
+struct CallArguments {
+    positional: Vec<PhpValue>,
+
+    /// The arguments passed by name, like `f(size: 10)`, with the name of their parameter.
+    named: Vec<(ByteString, PhpValue)>,
+
+    /// The variables and the array elements given to the parameters passed by reference,
+    /// with the name of their parameter.
+    references: Vec<(Vec<u8>, Rc<RefCell<PhpValue>>)>,
+}
+
+impl CallArguments {
+    fn new(positional: Vec<PhpValue>) -> CallArguments {
+        CallArguments {
+            positional,
+            named: vec![],
+            references: vec![],
+        }
+    }
+}
+
+/// The user defined function or method whose arguments are evaluated, its parameters
+/// tell which arguments are passed by reference.
+#[derive(Clone, Copy)]
+struct Callee<'a> {
+    function: &'a PhpCallable,
+
+    /// The class that declares the method, None for a function.
+    class_name: Option<&'a ByteString>,
+}
+
+impl<'w> Evaluator<'w> {
+    pub fn new(current_file: &str) -> Evaluator<'w> {
+        let mut evaluator = Evaluator {
+            output: Output::new(),
+            php_open: false,
+            die: false,
+            exit_code: 0,
+            env: Environment::new(),
+            warnings: vec![],
+            diagnostics: None,
+            collect_warnings: true,
+            silenced: 0,
+            included_files: vec![],
+            strict_files: vec![],
+            objects: vec![],
+            static_variables: HashMap::new(),
+            shutdown_functions: vec![],
+            shutting_down: false,
+            current_file: current_file.to_string(),
+            include_stack: vec![],
+            included_fatal: None,
+            output_start: None,
+            html_line: 1,
+            warn_output_before_code: false,
+            fatal_errors_to_diagnostics: false,
+            halt_compiler_offset: None,
+            error_handlers: vec![],
+            handling_error: false,
+            return_value: None,
+            loop_jump: None,
+            loop_depth: 0,
+            thrown: None,
+            current_class: None,
+            static_class: None,
+            ini: IniSettings::new(),
+            max_source_size: DEFAULT_MAX_SOURCE_SIZE,
+            tracer: None,
+            trace_depth: 0,
+            clock: None,
+            started_at: Instant::now(),
+            interrupted: None,
+        };
+
+        evaluator.declare_throwable_classes();
+
+        evaluator
+    }
+
+    /// Returns a new child evaluator based on the current evaluator.
+    ///
+    /// This is only used with include and require statements, `site` is the expression
+    /// that includes `current_file`. The child has no output, the output of the parent
+    /// is moved to it while it runs.
+    pub fn new_child(&self, current_file: &str, site: IncludeSite) -> Evaluator<'w> {
+        let mut include_stack = self.include_stack.clone();
+
+        include_stack.push(site);
+
+        Evaluator {
+            output: Output::new(),
+            php_open: false,
+            die: false,
+            exit_code: self.exit_code,
+            env: self.env.clone(),
+            warnings: vec![],
+            diagnostics: self.diagnostics.clone(),
+            collect_warnings: self.collect_warnings,
+            silenced: self.silenced,
+            included_files: self.included_files.clone(),
+            strict_files: self.strict_files.clone(),
+            objects: self.objects.clone(),
+            static_variables: self.static_variables.clone(),
+            shutdown_functions: vec![],
+            shutting_down: false,
+            current_file: current_file.to_string(),
+            include_stack,
+            included_fatal: None,
+            output_start: self.output_start.clone(),
+            html_line: 1,
+            warn_output_before_code: self.warn_output_before_code,
+            fatal_errors_to_diagnostics: self.fatal_errors_to_diagnostics,
+            halt_compiler_offset: None,
+            error_handlers: self.error_handlers.clone(),
+            handling_error: self.handling_error,
+            return_value: None,
+            loop_jump: None,
+            loop_depth: 0,
+            thrown: None,
+            current_class: None,
+            static_class: None,
+            ini: self.ini.clone(),
+            max_source_size: self.max_source_size,
+            tracer: self.tracer.clone(),
+            trace_depth: self.trace_depth,
+            clock: self.clock.clone(),
+            started_at: self.started_at,
+            interrupted: self.interrupted.clone(),
+        }
+    }
+
+    /// The current date and time, read from the clock of the options if there is one.
+    pub fn now(&self) -> SystemTime {
+        match &self.clock {
+            Some(clock) => clock.now(),
+            None => SystemTime::now(),
+        }
+    }
+
+    /// Sends the reported errors to `sink` when they happen.
+    ///
+    /// The errors are only kept in `warnings` too if `collect_warnings` is true.
+    pub fn set_diagnostics_sink(
+        &mut self,
+        sink: Rc<RefCell<dyn DiagnosticsSink>>,
+        collect_warnings: bool,
+    ) {
+        self.diagnostics = Some(sink);
+        self.collect_warnings = collect_warnings;
+    }
+
+    /// Sends every executed statement, call and included file to `sink`.
+    pub fn set_trace_sink(&mut self, sink: Rc<RefCell<dyn TraceSink>>) {
+        self.tracer = Some(sink);
+    }
+
+    /// Traces the start of an included file, the code of the file is one level deeper.
+    pub fn trace_enter_file(&mut self, file: &str) {
+        self.trace(&TraceEvent::EnterFile { file });
+
+        self.trace_depth += 1;
+    }
+
+    /// Traces the end of an included file.
+    pub fn trace_leave_file(&mut self, file: &str) {
+        self.trace_depth -= 1;
+
+        self.trace(&TraceEvent::LeaveFile { file });
+    }
+
+    /// Tells the diagnostics sink, if there is one, that the program stops because of `error`.
+    ///
+    /// The pending output is not flushed before, the error message is added to it.
+    pub fn report_fatal(&self, error: &PhpError) {
+        if let Some(sink) = &self.diagnostics {
+            sink.borrow_mut().on_fatal(error);
+        }
+    }
+
+    /// Writes the message of an error that stopped the program, a file or a shutdown
+    /// function after the output, or gives it to the sink when `fatal_errors_to_diagnostics`
+    /// is set.
+    pub fn write_fatal_message(&mut self, message: &str) {
+        if let (true, Some(sink)) = (self.fatal_errors_to_diagnostics, &self.diagnostics) {
+            sink.borrow_mut().on_fatal_message(message);
+
+            return;
+        }
+
+        self.output.write(&format!("{}\n", message));
+    }
+
+    /// Writes the pending output to the output target and flushes the target.
+    ///
+    /// Without a target the output is kept until the program ends.
+    pub fn flush_output(&mut self) -> io::Result<()> {
+        self.output.flush()
+    }
+
+    fn trace(&self, event: &TraceEvent) {
+        if let Some(tracer) = &self.tracer {
+            tracer.borrow_mut().on_event(event, self.trace_depth);
+        }
+    }
+
+    /// Traces a statement with the line of its first token.
+    ///
+    /// The tags and the blocks are skipped, a block is traced through its statements.
+    fn trace_statement(&self, statement: &Statement) {
+        if let Statement::FullOpeningTag(_)
+        | Statement::ShortOpeningTag(_)
+        | Statement::EchoOpeningTag(_)
+        | Statement::ClosingTag(_)
+        | Statement::Block(_) = statement
+        {
+            return;
+        }
+
+        let Some(span) = statement.first_span() else {
+            return;
+        };
+
+        self.trace(&TraceEvent::Statement {
+            file: &self.current_file,
+            line: span.line,
+        });
+    }
+
+    /// Registers the functions and classes declared at the top level of a program before it runs,
+    /// so they can be called before their declaration, and returns the remaining statements.
+    ///
+    /// Declarations nested inside a conditional, a loop or a function body are not hoisted,
+    /// they are registered when their statement is executed.
+    pub fn hoist_declarations(
+        &mut self,
+        statements: Vec<Statement>,
+    ) -> Result<Vec<Statement>, PhpError> {
+        self.read_strict_types(&statements)?;
+
+        let mut remaining_statements = vec![];
+
+        for statement in statements {
+            // __COMPILER_HALT_OFFSET__ can be used before __halt_compiler()
+            if let Statement::HaltCompiler(halt) = &statement {
+                self.halt_compiler_offset = Some(halt.offset);
+            }
+
+            let can_be_hoisted = match &statement {
+                Statement::Function(_) => true,
+                // a class can only be declared once its parent and its interfaces are
+                Statement::Class(class) => {
+                    class.extends.as_ref().is_none_or(|extends| {
+                        self.env.get_class(&extends.parent.value.bytes).is_some()
+                    }) && class.implements.as_ref().is_none_or(|implements| {
+                        implements
+                            .iter()
+                            .all(|name| self.env.get_interface(&name.value).is_some())
+                    })
+                }
+                Statement::Interface(interface) => {
+                    interface.extends.as_ref().is_none_or(|extends| {
+                        extends
+                            .parents
+                            .iter()
+                            .all(|name| self.env.get_interface(&name.value).is_some())
+                    })
+                }
+                Statement::UnitEnum(statement) => statement
+                    .implements
+                    .iter()
+                    .all(|name| self.env.get_interface(&name.value).is_some()),
+                Statement::BackedEnum(statement) => statement
+                    .implements
+                    .iter()
+                    .all(|name| self.env.get_interface(&name.value).is_some()),
+                _ => false,
+            };
+
+            if can_be_hoisted {
+                self.eval_statement(statement)?;
+            } else {
+                remaining_statements.push(statement);
+            }
+        }
+
+        Ok(remaining_statements)
+    }
+
+    /// Evaluates a statement, the Errors of the engine raised by it are thrown as objects
+    /// so that a catch can take them.
+    pub fn eval_statement(&mut self, node: Statement) -> Result<PhpValue, PhpError> {
+        self.eval_statement_node(node)
+            .map_err(|error| self.throw_engine_error(error))
+    }
+
+    fn eval_statement_node(&mut self, node: Statement) -> Result<PhpValue, PhpError> {
+        let is_interrupted = self
+            .interrupted
+            .as_ref()
+            .is_some_and(|interrupted| interrupted.load(Ordering::SeqCst));
+
+        if is_interrupted && !self.shutting_down {
+            self.die();
+
+            return Ok(NULL);
+        }
+
+        if self.tracer.is_some() {
+            self.trace_statement(&node);
+        }
+
+        match node {
+            Statement::FullOpeningTag(_) => {
+                self.php_open = true;
+
+                Ok(NULL)
+            }
+            Statement::ClosingTag(tag) => {
+                self.php_open = false;
+                self.html_line = tag.span.line;
+
+                Ok(NULL)
+            }
+            Statement::InlineHtml(html) => {
+                self.write_output(&get_string_from_bytes(&html.html), self.html_line)?;
+
+                Ok(NULL)
+            }
+            // the value is the result of the program when it is its last statement
+            Statement::Expression(e) => self.eval_expression(&e.expression),
+            Statement::Echo(echo) => self.eval_echo(echo),
+            Statement::Function(function) => self.declare_function(function),
+            Statement::Constant(statement) => self.eval_constant_statement(statement),
+            Statement::Class(class) => self.declare_class(class),
+            Statement::Interface(interface) => self.declare_interface(interface),
+            Statement::UnitEnum(statement) => self.declare_unit_enum(statement),
+            Statement::BackedEnum(statement) => self.declare_backed_enum(statement),
+            Statement::Return(statement) => self.eval_return(statement),
+            Statement::Block(block) => self.eval_statements(block.statements),
+            Statement::If(statement) => self.eval_if_statement(statement),
+            Statement::Switch(statement) => self.eval_switch_statement(statement),
+            Statement::Break(statement) => self.eval_break(statement),
+            Statement::Continue(statement) => self.eval_continue(statement),
+            Statement::Try(statement) => self.eval_try_statement(statement),
+            Statement::Global(statement) => self.eval_global_statement(statement),
+            Statement::Static(statement) => self.eval_static_statement(statement),
+            // the parser stopped there, the data after it is neither executed nor output
+            Statement::HaltCompiler(_) => Ok(NULL),
+            Statement::Comment(_) | Statement::Noop(_) => Ok(NULL),
+            // strict_types was read before the file runs, the other directives have no effect
+            Statement::Declare(declare) => match declare.body {
+                DeclareBody::Noop { .. } => Ok(NULL),
+                DeclareBody::Braced { statements, .. } | DeclareBody::Block { statements, .. } => {
+                    self.eval_statements(statements)
+                }
+                DeclareBody::Expression { expression, .. } => {
+                    self.eval_expression(&expression)?;
+
+                    Ok(NULL)
+                }
+            },
+            // like loops and namespaces, which are not implemented yet
+            statement => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!("Unsupported statement: {}", variant_name(&statement)),
+                line: statement.first_span().map_or(0, |span| span.line),
+            }),
+        }
+    }
+
+    fn eval_expression(&mut self, expr: &Expression) -> Result<PhpValue, PhpError> {
+        debug_assert!(
+            !self.env.has_borrowed_vars(),
+            "a variable is still borrowed while evaluating an expression"
+        );
+
+        match expr {
+            Expression::Empty(ee) => {
+                // empty() does not warn about undefined variables, elements or properties
+                let value = self.get_isset_value(&ee.argument.argument.value)?;
+
+                Ok(PhpValue::Bool(
+                    value.is_none_or(|value| !value.to_php_bool()),
+                ))
+            }
+            Expression::Die(die) => self.eval_exit(die.argument.as_deref(), die.die),
+            Expression::Exit(exit) => self.eval_exit(exit.argument.as_deref(), exit.exit),
+            Expression::Isset(ie) => {
+                for variable in &ie.variables {
+                    // a variable set to null is defined, but not set
+                    match self.get_isset_value(variable)? {
+                        None | Some(PhpValue::Null) => return Ok(PhpValue::Bool(false)),
+                        Some(_) => {}
+                    }
+                }
+
+                Ok(PhpValue::Bool(true))
+            }
+            Expression::Unset(ue) => {
+                let args = &ue.variables;
+
+				for arg in args {
+					let var_name = self.get_variable_name(arg)?;
+
+					self.env.delete_var(&var_name);
+				}
+
+                Ok(NULL)
+            }
+            Expression::Print(pe) => {
+                if pe.value.is_some() {
+                    let expr = *pe.value.clone().unwrap();
+
+                    let value = self.eval_expression(&expr)?;
+
+                    let value_as_string = self.value_to_output_string(value, pe.print)?;
+
+                    self.write_output(&value_as_string, pe.print.line)?;
+                } else if pe.argument.is_some() {
+                    let arg = *pe.argument.clone().unwrap();
+
+                    let value = self.eval_expression(&arg.argument.value)?;
+
+                    let value_as_string = self.value_to_output_string(value, pe.print)?;
+
+                    self.write_output(&value_as_string, pe.print.line)?;
+                }
+
+                Ok(PhpValue::Int(1))
+            }
+            Expression::Literal(l) => match l {
+                Literal::String(s) => Ok(PhpValue::String(s.value.clone())),
+                Literal::Integer(i) => {
+                    let str_value = str::from_utf8(i.value.as_ref()).unwrap();
+
+                    Ok(parse_integer_literal(str_value))
+                }
+                Literal::Float(f) => {
+                    let str_value = str::from_utf8(f.value.as_ref()).unwrap();
+
+                    let float_value: f64 = str_value.parse().unwrap();
+
+                    Ok(PhpValue::Float(float_value))
+                }
+            },
+            Expression::ArithmeticOperation(operation) => self.eval_arithmetic_operation(operation),
+            Expression::AssignmentOperation(operation) => self.eval_assignment_operation(operation),
+            Expression::BitwiseOperation(operation) => self.eval_bitwise_operation(operation),
+            Expression::ComparisonOperation(operation) => self.eval_comparison_operation(operation),
+            Expression::LogicalOperation(operation) => self.eval_logical_operation(operation),
+            Expression::Concat(expression) => {
+                let left_value = self.eval_expression(&expression.left)?;
+                let right_value = self.eval_expression(&expression.right)?;
+
+                let left_string = self.value_to_output_string(left_value, expression.dot)?;
+                let right_string = self.value_to_output_string(right_value, expression.dot)?;
+
+                Ok(PhpValue::String((left_string + &right_string).into()))
+            }
+            Expression::Instanceof(instanceof) => {
+                let left_expr_value = self.eval_expression(&instanceof.left)?;
+
+                let left_object = match left_expr_value {
+                    PhpValue::Object(object) => Some(object),
+                    PhpValue::Callable(_) => None,
+                    _ => {
+                        let error = format!(
+                            "Left side of instanceof must be an object, got {}",
+                            left_expr_value.type_name().error_name()
+                        );
+
+                        return Err(PhpError {
+                            level: ErrorLevel::Fatal,
+                            message: error,
+                            line: instanceof.instanceof.line,
+                        });
+                    }
+                };
+
+                let class_name = match &*instanceof.right {
+                    Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => {
+                        identifier.value.bytes.clone()
+                    }
+                    right => match self.eval_expression(right)? {
+                        PhpValue::Object(object) => object.borrow().class.name.bytes.clone(),
+                        PhpValue::String(name) => name.bytes,
+                        _ => {
+                            return Err(PhpError {
+                                level: ErrorLevel::Fatal,
+                                message: "Class name must be a valid object or a string"
+                                    .to_string(),
+                                line: instanceof.instanceof.line,
+                            })
+                        }
+                    },
+                };
+
+                // the closures are instances of the Closure class only
+                let is_instance_of = match left_object {
+                    Some(object) => object.borrow().is_instance_of(&class_name),
+                    None => class_name.eq_ignore_ascii_case(b"Closure"),
+                };
+
+                Ok(PhpValue::Bool(is_instance_of))
+            }
+            Expression::Reference(reference) => {
+                let error = format!("Unexpected reference expression",);
+
+                Err(PhpError {
+                    level: ErrorLevel::ParseError,
+                    message: error,
+                    line: reference.ampersand.line,
+                })
+            }
+            Expression::Parenthesized(parenthesized) => self.eval_expression(&parenthesized.expr),
+            Expression::ErrorSuppress(error_expression) => {
+                let old_php_die = self.die;
+
+                self.silenced += 1;
+
+                let result = self.eval_expression(&error_expression.expr);
+
+                self.silenced -= 1;
+
+                let value = result?;
+
+                self.die = old_php_die;
+
+                Ok(value)
+            }
+            Expression::Identifier(identifier) => match identifier {
+                Identifier::SimpleIdentifier(simple_identifier) => {
+                    let identifier_name = &simple_identifier.value;
+
+                    match self.get_constant(identifier_name) {
+                        Some(value) => Ok(value),
+                        None => {
+                            Err(self.undefined_constant(identifier_name, simple_identifier.span))
+                        }
+                    }
+                }
+                _ => Err(unsupported_expression(expr)),
+            },
+            Expression::Variable(var) => self.get_var(var),
+            Expression::Include(include) => {
+                self.handle_include(&include.path, false, include.include)
+            }
+            Expression::IncludeOnce(include) => {
+                self.handle_include(&include.path, true, include.include_once)
+            }
+            Expression::Require(require) => {
+                self.handle_require(&require.path, false, require.require)
+            }
+            Expression::RequireOnce(require) => {
+                self.handle_require(&require.path, true, require.require_once)
+            }
+            Expression::FunctionCall(call) => {
+                let span = call.arguments.left_parenthesis;
+
+                let target = match call.target.as_ref() {
+                    Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => {
+                        PhpValue::String(identifier.value.clone())
+                    }
+                    target => self.eval_expression(target)?,
+                };
+
+                // a closure of a native function is called like the function, so the
+                // arguments passed by reference are written back
+                if let PhpValue::Callable(function) = &target {
+                    if function
+                        .closure
+                        .as_ref()
+                        .is_none_or(|closure| closure.native_function.is_none())
+                    {
+                        let callee = Callee {
+                            function,
+                            class_name: None,
+                        };
+
+                        let arguments = self.eval_arguments(&call.arguments, Some(callee))?;
+
+                        return self.execute_plain_function(function.clone(), arguments, span);
+                    }
+                }
+
+                if let Some((object, name)) = match &target {
+                    PhpValue::Array(array) => get_callable_method(array),
+                    _ => None,
+                } {
+                    let arguments = self.eval_arguments(&call.arguments, None)?;
+
+                    return self.call_object_method(
+                        object,
+                        &get_string_from_bytes(&name),
+                        arguments.positional,
+                        span,
+                    );
+                }
+
+                let function_name = self.get_function_name(target, span)?;
+
+                let user_function = match self.env.get_identifier(&function_name) {
+                    Some(PhpValue::Callable(function)) => Some(function),
+                    _ => None,
+                };
+
+                let native_function = match user_function {
+                    Some(_) => None,
+                    None => get_native_function(&function_name),
+                };
+
+                if let Some(native_function) = native_function {
+                    self.define_referenced_variables(native_function, &call.arguments.arguments);
+                }
+
+                let callee = user_function.as_ref().map(|function| Callee {
+                    function,
+                    class_name: None,
+                });
+
+                let arguments = self.eval_arguments(&call.arguments, callee)?;
+
+                if let Some(function) = user_function {
+                    return self.execute_plain_function(function, arguments, span);
+                }
+
+                let Some(native_function) = native_function else {
+                    return self.call_function(&function_name, arguments.positional, span);
+                };
+
+                if !arguments.named.is_empty() {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "Named arguments are not supported by native functions"
+                            .to_string(),
+                        line: span.line,
+                    });
+                }
+
+                self.call_native_function(
+                    native_function,
+                    &call.arguments.arguments,
+                    arguments.positional,
+                    span,
+                )
+            }
+            Expression::New(new) => self.eval_new(new),
+            Expression::MethodCall(call) => {
+                let target = self.eval_expression(&call.target)?;
+
+                self.eval_method_call(target, &call.method, &call.arguments)
+            }
+            Expression::NullsafeMethodCall(call) => {
+                let target = self.eval_expression(&call.target)?;
+
+                if target.is_null() {
+                    return Ok(NULL);
+                }
+
+                self.eval_method_call(target, &call.method, &call.arguments)
+            }
+            Expression::ConstantFetch(fetch) => self.eval_constant_fetch(fetch),
+            Expression::StaticMethodCall(call) => self.eval_static_method_call(call),
+            Expression::StaticPropertyFetch(fetch) => {
+                let property = self.get_static_property(fetch)?;
+
+                let value = property.borrow().clone();
+
+                Ok(value)
+            }
+            Expression::PropertyFetch(fetch) => {
+                let target = self.eval_expression(&fetch.target)?;
+
+                self.eval_property_fetch(target, &fetch.property, fetch.arrow)
+            }
+            Expression::NullsafePropertyFetch(fetch) => {
+                let target = self.eval_expression(&fetch.target)?;
+
+                if target.is_null() {
+                    return Ok(NULL);
+                }
+
+                self.eval_property_fetch(target, &fetch.property, fetch.question_arrow)
+            }
+            Expression::Array(array) => self.eval_array_items(&array.items.inner, array.array),
+            Expression::ShortArray(array) => self.eval_array_items(&array.items.inner, array.start),
+            Expression::Bool(b) => Ok(PhpValue::Bool(b.value)),
+            Expression::Null => Ok(NULL),
+            Expression::MagicConstant(MagicConstantExpression::File(_)) => {
+                Ok(PhpValue::String(self.get_file_constant().into()))
+            }
+            Expression::MagicConstant(MagicConstantExpression::Directory(_)) => {
+                Ok(PhpValue::String(self.get_directory_constant().into()))
+            }
+            Expression::MagicConstant(MagicConstantExpression::Line(span)) => {
+                Ok(PhpValue::Int(span.line as i64))
+            }
+            Expression::MagicConstant(MagicConstantExpression::CompilerHaltOffset(span)) => {
+                match self.halt_compiler_offset {
+                    Some(offset) => Ok(PhpValue::Int(offset as i64)),
+                    None => Err(self.undefined_constant(b"__COMPILER_HALT_OFFSET__", *span)),
+                }
+            }
+            Expression::ArrayIndex(index) => self.eval_array_index(index),
+            Expression::InterpolatedString(string) => self.eval_string_parts(&string.parts),
+            Expression::Heredoc(heredoc) => self.eval_string_parts(&heredoc.parts),
+            Expression::Nowdoc(nowdoc) => Ok(PhpValue::String(nowdoc.value.clone())),
+            Expression::Closure(closure) => self.eval_closure(closure),
+            Expression::ArrowFunction(function) => self.eval_arrow_function(function),
+            Expression::FunctionClosureCreation(creation) => {
+                self.eval_function_closure_creation(creation)
+            }
+            Expression::MethodClosureCreation(creation) => {
+                self.eval_method_closure_creation(creation)
+            }
+            Expression::StaticMethodClosureCreation(creation) => {
+                self.eval_static_method_closure_creation(creation)
+            }
+            Expression::ShortTernary(ternary) => self.eval_short_ternary(ternary),
+            Expression::Ternary(ternary) => self.eval_ternary(ternary),
+            Expression::Coalesce(coalesce) => self.eval_coalesce(coalesce),
+            Expression::Match(r#match) => self.eval_match(r#match),
+            Expression::Throw(throw) => self.eval_throw(throw),
+            // like eval(), casts and clone, which are not implemented yet
+            _ => Err(unsupported_expression(expr)),
+        }
+    }
+
+    /// Calls a function by its name.
+    ///
+    /// User defined functions are looked up first and then the native ones.
+    pub fn call_function(
+        &mut self,
+        name: &[u8],
+        arguments: Vec<PhpValue>,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        if let Some(PhpValue::Callable(function)) = self.env.get_identifier(name) {
+            return self.call_user_function(function, arguments, span);
+        }
+
+        if let Some(native_function) = get_native_function(name) {
+            let mut arguments = arguments;
+
+            return (native_function.function)(self, &mut arguments, span);
+        }
+
+        Err(self.undefined_function(name, span))
+    }
+
+    /// The error of a call to a function that does not exist, it suggests the user defined
+    /// or native function with the closest name, if any.
+    fn undefined_function(&self, name: &[u8], span: Span) -> PhpError {
+        let candidates = self
+            .env
+            .function_names()
+            .chain(native_function_names().map(|name| name.as_bytes()));
+
+        let message = match suggest_name(name, candidates) {
+            Some(suggestion) => format!(
+                "Call to undefined function {}(), did you mean {}()?",
+                display_name(name),
+                display_name(suggestion)
+            ),
+            None => format!("Call to undefined function {}()", display_name(name)),
+        };
+
+        PhpError {
+            level: ErrorLevel::EngineError("Error"),
+            message,
+            line: span.line,
+        }
+    }
+
+    /// Calls a callable value, which is a function, the name of a function, or an array
+    /// with an object and the name of one of its methods, like `[$object, 'method']`.
+    pub fn call_callable(
+        &mut self,
+        callable: &PhpValue,
+        arguments: Vec<PhpValue>,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        match callable {
+            PhpValue::Callable(function) => {
+                self.call_user_function(function.clone(), arguments, span)
+            }
+            PhpValue::String(name) => self.call_function(&name.bytes, arguments, span),
+            PhpValue::Array(array) if get_callable_method(array).is_some() => {
+                let (object, name) = get_callable_method(array).unwrap();
+
+                self.call_object_method(object, &get_string_from_bytes(&name), arguments, span)
+            }
+            _ => Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Value of type {} is not callable",
+                    callable.type_name().error_name()
+                ),
+                line: span.line,
+            }),
+        }
+    }
+
+    /// Calls a method of an object by its name, for the natives that use the methods
+    /// of the predefined interfaces, like count() with Countable::count().
+    pub fn call_object_method(
+        &mut self,
+        object: Rc<RefCell<PhpObject>>,
+        name: &str,
+        arguments: Vec<PhpValue>,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let class = Rc::clone(&object.borrow().class);
+
+        let Some(resolved) = class.resolve_method(name.as_bytes()) else {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!("Call to undefined method {}::{}()", class.name, name),
+                line: span.line,
+            });
+        };
+
+        self.check_method_visibility(&resolved, span)?;
+
+        let method = resolved.method.clone();
+
+        let arguments = CallArguments::new(arguments);
+
+        self.call_method(object, method, arguments, span)
+    }
+
+    /// Whether the value can be called with call_callable().
+    pub fn is_callable(&self, value: &PhpValue) -> bool {
+        match value {
+            PhpValue::Callable(_) => true,
+            PhpValue::String(name) => {
+                matches!(self.env.get_identifier(&name.bytes), Some(PhpValue::Callable(_)))
+                    || get_native_function(&name.bytes).is_some()
+            }
+            PhpValue::Array(array) => get_callable_method(array)
+                .is_some_and(|(object, name)| object.borrow().class.get_method(&name).is_some()),
+            _ => false,
+        }
+    }
+
+    /// Converts a value to an array key, this must be used everywhere an array is indexed
+    /// so that reads and writes always agree on the key.
+    pub fn get_array_key(&mut self, value: &PhpValue, span: Span) -> Result<ArrayKey, PhpError> {
+        // the same conversion as the key, so the floats that wrap around are deprecated too
+        if let PhpValue::Float(f) = value {
+            if float_to_int(*f) as f64 != *f {
+                self.raise_error(PhpError {
+                    level: ErrorLevel::Deprecated,
+                    message: format!(
+                        "Implicit conversion from float {} to int loses precision",
+                        format_float(*f, -1)
+                    ),
+                    line: span.line,
+                })?;
+            }
+        }
+
+        ArrayKey::from_value(value).map_err(|mut error| {
+            error.line = span.line;
+
+            error
+        })
+    }
+
+    /// Keeps the fatal error raised in `file`, when it is not the current file, in
+    /// `included_fatal` like an included file does, so that it is reported with its file.
+    fn keep_fatal_of_other_file<T>(&mut self, result: &Result<T, PhpError>, file: &str) {
+        let Err(error) = result else {
+            return;
+        };
+
+        if file == self.current_file
+            || self.included_fatal.is_some()
+            || matches!(error.level, ErrorLevel::Exception | ErrorLevel::Raw)
+        {
+            return;
+        }
+
+        self.report_fatal(error);
+
+        self.included_fatal = Some(ReportedError {
+            error: error.clone(),
+            file: file.to_string(),
+            include_stack: self.include_stack.clone(),
+        });
+    }
+
+    /// Runs the functions registered with register_shutdown_function(), in registration order.
+    ///
+    /// They run once the script ended, normally, with exit() or with a fatal error.
+    /// An error in one of them does not prevent the others from running, but exit() does.
+    /// Functions registered while shutting down are executed too, and they are all removed
+    /// once they ran.
+    ///
+    /// Returns the last fatal error of the functions, the process exits with an error after it.
+    pub fn run_shutdown_functions(&mut self, input: &str) -> Option<PhpError> {
+        self.shutting_down = true;
+        self.die = false;
+
+        let mut fatal_error = None;
+
+        let mut index = 0;
+
+        while index < self.shutdown_functions.len() {
+            let shutdown_function = self.shutdown_functions[index].clone();
+
+            index += 1;
+
+            let result = self
+                .call_callable(
+                    &shutdown_function.callback,
+                    shutdown_function.arguments,
+                    shutdown_function.span,
+                )
+                .map_err(|error| self.throw_engine_error(error));
+
+            if let Err(error) = result {
+                let message = match self.included_fatal.take() {
+                    Some(fatal) => fatal.get_message_with_stack_trace(),
+                    None => {
+                        self.report_fatal(&error);
+
+                        error.clone().get_message(input)
+                    }
+                };
+
+                self.write_fatal_message(&message);
+
+                fatal_error = Some(error);
+            }
+
+            if self.die {
+                break;
+            }
+        }
+
+        // the next run of the evaluator starts without them
+        self.shutdown_functions.clear();
+        self.shutting_down = false;
+
+        fatal_error
+    }
+
+    /// Reports a warning, notice or deprecation.
+    ///
+    /// The error is only added to the warnings when no handler takes it, see `handle_error()`.
+    pub fn raise_error(&mut self, error: PhpError) -> Result<(), PhpError> {
+        if self.handle_error(&error)? {
+            return Ok(());
+        }
+
+        self.report_error(error)
+    }
+
+    /// Gives an error to the handler set with set_error_handler() for its level, if any,
+    /// and returns whether the handler took it.
+    ///
+    /// The handler is called with (errno, errstr, errfile, errline) and takes the error
+    /// unless it returns false. Errors raised inside the handler do not call it again.
+    pub fn handle_error(&mut self, error: &PhpError) -> Result<bool, PhpError> {
+        let handler = self.error_handlers.last().cloned();
+
+        let Some(handler) = handler else {
+            return Ok(false);
+        };
+
+        let errno = error.level.errno();
+
+        if self.handling_error
+            || handler.callback.is_null()
+            || !error.level.is_handleable()
+            || handler.error_levels & errno == 0
+        {
+            return Ok(false);
+        }
+
+        let arguments = vec![
+            PhpValue::Int(errno),
+            PhpValue::String(error.message.clone().into()),
+            PhpValue::String(self.current_file.clone().into()),
+            PhpValue::Int(error.line as i64),
+        ];
+
+        self.handling_error = true;
+
+        let result = self.call_callable(&handler.callback, arguments, Span {
+            line: error.line,
+            column: 0,
+            position: 0,
+        });
+
+        self.handling_error = false;
+
+        Ok(!matches!(result?, PhpValue::Bool(false)))
+    }
+
+    /*
+     * Private functions
+     */
+
+    /// Adds an error to the warnings and sends it to the diagnostics sink, unless the
+    /// "error_reporting" setting excludes its level or it comes from an expression silenced with `@`.
+    ///
+    /// The pending output is flushed before the sink receives the error, so the sink
+    /// and the writer see the output and the errors in the order they were produced.
+    fn report_error(&mut self, error: PhpError) -> Result<(), PhpError> {
+        let errno = error.level.errno();
+
+        if self.silenced > 0 || (errno != 0 && self.ini.get_int("error_reporting") & errno == 0) {
+            return Ok(());
+        }
+
+        let warning = ReportedError {
+            error,
+            file: self.current_file.clone(),
+            include_stack: self.include_stack.clone(),
+        };
+
+        if let Some(sink) = self.diagnostics.clone() {
+            self.write_pending_output()?;
+
+            sink.borrow_mut().on_warning(&warning);
+
+            if !self.collect_warnings {
+                return Ok(());
+            }
+        }
+
+        self.warnings.push(warning);
+
+        Ok(())
+    }
+
+    /// Executes the body of a user defined function in its own scope.
+    fn call_user_function(
+        &mut self,
+        function: PhpCallable,
+        arguments: Vec<PhpValue>,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let arguments = CallArguments::new(arguments);
+
+        self.execute_plain_function(function, arguments, span)
+    }
+
+    /// Executes a function that is not a method, it has no class scope even if it is
+    /// called from a method. A closure has the object and the class scope of the method
+    /// that created it.
+    fn execute_plain_function(
+        &mut self,
+        function: PhpCallable,
+        arguments: CallArguments,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let closure = function.closure.clone().unwrap_or_default();
+
+        if let Some(native_function) = closure.native_function {
+            if !arguments.named.is_empty() {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Named arguments are not supported by native functions".to_string(),
+                    line: span.line,
+                });
+            }
+
+            let mut arguments = arguments.positional;
+
+            return (native_function.function)(self, &mut arguments, span);
+        }
+
+        let old_current_class = std::mem::replace(&mut self.current_class, closure.class.clone());
+        let old_static_class =
+            std::mem::replace(&mut self.static_class, closure.static_class.clone());
+
+        let result = self.execute_function(function, arguments, span, closure.this.clone(), &[]);
+
+        self.current_class = old_current_class;
+        self.static_class = old_static_class;
+
+        result
+    }
+
+    /// Whether the code being executed can use a member of `declaring_class`.
+    ///
+    /// A private member can only be used from the class that declares it, a protected
+    /// one from the classes of the same hierarchy.
+    fn is_member_visible(&self, visibility: &Visibility, declaring_class: &[u8]) -> bool {
+        let scope = self.current_class.as_deref();
+
+        match visibility {
+            Visibility::Public => true,
+            Visibility::Private => {
+                scope.is_some_and(|scope| scope.name.bytes.eq_ignore_ascii_case(declaring_class))
+            }
+            Visibility::Protected => scope.is_some_and(|scope| {
+                scope.is_subclass_of(declaring_class)
+                    || self
+                        .env
+                        .get_class(declaring_class)
+                        .is_some_and(|class| class.is_subclass_of(&scope.name.bytes))
+            }),
+        }
+    }
+
+    /// Fails if the method is private or protected and the code being executed cannot call it.
+    fn check_method_visibility(
+        &self,
+        resolved: &ResolvedMethod,
+        span: Span,
+    ) -> Result<(), PhpError> {
+        let scope = self.current_class.as_deref();
+
+        if self.is_member_visible(&resolved.visibility, &resolved.class_name.bytes) {
+            return Ok(());
+        }
+
+        let method_name = &resolved.method.function.name;
+
+        let visibility = match resolved.visibility {
+            Visibility::Private => "private",
+            _ => "protected",
+        };
+
+        let caller = match scope {
+            Some(scope) => format!("scope {}", scope.name),
+            None => "global scope".to_string(),
+        };
+
+        let message = if method_name.bytes.eq_ignore_ascii_case(b"__construct") {
+            format!(
+                "Call to {} {}::__construct() from {}",
+                visibility, resolved.class_name, caller
+            )
+        } else {
+            format!(
+                "Call to {} method {}::{}() from {}",
+                visibility, resolved.class_name, method_name, caller
+            )
+        };
+
+        Err(PhpError {
+            level: ErrorLevel::EngineError("Error"),
+            message,
+            line: span.line,
+        })
+    }
+
+    /// Executes a method with `$this` bound to the object.
+    fn call_method(
+        &mut self,
+        object: Rc<RefCell<PhpObject>>,
+        method: PhpMethod,
+        arguments: CallArguments,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let old_current_class = self.current_class.take();
+        let old_static_class = self.static_class.take();
+
+        self.current_class = self.env.get_class(&method.class_name.bytes);
+        self.static_class = Some(Rc::clone(&object.borrow().class));
+
+        let result = self.execute_function(
+            method.function,
+            arguments,
+            span,
+            Some(object),
+            &method.promoted_parameters,
+        );
+
+        self.current_class = old_current_class;
+        self.static_class = old_static_class;
+
+        result
+    }
+
+    /// Executes a static method, without `$this`, `static_class` is the class of `static::`.
+    fn call_static_method(
+        &mut self,
+        static_class: Rc<PhpClass>,
+        method: PhpMethod,
+        arguments: CallArguments,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let old_current_class = self.current_class.take();
+        let old_static_class = self.static_class.take();
+
+        self.current_class = self.env.get_class(&method.class_name.bytes);
+        self.static_class = Some(static_class);
+
+        let result = self.execute_function(method.function, arguments, span, None, &[]);
+
+        self.current_class = old_current_class;
+        self.static_class = old_static_class;
+
+        result
+    }
+
+    /// Executes a function in its own scope, `promoted_parameters` are the parameters of a
+    /// constructor that are also properties of `this`.
+    fn execute_function(
+        &mut self,
+        mut function: PhpCallable,
+        arguments: CallArguments,
+        span: Span,
+        this: Option<Rc<RefCell<PhpObject>>>,
+        promoted_parameters: &[ByteString],
+    ) -> Result<PhpValue, PhpError> {
+        let old_vars = self.env.enter_scope();
+
+        if let Some(this) = &this {
+            self.env
+                .set_var(b"this", &PhpValue::Object(Rc::clone(this)));
+        }
+
+        if let Some(closure) = &function.closure {
+            for variable in &closure.variables {
+                if variable.by_reference {
+                    self.env
+                        .set_var_rc(&variable.name, Rc::clone(&variable.value));
+                } else {
+                    self.env.set_var(&variable.name, &variable.value.borrow());
+                }
+            }
+        }
+
+        if let Err(error) = self.bind_arguments(&function, arguments, span) {
+            self.env.leave_scope(old_vars);
+
+            return Err(error);
+        }
+
+        if let Some(this) = &this {
+            for name in promoted_parameters {
+                let value = self.env.get_var(&name.bytes).unwrap_or(NULL);
+
+                this.borrow_mut().set_property(&name.bytes, value);
+            }
+        }
+
+        // the name is only built when the call is traced
+        let traced_name = self.tracer.is_some().then(|| {
+            let name = match (self.env.get_var(b"this"), &self.current_class) {
+                (Some(PhpValue::Object(object)), _) => {
+                    format!("{}->{}", object.borrow().class.name, function.name)
+                }
+                (None, Some(class)) => format!("{}::{}", class.name, function.name),
+                _ => function.name.to_string(),
+            };
+
+            let arguments: Vec<String> = function
+                .parameters
+                .iter()
+                .filter_map(|parameter| self.env.get_var(variable_key(&parameter.name)))
+                .map(|value| summarize_value(&value))
+                .collect();
+
+            self.trace(&TraceEvent::Call {
+                function: &name,
+                arguments: &arguments.join(", "),
+                file: &self.current_file,
+                line: span.line,
+            });
+
+            name
+        });
+
+        self.trace_depth += 1;
+
+        // a break in the function cannot leave the switches of the caller
+        let old_loop_depth = std::mem::take(&mut self.loop_depth);
+
+        // the body runs in the file that declares the function, which can be an included file
+        let old_file = std::mem::replace(&mut self.current_file, function.file.clone());
+
+        let result = self.eval_statements(std::mem::take(&mut function.body));
+
+        self.current_file = old_file;
+
+        self.keep_fatal_of_other_file(&result, &function.file);
+
+        self.loop_depth = old_loop_depth;
+
+        self.trace_depth -= 1;
+
+        if let Some(name) = traced_name {
+            self.trace(&TraceEvent::Return { function: &name });
+        }
+
+        self.env.leave_scope(old_vars);
+
+        let return_value = self.return_value.take();
+
+        result?;
+
+        if self.die {
+            return Ok(NULL);
+        }
+
+        let function_name = match &self.current_class {
+            Some(class) => format!("{}::{}", class.name, function.name),
+            None => function.name.to_string(),
+        };
+
+        self.check_return_value(&function, &function_name, return_value, span)
+    }
+
+    /// Sets the parameters of a function in its scope: the positional arguments first,
+    /// then the named ones, then the defaults of the parameters that received nothing.
+    ///
+    /// The arguments were already evaluated from left to right, so the defaults are
+    /// evaluated after all of them, in the order of the parameters, and they see the
+    /// side effects of the arguments. They are evaluated on every call, so `new` in
+    /// a default gives a new object each time.
+    fn bind_arguments(
+        &mut self,
+        function: &PhpCallable,
+        arguments: CallArguments,
+        span: Span,
+    ) -> Result<(), PhpError> {
+        let error = |class: &'static str, message: String| PhpError {
+            level: ErrorLevel::EngineError(class),
+            message,
+            line: span.line,
+        };
+
+        let variadic = function
+            .parameters
+            .last()
+            .filter(|parameter| parameter.ellipsis);
+
+        let fixed_count = function.parameters.len() - usize::from(variadic.is_some());
+
+        let parameters = &function.parameters[..fixed_count];
+
+        // the arguments after the other parameters are the elements of the variadic one
+        let mut rest = PhpArray::new();
+
+        let mut is_bound = vec![false; parameters.len()];
+
+        let passed_count = arguments.positional.len() + arguments.named.len();
+
+        for (position, argument) in arguments.positional.into_iter().enumerate() {
+            let Some(parameter) = parameters.get(position) else {
+                let argument = self.check_variadic_argument(function, position, argument, span)?;
+
+                rest.push(argument).map_err(|error| PhpError {
+                    line: span.line,
+                    ..error
+                })?;
+
+                continue;
+            };
+
+            self.env.set_var(variable_key(&parameter.name), &argument);
+
+            is_bound[position] = true;
+        }
+
+        let has_named_arguments = !arguments.named.is_empty();
+
+        // a parameter without a default before one with a default is required too
+        let required_count = parameters
+            .iter()
+            .rposition(|parameter| parameter.default_value.is_none())
+            .map_or(0, |position| position + 1);
+
+        let function_name = match &self.current_class {
+            Some(class) => format!("{}::{}", class.name, function.name),
+            None => function.name.to_string(),
+        };
+
+        if !has_named_arguments && passed_count < required_count {
+            let expected = if required_count == function.parameters.len() {
+                "exactly"
+            } else {
+                "at least"
+            };
+
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("ArgumentCountError"),
+                message: format!(
+                    "Too few arguments to function {}(), {} passed in {} on line {} and {} {} expected",
+                    function_name, passed_count, self.current_file, span.line, expected, required_count
+                ),
+                line: span.line,
+            });
+        }
+
+        for (name, argument) in arguments.named {
+            let position = parameters
+                .iter()
+                .position(|parameter| variable_key(&parameter.name) == &name.bytes[..]);
+
+            let key = ArrayKey::String(name.clone());
+
+            let overwrites = match position {
+                Some(position) => is_bound[position],
+                None => rest.get(&key).is_some(),
+            };
+
+            if overwrites {
+                return Err(error(
+                    "Error",
+                    format!("Named parameter ${} overwrites previous argument", name),
+                ));
+            }
+
+            let Some(position) = position else {
+                if variadic.is_none() {
+                    return Err(error("Error", format!("Unknown named parameter ${}", name)));
+                }
+
+                // the variadic parameter takes the unknown names, with their name as key
+                let position = parameters.len() + rest.len();
+
+                let argument = self.check_variadic_argument(function, position, argument, span)?;
+
+                rest.insert(key, argument);
+
+                continue;
+            };
+
+            self.env
+                .set_var(variable_key(&parameters[position].name), &argument);
+
+            is_bound[position] = true;
+        }
+
+        for (name, reference) in arguments.references {
+            self.env.set_var_rc(&name, reference);
+        }
+
+        for (position, parameter) in parameters.iter().enumerate() {
+            if is_bound[position] {
+                self.check_argument(&function_name, position, parameter, span)?;
+
+                continue;
+            }
+
+            let Some(default_value) = &parameter.default_value else {
+                // a parameter skipped by the named arguments must have a default
+                return Err(error(
+                    "ArgumentCountError",
+                    format!(
+                        "{}(): Argument #{} ({}) not passed",
+                        function.name,
+                        position + 1,
+                        parameter.name.name
+                    ),
+                ));
+            };
+
+            let value = self
+                .eval_expression(default_value)
+                .map_err(|error| PhpError {
+                    line: span.line,
+                    ..error
+                })?;
+
+            self.env.set_var(variable_key(&parameter.name), &value);
+        }
+
+        if let Some(variadic) = variadic {
+            self.env
+                .set_var(variable_key(&variadic.name), &PhpValue::Array(rest));
+        }
+
+        Ok(())
+    }
+
+    /// Evaluates the arguments of a call, from left to right.
+    ///
+    /// An array unpacked with `...` gives its elements, the ones with an int key are
+    /// positional arguments and the ones with a string key are named arguments.
+    /// The arguments of the parameters of `callee` passed by reference are shared with
+    /// the parameters.
+    fn eval_arguments(
+        &mut self,
+        arguments: &ArgumentList,
+        callee: Option<Callee>,
+    ) -> Result<CallArguments, PhpError> {
+        let mut values = CallArguments::new(vec![]);
+
+        for argument in &arguments.arguments {
+            match argument {
+                Argument::Positional(PositionalArgument {
+                    ellipsis: Some(ellipsis),
+                    value,
+                    ..
+                }) => {
+                    let error = |message: &str| PhpError {
+                        level: ErrorLevel::EngineError("Error"),
+                        message: message.to_string(),
+                        line: ellipsis.line,
+                    };
+
+                    let PhpValue::Array(array) = self.eval_expression(value)? else {
+                        return Err(error("Only arrays and Traversables can be unpacked"));
+                    };
+
+                    for (key, value) in array.iter() {
+                        match key {
+                            ArrayKey::Int(_) if !values.named.is_empty() => {
+                                return Err(error(
+                                    "Cannot use positional argument after named argument during unpacking",
+                                ))
+                            }
+                            ArrayKey::Int(_) => values.positional.push(value.into_owned()),
+                            ArrayKey::String(name) => {
+                                values.named.push((name.clone(), value.into_owned()))
+                            }
+                        }
+                    }
+                }
+                Argument::Positional(argument) => {
+                    let position = values.positional.len();
+
+                    let value = self.eval_argument(
+                        &argument.value,
+                        callee,
+                        Some(position),
+                        &mut values.references,
+                        arguments.left_parenthesis,
+                    )?;
+
+                    values.positional.push(value);
+                }
+                Argument::Named(argument) => {
+                    let name = &argument.name.value;
+
+                    let position = callee.and_then(|callee| {
+                        let parameters = &callee.function.parameters;
+
+                        parameters
+                            .iter()
+                            .position(|parameter| variable_key(&parameter.name) == &name.bytes[..])
+                    });
+
+                    let value = self.eval_argument(
+                        &argument.value,
+                        callee,
+                        position,
+                        &mut values.references,
+                        arguments.left_parenthesis,
+                    )?;
+
+                    values.named.push((name.clone(), value));
+                }
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Returns the name of a method or a property, which is either an identifier
+    /// or an expression such as `$object->$name`.
+    fn get_member_name(&mut self, member: &Expression) -> Result<Vec<u8>, PhpError> {
+        if let Expression::Identifier(Identifier::SimpleIdentifier(identifier)) = member {
+            return Ok(identifier.value.bytes.clone());
+        }
+
+        let value = self.eval_expression(member)?;
+
+        match value {
+            PhpValue::String(name) => Ok(name.bytes),
+            _ => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Member name must be a string, {} given",
+                    value.type_name().error_name()
+                ),
+                line: member.first_span().map_or(0, |span| span.line),
+            }),
+        }
+    }
+
+    fn get_class_property(
+        &mut self,
+        entry: PropertyEntry,
+        modifiers: PropertyModifierGroup,
+        data_type: Option<Type>,
+        doc_comment: Option<ByteString>,
+    ) -> Result<ClassProperty, PhpError> {
+        let (variable, default_value) = match entry {
+            PropertyEntry::Uninitialized { variable } => (variable, NULL),
+            PropertyEntry::Initialized {
+                variable, value, ..
+            } => {
+                if contains_new_expression(&value) {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "New expressions are not supported in this context".to_string(),
+                        line: variable.span.line,
+                    });
+                }
+
+                let value = self.eval_expression(&value)?;
+
+                (variable, value)
+            }
+        };
+
+        Ok(ClassProperty {
+            name: ByteString::new(variable.name.bytes[1..].to_vec()),
+            modifiers,
+            data_type,
+            default_value,
+            doc_comment,
+        })
+    }
+
+    fn eval_new(&mut self, new: &NewExpression) -> Result<PhpValue, PhpError> {
+        if let Some(interface) = self.get_interface(&new.target) {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!("Cannot instantiate interface {}", interface.name),
+                line: new.new.line,
+            });
+        }
+
+        let class = self.resolve_class(&new.target, new.new)?;
+
+        if class.enum_cases.is_some() {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!("Cannot instantiate enum {}", class.name),
+                line: new.new.line,
+            });
+        }
+
+        if class.modifiers.has_abstract() {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!("Cannot instantiate abstract class {}", class.name),
+                line: new.new.line,
+            });
+        }
+
+        let constructor = class.resolve_method(b"__construct");
+
+        if let Some(constructor) = &constructor {
+            self.check_method_visibility(constructor, new.new)?;
+        }
+
+        let constructor = constructor.map(|constructor| constructor.method);
+
+        let callee = constructor.map(|constructor| Callee {
+            function: &constructor.function,
+            class_name: Some(&constructor.class_name),
+        });
+
+        let arguments = match &new.arguments {
+            Some(arguments) => self.eval_arguments(arguments, callee)?,
+            None => CallArguments::new(vec![]),
+        };
+
+        let object = self.new_object(&class);
+
+        // an exception knows where it was created, not where it is thrown
+        if class.implements(b"Throwable") {
+            let mut exception = object.borrow_mut();
+
+            exception.set_property(b"file", PhpValue::String(self.current_file.as_str().into()));
+            exception.set_property(b"line", PhpValue::Int(new.new.line as i64));
+        }
+
+        if let Some(constructor) = class.get_method(b"__construct") {
+            self.call_method(Rc::clone(&object), constructor.clone(), arguments, new.new)?;
+        }
+
+        Ok(PhpValue::Object(object))
+    }
+
+    /// Creates an object of the class with the first id that is not used by a live object.
+    fn new_object(&mut self, class: &Rc<PhpClass>) -> Rc<RefCell<PhpObject>> {
+        let id = self.free_object_id();
+
+        let object = Rc::new(RefCell::new(class.instantiate(id)));
+
+        self.keep_object_id(id, Rc::downgrade(&object) as Weak<dyn Any>);
+
+        object
+    }
+
+    /// Gives the scope of a new closure the first id that is not used by a live object.
+    pub(super) fn new_closure_scope(&mut self, mut scope: ClosureScope) -> Rc<ClosureScope> {
+        scope.id = self.free_object_id();
+
+        let scope = Rc::new(scope);
+
+        self.keep_object_id(scope.id, Rc::downgrade(&scope) as Weak<dyn Any>);
+
+        scope
+    }
+
+    /// The first id that is not used by a live object or closure.
+    fn free_object_id(&self) -> usize {
+        let free_slot = self
+            .objects
+            .iter()
+            .position(|object| object.strong_count() == 0);
+
+        free_slot.unwrap_or(self.objects.len()) + 1
+    }
+
+    /// Keeps the object or the closure that has the id, the id is free once it is destroyed.
+    fn keep_object_id(&mut self, id: usize, object: Weak<dyn Any>) {
+        if id > self.objects.len() {
+            self.objects.push(object);
+        } else {
+            self.objects[id - 1] = object;
+        }
+    }
+
+    /// Returns the class referenced by a class name, `self`, `static`, `parent`,
+    /// or an expression whose value is a class name or an object.
+    fn resolve_class(&mut self, target: &Expression, span: Span) -> Result<Rc<PhpClass>, PhpError> {
+        let class_name = match target {
+            Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => {
+                identifier.value.bytes.clone()
+            }
+            Expression::Self_ => {
+                return self.current_class.clone().ok_or_else(|| PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Cannot use \"self\" when no class scope is active".to_string(),
+                    line: span.line,
+                })
+            }
+            Expression::Static => {
+                return self.static_class.clone().ok_or_else(|| PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Cannot use \"static\" when no class scope is active".to_string(),
+                    line: span.line,
+                })
+            }
+            Expression::Parent => {
+                let Some(current_class) = &self.current_class else {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "Cannot use \"parent\" when no class scope is active"
+                            .to_string(),
+                        line: span.line,
+                    });
+                };
+
+                return current_class.parent.clone().ok_or_else(|| PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Cannot use \"parent\" when current class scope has no parent"
+                        .to_string(),
+                    line: span.line,
+                });
+            }
+            target => match self.eval_expression(target)? {
+                PhpValue::String(name) => name.bytes,
+                PhpValue::Object(object) => return Ok(Rc::clone(&object.borrow().class)),
+                _ => {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "Cannot use a value that is not a class name as a class"
+                            .to_string(),
+                        line: span.line,
+                    })
+                }
+            },
+        };
+
+        self.env.get_class(&class_name).ok_or_else(|| PhpError {
+            level: ErrorLevel::EngineError("Error"),
+            message: format!("Class \"{}\" not found", display_name(&class_name)),
+            line: span.line,
+        })
+    }
+
+    /// The interface named by the target of `new` or `::`, when it is written as a name.
+    fn get_interface(&self, target: &Expression) -> Option<Rc<PhpInterface>> {
+        match target {
+            Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => {
+                self.env.get_interface(&identifier.value)
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the value of a class constant, like `Foo::BAR`.
+    ///
+    /// `Foo::class` is the name of the class, written like in the code, the class
+    /// does not have to exist. With `self`, `static`, `parent` or an object, it is
+    /// the name of the class they refer to.
+    fn eval_constant_fetch(
+        &mut self,
+        fetch: &ConstantFetchExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let Identifier::SimpleIdentifier(constant) = &fetch.constant else {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "Dynamic class constant names are not supported".to_string(),
+                line: fetch.double_colon.line,
+            });
+        };
+
+        let is_class_name = constant.value.bytes.eq_ignore_ascii_case(b"class");
+
+        if is_class_name {
+            if let Expression::Identifier(Identifier::SimpleIdentifier(identifier)) = &*fetch.target
+            {
+                return Ok(PhpValue::String(identifier.value.clone()));
+            }
+        }
+
+        if let Some(interface) = self.get_interface(&fetch.target) {
+            return match interface.get_constant(&constant.value) {
+                Some(value) => Ok(value.clone()),
+                None => Err(PhpError {
+                    level: ErrorLevel::EngineError("Error"),
+                    message: format!(
+                        "Undefined constant {}::{}",
+                        interface.name,
+                        display_name(&constant.value)
+                    ),
+                    line: fetch.double_colon.line,
+                }),
+            };
+        }
+
+        let class = self.resolve_class(&fetch.target, fetch.double_colon)?;
+
+        if is_class_name {
+            return Ok(PhpValue::String(class.name.clone()));
+        }
+
+        if let Some(case) = self.get_enum_case(&class, &constant.value) {
+            return Ok(case);
+        }
+
+        match class.get_constant(&constant.value) {
+            Some(value) => Ok(value.clone()),
+            None => Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Undefined constant {}::{}",
+                    class.name,
+                    display_name(&constant.value)
+                ),
+                line: fetch.double_colon.line,
+            }),
+        }
+    }
+
+    /// Returns the storage of a static property, like `Foo::$bar`.
+    fn get_static_property(
+        &mut self,
+        fetch: &StaticPropertyFetchExpression,
+    ) -> Result<Rc<RefCell<PhpValue>>, PhpError> {
+        let class = self.resolve_class(&fetch.target, fetch.double_colon)?;
+
+        let Variable::SimpleVariable(variable) = &fetch.property else {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "Dynamic static property names are not supported".to_string(),
+                line: fetch.double_colon.line,
+            });
+        };
+
+        let property_name = &variable.name.bytes[1..];
+
+        let declaration = class.ancestors().find_map(|declaring_class| {
+            declaring_class
+                .static_property_declarations
+                .iter()
+                .find(|property| property.name.bytes == property_name)
+                .map(|property| (declaring_class, property.modifiers.visibility()))
+        });
+
+        if let Some((declaring_class, visibility)) = declaration {
+            if !self.is_member_visible(&visibility, &declaring_class.name.bytes) {
+                return Err(PhpError {
+                    level: ErrorLevel::EngineError("Error"),
+                    message: format!(
+                        "Cannot access {} property {}::${}",
+                        if visibility == Visibility::Private {
+                            "private"
+                        } else {
+                            "protected"
+                        },
+                        class.name,
+                        display_name(property_name)
+                    ),
+                    line: fetch.double_colon.line,
+                });
+            }
+        }
+
+        class
+            .get_static_property(property_name)
+            .ok_or_else(|| PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Access to undeclared static property {}::${}",
+                    class.name,
+                    display_name(property_name)
+                ),
+                line: fetch.double_colon.line,
+            })
+    }
+
+    fn eval_method_call(
+        &mut self,
+        target: PhpValue,
+        method: &Expression,
+        arguments: &ArgumentList,
+    ) -> Result<PhpValue, PhpError> {
+        let span = arguments.left_parenthesis;
+
+        let method_name = self.get_member_name(method)?;
+
+        let PhpValue::Object(object) = target else {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Call to a member function {}() on {}",
+                    display_name(&method_name),
+                    target.type_name().error_name()
+                ),
+                line: span.line,
+            });
+        };
+
+        let class = Rc::clone(&object.borrow().class);
+
+        let Some(resolved) = class.resolve_method(&method_name) else {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Call to undefined method {}::{}()",
+                    class.name,
+                    display_name(&method_name)
+                ),
+                line: span.line,
+            });
+        };
+
+        self.check_method_visibility(&resolved, span)?;
+
+        let method = resolved.method;
+
+        let callee = Callee {
+            function: &method.function,
+            class_name: Some(&method.class_name),
+        };
+
+        let arguments = self.eval_arguments(arguments, Some(callee))?;
+
+        self.call_method(object, method.clone(), arguments, span)
+    }
+
+    /// Calls a method with `Foo::method()`, `self::method()`, `parent::method()`
+    /// or `static::method()`.
+    ///
+    /// A static method is called without `$this`. `self` and `parent` keep the class of
+    /// `static::`, like PHP forwards it, a class name sets it to that class.
+    /// A method that is not static can only be called this way from a method of an object
+    /// of the class, on that object, like `parent::__construct()`.
+    fn eval_static_method_call(
+        &mut self,
+        call: &StaticMethodCallExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let span = call.arguments.left_parenthesis;
+
+        let class = self.resolve_class(&call.target, call.double_colon)?;
+
+        let Identifier::SimpleIdentifier(method_name) = &call.method else {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "Dynamic static method names are not supported".to_string(),
+                line: span.line,
+            });
+        };
+
+        if Self::is_enum_method(&class, &method_name.value) {
+            let arguments = self.eval_arguments(&call.arguments, None)?;
+
+            return self.call_enum_method(&class, &method_name.value, arguments, span);
+        }
+
+        let Some(resolved) = class.resolve_method(&method_name.value) else {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Call to undefined method {}::{}()",
+                    class.name,
+                    display_name(&method_name.value)
+                ),
+                line: span.line,
+            });
+        };
+
+        self.check_method_visibility(&resolved, span)?;
+
+        let method = resolved.method.clone();
+
+        let callee = Callee {
+            function: &method.function,
+            class_name: Some(&method.class_name),
+        };
+
+        if !resolved.is_static {
+            let this = match self.env.get_var(b"this") {
+                Some(PhpValue::Object(object)) if object.borrow().is_instance_of(&class.name) => {
+                    object
+                }
+                _ => {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: format!(
+                            "Non-static method {}::{}() cannot be called statically",
+                            method.class_name, method.function.name
+                        ),
+                        line: span.line,
+                    })
+                }
+            };
+
+            let arguments = self.eval_arguments(&call.arguments, Some(callee))?;
+
+            return self.call_method(this, method, arguments, span);
+        }
+
+        let static_class = match *call.target {
+            Expression::Self_ | Expression::Parent => self.static_class.clone(),
+            _ => None,
+        };
+
+        let arguments = self.eval_arguments(&call.arguments, Some(callee))?;
+
+        self.call_static_method(static_class.unwrap_or(class), method, arguments, span)
+    }
+
+    fn eval_property_fetch(
+        &mut self,
+        target: PhpValue,
+        property: &Expression,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let property_name = self.get_member_name(property)?;
+
+        let PhpValue::Object(object) = target else {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: format!(
+                    "Attempt to read property \"{}\" on {}",
+                    display_name(&property_name),
+                    target.type_name().error_name()
+                ),
+                line: span.line,
+            })?;
+
+            return Ok(NULL);
+        };
+
+        let value = object.borrow().get_property(&property_name).cloned();
+
+        match value {
+            Some(value) => Ok(value),
+            None => {
+                let class_name = object.borrow().class.name.clone();
+
+                self.raise_error(PhpError {
+                    level: ErrorLevel::Warning,
+                    message: format!(
+                        "Undefined property: {}::${}",
+                        class_name,
+                        display_name(&property_name)
+                    ),
+                    line: span.line,
+                })?;
+
+                Ok(NULL)
+            }
+        }
+    }
+
+    /// Assigns a property, the object and the name of the property are evaluated
+    /// before `value` gives the value.
+    fn assign_property(
+        &mut self,
+        fetch: &PropertyFetchExpression,
+        value: impl FnOnce(&mut Self) -> Result<PhpValue, PhpError>,
+    ) -> Result<PhpValue, PhpError> {
+        let target = self.eval_expression(&fetch.target)?;
+
+        let property_name = self.get_member_name(&fetch.property)?;
+
+        let value = value(self)?;
+
+        let PhpValue::Object(object) = target else {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Attempt to assign property \"{}\" on {}",
+                    display_name(&property_name),
+                    target.type_name().error_name()
+                ),
+                line: fetch.arrow.line,
+            });
+        };
+
+        self.check_property_write(&object.borrow(), &property_name, fetch.arrow)?;
+
+        object
+            .borrow_mut()
+            .set_property(&property_name, value.clone());
+
+        Ok(value)
+    }
+
+    /// Defines as null the undefined variables passed by reference to a native function,
+    /// like PHP does, so reading them for the call does not warn.
+    fn define_referenced_variables(&mut self, function: &NativeFunction, arguments: &[Argument]) {
+        for position in function.by_reference {
+            let Some(Argument::Positional(argument)) = arguments.get(*position) else {
+                continue;
+            };
+
+            if let Expression::Variable(Variable::SimpleVariable(variable)) = &argument.value {
+                if self.env.get_var(variable_key(variable)).is_none() {
+                    self.env.set_var(variable_key(variable), &NULL);
+                }
+            }
+        }
+    }
+
+    /// Calls a native function and writes back the arguments passed by reference.
+    fn call_native_function(
+        &mut self,
+        function: &NativeFunction,
+        argument_expressions: &[Argument],
+        mut arguments: Vec<PhpValue>,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let mut referenced_variables = vec![];
+
+        for position in function.by_reference {
+            let Some(Argument::Positional(argument)) = argument_expressions.get(*position) else {
+                continue;
+            };
+
+            let Expression::Variable(variable) = &argument.value else {
+                // the result of a call is a temporary value, PHP only emits a notice for it
+                if let Expression::FunctionCall(_)
+                | Expression::MethodCall(_)
+                | Expression::NullsafeMethodCall(_)
+                | Expression::StaticMethodCall(_)
+                | Expression::New(_) = argument.value
+                {
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Notice,
+                        message: "Only variables should be passed by reference".to_string(),
+                        line: span.line,
+                    })?;
+
+                    continue;
+                }
+
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: format!(
+                        "{}(): Argument #{} could not be passed by reference",
+                        function.name,
+                        position + 1
+                    ),
+                    line: span.line,
+                });
+            };
+
+            referenced_variables.push((*position, self.get_variable_name(variable)?));
+        }
+
+        let result = (function.function)(self, &mut arguments, span);
+
+        for (position, variable_name) in referenced_variables {
+            self.env
+                .update_var(&variable_name, arguments[position].clone());
+        }
+
+        result
+    }
+
+    /// Evaluates the items of an array literal.
+    fn eval_array_items(&mut self, items: &[ArrayItem], span: Span) -> Result<PhpValue, PhpError> {
+        let mut array = PhpArray::new();
+
+        for item in items {
+            match item {
+                ArrayItem::Skipped => {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "Cannot use empty array elements in arrays".to_string(),
+                        line: span.line,
+                    });
+                }
+                ArrayItem::Value { value } => {
+                    let value = self.eval_expression(value)?;
+
+                    array.push(value).map_err(|error| PhpError {
+                        line: span.line,
+                        ..error
+                    })?;
+                }
+                ArrayItem::KeyValue { key, value, .. } => {
+                    let key = self.eval_expression(key)?;
+                    let value = self.eval_expression(value)?;
+
+                    let key = self.get_array_key(&key, span)?;
+
+                    array.insert(key, value);
+                }
+                ArrayItem::SpreadValue { ellipsis, value } => {
+                    let value = self.eval_expression(value)?;
+
+                    let PhpValue::Array(spread_array) = value else {
+                        return Err(PhpError {
+                            level: ErrorLevel::Fatal,
+                            message: "Only arrays and Traversables can be unpacked".to_string(),
+                            line: ellipsis.line,
+                        });
+                    };
+
+                    for (key, value) in spread_array.iter() {
+                        let key = match key {
+                            ArrayKey::Int(_) => array.next_key().map_err(|error| PhpError {
+                                line: ellipsis.line,
+                                ..error
+                            })?,
+                            ArrayKey::String(_) => key.clone(),
+                        };
+
+                        array.insert(key, value.into_owned());
+                    }
+                }
+                ArrayItem::ReferencedValue { ampersand, value } => {
+                    let reference = self.get_reference(value, *ampersand)?;
+
+                    let key = array.next_key().map_err(|error| PhpError {
+                        line: ampersand.line,
+                        ..error
+                    })?;
+
+                    array.insert_reference(key, reference);
+                }
+                ArrayItem::ReferencedKeyValue {
+                    key,
+                    ampersand,
+                    value,
+                    ..
+                } => {
+                    let key = self.eval_expression(key)?;
+                    let reference = self.get_reference(value, *ampersand)?;
+
+                    let key = self.get_array_key(&key, span)?;
+
+                    array.insert_reference(key, reference);
+                }
+            }
+        }
+
+        Ok(PhpValue::Array(array))
+    }
+
+    /// The value of a variable, an array element or a property for isset() and empty(),
+    /// None from the first link of the chain that is not set, like `$a` in `$a['b']->c`.
+    ///
+    /// The missing links do not warn, but the keys are evaluated as usual. Any other
+    /// expression is evaluated, empty() accepts them.
+    fn get_isset_value(&mut self, expression: &Expression) -> Result<Option<PhpValue>, PhpError> {
+        match expression {
+            Expression::Variable(variable) => {
+                let name = self.get_variable_name(variable)?;
+
+                Ok(self.env.get_var(&name))
+            }
+            Expression::ArrayIndex(fetch) => {
+                let container = self.get_isset_value(&fetch.array)?;
+
+                let Some(index) = &fetch.index else {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "Cannot use [] for reading".to_string(),
+                        line: fetch.left_bracket.line,
+                    });
+                };
+
+                let Some(container) = container else {
+                    return Ok(None);
+                };
+
+                let index = self.eval_expression(index)?;
+
+                match container {
+                    PhpValue::Array(array) => {
+                        let Ok(key) = ArrayKey::from_value(&index) else {
+                            return Err(PhpError {
+                                level: ErrorLevel::Fatal,
+                                message: format!(
+                                    "Cannot access offset of type {} in isset or empty",
+                                    index.type_name().error_name()
+                                ),
+                                line: fetch.left_bracket.line,
+                            });
+                        };
+
+                        Ok(array.get(&key).map(Cow::into_owned))
+                    }
+                    // only integer offsets are set in a string
+                    PhpValue::String(string) => {
+                        let offset = match ArrayKey::from_value(&index) {
+                            Ok(ArrayKey::Int(offset)) if !matches!(index, PhpValue::Null) => offset,
+                            _ => return Ok(None),
+                        };
+
+                        let position = if offset < 0 {
+                            string.len() as i64 + offset
+                        } else {
+                            offset
+                        };
+
+                        if position < 0 || position >= string.len() as i64 {
+                            return Ok(None);
+                        }
+
+                        Ok(Some(PhpValue::String(ByteString::new(vec![
+                            string[position as usize],
+                        ]))))
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Expression::PropertyFetch(PropertyFetchExpression {
+                target, property, ..
+            })
+            | Expression::NullsafePropertyFetch(NullsafePropertyFetchExpression {
+                target,
+                property,
+                ..
+            }) => {
+                let target = self.get_isset_value(target)?;
+
+                let property_name = self.get_member_name(property)?;
+
+                match target {
+                    Some(PhpValue::Object(object)) => {
+                        Ok(object.borrow().get_property(&property_name).cloned())
+                    }
+                    _ => Ok(None),
+                }
+            }
+            Expression::StaticPropertyFetch(fetch) => {
+                let class = self.resolve_class(&fetch.target, fetch.double_colon)?;
+
+                let Variable::SimpleVariable(variable) = &fetch.property else {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: "Dynamic static property names are not supported".to_string(),
+                        line: fetch.double_colon.line,
+                    });
+                };
+
+                Ok(class
+                    .get_static_property(&variable.name.bytes[1..])
+                    .map(|property| property.borrow().clone()))
+            }
+            expression => Ok(Some(self.eval_expression(expression)?)),
+        }
+    }
+
+    /// Reads an element of an array or a character of a string, like `$array['key']`.
+    fn eval_array_index(&mut self, fetch: &ArrayIndexExpression) -> Result<PhpValue, PhpError> {
+        let value = self.eval_expression(&fetch.array)?;
+
+        let Some(index) = &fetch.index else {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "Cannot use [] for reading".to_string(),
+                line: fetch.left_bracket.line,
+            });
+        };
+
+        let index = self.eval_expression(index)?;
+
+        match value {
+            PhpValue::Array(array) => {
+                let key = self.get_array_key(&index, fetch.left_bracket)?;
+
+                if let Some(element) = array.get(&key) {
+                    return Ok(element.into_owned());
+                }
+
+                self.raise_error(undefined_array_key(&key, fetch.left_bracket))?;
+
+                Ok(NULL)
+            }
+            PhpValue::String(string) => {
+                let offset = match &index {
+                    PhpValue::Int(i) => Some(*i),
+                    PhpValue::String(s) => str::from_utf8(s).ok().and_then(|s| s.parse().ok()),
+                    _ => None,
+                };
+
+                let Some(offset) = offset else {
+                    return Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: format!(
+                            "Cannot access offset of type {} on string",
+                            index.type_name().error_name()
+                        ),
+                        line: fetch.left_bracket.line,
+                    });
+                };
+
+                // negative offsets are counted from the end of the string
+                let position = if offset < 0 {
+                    string.len() as i64 + offset
+                } else {
+                    offset
+                };
+
+                if position < 0 || position >= string.len() as i64 {
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Warning,
+                        message: format!("Uninitialized string offset {}", offset),
+                        line: fetch.left_bracket.line,
+                    })?;
+
+                    return Ok(PhpValue::String(ByteString::default()));
+                }
+
+                Ok(PhpValue::String(ByteString::new(vec![string[position as usize]])))
+            }
+            PhpValue::Object(object) => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "Cannot use object of type {} as array",
+                    object.borrow().class.name
+                ),
+                line: fetch.left_bracket.line,
+            }),
+            value => {
+                self.raise_error(PhpError {
+                    level: ErrorLevel::Warning,
+                    message: format!(
+                        "Trying to access array offset on value of type {}",
+                        value.type_name().error_name()
+                    ),
+                    line: fetch.left_bracket.line,
+                })?;
+
+                Ok(NULL)
+            }
+        }
+    }
+
+    /// Builds a double quoted string or a heredoc from its parts.
+    fn eval_string_parts(&mut self, parts: &[StringPart]) -> Result<PhpValue, PhpError> {
+        let mut bytes = vec![];
+
+        for part in parts {
+            let expression = match part {
+                StringPart::Literal(literal) => {
+                    bytes.extend_from_slice(&literal.value);
+
+                    continue;
+                }
+                StringPart::Expression(part) => &part.expression,
+            };
+
+            let value = self.eval_interpolated_expression(expression)?;
+
+            match value {
+                PhpValue::String(string) => bytes.extend_from_slice(&string),
+                value => {
+                    let span = get_span_from_expression(expression);
+
+                    let value_as_string = self.value_to_output_string(value, span)?;
+
+                    bytes.extend_from_slice(value_as_string.as_bytes());
+                }
+            }
+        }
+
+        Ok(PhpValue::String(ByteString::new(bytes)))
+    }
+
+    /// Evaluates an expression inside a string.
+    ///
+    /// The deprecated `"${var}"` and `"${expr}"` forms are parsed as braced variable variables,
+    /// where `var` is an identifier instead of a variable.
+    fn eval_interpolated_expression(
+        &mut self,
+        expression: &Expression,
+    ) -> Result<PhpValue, PhpError> {
+        let Expression::Variable(Variable::BracedVariableVariable(variable)) = expression else {
+            return self.eval_expression(expression);
+        };
+
+        let as_variable = |identifier: &Identifier| match identifier {
+            Identifier::SimpleIdentifier(identifier) => {
+                let mut name = b"$".to_vec();
+
+                name.extend_from_slice(&identifier.value);
+
+                Some(Expression::Variable(Variable::SimpleVariable(SimpleVariable {
+                    span: identifier.span,
+                    name: ByteString::new(name),
+                })))
+            }
+            _ => None,
+        };
+
+        // "${var}" and "${var[0]}"
+        let rewritten = match variable.variable.as_ref() {
+            Expression::Identifier(identifier) => as_variable(identifier),
+            Expression::ArrayIndex(index) => match index.array.as_ref() {
+                Expression::Identifier(identifier) => {
+                    as_variable(identifier).map(|array| {
+                        Expression::ArrayIndex(ArrayIndexExpression {
+                            array: Box::new(array),
+                            ..index.clone()
+                        })
+                    })
+                }
+                _ => None,
+            },
+            _ => None,
+        };
+
+        // "{${expr}}" has the same shape as "${expr}", so it also emits the deprecation
+        let message = if rewritten.is_some() {
+            "Using ${var} in strings is deprecated, use {$var} instead"
+        } else {
+            "Using ${expr} (variable variables) in strings is deprecated, use {${expr}} instead"
+        };
+
+        self.raise_error(PhpError {
+            level: ErrorLevel::Deprecated,
+            message: message.to_string(),
+            line: variable.start.line,
+        })?;
+
+        self.eval_expression(rewritten.as_ref().unwrap_or(expression))
+    }
+
+    /// Returns the name of the function called with the value of a function call target.
+    fn get_function_name(&mut self, target: PhpValue, span: Span) -> Result<Vec<u8>, PhpError> {
+        let target = match target {
+            PhpValue::Callable(function) => return Ok(function.name.bytes),
+            PhpValue::String(_) => target,
+            PhpValue::Object(object) => {
+                return Err(PhpError {
+                    level: ErrorLevel::EngineError("Error"),
+                    message: format!(
+                        "Object of type {} is not callable",
+                        object.borrow().class.name
+                    ),
+                    line: span.line,
+                })
+            }
+            _ => {
+                return Err(PhpError {
+                    level: ErrorLevel::EngineError("Error"),
+                    message: format!(
+                        "Value of type {} is not callable",
+                        target.type_name().error_name()
+                    ),
+                    line: span.line,
+                })
+            }
+        };
+
+        let target_name = target.to_string();
+
+        if target_name.is_none() {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: format!(
+                    "{} to string conversion failed",
+                    target.type_name().error_name()
+                ),
+                line: span.line,
+            })?;
+        }
+
+        Ok(target_name.unwrap_or("".to_string()).into_bytes())
+    }
+
+    fn die(&mut self) {
+        self.die = true;
+    }
+
+	/// Check that `value` is PhpValue, if it is not it returns the error.
+	///
+	/// It is used with arithmetic operations and logical operations.
+    fn php_value_or_die(
+        &mut self,
+		span: &Span,
+        value: Result<PhpValue, PhpError>,
+    ) -> Result<PhpValue, PhpError> {
+        match value {
+            Ok(value) => Ok(value),
+            Err(mut error) => {
+				error.line = span.line;
+
+				Err(error)
+			},
+        }
+    }
+
+    fn get_variable_name(&mut self, variable: &Variable) -> Result<Vec<u8>, PhpError> {
+        match variable {
+            Variable::SimpleVariable(sv) => Ok(variable_key(sv).to_vec()),
+            Variable::VariableVariable(vv) => {
+                let value = self.get_variable_value(&vv.variable)?;
+
+                if let PhpValue::String(value) = value {
+                    Ok(value.bytes)
+                } else {
+                    let error = format!(
+                        "Variable variable must be a string, got {}",
+                        value.type_name().error_name(),
+                    );
+
+                    Err(PhpError {
+                        level: ErrorLevel::Fatal,
+                        message: error,
+                        line: vv.span.line,
+                    })
+                }
+            }
+            Variable::BracedVariableVariable(bvv) => {
+                let expr_value = self.eval_expression(&bvv.variable)?;
+
+                let expr_as_string = expr_value.to_string();
+
+                if expr_as_string.is_none() {
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Warning,
+                        message: format!(
+                            "{} to string conversion failed",
+                            expr_value.type_name().error_name()
+                        ),
+                        line: bvv.start.line,
+                    })?;
+
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Warning,
+                        message: format!("Undefined variable $ on line {}", bvv.start.line),
+                        line: bvv.start.line,
+                    })?;
+
+                    return Ok(b"".to_vec());
+                }
+
+                let variable_name = expr_as_string.unwrap();
+
+                Ok(variable_name.into())
+            }
+        }
+    }
+
+    fn get_variable_value(&mut self, variable: &Variable) -> Result<PhpValue, PhpError> {
+        match variable {
+            Variable::SimpleVariable(sv) => {
+                let var_name = variable_key(sv);
+
+                let value = self.env.get_var(var_name);
+
+                if value.is_some() {
+                    Ok(value.unwrap())
+                } else {
+                    let warning = format!(
+                        "Undefined variable {} on line {}",
+                        display_variable_name(var_name),
+                        sv.span.line
+                    );
+
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Warning,
+                        message: warning,
+                        line: sv.span.line,
+                    })?;
+
+                    Ok(NULL)
+                }
+            }
+            Variable::VariableVariable(vv) => self.get_var(&vv.variable),
+            Variable::BracedVariableVariable(bvv) => {
+                let expr_value = self.eval_expression(&bvv.variable)?;
+
+                let expr_as_string = expr_value.to_string();
+
+                if expr_as_string.is_none() {
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Warning,
+                        message: format!(
+                            "Braced variable variable must be a string, got {}",
+                            expr_value.type_name().error_name(),
+                        ),
+                        line: bvv.start.line,
+                    })?;
+
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Warning,
+                        message: "Undefined variable $".to_string(),
+                        line: bvv.start.line,
+                    })?;
+
+                    return Ok(NULL);
+                }
+
+                let variable_name = expr_as_string.unwrap();
+
+                if !self.env.var_exists(&variable_name.as_bytes()) {
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Warning,
+                        message: format!("Undefined variable $ on line {}", bvv.start.line),
+                        line: bvv.start.line,
+                    })?;
+
+                    return Ok(NULL);
+                }
+
+                Ok(self.env.get_var(variable_name.as_bytes()).unwrap())
+            }
+        }
+    }
+
+    /// Returns the value of the variable. If it does not exist, the warning is added and Null is returned.
+    fn get_var(&mut self, variable: &Variable) -> Result<PhpValue, PhpError> {
+        let var_name = self.get_variable_name(&variable)?;
+
+        let value = self.env.get_var(&var_name);
+
+        if value.is_some() {
+            Ok(value.unwrap())
+        } else if var_name == b"this" {
+            Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: "Using $this when not in object context".to_string(),
+                line: get_span_from_var(variable).line,
+            })
+        } else {
+            let warning = format!("Undefined variable {}", display_variable_name(&var_name));
+
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: warning,
+                line: get_span_from_var(variable).line,
+            })?;
+
+            Ok(NULL)
+        }
+    }
+
+    pub fn set_identifier(
+        &mut self,
+        ident: &[u8],
+        value: PhpValue,
+        span: Span,
+    ) -> Option<PhpError> {
+        match self.env.identifier_entry(ident.to_vec()) {
+            std::collections::hash_map::Entry::Occupied(entry) => Some(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!("Cannot redeclare identifier {}", display_name(entry.key())),
+                line: span.line,
+            }),
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(value);
+
+                None
+            }
+        }
+    }
+}
+
+/// The value of `true`, `false` and `null`, written in any case and maybe fully qualified like `\TRUE`.
+fn get_keyword_literal(name: &[u8]) -> Option<PhpValue> {
+    let name = name.strip_prefix(b"\\").unwrap_or(name);
+
+    if name.eq_ignore_ascii_case(b"true") {
+        Some(PhpValue::Bool(true))
+    } else if name.eq_ignore_ascii_case(b"false") {
+        Some(PhpValue::Bool(false))
+    } else if name.eq_ignore_ascii_case(b"null") {
+        Some(NULL)
+    } else {
+        None
+    }
+}
+
+/// The warning of a read of an array key that does not exist.
+fn undefined_array_key(key: &ArrayKey, span: Span) -> PhpError {
+    let key = match key {
+        ArrayKey::Int(i) => i.to_string(),
+        ArrayKey::String(s) => display_string(&s.bytes),
+    };
+
+    PhpError {
+        level: ErrorLevel::Warning,
+        message: format!("Undefined array key {}", key),
+        line: span.line,
+    }
+}
+
+/// The object and the method name of a callable array, `[$object, 'method']`.
+fn get_callable_method(array: &PhpArray) -> Option<(Rc<RefCell<PhpObject>>, Vec<u8>)> {
+    if array.len() != 2 {
+        return None;
+    }
+
+    match (
+        array.get(&ArrayKey::Int(0)).as_deref(),
+        array.get(&ArrayKey::Int(1)).as_deref(),
+    ) {
+        (Some(PhpValue::Object(object)), Some(PhpValue::String(name))) => {
+            Some((Rc::clone(object), name.bytes.clone()))
+        }
+        _ => None,
+    }
+}
+
+/// The error of an expression that the evaluator does not support, like a statement.
+fn unsupported_expression(expression: &Expression) -> PhpError {
+    PhpError {
+        level: ErrorLevel::Fatal,
+        message: format!("Unsupported expression: {}", variant_name(expression)),
+        line: expression.first_span().map_or(0, |span| span.line),
+    }
+}
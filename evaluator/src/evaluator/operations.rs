@@ -0,0 +1,582 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use php_parser_rs::lexer::byte_string::ByteString;
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::operators::{
+    ArithmeticOperationExpression, BitwiseOperationExpression, ComparisonOperationExpression,
+    LogicalOperationExpression,
+};
+use php_parser_rs::parser::ast::{
+    CoalesceExpression, Expression, ShortTernaryExpression, TernaryExpression,
+};
+
+use crate::arithmetic::unsupported_operand_types;
+use crate::conversion::{parse_leading_number, parse_numeric_string};
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::{Evaluator, NULL};
+
+impl<'w> Evaluator<'w> {
+    pub(super) fn eval_arithmetic_operation(
+        &mut self,
+        operation: &ArithmeticOperationExpression,
+    ) -> Result<PhpValue, PhpError> {
+        match operation {
+            ArithmeticOperationExpression::Addition { left, plus, right } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "+", right_value, plus)
+            }
+            ArithmeticOperationExpression::Subtraction { left, minus, right } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "-", right_value, minus)
+            }
+            ArithmeticOperationExpression::Multiplication {
+                left,
+                asterisk,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "*", right_value, asterisk)
+            }
+            ArithmeticOperationExpression::Division { left, slash, right } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "/", right_value, slash)
+            }
+            ArithmeticOperationExpression::Modulo {
+                left,
+                percent,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "%", right_value, percent)
+            }
+            ArithmeticOperationExpression::Exponentiation { left, pow, right } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "**", right_value, pow)
+            }
+            ArithmeticOperationExpression::Negative { right, minus } => {
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(right_value, "*", PhpValue::Int(-1), minus)
+            }
+            ArithmeticOperationExpression::Positive { right, plus } => {
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(right_value, "*", PhpValue::Int(1), plus)
+            }
+            ArithmeticOperationExpression::PreIncrement { right, increment } => {
+                let (_, new_value) = self.increment_or_decrement(right, *increment, true)?;
+
+                Ok(new_value)
+            }
+            ArithmeticOperationExpression::PostIncrement { left, increment } => {
+                let (old_value, _) = self.increment_or_decrement(left, *increment, true)?;
+
+                Ok(old_value)
+            }
+            ArithmeticOperationExpression::PreDecrement { right, decrement } => {
+                let (_, new_value) = self.increment_or_decrement(right, *decrement, false)?;
+
+                Ok(new_value)
+            }
+            ArithmeticOperationExpression::PostDecrement { left, decrement } => {
+                let (old_value, _) = self.increment_or_decrement(left, *decrement, false)?;
+
+                Ok(old_value)
+            }
+        }
+    }
+
+    pub(super) fn eval_bitwise_operation(
+        &mut self,
+        operation: &BitwiseOperationExpression,
+    ) -> Result<PhpValue, PhpError> {
+        match operation {
+            BitwiseOperationExpression::And { left, and, right } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "&", right_value, and)
+            }
+            BitwiseOperationExpression::Or { left, or, right } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "|", right_value, or)
+            }
+            BitwiseOperationExpression::Xor { left, xor, right } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "^", right_value, xor)
+            }
+            BitwiseOperationExpression::LeftShift {
+                left,
+                left_shift,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, "<<", right_value, left_shift)
+            }
+            BitwiseOperationExpression::RightShift {
+                left,
+                right_shift,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                self.eval_arithmetic(left_value, ">>", right_value, right_shift)
+            }
+            BitwiseOperationExpression::Not { right, not } => {
+                let right_value = self.eval_expression(&right)?;
+
+                self.php_value_or_die(not, !right_value)
+            }
+        }
+    }
+
+    pub(super) fn eval_comparison_operation(
+        &mut self,
+        operation: &ComparisonOperationExpression,
+    ) -> Result<PhpValue, PhpError> {
+        match operation {
+            ComparisonOperationExpression::Equal {
+                left,
+                double_equals,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let equals = self.values_loosely_equal(&left_value, &right_value, double_equals)?;
+
+                Ok(PhpValue::Bool(equals))
+            }
+            ComparisonOperationExpression::Identical {
+                left,
+                triple_equals,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let is_identical = left_value.is_identical(&right_value).map(PhpValue::Bool);
+
+                self.php_value_or_die(triple_equals, is_identical)
+            }
+            ComparisonOperationExpression::NotEqual {
+                left,
+                bang_equals,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let equals = self.values_loosely_equal(&left_value, &right_value, bang_equals)?;
+
+                Ok(PhpValue::Bool(!equals))
+            }
+            ComparisonOperationExpression::AngledNotEqual {
+                left,
+                angled_left_right,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let equals =
+                    self.values_loosely_equal(&left_value, &right_value, angled_left_right)?;
+
+                Ok(PhpValue::Bool(!equals))
+            }
+            ComparisonOperationExpression::NotIdentical {
+                left,
+                bang_double_equals,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let is_not_identical = left_value
+                    .is_identical(&right_value)
+                    .map(|is_identical| PhpValue::Bool(!is_identical));
+
+                self.php_value_or_die(bang_double_equals, is_not_identical)
+            }
+            ComparisonOperationExpression::LessThan {
+                left,
+                less_than,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let ordering = self.compare_values(&left_value, &right_value, less_than)?;
+
+                Ok(PhpValue::Bool(ordering == Some(Ordering::Less)))
+            }
+            ComparisonOperationExpression::GreaterThan {
+                left,
+                greater_than,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let ordering = self.compare_values(&left_value, &right_value, greater_than)?;
+
+                Ok(PhpValue::Bool(ordering == Some(Ordering::Greater)))
+            }
+            ComparisonOperationExpression::LessThanOrEqual {
+                left,
+                less_than_equals,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let ordering = self.compare_values(&left_value, &right_value, less_than_equals)?;
+
+                Ok(PhpValue::Bool(matches!(
+                    ordering,
+                    Some(Ordering::Less | Ordering::Equal)
+                )))
+            }
+            ComparisonOperationExpression::GreaterThanOrEqual {
+                left,
+                greater_than_equals,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let ordering =
+                    self.compare_values(&left_value, &right_value, greater_than_equals)?;
+
+                Ok(PhpValue::Bool(matches!(
+                    ordering,
+                    Some(Ordering::Greater | Ordering::Equal)
+                )))
+            }
+            ComparisonOperationExpression::Spaceship {
+                left,
+                spaceship,
+                right,
+            } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                let ordering = self.compare_values(&left_value, &right_value, spaceship)?;
+
+                // values that cannot be compared are reported as greater, like PHP does
+                match ordering {
+                    Some(Ordering::Less) => Ok(PhpValue::Int(-1)),
+                    Some(Ordering::Equal) => Ok(PhpValue::Int(0)),
+                    _ => Ok(PhpValue::Int(1)),
+                }
+            }
+        }
+    }
+
+    pub(super) fn eval_logical_operation(
+        &mut self,
+        operation: &LogicalOperationExpression,
+    ) -> Result<PhpValue, PhpError> {
+        match operation {
+            LogicalOperationExpression::And { left, right, .. } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                Ok(PhpValue::Bool(
+                    left_value.is_true() && right_value.is_true(),
+                ))
+            }
+            LogicalOperationExpression::Or { left, right, .. } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                Ok(PhpValue::Bool(
+                    left_value.is_true() || right_value.is_true(),
+                ))
+            }
+            LogicalOperationExpression::Not { right, .. } => {
+                let right_value = self.eval_expression(&right)?;
+
+                Ok(PhpValue::Bool(!right_value.is_true()))
+            }
+            LogicalOperationExpression::LogicalAnd { left, right, .. } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                Ok(PhpValue::Bool(
+                    left_value.is_true() && right_value.is_true(),
+                ))
+            }
+            LogicalOperationExpression::LogicalOr { left, right, .. } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                Ok(PhpValue::Bool(
+                    left_value.is_true() || right_value.is_true(),
+                ))
+            }
+            LogicalOperationExpression::LogicalXor { left, right, .. } => {
+                let left_value = self.eval_expression(&left)?;
+                let right_value = self.eval_expression(&right)?;
+
+                Ok(PhpValue::Bool(left_value.is_true() ^ right_value.is_true()))
+            }
+        }
+    }
+
+    /// Evaluates `condition ? then : else`, only the taken branch is evaluated.
+    pub(super) fn eval_ternary(
+        &mut self,
+        ternary: &TernaryExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let condition = self.eval_expression(&ternary.condition)?;
+
+        // `condition ? : else`, with a space or a comment, is a short ternary
+        if condition.clone().is_true() {
+            match &*ternary.then {
+                Expression::Noop => Ok(condition),
+                then => self.eval_expression(then),
+            }
+        } else {
+            self.eval_expression(&ternary.r#else)
+        }
+    }
+
+    /// Evaluates `condition ?: else`, the condition is evaluated once and given when it is true.
+    pub(super) fn eval_short_ternary(
+        &mut self,
+        ternary: &ShortTernaryExpression,
+    ) -> Result<PhpValue, PhpError> {
+        let condition = self.eval_expression(&ternary.condition)?;
+
+        if condition.clone().is_true() {
+            Ok(condition)
+        } else {
+            self.eval_expression(&ternary.r#else)
+        }
+    }
+
+    /// Evaluates `lhs ?? rhs`, the right side is evaluated only when the left side is
+    /// null or undefined.
+    ///
+    /// Like isset(), reading an undefined variable, array key or property on the left
+    /// side does not warn.
+    pub(super) fn eval_coalesce(
+        &mut self,
+        coalesce: &CoalesceExpression,
+    ) -> Result<PhpValue, PhpError> {
+        match self.eval_quietly(&coalesce.lhs)? {
+            PhpValue::Null => self.eval_expression(&coalesce.rhs),
+            value => Ok(value),
+        }
+    }
+
+    /// Evaluates a variable, an array element or a property, giving null instead of
+    /// warning when it or one of its containers does not exist.
+    ///
+    /// Any other expression is evaluated as usual, so the calls in it still warn.
+    fn eval_quietly(&mut self, expression: &Expression) -> Result<PhpValue, PhpError> {
+        match expression {
+            Expression::Variable(variable) => {
+                let name = self.get_variable_name(variable)?;
+
+                Ok(self.env.get_var(&name).unwrap_or(NULL))
+            }
+            Expression::ArrayIndex(fetch) => {
+                let Some(index) = &fetch.index else {
+                    return self.eval_expression(expression);
+                };
+
+                let value = self.eval_quietly(&fetch.array)?;
+
+                let index = self.eval_expression(index)?;
+
+                match value {
+                    PhpValue::Array(array) => {
+                        let key = self.get_array_key(&index, fetch.left_bracket)?;
+
+                        Ok(array.get(&key).map_or(NULL, Cow::into_owned))
+                    }
+                    PhpValue::String(string) => {
+                        let offset = match &index {
+                            PhpValue::Int(i) => Some(*i),
+                            PhpValue::String(s) => {
+                                std::str::from_utf8(s).ok().and_then(|s| s.parse().ok())
+                            }
+                            _ => None,
+                        };
+
+                        // negative offsets are counted from the end of the string
+                        let position = offset.map(|offset| {
+                            if offset < 0 {
+                                string.len() as i64 + offset
+                            } else {
+                                offset
+                            }
+                        });
+
+                        match position {
+                            Some(position) if position >= 0 && position < string.len() as i64 => {
+                                Ok(PhpValue::String(ByteString::new(vec![
+                                    string[position as usize],
+                                ])))
+                            }
+                            _ => Ok(NULL),
+                        }
+                    }
+                    PhpValue::Object(_) => self.eval_expression(expression),
+                    _ => Ok(NULL),
+                }
+            }
+            Expression::PropertyFetch(fetch) => {
+                let target = self.eval_quietly(&fetch.target)?;
+
+                self.get_property_quietly(target, &fetch.property)
+            }
+            Expression::NullsafePropertyFetch(fetch) => {
+                let target = self.eval_quietly(&fetch.target)?;
+
+                self.get_property_quietly(target, &fetch.property)
+            }
+            Expression::Parenthesized(parenthesized) => self.eval_quietly(&parenthesized.expr),
+            expression => self.eval_expression(expression),
+        }
+    }
+
+    /// The property of `target`, null when it is not an object or has no such property.
+    fn get_property_quietly(
+        &mut self,
+        target: PhpValue,
+        property: &Expression,
+    ) -> Result<PhpValue, PhpError> {
+        let property_name = self.get_member_name(property)?;
+
+        let PhpValue::Object(object) = target else {
+            return Ok(NULL);
+        };
+
+        let value = object.borrow().get_property(&property_name).cloned();
+
+        Ok(value.unwrap_or(NULL))
+    }
+
+    pub(super) fn compare_values(
+        &mut self,
+        left: &PhpValue,
+        right: &PhpValue,
+        span: &Span,
+    ) -> Result<Option<Ordering>, PhpError> {
+        left.compare(right).map_err(|mut error| {
+            error.line = span.line;
+
+            error
+        })
+    }
+
+    pub(super) fn values_loosely_equal(
+        &mut self,
+        left: &PhpValue,
+        right: &PhpValue,
+        span: &Span,
+    ) -> Result<bool, PhpError> {
+        left.loosely_equals(right).map_err(|mut error| {
+            error.line = span.line;
+
+            error
+        })
+    }
+
+    /// Performs `left operation_sign right` for the arithmetic and bitwise operators.
+    ///
+    /// The operands are converted to numbers first: null is 0, a boolean is 0 or 1 and a
+    /// numeric string is its number. A string that only starts with a number is that
+    /// number with a warning, and any other string is a TypeError.
+    pub(super) fn eval_arithmetic(
+        &mut self,
+        left: PhpValue,
+        operation_sign: &str,
+        right: PhpValue,
+        span: &Span,
+    ) -> Result<PhpValue, PhpError> {
+        let left_operand = self.get_arithmetic_operand(&left, span)?;
+        let right_operand = self.get_arithmetic_operand(&right, span)?;
+
+        let (Some(left_operand), Some(right_operand)) = (left_operand, right_operand) else {
+            let mut error = unsupported_operand_types(&left, operation_sign, &right);
+
+            error.line = span.line;
+
+            return Err(error);
+        };
+
+        let result = match operation_sign {
+            "+" => left_operand + right_operand,
+            "-" => left_operand - right_operand,
+            "*" => left_operand * right_operand,
+            "/" => left_operand / right_operand,
+            "%" => left_operand % right_operand,
+            "**" => left_operand.pow(right_operand),
+            "&" => left_operand & right_operand,
+            "|" => left_operand | right_operand,
+            "^" => left_operand ^ right_operand,
+            "<<" => left_operand << right_operand,
+            ">>" => left_operand >> right_operand,
+            _ => unreachable!("{} is not an arithmetic operator", operation_sign),
+        };
+
+        self.php_value_or_die(span, result)
+    }
+
+    /// The value that `value` has as an operand of an arithmetic operation, None if it is
+    /// a string that is not a number. Arrays and objects are kept, the operation itself
+    /// gives their error.
+    fn get_arithmetic_operand(
+        &mut self,
+        value: &PhpValue,
+        span: &Span,
+    ) -> Result<Option<PhpValue>, PhpError> {
+        let string = match value {
+            PhpValue::Null => return Ok(Some(PhpValue::Int(0))),
+            PhpValue::Bool(bool) => return Ok(Some(PhpValue::Int(*bool as i64))),
+            PhpValue::String(string) => string,
+            value => return Ok(Some(value.clone())),
+        };
+
+        if let Some(number) = parse_numeric_string(string) {
+            return Ok(Some(number));
+        }
+
+        let Some(number) = parse_leading_number(string) else {
+            return Ok(None);
+        };
+
+        self.raise_error(PhpError {
+            level: ErrorLevel::Warning,
+            message: "A non-numeric value encountered".to_string(),
+            line: span.line,
+        })?;
+
+        Ok(Some(number))
+    }
+}
@@ -0,0 +1,131 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::str;
+
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::EchoStatement;
+
+use crate::conversion::scalar_to_string;
+use crate::php_value::{ErrorLevel, PhpError, PhpObject, PhpValue};
+
+use super::{Evaluator, NULL};
+
+impl<'w> Evaluator<'w> {
+    /// Each value is evaluated and written before the next one is touched,
+    /// so output produced while evaluating a value comes first.
+    /// A failed conversion only emits a warning, it never stops the remaining values.
+    pub(super) fn eval_echo(&mut self, echo: EchoStatement) -> Result<PhpValue, PhpError> {
+        for expr in echo.values {
+            let expression_result = self.eval_expression(&expr)?;
+
+            let expression_as_string = self.value_to_output_string(expression_result, echo.echo)?;
+
+            self.write_output(&expression_as_string, echo.echo.line)?;
+        }
+
+        Ok(NULL)
+    }
+
+    /// Adds text to the output, it is flushed right away if the "implicit_flush" setting is on.
+    ///
+    /// `line` is the line of the code that writes it, kept for headers_sent() if it is the first output.
+    pub(crate) fn write_output(&mut self, text: &str, line: usize) -> Result<(), PhpError> {
+        if self.output_start.is_none() && !text.is_empty() {
+            self.output_start = Some((self.current_file.clone(), line));
+        }
+
+        self.output.write(text);
+
+        if self.ini.get_int("implicit_flush") != 0 {
+            self.write_pending_output()?;
+        }
+
+        Ok(())
+    }
+
+    /// Same as `flush_output()`, an IO error stops the program.
+    pub(super) fn write_pending_output(&mut self) -> Result<(), PhpError> {
+        self.flush_output().map_err(|error| PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!("Unable to write the output: {}", error),
+            line: 0,
+        })
+    }
+
+    /// Converts a value to the string that `echo` and `print` write.
+    ///
+    /// Arrays cannot be converted, so the warning is added and "Array" is returned,
+    /// just like PHP does. Any other value that cannot be converted is a fatal error.
+    /// Converts a value to a string, with the "precision" setting for floats.
+    ///
+    /// Arrays are converted to "Array" with a warning, and objects with their
+    /// __toString() method.
+    pub(crate) fn value_to_output_string(
+        &mut self,
+        value: PhpValue,
+        span: Span,
+    ) -> Result<String, PhpError> {
+        let value_as_string = scalar_to_string(&value, self.ini.get_int("precision"));
+
+        if let Some(value_as_string) = value_as_string {
+            return Ok(value_as_string);
+        }
+
+        if let PhpValue::Array(_) = value {
+            self.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: "Array to string conversion".to_string(),
+                line: span.line,
+            })?;
+
+            return Ok("Array".to_string());
+        }
+
+        if let PhpValue::Object(object) = &value {
+            return self.object_to_string(object.clone(), span);
+        }
+
+        Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "{} to string conversion failed",
+                value.type_name().error_name()
+            ),
+            line: span.line,
+        })
+    }
+
+    /// Converts an object to a string with its __toString() method, an object without
+    /// one cannot be converted.
+    fn object_to_string(
+        &mut self,
+        object: Rc<RefCell<PhpObject>>,
+        span: Span,
+    ) -> Result<String, PhpError> {
+        let class_name = object.borrow().class.name.clone();
+
+        if object.borrow().class.get_method(b"__toString").is_none() {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: format!(
+                    "Object of class {} could not be converted to string",
+                    class_name
+                ),
+                line: span.line,
+            });
+        }
+
+        match self.call_object_method(object, "__toString", vec![], span)? {
+            PhpValue::String(string) => Ok(String::from_utf8_lossy(&string).to_string()),
+            value => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "{}::__toString(): Return value must be of type string, {} returned",
+                    class_name,
+                    value.type_name().error_name()
+                ),
+                line: span.line,
+            }),
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::Expression;
+
+use crate::helpers::variable_key;
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::{Callee, Evaluator, NULL};
+
+/// The indexes of an array element, None is the index of `[]`.
+type Indexes = Vec<(Option<PhpValue>, Span)>;
+
+/// A variable or an element of an array in a variable, like `$a['b'][0]`, with its
+/// indexes already evaluated. It is the place that a reference is taken to.
+pub(super) struct ReferencePath {
+    name: Vec<u8>,
+    indexes: Indexes,
+}
+
+impl ReferencePath {
+    /// The path of the element `key` of the array at this path.
+    pub(super) fn element(&self, key: &ArrayKey, span: Span) -> ReferencePath {
+        let mut indexes = self.indexes.clone();
+
+        indexes.push((Some(key.to_value()), span));
+
+        ReferencePath {
+            name: self.name.clone(),
+            indexes,
+        }
+    }
+}
+
+impl<'w> Evaluator<'w> {
+    /// The shared value of the variable or the array element that `&` is applied to,
+    /// like `&$a` or `&$a['key'][]`. A variable or an element that does not exist is
+    /// created with null.
+    pub(super) fn get_reference(
+        &mut self,
+        expression: &Expression,
+        ampersand: Span,
+    ) -> Result<Rc<RefCell<PhpValue>>, PhpError> {
+        let path = self.get_reference_path(expression, ampersand)?;
+
+        self.get_path_reference(&path)
+    }
+
+    /// The shared value at a path, the variable and the elements that do not exist are
+    /// created with null.
+    pub(super) fn get_path_reference(
+        &mut self,
+        path: &ReferencePath,
+    ) -> Result<Rc<RefCell<PhpValue>>, PhpError> {
+        let variable = self.get_variable_for_reference(&path.name);
+
+        if path.indexes.is_empty() {
+            return Ok(variable);
+        }
+
+        self.change_referenced_element(&variable, &path.indexes, |array, key| {
+            array.make_reference(key)
+        })
+    }
+
+    /// Evaluates the argument given to the parameter at `position` of the callee.
+    ///
+    /// A variable or an array element given to a parameter passed by reference is shared
+    /// with the parameter and added to `references`. The result of a call is a temporary
+    /// value, it is passed by value with a notice, like PHP does. The references to
+    /// properties are not supported, a property is passed by value.
+    pub(super) fn eval_argument(
+        &mut self,
+        argument: &Expression,
+        callee: Option<Callee>,
+        position: Option<usize>,
+        references: &mut Vec<(Vec<u8>, Rc<RefCell<PhpValue>>)>,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let parameter = callee
+            .zip(position)
+            .and_then(|(callee, position)| callee.function.parameters.get(position))
+            .filter(|parameter| parameter.by_reference && !parameter.ellipsis);
+
+        let (Some(callee), Some(parameter)) = (callee, parameter) else {
+            return self.eval_expression(argument);
+        };
+
+        if is_reference_path(argument) {
+            let reference = self.get_reference(argument, span)?;
+
+            let value = reference.borrow().clone();
+
+            references.push((variable_key(&parameter.name).to_vec(), reference));
+
+            return Ok(value);
+        }
+
+        if let Expression::PropertyFetch(_)
+        | Expression::NullsafePropertyFetch(_)
+        | Expression::StaticPropertyFetch(_) = argument
+        {
+            return self.eval_expression(argument);
+        }
+
+        if let Expression::FunctionCall(_)
+        | Expression::MethodCall(_)
+        | Expression::NullsafeMethodCall(_)
+        | Expression::StaticMethodCall(_)
+        | Expression::New(_) = argument
+        {
+            let value = self.eval_expression(argument)?;
+
+            self.raise_error(PhpError {
+                level: ErrorLevel::Notice,
+                message: "Only variables should be passed by reference".to_string(),
+                line: span.line,
+            })?;
+
+            return Ok(value);
+        }
+
+        let function_name = match callee.class_name {
+            Some(class_name) => format!("{}::{}", class_name, callee.function.name),
+            None => callee.function.name.to_string(),
+        };
+
+        Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "{}(): Argument #{} ({}) could not be passed by reference",
+                function_name,
+                position.unwrap_or_default() + 1,
+                parameter.name.name
+            ),
+            line: span.line,
+        })
+    }
+
+    /// Makes a variable or an array element share `reference`, like `$a = &$b` or
+    /// `$a['key'] = &$b`. The value the target had before is not changed.
+    pub(super) fn assign_reference(
+        &mut self,
+        target: &Expression,
+        reference: Rc<RefCell<PhpValue>>,
+        equals: Span,
+    ) -> Result<(), PhpError> {
+        let ReferencePath { name, indexes } = self.get_reference_path(target, equals)?;
+
+        if name == b"this" {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "Cannot re-assign $this".to_string(),
+                line: equals.line,
+            });
+        }
+
+        if indexes.is_empty() {
+            self.env.set_var_rc(&name, reference);
+
+            return Ok(());
+        }
+
+        let variable = self.get_variable_for_reference(&name);
+
+        self.change_referenced_element(&variable, &indexes, |array, key| {
+            array.insert_reference(key, reference)
+        })
+    }
+
+    /// The name of the variable and the evaluated indexes of `$a['b']['c']`, from left
+    /// to right.
+    pub(super) fn get_reference_path(
+        &mut self,
+        expression: &Expression,
+        span: Span,
+    ) -> Result<ReferencePath, PhpError> {
+        match expression {
+            Expression::Variable(variable) => Ok(ReferencePath {
+                name: self.get_variable_name(variable)?,
+                indexes: vec![],
+            }),
+            Expression::ArrayIndex(index) => {
+                let mut path = self.get_reference_path(&index.array, span)?;
+
+                let value = match &index.index {
+                    Some(value) => Some(self.eval_expression(value)?),
+                    None => None,
+                };
+
+                path.indexes.push((value, index.left_bracket));
+
+                Ok(path)
+            }
+            _ => Err(PhpError {
+                level: ErrorLevel::ParseError,
+                message: "References must be to variables or array elements".to_string(),
+                line: span.line,
+            }),
+        }
+    }
+
+    fn get_variable_for_reference(&mut self, name: &[u8]) -> Rc<RefCell<PhpValue>> {
+        if !self.env.var_exists(name) {
+            self.env.set_var(name, &NULL);
+        }
+
+        self.env.get_var_with_rc(name).unwrap()
+    }
+
+    /// Calls `change` with the array and the key of the last index, the arrays in the
+    /// path are created like in an assignment.
+    ///
+    /// Every value is taken out of its variable or its reference while it is changed,
+    /// so that the errors raised meanwhile can run an error handler.
+    fn change_referenced_element<T>(
+        &mut self,
+        container: &Rc<RefCell<PhpValue>>,
+        indexes: &[(Option<PhpValue>, Span)],
+        change: impl FnOnce(&mut PhpArray, ArrayKey) -> T,
+    ) -> Result<T, PhpError> {
+        let mut current = std::mem::replace(&mut *container.borrow_mut(), NULL);
+
+        let result = self.change_element_path(&mut current, indexes, change);
+
+        *container.borrow_mut() = current;
+
+        result
+    }
+
+    fn change_element_path<T>(
+        &mut self,
+        container: &mut PhpValue,
+        indexes: &[(Option<PhpValue>, Span)],
+        change: impl FnOnce(&mut PhpArray, ArrayKey) -> T,
+    ) -> Result<T, PhpError> {
+        let ((index, span), remaining_indexes) = indexes.split_first().unwrap();
+
+        self.convert_to_array(container, *span)?;
+
+        let array = match container {
+            PhpValue::Array(array) => array,
+            PhpValue::String(_) => {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Cannot create references to/from string offsets".to_string(),
+                    line: span.line,
+                })
+            }
+            PhpValue::Object(object) => {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: format!(
+                        "Cannot use object of type {} as array",
+                        object.borrow().class.name
+                    ),
+                    line: span.line,
+                })
+            }
+            _ => {
+                return Err(PhpError {
+                    level: ErrorLevel::Fatal,
+                    message: "Cannot use a scalar value as an array".to_string(),
+                    line: span.line,
+                })
+            }
+        };
+
+        let key = match index {
+            Some(index) => self.get_array_key(index, *span)?,
+            None => array.next_key().map_err(|error| PhpError {
+                line: span.line,
+                ..error
+            })?,
+        };
+
+        if remaining_indexes.is_empty() {
+            return Ok(change(array, key));
+        }
+
+        if let Some(reference) = array.get_reference(&key) {
+            return self.change_referenced_element(&reference, remaining_indexes, change);
+        }
+
+        if array.get(&key).is_none() {
+            array.insert(key.clone(), NULL);
+        }
+
+        let element = array.get_mut(&key).unwrap();
+
+        self.change_element_path(element, remaining_indexes, change)
+    }
+}
+
+/// Whether a reference can be taken to the expression, which is a variable or an element
+/// of an array in a variable, like `$a['key'][0]`.
+pub(super) fn is_reference_path(expression: &Expression) -> bool {
+    match expression {
+        Expression::Variable(_) => true,
+        Expression::ArrayIndex(index) => is_reference_path(&index.array),
+        _ => false,
+    }
+}
@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use php_parser_rs::parser::ast::{GlobalStatement, StaticStatement};
+
+use crate::helpers::get_span_from_var;
+use crate::php_value::{PhpError, PhpValue};
+
+use super::{Evaluator, NULL};
+
+impl<'w> Evaluator<'w> {
+    /// Evaluates `global $a, $b;`, the variables of the current scope become
+    /// references to the global ones, which are created as null if needed.
+    pub(super) fn eval_global_statement(
+        &mut self,
+        statement: GlobalStatement,
+    ) -> Result<PhpValue, PhpError> {
+        for variable in &statement.variables {
+            let name = self.get_variable_name(variable)?;
+
+            let value = self.env.get_global_var_with_rc(&name);
+
+            self.env.set_var_rc(&name, value);
+        }
+
+        Ok(NULL)
+    }
+
+    /// Evaluates `static $a = value, $b;`, the variables keep their value between
+    /// the calls of the function.
+    ///
+    /// The value is evaluated the first time the statement runs. The variables belong
+    /// to the statement, so the methods inherited by the child classes and the
+    /// closures created by the same code share them.
+    pub(super) fn eval_static_statement(
+        &mut self,
+        statement: StaticStatement,
+    ) -> Result<PhpValue, PhpError> {
+        for variable in &statement.vars {
+            let name = self.get_variable_name(&variable.var)?;
+
+            let key = (
+                self.current_file.clone(),
+                get_span_from_var(&variable.var).position,
+            );
+
+            let value = match self.static_variables.get(&key) {
+                Some(value) => Rc::clone(value),
+                None => {
+                    let initial_value = match &variable.default {
+                        Some(default) => self.eval_expression(default)?,
+                        None => NULL,
+                    };
+
+                    let value = Rc::new(RefCell::new(initial_value));
+
+                    self.static_variables.insert(key, Rc::clone(&value));
+
+                    value
+                }
+            };
+
+            self.env.set_var_rc(&name, value);
+        }
+
+        Ok(NULL)
+    }
+}
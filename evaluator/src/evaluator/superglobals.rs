@@ -0,0 +1,67 @@
+use std::time::SystemTime;
+
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_value::PhpValue;
+
+use super::Evaluator;
+
+/// The superglobals that stay empty, there is no request in the command line.
+const EMPTY_SUPERGLOBALS: &[&[u8]] = &[b"_GET", b"_POST", b"_COOKIE"];
+
+impl<'w> Evaluator<'w> {
+    /// Defines $_SERVER, $_ENV and the other superglobals, and $argv and $argc.
+    ///
+    /// `arguments` are the ones given to the script, the name of the script comes
+    /// before them in $argv. $GLOBALS is given by the environment when it is read.
+    pub(crate) fn define_superglobals(&mut self, arguments: &[String]) {
+        let argv: PhpArray = std::iter::once(self.current_file.as_str())
+            .chain(arguments.iter().map(String::as_str))
+            .map(|argument| PhpValue::String(argument.into()))
+            .collect();
+
+        let argc = PhpValue::Int(argv.len() as i64);
+        let argv = PhpValue::Array(argv);
+
+        let request_time = self
+            .now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        let mut server = PhpArray::new();
+
+        let script = PhpValue::String(self.current_file.as_str().into());
+
+        for (name, value) in [
+            ("PHP_SELF", script.clone()),
+            ("SCRIPT_NAME", script.clone()),
+            ("SCRIPT_FILENAME", script),
+            ("REQUEST_TIME", PhpValue::Int(request_time as i64)),
+            ("argv", argv.clone()),
+            ("argc", argc.clone()),
+        ] {
+            server.insert(ArrayKey::String(name.into()), value);
+        }
+
+        let mut env = PhpArray::new();
+
+        for (name, value) in std::env::vars() {
+            env.insert(
+                ArrayKey::String(name.into()),
+                PhpValue::String(value.into()),
+            );
+        }
+
+        self.env
+            .set_superglobal(b"_SERVER", PhpValue::Array(server));
+        self.env.set_superglobal(b"_ENV", PhpValue::Array(env));
+
+        for name in EMPTY_SUPERGLOBALS {
+            self.env
+                .set_superglobal(name, PhpValue::Array(PhpArray::new()));
+        }
+
+        self.env.set_var(b"argv", &argv);
+        self.env.set_var(b"argc", &argc);
+    }
+}
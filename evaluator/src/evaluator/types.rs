@@ -0,0 +1,504 @@
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::data_type::Type;
+use php_parser_rs::parser::ast::declares::DeclareBody;
+use php_parser_rs::parser::ast::literals::Literal;
+use php_parser_rs::parser::ast::{Expression, Statement};
+use php_parser_rs::visitor::{walk, Visitor};
+
+use crate::conversion::{parse_numeric_string, scalar_to_string, DEFAULT_PRECISION};
+use crate::php_class::PhpClass;
+use crate::helpers::variable_key;
+use crate::php_value::{CallableArgument, ErrorLevel, PhpCallable, PhpError, PhpValue};
+
+use super::Evaluator;
+
+impl<'w> Evaluator<'w> {
+    /// Reads the `declare(strict_types=1)` of a file before it runs, the mode applies to
+    /// the calls made from the whole file, like PHP reads it when it compiles the file.
+    pub(super) fn read_strict_types(&mut self, statements: &[Statement]) -> Result<(), PhpError> {
+        let mut strict = false;
+
+        for (index, statement) in statements.iter().enumerate() {
+            let Statement::Declare(declare) = statement else {
+                continue;
+            };
+
+            let error = |message: &str| PhpError {
+                level: ErrorLevel::Fatal,
+                message: message.to_string(),
+                line: declare.declare.line,
+            };
+
+            for entry in &declare.entries.entries {
+                if !entry.key.value.bytes.eq_ignore_ascii_case(b"strict_types") {
+                    continue;
+                }
+
+                // only the opening tag and comments can come before it
+                let is_first = statements[..index].iter().all(|statement| {
+                    matches!(
+                        statement,
+                        Statement::FullOpeningTag(_)
+                            | Statement::ShortOpeningTag(_)
+                            | Statement::Comment(_)
+                            | Statement::Noop(_)
+                    )
+                });
+
+                if !is_first {
+                    return Err(error(
+                        "strict_types declaration must be the very first statement in the script",
+                    ));
+                }
+
+                if !matches!(declare.body, DeclareBody::Noop { .. }) {
+                    return Err(error("strict_types declaration must not use block mode"));
+                }
+
+                strict = match &entry.value {
+                    Literal::Integer(integer) if integer.value.bytes == b"1" => true,
+                    Literal::Integer(integer) if integer.value.bytes == b"0" => false,
+                    _ => {
+                        return Err(error(
+                            "strict_types declaration must have 0 or 1 as its value",
+                        ))
+                    }
+                };
+            }
+        }
+
+        // a file evaluated again, like the code of Interpreter::eval_str(), can change its mode
+        let file = self.current_file.clone();
+
+        self.strict_files.retain(|strict_file| *strict_file != file);
+
+        if strict {
+            self.strict_files.push(file);
+        }
+
+        Ok(())
+    }
+
+    /// Whether the code being evaluated is in a file that declares strict_types=1.
+    ///
+    /// The arguments are bound and the return value is checked in the file of the call,
+    /// so the mode of the caller applies to them, for the natives too.
+    pub fn is_strict(&self) -> bool {
+        self.strict_files.contains(&self.current_file)
+    }
+
+    /// Checks the value given by a return statement, or None if the function ended without one,
+    /// against the return type of the function.
+    ///
+    /// Like in PHP without strict_types, a scalar is converted to a scalar type of the return type.
+    /// With strict_types, only an int is converted, to float.
+    pub(super) fn check_return_value(
+        &mut self,
+        function: &PhpCallable,
+        function_name: &str,
+        value: Option<PhpValue>,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let Some(return_type) = &function.return_type else {
+            return Ok(value.unwrap_or(PhpValue::Null));
+        };
+
+        let error = |message: String| PhpError {
+            level: ErrorLevel::EngineError("TypeError"),
+            message: format!("{}(): {}", function_name, message),
+            line: span.line,
+        };
+
+        // the return statements were checked when the function was declared
+        let value = match (&return_type.data_type, value) {
+            (Type::Void(_), value) => return Ok(value.unwrap_or(PhpValue::Null)),
+            (Type::Never(_), _) => {
+                return Err(error(
+                    "never-returning function must not implicitly return".to_string(),
+                ))
+            }
+            (data_type, None) => {
+                return Err(error(format!(
+                    "Return value must be of type {}, none returned",
+                    data_type
+                )))
+            }
+            (_, Some(value)) => value,
+        };
+
+        if self.value_matches_type(&value, &return_type.data_type) {
+            return Ok(value);
+        }
+
+        if let Some(converted) = self.coerce_scalar(&value, &return_type.data_type, span)? {
+            return Ok(converted);
+        }
+
+        Err(error(format!(
+            "Return value must be of type {}, {} returned",
+            return_type.data_type,
+            type_name(&value)
+        )))
+    }
+
+    /// Checks the argument bound to a parameter that is not the variadic one against the
+    /// type of the parameter, `position` is the one of the parameter.
+    ///
+    /// A scalar is converted like a return value, the variable of the parameter is updated
+    /// so that an argument passed by reference is converted too.
+    pub(super) fn check_argument(
+        &mut self,
+        function_name: &str,
+        position: usize,
+        parameter: &CallableArgument,
+        span: Span,
+    ) -> Result<(), PhpError> {
+        let Some(data_type) = &parameter.data_type else {
+            return Ok(());
+        };
+
+        let Some(variable) = self.env.get_var_with_rc(variable_key(&parameter.name)) else {
+            return Ok(());
+        };
+
+        let value = variable.borrow().clone();
+
+        // a parameter with a null default also accepts null, like `Foo $foo = null`
+        let accepts_null = matches!(parameter.default_value, Some(Expression::Null));
+
+        if self.value_matches_type(&value, data_type) || (accepts_null && value.is_null()) {
+            return Ok(());
+        }
+
+        if let Some(converted) = self.coerce_scalar(&value, data_type, span)? {
+            *variable.borrow_mut() = converted;
+
+            return Ok(());
+        }
+
+        Err(PhpError {
+            level: ErrorLevel::EngineError("TypeError"),
+            message: format!(
+                "{}(): Argument #{} ({}) must be of type {}, {} given, called in {} on line {}",
+                function_name,
+                position + 1,
+                parameter.name.name,
+                data_type,
+                type_name(&value),
+                self.current_file,
+                span.line
+            ),
+            line: span.line,
+        })
+    }
+
+    /// Checks an argument given to the variadic parameter of a function against the
+    /// type of the parameter, `position` is the one of the argument in the call.
+    ///
+    /// A scalar is converted like a return value.
+    pub(super) fn check_variadic_argument(
+        &mut self,
+        function: &PhpCallable,
+        position: usize,
+        value: PhpValue,
+        span: Span,
+    ) -> Result<PhpValue, PhpError> {
+        let Some(data_type) = function
+            .parameters
+            .last()
+            .and_then(|parameter| parameter.data_type.as_ref())
+        else {
+            return Ok(value);
+        };
+
+        if self.value_matches_type(&value, data_type) {
+            return Ok(value);
+        }
+
+        if let Some(converted) = self.coerce_scalar(&value, data_type, span)? {
+            return Ok(converted);
+        }
+
+        // a variadic parameter is not named in the error
+        Err(PhpError {
+            level: ErrorLevel::EngineError("TypeError"),
+            message: format!(
+                "{}(): Argument #{} must be of type {}, {} given",
+                function.name,
+                position + 1,
+                data_type,
+                type_name(&value)
+            ),
+            line: span.line,
+        })
+    }
+
+    /// Whether the value is of the type, without any conversion.
+    pub(super) fn value_matches_type(&self, value: &PhpValue, data_type: &Type) -> bool {
+        match data_type {
+            Type::Nullable(_, inner) => value.is_null() || self.value_matches_type(value, inner),
+            Type::Union(types) => types
+                .iter()
+                .any(|inner| self.value_matches_type(value, inner)),
+            Type::Intersection(types) => types
+                .iter()
+                .all(|inner| self.value_matches_type(value, inner)),
+            Type::Mixed(_) => true,
+            Type::Null(_) | Type::Void(_) => value.is_null(),
+            Type::Never(_) => false,
+            Type::True(_) => matches!(value, PhpValue::Bool(true)),
+            Type::False(_) => matches!(value, PhpValue::Bool(false)),
+            Type::Boolean(_) => matches!(value, PhpValue::Bool(_)),
+            Type::Integer(_) => matches!(value, PhpValue::Int(_)),
+            Type::Float(_) => matches!(value, PhpValue::Float(_)),
+            Type::String(_) => matches!(value, PhpValue::String(_)),
+            Type::Array(_) => matches!(value, PhpValue::Array(_)),
+            Type::Object(_) => matches!(value, PhpValue::Object(_)),
+            Type::Callable(_) => self.is_callable(value),
+            Type::Iterable(_) => match value {
+                PhpValue::Array(_) => true,
+                PhpValue::Object(object) => object.borrow().is_instance_of(b"Traversable"),
+                _ => false,
+            },
+            Type::Named(_, name) => match value {
+                PhpValue::Object(object) => object.borrow().is_instance_of(&name.bytes),
+                PhpValue::Callable(_) => name.bytes.eq_ignore_ascii_case(b"Closure"),
+                _ => false,
+            },
+            Type::SelfReference(_) => {
+                self.is_instance_of_class(value, self.current_class.as_deref())
+            }
+            Type::StaticReference(_) => {
+                self.is_instance_of_class(value, self.static_class.as_deref())
+            }
+            Type::ParentReference(_) => {
+                let parent = self
+                    .current_class
+                    .as_ref()
+                    .and_then(|class| class.parent.as_deref());
+
+                self.is_instance_of_class(value, parent)
+            }
+        }
+    }
+
+    fn is_instance_of_class(&self, value: &PhpValue, class: Option<&PhpClass>) -> bool {
+        match (value, class) {
+            (PhpValue::Object(object), Some(class)) => {
+                object.borrow().is_instance_of(&class.name.bytes)
+            }
+            _ => false,
+        }
+    }
+
+    /// Converts a bool, an int, a float or a string to a scalar type of `data_type`.
+    ///
+    /// The types are tried in the order PHP tries them: int, float, string, then bool.
+    /// A float with a fractional part is only converted to int if the type has no float,
+    /// with the deprecation PHP gives since 8.1. With strict_types, an int can only
+    /// become a float.
+    fn coerce_scalar(
+        &mut self,
+        value: &PhpValue,
+        data_type: &Type,
+        span: Span,
+    ) -> Result<Option<PhpValue>, PhpError> {
+        if self.is_strict() {
+            return Ok(match value {
+                PhpValue::Int(i)
+                    if has_scalar_type(data_type, &|inner| matches!(inner, Type::Float(_))) =>
+                {
+                    Some(PhpValue::Float(*i as f64))
+                }
+                _ => None,
+            });
+        }
+
+        if !matches!(
+            value,
+            PhpValue::Bool(_) | PhpValue::Int(_) | PhpValue::Float(_) | PhpValue::String(_)
+        ) {
+            return Ok(None);
+        }
+
+        let number = match value {
+            PhpValue::String(string) => parse_numeric_string(string),
+            PhpValue::Bool(b) => Some(PhpValue::Int(*b as i64)),
+            value => Some(value.clone()),
+        };
+
+        if has_scalar_type(data_type, &|inner| matches!(inner, Type::Integer(_))) {
+            match number {
+                Some(PhpValue::Int(i)) => return Ok(Some(PhpValue::Int(i))),
+                Some(PhpValue::Float(f)) if f.is_finite() && f.fract() == 0.0 => {
+                    return Ok(Some(PhpValue::Int(f as i64)))
+                }
+                Some(PhpValue::Float(f))
+                    if f.is_finite()
+                        && !has_scalar_type(data_type, &|inner| {
+                            matches!(inner, Type::Float(_))
+                        }) =>
+                {
+                    self.raise_error(PhpError {
+                        level: ErrorLevel::Deprecated,
+                        message: format!(
+                            "Implicit conversion from float {} to int loses precision",
+                            scalar_to_string(&PhpValue::Float(f), DEFAULT_PRECISION)
+                                .unwrap_or_default()
+                        ),
+                        line: span.line,
+                    })?;
+
+                    return Ok(Some(PhpValue::Int(f as i64)));
+                }
+                _ => {}
+            }
+        }
+
+        if has_scalar_type(data_type, &|inner| matches!(inner, Type::Float(_))) {
+            match number {
+                Some(PhpValue::Int(i)) => return Ok(Some(PhpValue::Float(i as f64))),
+                Some(PhpValue::Float(f)) => return Ok(Some(PhpValue::Float(f))),
+                _ => {}
+            }
+        }
+
+        if has_scalar_type(data_type, &|inner| matches!(inner, Type::String(_))) {
+            let string = scalar_to_string(value, self.ini.get_int("precision")).unwrap_or_default();
+
+            return Ok(Some(PhpValue::String(string.into())));
+        }
+
+        if has_scalar_type(data_type, &|inner| matches!(inner, Type::Boolean(_))) {
+            return Ok(Some(PhpValue::Bool(value.clone().is_true())));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Whether `data_type`, or one of the types of its union, is the scalar type.
+fn has_scalar_type(data_type: &Type, is_type: &dyn Fn(&Type) -> bool) -> bool {
+    match data_type {
+        Type::Nullable(_, inner) => has_scalar_type(inner, is_type),
+        Type::Union(types) => types.iter().any(|inner| has_scalar_type(inner, is_type)),
+        data_type => is_type(data_type),
+    }
+}
+
+/// The name of the type of a value in a type error, objects are named by their class.
+fn type_name(value: &PhpValue) -> String {
+    match value {
+        PhpValue::Object(object) => object.borrow().class.name.to_string(),
+        PhpValue::Callable(_) => "Closure".to_string(),
+        value => value.type_name().error_name().to_string(),
+    }
+}
+
+/// Checks the return statements of a function body against its return type, like PHP
+/// does when it compiles the function.
+///
+/// The return statements of the functions, closures and classes declared in the body
+/// belong to them and are not checked.
+pub(super) fn check_return_statements(
+    return_type: Option<&Type>,
+    body: &[Statement],
+) -> Result<(), PhpError> {
+    struct ReturnFinder {
+        depth: usize,
+        returns: Vec<(Span, Option<Expression>)>,
+    }
+
+    fn is_declaration(statement: &Statement) -> bool {
+        matches!(
+            statement,
+            Statement::Function(_)
+                | Statement::Class(_)
+                | Statement::Interface(_)
+                | Statement::Trait(_)
+                | Statement::UnitEnum(_)
+                | Statement::BackedEnum(_)
+        )
+    }
+
+    fn is_function_expression(expression: &Expression) -> bool {
+        matches!(
+            expression,
+            Expression::Closure(_) | Expression::ArrowFunction(_) | Expression::AnonymousClass(_)
+        )
+    }
+
+    impl Visitor for ReturnFinder {
+        fn enter_statement(&mut self, statement: &mut Statement, _span: Option<Span>) {
+            if is_declaration(statement) {
+                self.depth += 1;
+            }
+
+            if let Statement::Return(statement) = statement {
+                if self.depth == 0 {
+                    self.returns
+                        .push((statement.r#return, statement.value.clone()));
+                }
+            }
+        }
+
+        fn leave_statement(&mut self, statement: &mut Statement, _span: Option<Span>) {
+            if is_declaration(statement) {
+                self.depth -= 1;
+            }
+        }
+
+        fn enter_expression(&mut self, expression: &mut Expression, _span: Option<Span>) {
+            if is_function_expression(expression) {
+                self.depth += 1;
+            }
+        }
+
+        fn leave_expression(&mut self, expression: &mut Expression, _span: Option<Span>) {
+            if is_function_expression(expression) {
+                self.depth -= 1;
+            }
+        }
+    }
+
+    let Some(return_type) = return_type else {
+        return Ok(());
+    };
+
+    let mut finder = ReturnFinder {
+        depth: 0,
+        returns: vec![],
+    };
+
+    walk(&mut finder, &mut body.to_vec());
+
+    let accepts_null = match return_type {
+        Type::Nullable(..) | Type::Mixed(_) => true,
+        Type::Union(types) => types.iter().any(|inner| matches!(inner, Type::Null(_))),
+        _ => false,
+    };
+
+    for (span, value) in finder.returns {
+        let message = match (return_type, value) {
+            (Type::Never(_), _) => "A never-returning function must not return",
+            (Type::Void(_), Some(Expression::Null)) => {
+                "A void function must not return a value (did you mean \"return;\" instead of \"return null;\"?)"
+            }
+            (Type::Void(_), Some(_)) => "A void function must not return a value",
+            (Type::Void(_), None) => continue,
+            (_, None) if accepts_null => {
+                "A function with return type must return a value (did you mean \"return null;\" instead of \"return;\"?)"
+            }
+            (_, None) => "A function with return type must return a value",
+            (_, Some(_)) => continue,
+        };
+
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: message.to_string(),
+            line: span.line,
+        });
+    }
+
+    Ok(())
+}
@@ -2,12 +2,21 @@ use core::panic;
 
 use php_parser_rs::{
     lexer::token::Span,
-    parser::{self, ast::variables::Variable},
+    parser::{
+        self,
+        ast::{
+            identifiers::Identifier,
+            literals::Literal,
+            variables::{SimpleVariable, Variable},
+            Expression,
+        },
+    },
+    visitor::{walk_node, Visitor},
 };
 
 use crate::{
     evaluator::Evaluator,
-    php_value::{PhpError, PhpValue},
+    php_value::{CallableArgument, ErrorLevel, IncludeSite, PhpError, PhpValue, ReportedError},
 };
 
 pub fn get_span_from_var(var: &Variable) -> Span {
@@ -18,62 +27,166 @@ pub fn get_span_from_var(var: &Variable) -> Span {
     }
 }
 
+/// Returns the span of the expressions that can be interpolated in strings.
+///
+/// Other expressions do not store a span where they start, so the default span is returned.
+pub fn get_span_from_expression(expression: &Expression) -> Span {
+    match expression {
+        Expression::Variable(variable) => get_span_from_var(variable),
+        Expression::ArrayIndex(index) => index.left_bracket,
+        Expression::PropertyFetch(fetch) => fetch.arrow,
+        Expression::NullsafePropertyFetch(fetch) => fetch.question_arrow,
+        Expression::StaticPropertyFetch(fetch) => fetch.double_colon,
+        Expression::MethodCall(call) => call.arrow,
+        Expression::NullsafeMethodCall(call) => call.question_arrow,
+        Expression::FunctionCall(call) => get_span_from_expression(&call.target),
+        Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => identifier.span,
+        _ => Span {
+            line: 0,
+            column: 0,
+            position: 0,
+        },
+    }
+}
+
+/// The name of the variant of a statement or an expression, like `While` or `Cast`, for
+/// the errors about the ones that are not supported.
+pub fn variant_name(node: &impl std::fmt::Debug) -> String {
+    format!("{:?}", node)
+        .chars()
+        .take_while(char::is_ascii_alphanumeric)
+        .collect()
+}
+
+/// Whether an expression contains a `new` expression, like `[new Foo()]`.
+pub fn contains_new_expression(expression: &Expression) -> bool {
+    struct NewFinder {
+        found: bool,
+    }
+
+    impl Visitor for NewFinder {
+        fn enter_expression(&mut self, expression: &mut Expression, _span: Option<Span>) {
+            if let Expression::New(_) = expression {
+                self.found = true;
+            }
+        }
+    }
+
+    let mut finder = NewFinder { found: false };
+
+    walk_node(&mut finder, &mut expression.clone());
+
+    finder.found
+}
+
 /// Parses a PHP file and returns the result, this function is used with "require" and "include".
+///
+/// The file runs in the scope of `evaluator` and the value of its top-level return
+/// statement is the result, true without one. The output and the warnings of the file
+/// are added to the ones of `evaluator`, in order, even when the file stops with a fatal
+/// error. The fatal error is given back so that it stops the program, and it is kept in
+/// `included_fatal` with the file where it was raised.
+///
+/// A file that cannot be parsed stops the program with require, with include the parse
+/// error is output and the result is false.
 pub fn parse_php_file(
     evaluator: &mut Evaluator,
     input: &str,
     content: &str,
+    site: IncludeSite,
 ) -> Result<PhpValue, PhpError> {
     match parser::parse(content) {
         Ok(ast) => {
-            let mut child_evalutor = evaluator.new_child();
+            let mut child_evalutor = evaluator.new_child(input, site);
 
-            let mut last_result = PhpValue::Null;
+            // the child continues the output of the parent, so flush() writes everything in order
+            child_evalutor.output = std::mem::take(&mut evaluator.output);
 
-            for node in ast {
-                let result = child_evalutor.eval_statement(node);
+            let mut result = Ok(PhpValue::Null);
 
-                if child_evalutor.die || result.is_err() {
-                    if result.is_err() {
-                        evaluator.output = format!(
-                            "PHP Fatal Error: {}",
-                            result.unwrap_err().get_message(input)
-                        );
-                    }
+            child_evalutor.trace_enter_file(input);
 
-                    break;
-                }
+            let ast = child_evalutor
+                .check_output_before_code(&ast)
+                .and_then(|_| child_evalutor.hoist_declarations(ast));
 
-                last_result = result.unwrap();
-            }
+            match ast {
+                Ok(ast) => {
+                    for node in ast {
+                        // only a return statement gives a value to the include
+                        result = child_evalutor.eval_statement(node).map(|_| PhpValue::Null);
 
-            for warning in child_evalutor.warnings {
-                // Note that here, although the error is a warning,
-                // it is converted to an ErrorLevel::Raw so that
-                // the error is not modified when calling get_message() twice on the same error.
+                        if child_evalutor.die || result.is_err() {
+                            break;
+                        }
 
-                let new_warning = PhpError {
-                    level: crate::php_value::ErrorLevel::Raw,
-                    message: format!(
-                        "PHP Warning: {} in {} on line {}",
-                        warning.message, input, warning.line
-                    ),
-                    line: 0,
-                };
+                        // a return statement stops the file and its value is the result of the include
+                        if let Some(return_value) = child_evalutor.return_value.take() {
+                            result = Ok(return_value);
 
-                evaluator.warnings.push(new_warning);
+                            break;
+                        }
+                    }
+                }
+                Err(error) => result = Err(error),
             }
 
-            evaluator.output += &child_evalutor.output;
+            child_evalutor.trace_leave_file(input);
 
-            // copy the environment
-            evaluator.env.get_and_set_diff(child_evalutor.env);
+            // the innermost file reports the error, the files that include it only pass it on
+            if let Err(error) = &result {
+                // an exception is not reported yet, a catch of the including file can take it
+                if let ErrorLevel::Exception = error.level {
+                    evaluator.thrown = child_evalutor.thrown.take();
+                } else if child_evalutor.included_fatal.is_none()
+                    && !matches!(error.level, ErrorLevel::Raw)
+                {
+                    child_evalutor.report_fatal(error);
 
-            if last_result.is_null() {
-                last_result = PhpValue::Bool(true);
+                    child_evalutor.included_fatal = Some(ReportedError {
+                        error: error.clone(),
+                        file: input.to_string(),
+                        include_stack: child_evalutor.include_stack.clone(),
+                    });
+                }
             }
 
-            Ok(last_result)
+            evaluator.warnings.append(&mut child_evalutor.warnings);
+
+            evaluator.included_fatal = child_evalutor.included_fatal.take();
+
+            // exit() in the included file stops the including file too
+            evaluator.die = child_evalutor.die;
+            evaluator.exit_code = child_evalutor.exit_code;
+
+            evaluator.output = std::mem::take(&mut child_evalutor.output);
+
+            evaluator
+                .shutdown_functions
+                .append(&mut child_evalutor.shutdown_functions);
+
+            evaluator.error_handlers = child_evalutor.error_handlers;
+
+            evaluator.ini = child_evalutor.ini;
+
+            evaluator.included_files = child_evalutor.included_files;
+
+            evaluator.strict_files = child_evalutor.strict_files;
+
+            evaluator.objects = child_evalutor.objects;
+
+            evaluator.static_variables = child_evalutor.static_variables;
+
+            evaluator.output_start = child_evalutor.output_start;
+
+            // the child started with the environment of the parent, the variables it defined
+            // or unset and the references it created stay after it
+            evaluator.env = child_evalutor.env;
+
+            match result {
+                Ok(PhpValue::Null) => Ok(PhpValue::Bool(true)),
+                result => result,
+            }
         }
         Err(err) => {
             let err = err.report(&content, Some(input), false, false);
@@ -82,9 +195,27 @@ pub fn parse_php_file(
                 panic!("{}", err.unwrap_err());
             }
 
+            let message = format!("PHP Parse Error in {}: {}", input, err.unwrap());
+
+            // like a file that cannot be opened, only require stops the program
+            if site.function.starts_with("include") {
+                evaluator.output.write(&format!("{}\n", message));
+
+                evaluator.raise_error(PhpError {
+                    level: ErrorLevel::Warning,
+                    message: format!(
+                        "{}(): Failed opening '{}' for inclusion",
+                        site.function, input
+                    ),
+                    line: site.line,
+                })?;
+
+                return Ok(PhpValue::Bool(false));
+            }
+
             Err(PhpError {
-                level: crate::php_value::ErrorLevel::Raw,
-                message: format!("PHP Parse Error in {}: {}", input, err.unwrap()),
+                level: ErrorLevel::Raw,
+                message,
                 line: 0,
             })
         }
@@ -94,3 +225,251 @@ pub fn parse_php_file(
 pub fn get_string_from_bytes(var: &[u8]) -> String {
     String::from_utf8_lossy(var).to_string()
 }
+
+/// How many bytes of a name or a string value are written in an error message.
+pub const MAX_DISPLAYED_LENGTH: usize = 128;
+
+/// A name given by the program, like the name of a variable, a function or a class,
+/// as it is written in an error message.
+///
+/// Names built at runtime can contain anything, so the control characters and the bytes
+/// that are not UTF-8 are escaped like in a double quoted string, `\n` or `\x1b`, and
+/// a name longer than `MAX_DISPLAYED_LENGTH` is cut with "...".
+pub fn display_name(name: &[u8]) -> String {
+    escape_for_display(name, false)
+}
+
+/// The name of a variable as it is written in an error message, with its `$`, see `display_name()`.
+///
+/// `name` is the key of the variable in the scope, which has no `$`.
+pub fn display_variable_name(name: &[u8]) -> String {
+    format!("${}", display_name(name))
+}
+
+/// The key of a variable in the scope: its name without the `$`, like the name given
+/// to a variable variable. `${'$a'}` is a variable whose key is `$a`.
+pub fn variable_key(variable: &SimpleVariable) -> &[u8] {
+    variable
+        .name
+        .bytes
+        .strip_prefix(b"$")
+        .unwrap_or(&variable.name.bytes)
+}
+
+/// A string value as it is written in an error message, in double quotes and escaped
+/// like `display_name()`, with `"` and `\` escaped too.
+pub fn display_string(value: &[u8]) -> String {
+    format!("\"{}\"", escape_for_display(value, true))
+}
+
+fn escape_for_display(bytes: &[u8], quoted: bool) -> String {
+    let mut end = bytes.len().min(MAX_DISPLAYED_LENGTH);
+
+    // the cut does not split a character
+    while end < bytes.len() && end > 0 && bytes[end] & 0b1100_0000 == 0b1000_0000 {
+        end -= 1;
+    }
+
+    let mut displayed = String::new();
+
+    for chunk in bytes[..end].utf8_chunks() {
+        for character in chunk.valid().chars() {
+            match character {
+                '\n' => displayed.push_str("\\n"),
+                '\r' => displayed.push_str("\\r"),
+                '\t' => displayed.push_str("\\t"),
+                '"' | '\\' if quoted => {
+                    displayed.push('\\');
+                    displayed.push(character);
+                }
+                character if character.is_ascii_control() => {
+                    displayed.push_str(&format!("\\x{:02x}", character as u32))
+                }
+                character if character.is_control() => {
+                    displayed.push_str(&format!("\\u{{{:x}}}", character as u32))
+                }
+                character => displayed.push(character),
+            }
+        }
+
+        for byte in chunk.invalid() {
+            displayed.push_str(&format!("\\x{:02x}", byte));
+        }
+    }
+
+    if end < bytes.len() {
+        displayed.push_str("...");
+    }
+
+    displayed
+}
+
+/// Writes the parameters of a function like they are declared, like `int $size = 10, ...$items`.
+pub fn describe_parameters(parameters: &[CallableArgument]) -> String {
+    let parameters: Vec<_> = parameters
+        .iter()
+        .map(|parameter| {
+            let mut description = String::new();
+
+            if let Some(data_type) = &parameter.data_type {
+                description.push_str(&format!("{} ", data_type));
+            }
+
+            if parameter.by_reference {
+                description.push('&');
+            }
+
+            if parameter.ellipsis {
+                description.push_str("...");
+            }
+
+            description.push_str(&parameter.name.name.to_string());
+
+            if let Some(default_value) = &parameter.default_value {
+                description.push_str(&format!(" = {}", describe_default_value(default_value)));
+            }
+
+            description
+        })
+        .collect();
+
+    parameters.join(", ")
+}
+
+/// Writes the default value of a parameter like it is in the code, the expressions
+/// that are not a literal are written as "...".
+fn describe_default_value(expression: &Expression) -> String {
+    match expression {
+        Expression::Literal(Literal::Integer(integer)) => integer.value.to_string(),
+        Expression::Literal(Literal::Float(float)) => float.value.to_string(),
+        Expression::Literal(Literal::String(string)) => {
+            format!("'{}'", string.value.to_string().replace('\'', "\\'"))
+        }
+        Expression::Null => "null".to_string(),
+        Expression::Bool(b) => b.value.to_string(),
+        Expression::ShortArray(array) if array.items.inner.is_empty() => "[]".to_string(),
+        Expression::Array(array) if array.items.inner.is_empty() => "[]".to_string(),
+        Expression::Identifier(Identifier::SimpleIdentifier(identifier)) => {
+            identifier.value.to_string()
+        }
+        _ => "...".to_string(),
+    }
+}
+
+/// Increments a string like PHP does, "a9" becomes "b0", "Az" becomes "Ba" and "zz" becomes "aaa".
+///
+/// The letters and the digits carry to the character on their left, the increment stops at
+/// the first character that is not a letter or a digit, so "a!" does not change.
+pub fn increment_string(string: &[u8]) -> Vec<u8> {
+    let mut result = string.to_vec();
+
+    let mut carry = None;
+
+    for byte in result.iter_mut().rev() {
+        let (first, last) = match byte {
+            b'a'..=b'z' => (b'a', b'z'),
+            b'A'..=b'Z' => (b'A', b'Z'),
+            b'0'..=b'9' => (b'0', b'9'),
+            _ => {
+                carry = None;
+
+                break;
+            }
+        };
+
+        if *byte == last {
+            *byte = first;
+
+            carry = Some(first);
+        } else {
+            *byte += 1;
+
+            carry = None;
+
+            break;
+        }
+    }
+
+    // a carry out of the first character adds a new one, "z" becomes "aa" and "9" becomes "10"
+    if let Some(first) = carry {
+        result.insert(0, if first == b'0' { b'1' } else { first });
+    }
+
+    result
+}
+
+/// The candidate that is the closest to `name` if it is close enough to be a typo of it,
+/// like "strlen" for "strlne". The names are compared without case.
+///
+/// A candidate is close enough when at most a third of the characters of `name`, and
+/// at least one, must be changed to get it. Ties are broken by the order of the names,
+/// so the suggestion does not depend on the order of the candidates.
+pub fn suggest_name<'a>(
+    name: &[u8],
+    candidates: impl IntoIterator<Item = &'a [u8]>,
+) -> Option<&'a [u8]> {
+    let name = name.to_ascii_lowercase();
+    let max_distance = (name.len() / 3).max(1);
+
+    let mut best: Option<(usize, Vec<u8>, &[u8])> = None;
+
+    for candidate in candidates {
+        let lowercase = candidate.to_ascii_lowercase();
+
+        if lowercase == name {
+            continue;
+        }
+
+        let Some(distance) = edit_distance(&name, &lowercase, max_distance) else {
+            continue;
+        };
+
+        let is_better = best.as_ref().is_none_or(|(best_distance, best_name, _)| {
+            (distance, &lowercase) < (*best_distance, best_name)
+        });
+
+        if is_better {
+            best = Some((distance, lowercase, candidate));
+        }
+    }
+
+    best.map(|(_, _, candidate)| candidate)
+}
+
+/// The number of insertions, deletions, substitutions and swaps of two adjacent
+/// characters that change `a` into `b`, or None if it is more than `max`.
+///
+/// Most candidates are rejected by their length or after a few rows, the full
+/// comparison only runs for the names that are close.
+fn edit_distance(a: &[u8], b: &[u8], max: usize) -> Option<usize> {
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+
+    let mut before_previous: Vec<usize> = vec![];
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        let mut current = vec![i; b.len() + 1];
+
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+
+            current[j] = (previous[j] + 1)
+                .min(current[j - 1] + 1)
+                .min(previous[j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                current[j] = current[j].min(before_previous[j - 2] + 1);
+            }
+        }
+
+        if current.iter().min().is_some_and(|&min| min > max) {
+            return None;
+        }
+
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+
+    Some(previous[b.len()]).filter(|&distance| distance <= max)
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+/// The settings known by the evaluator: their name, default value and whether
+/// ini_set() can change them.
+const DEFAULT_SETTINGS: &[(&str, &str, bool)] = &[
+    ("default_charset", "UTF-8", true),
+    ("display_errors", "1", true),
+    ("error_reporting", "32767", true),
+    // off by default, unlike the PHP CLI, so a fatal error still replaces the output
+    ("implicit_flush", "0", true),
+    ("include_path", ".", true),
+    ("max_execution_time", "0", true),
+    ("memory_limit", "128M", true),
+    ("precision", "14", true),
+    ("serialize_precision", "-1", true),
+    ("short_open_tag", "1", false),
+];
+
+/// A setting that can be read with ini_get() and changed with ini_set().
+#[derive(Debug, Clone)]
+struct IniEntry {
+    /// The value given by the configuration or the default one, ini_restore() goes back to it.
+    initial_value: String,
+
+    value: String,
+
+    /// Whether the script can change the value.
+    changeable: bool,
+}
+
+/// The ini settings of a program.
+///
+/// The values are strings, just like in PHP, use the typed getters to read them.
+/// The settings are read every time they are needed, so a change with ini_set()
+/// takes effect right away.
+#[derive(Debug, Clone)]
+pub struct IniSettings {
+    entries: HashMap<String, IniEntry>,
+
+    /// The values given by the configuration, returned by get_cfg_var().
+    config: HashMap<String, String>,
+}
+
+impl IniSettings {
+    pub fn new() -> IniSettings {
+        let entries = DEFAULT_SETTINGS
+            .iter()
+            .map(|(name, value, changeable)| {
+                let entry = IniEntry {
+                    initial_value: value.to_string(),
+                    value: value.to_string(),
+                    changeable: *changeable,
+                };
+
+                (name.to_string(), entry)
+            })
+            .collect();
+
+        IniSettings {
+            entries,
+            config: HashMap::new(),
+        }
+    }
+
+    /// Sets a value from the configuration, it replaces the default one.
+    pub fn configure(&mut self, name: &str, value: &str) {
+        if let Some(entry) = self.entries.get_mut(name) {
+            entry.initial_value = value.to_string();
+            entry.value = value.to_string();
+        }
+
+        self.config.insert(name.to_string(), value.to_string());
+    }
+
+    /// Prevents the script from changing a setting.
+    pub fn lock(&mut self, name: &str) {
+        if let Some(entry) = self.entries.get_mut(name) {
+            entry.changeable = false;
+        }
+    }
+
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries.get(name).map(|entry| entry.value.as_str())
+    }
+
+    /// Changes a setting and returns its old value.
+    ///
+    /// It returns None if the setting does not exist or cannot be changed.
+    pub fn set(&mut self, name: &str, value: String) -> Option<String> {
+        let entry = self.entries.get_mut(name)?;
+
+        if !entry.changeable {
+            return None;
+        }
+
+        Some(std::mem::replace(&mut entry.value, value))
+    }
+
+    /// Restores the value that the setting had when the program started.
+    pub fn restore(&mut self, name: &str) {
+        if let Some(entry) = self.entries.get_mut(name) {
+            if entry.changeable {
+                entry.value = entry.initial_value.clone();
+            }
+        }
+    }
+
+    pub fn get_config(&self, name: &str) -> Option<&str> {
+        self.config.get(name).map(String::as_str)
+    }
+
+    /// Reads a setting as an integer, like PHP does it reads the leading digits
+    /// and it is 0 if there are none.
+    pub fn get_int(&self, name: &str) -> i64 {
+        let value = self.get(name).unwrap_or("").trim_start();
+
+        let end = value
+            .char_indices()
+            .find(|(i, c)| !(c.is_ascii_digit() || (*i == 0 && (*c == '-' || *c == '+'))))
+            .map_or(value.len(), |(i, _)| i);
+
+        value[..end].parse().unwrap_or(0)
+    }
+}
+
+impl Default for IniSettings {
+    fn default() -> Self {
+        IniSettings::new()
+    }
+}
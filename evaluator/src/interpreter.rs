@@ -0,0 +1,249 @@
+use std::io::Result;
+use std::rc::Rc;
+
+use php_parser_rs::parser;
+
+use crate::evaluator::Evaluator;
+use crate::output::OutputTarget;
+use crate::program::{EvalOptions, ERROR_EXIT_CODE};
+
+pub use crate::php_value::{ErrorLevel, PhpError, PhpValue, ReportedError};
+pub use php_parser_rs::lexer::byte_string::ByteString;
+
+/// The file name of the code given to `eval_str()`, the one PHP uses for the code of `php -r`.
+pub const COMMAND_LINE_CODE: &str = "Command line code";
+
+/// What an evaluation gives back, see `Interpreter::eval_str()`.
+#[derive(Debug)]
+pub struct EvalResult {
+    /// The output that was not written to the output target, all of it without a target.
+    ///
+    /// The message of a fatal error comes after the output that is still pending.
+    pub output: String,
+
+    /// The warnings, notices and deprecations, with the file where they were raised.
+    ///
+    /// With a diagnostics sink, they are only here when `EvalOptions::collect_warnings` is true.
+    pub warnings: Vec<ReportedError>,
+
+    /// The value of the last statement when it is an expression, or the value of a
+    /// return statement of the top level. Null otherwise.
+    pub value: PhpValue,
+
+    /// The error that stopped the program, its message is in the output.
+    pub error: Option<PhpError>,
+
+    /// The int given to exit() or die(), 0 if there is none, `ERROR_EXIT_CODE` if the
+    /// program stopped because of an error.
+    pub exit_status: i32,
+}
+
+/// Evaluates PHP code from a Rust program.
+///
+/// The interpreter keeps its state between two evaluations: the variables of the global
+/// scope, the functions, the classes and the constants. Each evaluation is a whole program
+/// run, so the shutdown functions run at the end of each one.
+pub struct Interpreter<'w> {
+    evaluator: Evaluator<'w>,
+
+    /// The arguments of the script, $argv is defined on the first evaluation since it
+    /// starts with the name of the file.
+    argv: Option<Vec<String>>,
+}
+
+impl<'w> Interpreter<'w> {
+    pub fn new() -> Interpreter<'w> {
+        Interpreter::with_options(&EvalOptions::default())
+    }
+
+    /// An interpreter with the given options, `without_opening_tag` is ignored since
+    /// `eval_str()` and `eval_script()` say what the code is.
+    pub fn with_options(options: &EvalOptions) -> Interpreter<'w> {
+        let mut evaluator = Evaluator::new(COMMAND_LINE_CODE);
+
+        for (name, value) in &options.ini {
+            evaluator.ini.configure(name, value);
+        }
+
+        for name in &options.locked_ini {
+            evaluator.ini.lock(name);
+        }
+
+        evaluator.max_source_size = options.max_source_size;
+        evaluator.warn_output_before_code = options.warn_output_before_code;
+        evaluator.fatal_errors_to_diagnostics = options.fatal_errors_to_diagnostics;
+        evaluator.clock = options.clock.clone();
+        evaluator.interrupted = options.interrupted.clone();
+
+        if let Some(sink) = &options.trace {
+            evaluator.set_trace_sink(Rc::clone(sink));
+        }
+
+        if let Some(sink) = &options.diagnostics {
+            evaluator.set_diagnostics_sink(Rc::clone(sink), options.collect_warnings);
+        }
+
+        Interpreter {
+            evaluator,
+            argv: Some(options.argv.clone()),
+        }
+    }
+
+    /// Sets where the output is written, like stdout with `output::WriterTarget`.
+    ///
+    /// Without a target, the output is in `EvalResult::output`.
+    pub fn set_output_target(&mut self, target: &'w mut dyn OutputTarget) {
+        self.evaluator.output.set_target(target);
+    }
+
+    /// Sets a variable of the global scope, `name` is without the `$`.
+    pub fn set_variable(&mut self, name: &str, value: PhpValue) {
+        self.evaluator.env.set_var(name.as_bytes(), &value);
+    }
+
+    /// The value of a variable of the global scope, `name` is without the `$`.
+    pub fn get_variable(&self, name: &str) -> Option<PhpValue> {
+        self.evaluator.env.get_var(name.as_bytes())
+    }
+
+    /// Evaluates code without an opening tag, like the code of `php -r`.
+    pub fn eval_str(&mut self, code: &str) -> Result<EvalResult> {
+        self.eval(COMMAND_LINE_CODE, code, true)
+    }
+
+    /// Evaluates the content of a file, it can start with a shebang line.
+    ///
+    /// `file_name` is the file of the errors and of `__FILE__`, the file is not read.
+    pub fn eval_script(&mut self, file_name: &str, content: &str) -> Result<EvalResult> {
+        self.eval(file_name, content, false)
+    }
+
+    /// Evaluates `content` as the file `input`, see `EvalOptions::without_opening_tag`.
+    pub(crate) fn eval(
+        &mut self,
+        input: &str,
+        content: &str,
+        without_opening_tag: bool,
+    ) -> Result<EvalResult> {
+        // only the main script can start with a shebang line, in included files it is output
+        let ast = if without_opening_tag {
+            parser::parse_code(content)
+        } else {
+            parser::parse_script(content)
+        };
+
+        let evaluator = &mut self.evaluator;
+
+        let ast = match ast {
+            Ok(ast) => ast,
+            Err(err) => {
+                let report = err.report(content, Some(input), true, false)?;
+
+                evaluator.write_fatal_message(&report);
+                evaluator.flush_output()?;
+
+                return Ok(EvalResult {
+                    output: evaluator.output.take_pending(),
+                    warnings: vec![],
+                    value: PhpValue::Null,
+                    error: Some(PhpError {
+                        level: ErrorLevel::Raw,
+                        message: report,
+                        line: 0,
+                    }),
+                    exit_status: ERROR_EXIT_CODE,
+                });
+            }
+        };
+
+        evaluator.current_file = input.to_string();
+        evaluator.die = false;
+        evaluator.exit_code = 0;
+
+        if let Some(argv) = self.argv.take() {
+            evaluator.define_superglobals(&argv);
+        }
+
+        let mut value = PhpValue::Null;
+        let mut stopping_error = None;
+
+        let ast = match evaluator.hoist_declarations(ast) {
+            Ok(ast) => ast,
+            Err(error) => {
+                evaluator.report_fatal(&error);
+
+                evaluator.write_fatal_message(&error.clone().get_message(input));
+
+                stopping_error = Some(error);
+
+                vec![]
+            }
+        };
+
+        for node in ast {
+            let result = evaluator.eval_statement(node);
+
+            if let Some(return_value) = evaluator.return_value.take() {
+                value = return_value;
+
+                break;
+            }
+
+            match result {
+                Ok(statement_value) => value = statement_value,
+                Err(error) => {
+                    value = PhpValue::Null;
+
+                    match evaluator.included_fatal.take() {
+                        // the included file reported it, the output before it is kept
+                        Some(fatal) => {
+                            evaluator.write_fatal_message(&fatal.get_message_with_stack_trace());
+                        }
+                        // like PHP, the output before the error is kept
+                        None => {
+                            evaluator.report_fatal(&error);
+
+                            evaluator.write_fatal_message(&error.clone().get_message(input));
+                        }
+                    }
+
+                    stopping_error = Some(error);
+
+                    break;
+                }
+            }
+
+            if evaluator.die {
+                break;
+            }
+        }
+
+        if let Some(error) = evaluator.run_shutdown_functions(input) {
+            stopping_error.get_or_insert(error);
+        }
+
+        // like PHP, the capture buffers that are still open are output at the end
+        evaluator.output.end_buffers();
+
+        evaluator.flush_output()?;
+
+        let exit_status = match stopping_error {
+            Some(_) => ERROR_EXIT_CODE,
+            None => evaluator.exit_code,
+        };
+
+        Ok(EvalResult {
+            output: evaluator.output.take_pending(),
+            warnings: std::mem::take(&mut evaluator.warnings),
+            value,
+            error: stopping_error,
+            exit_status,
+        })
+    }
+}
+
+impl<'w> Default for Interpreter<'w> {
+    fn default() -> Self {
+        Interpreter::new()
+    }
+}
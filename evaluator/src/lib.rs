@@ -1,5 +1,16 @@
+mod arithmetic;
+mod comparison;
+mod conversion;
 mod environment;
 mod evaluator;
 mod helpers;
+mod ini;
+pub mod interpreter;
+mod native;
+mod ordered_map;
+pub mod output;
+mod php_array;
+mod php_class;
 mod php_value;
 pub mod program;
+pub mod trace;
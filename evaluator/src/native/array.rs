@@ -0,0 +1,1096 @@
+use std::borrow::Cow;
+use std::rc::Rc;
+
+use php_parser_rs::lexer::token::Span;
+
+use crate::conversion::parse_numeric_string;
+use crate::evaluator::Evaluator;
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::{
+    argument_type_error, argument_value_error, check_arguments_count, check_callable_argument,
+    check_not_negative, get_bool_argument, get_int_argument, value_error,
+};
+
+/// The flags of array_unique().
+const SORT_REGULAR: i64 = 0;
+const SORT_NUMERIC: i64 = 1;
+const SORT_STRING: i64 = 2;
+
+/// The modes of count().
+const COUNT_NORMAL: i64 = 0;
+const COUNT_RECURSIVE: i64 = 1;
+
+/// The modes of array_filter(), by default the callback is given the value.
+const ARRAY_FILTER_USE_BOTH: i64 = 1;
+const ARRAY_FILTER_USE_KEY: i64 = 2;
+
+/// Returns the only argument of the function, which must be an array.
+fn get_array_argument<'a>(
+    function_name: &str,
+    arguments: &'a mut [PhpValue],
+    span: Span,
+) -> Result<&'a mut PhpArray, PhpError> {
+    if arguments.len() != 1 {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "{}() expects exactly 1 argument, {} given",
+                function_name,
+                arguments.len()
+            ),
+            line: span.line,
+        });
+    }
+
+    match &mut arguments[0] {
+        PhpValue::Array(array) => Ok(array),
+        value => Err(PhpError {
+            level: ErrorLevel::EngineError("TypeError"),
+            message: format!(
+                "{}(): Argument #1 ($array) must be of type array, {} given",
+                function_name,
+                value.type_name().error_name()
+            ),
+            line: span.line,
+        }),
+    }
+}
+
+/// Returns the argument at `position`, which must be an array.
+fn get_array_argument_at<'a>(
+    function_name: &str,
+    arguments: &'a [PhpValue],
+    position: usize,
+    parameter: &str,
+    span: Span,
+) -> Result<&'a PhpArray, PhpError> {
+    match &arguments[position] {
+        PhpValue::Array(array) => Ok(array),
+        value => Err(argument_type_error(
+            function_name,
+            position,
+            parameter,
+            "array",
+            value,
+            span,
+        )),
+    }
+}
+
+/// Returns the start and the end of a slice of an array of `length` elements,
+/// negative offsets and lengths are counted from the end of the array.
+fn get_slice_bounds(length: usize, offset: i64, slice_length: Option<i64>) -> (usize, usize) {
+    let length = length as i64;
+
+    let start = match offset {
+        offset if offset < 0 => (length + offset).max(0),
+        offset => offset.min(length),
+    };
+
+    let end = match slice_length {
+        None => length,
+        Some(slice_length) if slice_length < 0 => (length + slice_length).max(start),
+        Some(slice_length) => (start + slice_length).min(length),
+    };
+
+    (start as usize, end as usize)
+}
+
+/// Adds an element to an array, integer keys are renumbered unless `preserve_keys` is true.
+fn add_element(
+    array: &mut PhpArray,
+    key: &ArrayKey,
+    value: PhpValue,
+    preserve_keys: bool,
+    span: Span,
+) -> Result<(), PhpError> {
+    match key {
+        ArrayKey::Int(_) if !preserve_keys => push(array, value, span),
+        key => {
+            array.insert(key.clone(), value);
+
+            Ok(())
+        }
+    }
+}
+
+/// Appends a value to an array, the error of a full array is given at the line of the call.
+fn push(array: &mut PhpArray, value: PhpValue, span: Span) -> Result<(), PhpError> {
+    array.push(value).map_err(|error| PhpError {
+        line: span.line,
+        ..error
+    })
+}
+
+/// The value of an element, or false when the internal pointer is past the end.
+fn value_or_false(element: Option<(&ArrayKey, Cow<'_, PhpValue>)>) -> PhpValue {
+    element
+        .map(|(_, value)| value.into_owned())
+        .unwrap_or(PhpValue::Bool(false))
+}
+
+/// array_key_first(array $array): int|string|null
+pub fn array_key_first(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("array_key_first", arguments, span)?;
+
+    Ok(array.first_key().map_or(PhpValue::Null, ArrayKey::to_value))
+}
+
+/// array_key_last(array $array): int|string|null
+pub fn array_key_last(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("array_key_last", arguments, span)?;
+
+    Ok(array.last_key().map_or(PhpValue::Null, ArrayKey::to_value))
+}
+
+/// current(array|object $array): mixed
+pub fn current(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("current", arguments, span)?;
+
+    Ok(value_or_false(array.current()))
+}
+
+/// key(array|object $array): int|string|null
+pub fn key(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("key", arguments, span)?;
+
+    Ok(array
+        .current()
+        .map_or(PhpValue::Null, |(key, _)| key.to_value()))
+}
+
+/// next(array|object &$array): mixed
+pub fn next(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("next", arguments, span)?;
+
+    Ok(value_or_false(array.next()))
+}
+
+/// prev(array|object &$array): mixed
+pub fn prev(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("prev", arguments, span)?;
+
+    Ok(value_or_false(array.prev()))
+}
+
+/// reset(array|object &$array): mixed
+pub fn reset(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("reset", arguments, span)?;
+
+    Ok(value_or_false(array.reset()))
+}
+
+/// end(array|object &$array): mixed
+pub fn end(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("end", arguments, span)?;
+
+    Ok(value_or_false(array.end()))
+}
+
+/// array_key_exists(string|int|float|bool|resource|null $key, array $array): bool
+pub fn array_key_exists(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_key_exists", arguments, 2, 2, span)?;
+
+    let array = get_array_argument_at("array_key_exists", arguments, 1, "array", span)?;
+
+    let key = evaluator.get_array_key(&arguments[0], span)?;
+
+    // unlike isset(), an element set to null exists
+    Ok(PhpValue::Bool(array.get(&key).is_some()))
+}
+
+/// array_search(mixed $needle, array $haystack, bool $strict = false): int|string|false
+pub fn array_search(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_search", arguments, 2, 3, span)?;
+
+    let haystack = get_array_argument_at("array_search", arguments, 1, "haystack", span)?;
+
+    let strict = match arguments.get(2) {
+        Some(_) => get_bool_argument(evaluator, "array_search", arguments, 2, "strict", span)?,
+        None => false,
+    };
+
+    for (key, value) in haystack.iter() {
+        if matches_search_value(&value, &arguments[0], strict, span)? {
+            return Ok(key.to_value());
+        }
+    }
+
+    Ok(PhpValue::Bool(false))
+}
+
+/// in_array(mixed $needle, array $haystack, bool $strict = false): bool
+pub fn in_array(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("in_array", arguments, 2, 3, span)?;
+
+    let haystack = get_array_argument_at("in_array", arguments, 1, "haystack", span)?;
+
+    let strict = match arguments.get(2) {
+        Some(_) => get_bool_argument(evaluator, "in_array", arguments, 2, "strict", span)?,
+        None => false,
+    };
+
+    for (_, value) in haystack.iter() {
+        if matches_search_value(&value, &arguments[0], strict, span)? {
+            return Ok(PhpValue::Bool(true));
+        }
+    }
+
+    Ok(PhpValue::Bool(false))
+}
+
+/// array_keys(array $array, mixed $filter_value, bool $strict = false): array
+///
+/// Without a filter value all the keys are returned.
+pub fn array_keys(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_keys", arguments, 1, 3, span)?;
+
+    let array = get_array_argument_at("array_keys", arguments, 0, "array", span)?;
+
+    let strict = match arguments.get(2) {
+        Some(_) => get_bool_argument(evaluator, "array_keys", arguments, 2, "strict", span)?,
+        None => false,
+    };
+
+    let mut keys = PhpArray::new();
+
+    for (key, value) in array.iter() {
+        let is_kept = match arguments.get(1) {
+            Some(filter_value) => matches_search_value(&value, filter_value, strict, span)?,
+            None => true,
+        };
+
+        if is_kept {
+            push(&mut keys, key.to_value(), span)?;
+        }
+    }
+
+    Ok(PhpValue::Array(keys))
+}
+
+/// array_values(array $array): array
+pub fn array_values(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let array = get_array_argument("array_values", arguments, span)?;
+
+    let mut values = PhpArray::new();
+
+    for (_, value) in array.iter() {
+        push(&mut values, value.into_owned(), span)?;
+    }
+
+    Ok(PhpValue::Array(values))
+}
+
+/// count(Countable|array $value, int $mode = COUNT_NORMAL): int
+///
+/// With COUNT_RECURSIVE the elements of the arrays in the array are counted too.
+/// An object that implements Countable is counted by its count() method.
+pub fn count(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("count", arguments, 1, 2, span)?;
+
+    let mode = match arguments.get(1) {
+        Some(_) => get_int_argument(evaluator, "count", arguments, 1, "mode", span)?,
+        None => COUNT_NORMAL,
+    };
+
+    if mode != COUNT_NORMAL && mode != COUNT_RECURSIVE {
+        return Err(argument_value_error(
+            "count",
+            1,
+            "mode",
+            "must be either COUNT_NORMAL or COUNT_RECURSIVE",
+            span,
+        ));
+    }
+
+    match &arguments[0] {
+        PhpValue::Array(array) => Ok(PhpValue::Int(
+            count_elements(array, mode == COUNT_RECURSIVE) as i64,
+        )),
+        PhpValue::Object(object) if object.borrow().is_instance_of(b"Countable") => {
+            let count = evaluator.call_object_method(Rc::clone(object), "count", vec![], span)?;
+
+            // the result is converted to int, like the int return type of Countable::count()
+            Ok(PhpValue::Int(match count {
+                PhpValue::Int(count) => count,
+                PhpValue::Float(count) => count as i64,
+                count => count.is_true() as i64,
+            }))
+        }
+        value => Err(argument_type_error(
+            "count",
+            0,
+            "value",
+            "Countable|array",
+            value,
+            span,
+        )),
+    }
+}
+
+/// The number of elements of an array, with the ones of the arrays inside of it if
+/// `recursive` is set.
+fn count_elements(array: &PhpArray, recursive: bool) -> usize {
+    if !recursive {
+        return array.len();
+    }
+
+    array.len()
+        + array
+            .iter()
+            .map(|(_, value)| match &*value {
+                PhpValue::Array(inner) => count_elements(inner, true),
+                _ => 0,
+            })
+            .sum::<usize>()
+}
+
+/// array_push(array &$array, mixed ...$values): int
+///
+/// The values get the next integer keys, the new number of elements is returned.
+pub fn array_push(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_push", arguments, 1, usize::MAX, span)?;
+
+    let (array, values) = arguments.split_first_mut().unwrap();
+
+    match array {
+        PhpValue::Array(array) => {
+            for value in values.iter() {
+                push(array, value.clone(), span)?;
+            }
+
+            Ok(PhpValue::Int(array.len() as i64))
+        }
+        value => Err(argument_type_error(
+            "array_push",
+            0,
+            "array",
+            "array",
+            value,
+            span,
+        )),
+    }
+}
+
+/// array_merge(array ...$arrays): array
+///
+/// The elements with an integer key are appended and renumbered from 0, the ones
+/// with a string key replace the value of an earlier array that has the same key.
+pub fn array_merge(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let mut merged = PhpArray::new();
+
+    for (position, argument) in arguments.iter().enumerate() {
+        let PhpValue::Array(array) = argument else {
+            // a variadic parameter is not named in the error
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("TypeError"),
+                message: format!(
+                    "array_merge(): Argument #{} must be of type array, {} given",
+                    position + 1,
+                    argument.type_name().error_name()
+                ),
+                line: span.line,
+            });
+        };
+
+        for (key, value) in array.iter() {
+            add_element(&mut merged, key, value.into_owned(), false, span)?;
+        }
+    }
+
+    Ok(PhpValue::Array(merged))
+}
+
+/// array_map(?callable $callback, array $array, array ...$arrays): array
+///
+/// With one array the keys are kept, with more the result is a list and the shorter
+/// arrays are filled with null.
+pub fn array_map(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_map", arguments, 2, usize::MAX, span)?;
+
+    check_callable_argument(evaluator, "array_map", arguments, 0, "callback", true, span)?;
+
+    let mut arrays = vec![get_array_argument_at("array_map", arguments, 1, "array", span)?.clone()];
+
+    for (position, argument) in arguments.iter().enumerate().skip(2) {
+        let PhpValue::Array(array) = argument else {
+            // a variadic parameter is not named in the error
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("TypeError"),
+                message: format!(
+                    "array_map(): Argument #{} must be of type array, {} given",
+                    position + 1,
+                    argument.type_name().error_name()
+                ),
+                line: span.line,
+            });
+        };
+
+        arrays.push(array.clone());
+    }
+
+    let callback = arguments[0].clone();
+
+    let mut mapped = PhpArray::new();
+
+    if arrays.len() == 1 {
+        if callback.is_null() {
+            return Ok(PhpValue::Array(arrays.remove(0)));
+        }
+
+        for (key, value) in arrays[0].iter() {
+            let value = evaluator.call_callable(&callback, vec![value.into_owned()], span)?;
+
+            mapped.insert(key.clone(), value);
+        }
+
+        return Ok(PhpValue::Array(mapped));
+    }
+
+    let length = arrays.iter().map(PhpArray::len).max().unwrap_or(0);
+
+    for position in 0..length {
+        let values: Vec<PhpValue> = arrays
+            .iter()
+            .map(|array| {
+                array
+                    .iter()
+                    .nth(position)
+                    .map_or(PhpValue::Null, |(_, value)| value.into_owned())
+            })
+            .collect();
+
+        if callback.is_null() {
+            let mut zipped = PhpArray::new();
+
+            for value in values {
+                push(&mut zipped, value, span)?;
+            }
+
+            push(&mut mapped, PhpValue::Array(zipped), span)?;
+        } else {
+            let value = evaluator.call_callable(&callback, values, span)?;
+
+            push(&mut mapped, value, span)?;
+        }
+    }
+
+    Ok(PhpValue::Array(mapped))
+}
+
+/// array_filter(array $array, ?callable $callback = null, int $mode = 0): array
+pub fn array_filter(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_filter", arguments, 1, 3, span)?;
+
+    let array = get_array_argument_at("array_filter", arguments, 0, "array", span)?;
+
+    let callback = match arguments.get(1) {
+        Some(_) => {
+            check_callable_argument(
+                evaluator,
+                "array_filter",
+                arguments,
+                1,
+                "callback",
+                true,
+                span,
+            )?;
+
+            arguments[1].clone()
+        }
+        None => PhpValue::Null,
+    };
+
+    let mode = match arguments.get(2) {
+        Some(_) => get_int_argument(evaluator, "array_filter", arguments, 2, "mode", span)?,
+        None => 0,
+    };
+
+    let mut filtered = PhpArray::new();
+
+    for (key, value) in array.iter() {
+        let keep = if callback.is_null() {
+            value.to_php_bool()
+        } else {
+            let callback_arguments = match mode {
+                ARRAY_FILTER_USE_BOTH => vec![(*value).clone(), key.to_value()],
+                ARRAY_FILTER_USE_KEY => vec![key.to_value()],
+                _ => vec![(*value).clone()],
+            };
+
+            evaluator
+                .call_callable(&callback, callback_arguments, span)?
+                .is_true()
+        };
+
+        if keep {
+            filtered.insert(key.clone(), value.into_owned());
+        }
+    }
+
+    Ok(PhpValue::Array(filtered))
+}
+
+/// usort(array &$array, callable $callback): true
+///
+/// The sort is stable, like the one of PHP 8, and the array gets new keys.
+pub fn usort(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("usort", arguments, 2, 2, span)?;
+
+    let array = get_array_argument_at("usort", arguments, 0, "array", span)?;
+
+    check_callable_argument(evaluator, "usort", arguments, 1, "callback", false, span)?;
+
+    let callback = arguments[1].clone();
+
+    let values: Vec<PhpValue> = array.iter().map(|(_, value)| value.into_owned()).collect();
+
+    let sorted = merge_sort(values, &mut |a, b| {
+        let result = evaluator.call_callable(&callback, vec![a.clone(), b.clone()], span)?;
+
+        Ok(match result {
+            PhpValue::Int(i) => i > 0,
+            PhpValue::Float(f) => f as i64 > 0,
+            result => result.is_true(),
+        })
+    })?;
+
+    let mut array = PhpArray::new();
+
+    for value in sorted {
+        push(&mut array, value, span)?;
+    }
+
+    arguments[0] = PhpValue::Array(array);
+
+    Ok(PhpValue::Bool(true))
+}
+
+/// A stable merge sort, `is_greater` can fail, and a callback that does not order the
+/// values consistently gives some order instead of a panic like `sort_by()`.
+fn merge_sort<F>(mut values: Vec<PhpValue>, is_greater: &mut F) -> Result<Vec<PhpValue>, PhpError>
+where
+    F: FnMut(&PhpValue, &PhpValue) -> Result<bool, PhpError>,
+{
+    if values.len() <= 1 {
+        return Ok(values);
+    }
+
+    let right = values.split_off(values.len() / 2);
+
+    let left = merge_sort(values, is_greater)?;
+    let right = merge_sort(right, is_greater)?;
+
+    let mut merged = Vec::with_capacity(left.len() + right.len());
+
+    let mut left = left.into_iter().peekable();
+    let mut right = right.into_iter().peekable();
+
+    while let (Some(a), Some(b)) = (left.peek(), right.peek()) {
+        // equal values keep their order, the one of the left goes first
+        if is_greater(a, b)? {
+            merged.push(right.next().unwrap());
+        } else {
+            merged.push(left.next().unwrap());
+        }
+    }
+
+    merged.extend(left);
+    merged.extend(right);
+
+    Ok(merged)
+}
+
+/// Whether an element is the value searched by in_array(), array_search() and array_keys(),
+/// the loose comparison is the one of `==`.
+fn matches_search_value(
+    value: &PhpValue,
+    search_value: &PhpValue,
+    strict: bool,
+    span: Span,
+) -> Result<bool, PhpError> {
+    let matches = if strict {
+        value.is_identical(search_value)
+    } else {
+        value.loosely_equals(search_value)
+    };
+
+    matches.map_err(|mut error| {
+        error.line = span.line;
+
+        error
+    })
+}
+
+/// array_flip(array $array): array
+pub fn array_flip(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_flip", arguments, 1, 1, span)?;
+
+    let array = get_array_argument_at("array_flip", arguments, 0, "array", span)?;
+
+    let mut flipped = PhpArray::new();
+
+    for (key, value) in array.iter() {
+        match &*value {
+            PhpValue::Int(_) | PhpValue::String(_) => {
+                flipped.insert(ArrayKey::from_value(&value)?, key.to_value());
+            }
+            _ => evaluator.raise_error(PhpError {
+                level: ErrorLevel::Warning,
+                message: "array_flip(): Can only flip string and integer values, entry skipped"
+                    .to_string(),
+                line: span.line,
+            })?,
+        }
+    }
+
+    Ok(PhpValue::Array(flipped))
+}
+
+/// array_unique(array $array, int $flags = SORT_STRING): array
+pub fn array_unique(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_unique", arguments, 1, 2, span)?;
+
+    let array = get_array_argument_at("array_unique", arguments, 0, "array", span)?;
+
+    let flags = match arguments.get(1) {
+        Some(_) => get_int_argument(evaluator, "array_unique", arguments, 1, "flags", span)?,
+        None => SORT_STRING,
+    };
+
+    let is_duplicate = |a: &PhpValue, b: &PhpValue| match flags {
+        SORT_REGULAR => a == b,
+        SORT_NUMERIC => a.to_float().unwrap_or(0.0) == b.to_float().unwrap_or(0.0),
+        // SORT_STRING and SORT_LOCALE_STRING
+        _ => {
+            let as_string = |value: &PhpValue| match value {
+                PhpValue::Null => String::new(),
+                value => value.to_string().unwrap_or_else(|| "Array".to_string()),
+            };
+
+            as_string(a) == as_string(b)
+        }
+    };
+
+    let mut unique = PhpArray::new();
+
+    // the first element of every group of duplicates is kept, with its key
+    for (key, value) in array.iter() {
+        if !unique.iter().any(|(_, kept)| is_duplicate(&kept, &value)) {
+            unique.insert(key.clone(), value.into_owned());
+        }
+    }
+
+    Ok(PhpValue::Array(unique))
+}
+
+/// array_slice(array $array, int $offset, ?int $length = null, bool $preserve_keys = false): array
+pub fn array_slice(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_slice", arguments, 2, 4, span)?;
+
+    let array = get_array_argument_at("array_slice", arguments, 0, "array", span)?;
+
+    let offset = get_int_argument(evaluator, "array_slice", arguments, 1, "offset", span)?;
+
+    let length = match arguments.get(2) {
+        None | Some(PhpValue::Null) => None,
+        Some(_) => Some(get_int_argument(
+            evaluator,
+            "array_slice",
+            arguments,
+            2,
+            "length",
+            span,
+        )?),
+    };
+
+    let preserve_keys = match arguments.get(3) {
+        Some(_) => get_bool_argument(
+            evaluator,
+            "array_slice",
+            arguments,
+            3,
+            "preserve_keys",
+            span,
+        )?,
+        None => false,
+    };
+
+    let (start, end) = get_slice_bounds(array.len(), offset, length);
+
+    let mut slice = PhpArray::new();
+
+    for (key, value) in array.iter().skip(start).take(end - start) {
+        add_element(&mut slice, key, value.into_owned(), preserve_keys, span)?;
+    }
+
+    Ok(PhpValue::Array(slice))
+}
+
+/// array_splice(array &$array, int $offset, ?int $length = null, mixed $replacement = []): array
+pub fn array_splice(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_splice", arguments, 2, 4, span)?;
+
+    let array = get_array_argument_at("array_splice", arguments, 0, "array", span)?;
+
+    let offset = get_int_argument(evaluator, "array_splice", arguments, 1, "offset", span)?;
+
+    let length = match arguments.get(2) {
+        None | Some(PhpValue::Null) => None,
+        Some(_) => Some(get_int_argument(
+            evaluator,
+            "array_splice",
+            arguments,
+            2,
+            "length",
+            span,
+        )?),
+    };
+
+    // a replacement that is not an array is the only element of the replacement
+    let replacement: Vec<PhpValue> = match arguments.get(3) {
+        None => vec![],
+        Some(PhpValue::Array(replacement)) => replacement
+            .iter()
+            .map(|(_, value)| value.into_owned())
+            .collect(),
+        Some(replacement) => vec![replacement.clone()],
+    };
+
+    let (start, end) = get_slice_bounds(array.len(), offset, length);
+
+    let mut spliced = PhpArray::new();
+    let mut removed = PhpArray::new();
+
+    for (position, (key, value)) in array.iter().enumerate() {
+        if position == start {
+            for value in &replacement {
+                push(&mut spliced, value.clone(), span)?;
+            }
+        }
+
+        if position >= start && position < end {
+            add_element(&mut removed, key, value.into_owned(), false, span)?;
+        } else {
+            add_element(&mut spliced, key, value.into_owned(), false, span)?;
+        }
+    }
+
+    if start == array.len() {
+        for value in replacement {
+            push(&mut spliced, value, span)?;
+        }
+    }
+
+    arguments[0] = PhpValue::Array(spliced);
+
+    Ok(PhpValue::Array(removed))
+}
+
+/// array_combine(array $keys, array $values): array
+pub fn array_combine(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_combine", arguments, 2, 2, span)?;
+
+    let keys = get_array_argument_at("array_combine", arguments, 0, "keys", span)?;
+    let values = get_array_argument_at("array_combine", arguments, 1, "values", span)?;
+
+    if keys.len() != values.len() {
+        return Err(value_error(
+            "array_combine(): Argument #1 ($keys) and argument #2 ($values) must have the same number of elements".to_string(),
+            span,
+        ));
+    }
+
+    let mut combined = PhpArray::new();
+
+    for ((_, key), (_, value)) in keys.iter().zip(values.iter()) {
+        let key = value_to_key(evaluator, key.into_owned(), span)?;
+
+        combined.insert(key, value.into_owned());
+    }
+
+    Ok(PhpValue::Array(combined))
+}
+
+/// array_fill(int $start_index, int $count, mixed $value): array
+pub fn array_fill(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_fill", arguments, 3, 3, span)?;
+
+    let start_index = get_int_argument(evaluator, "array_fill", arguments, 0, "start_index", span)?;
+    let count = get_int_argument(evaluator, "array_fill", arguments, 1, "count", span)?;
+
+    check_not_negative("array_fill", count, 1, "count", span)?;
+
+    let mut array = PhpArray::new();
+
+    for i in 0..count {
+        array.insert(
+            ArrayKey::Int(start_index.saturating_add(i)),
+            arguments[2].clone(),
+        );
+    }
+
+    Ok(PhpValue::Array(array))
+}
+
+/// array_fill_keys(array $keys, mixed $value): array
+pub fn array_fill_keys(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("array_fill_keys", arguments, 2, 2, span)?;
+
+    let keys = get_array_argument_at("array_fill_keys", arguments, 0, "keys", span)?;
+
+    let mut array = PhpArray::new();
+
+    for (_, key) in keys.iter() {
+        let key = value_to_key(evaluator, key.into_owned(), span)?;
+
+        array.insert(key, arguments[1].clone());
+    }
+
+    Ok(PhpValue::Array(array))
+}
+
+/// Converts a value of array_combine() or array_fill_keys() to a key, a value that is not
+/// an int is converted to a string first, so 1.5 gives "1.5" and true gives 1.
+fn value_to_key(
+    evaluator: &mut Evaluator,
+    value: PhpValue,
+    span: Span,
+) -> Result<ArrayKey, PhpError> {
+    if let PhpValue::Int(i) = value {
+        return Ok(ArrayKey::Int(i));
+    }
+
+    let string = evaluator.value_to_output_string(value, span)?;
+
+    evaluator.get_array_key(&PhpValue::String(string.into()), span)
+}
+
+/// range(string|int|float $start, string|int|float $end, int|float $step = 1): array
+pub fn range(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("range", arguments, 2, 3, span)?;
+
+    let step = match arguments.get(2) {
+        None => PhpValue::Int(1),
+        Some(PhpValue::Float(f)) => PhpValue::Float(*f),
+        Some(_) => PhpValue::Int(get_int_argument(
+            evaluator, "range", arguments, 2, "step", span,
+        )?),
+    };
+
+    let step_size = step.to_float().unwrap_or(0.0).abs();
+
+    if step_size == 0.0 {
+        return Err(argument_value_error(
+            "range",
+            2,
+            "step",
+            "cannot be 0",
+            span,
+        ));
+    }
+
+    let step_error = || {
+        argument_value_error(
+            "range",
+            2,
+            "step",
+            "must not exceed the specified range",
+            span,
+        )
+    };
+
+    let mut array = PhpArray::new();
+
+    // character ranges, like range('a', 'e')
+    if let (PhpValue::String(start), PhpValue::String(end)) = (&arguments[0], &arguments[1]) {
+        if !start.is_empty()
+            && !end.is_empty()
+            && parse_numeric_string(start).is_none()
+            && parse_numeric_string(end).is_none()
+        {
+            let (start, end) = (start[0] as i64, end[0] as i64);
+
+            let step = step_size as i64;
+
+            if step > (end - start).abs() && start != end {
+                return Err(step_error());
+            }
+
+            let direction = if start <= end { 1 } else { -1 };
+
+            for i in 0..=((end - start).abs() / step.max(1)) {
+                let character = (start + i * step.max(1) * direction) as u8;
+
+                push(&mut array, PhpValue::String(vec![character].into()), span)?;
+            }
+
+            return Ok(PhpValue::Array(array));
+        }
+    }
+
+    let to_number = |value: &PhpValue| match value {
+        PhpValue::Int(i) => PhpValue::Int(*i),
+        PhpValue::Float(f) => PhpValue::Float(*f),
+        PhpValue::String(s) => parse_numeric_string(s).unwrap_or(PhpValue::Int(0)),
+        PhpValue::Bool(b) => PhpValue::Int(*b as i64),
+        _ => PhpValue::Int(0),
+    };
+
+    let (start, end) = (to_number(&arguments[0]), to_number(&arguments[1]));
+
+    match (start, end, step) {
+        (PhpValue::Int(start), PhpValue::Int(end), PhpValue::Int(_)) => {
+            // the distance between two ints does not always fit in an int
+            let (start, end) = (start as i128, end as i128);
+
+            let step = step_size as i128;
+
+            if step > (end - start).abs() && start != end {
+                return Err(step_error());
+            }
+
+            let direction = if start <= end { 1 } else { -1 };
+
+            for i in 0..=((end - start).abs() / step) {
+                push(
+                    &mut array,
+                    PhpValue::Int((start + i * step * direction) as i64),
+                    span,
+                )?;
+            }
+        }
+        (start, end, _) => {
+            let start = start.to_float().unwrap_or(0.0);
+            let end = end.to_float().unwrap_or(0.0);
+
+            if step_size > (end - start).abs() && start != end {
+                return Err(step_error());
+            }
+
+            let direction = if start <= end { 1.0 } else { -1.0 };
+
+            let count = ((end - start).abs() / step_size).floor() as i64;
+
+            for i in 0..=count {
+                push(
+                    &mut array,
+                    PhpValue::Float(start + i as f64 * step_size * direction),
+                    span,
+                )?;
+            }
+        }
+    }
+
+    Ok(PhpValue::Array(array))
+}
@@ -0,0 +1,304 @@
+use php_parser_rs::lexer::byte_string::ByteString;
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::modifiers::Visibility;
+
+use crate::evaluator::Evaluator;
+use crate::helpers::{describe_parameters, display_name};
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_class::{ClassProperty, PhpClass, PhpMethod};
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::{
+    check_arguments_count, get_bool_argument, get_object_id_argument, get_string_argument,
+};
+
+/// class_exists(string $class, bool $autoload = true): bool
+///
+/// Whether the class is declared, an enum is a class too. A class declared inside
+/// a condition or a function exists once its statement has run. There is no autoloader,
+/// `$autoload` is ignored.
+pub fn class_exists(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("class_exists", arguments, 1, 2, span)?;
+
+    let name = get_string_argument(evaluator, "class_exists", arguments, 0, "class", span)?;
+
+    if arguments.len() > 1 {
+        get_bool_argument(evaluator, "class_exists", arguments, 1, "autoload", span)?;
+    }
+
+    let name = name.strip_prefix(b"\\").unwrap_or(&name);
+
+    Ok(PhpValue::Bool(evaluator.env.get_class(name).is_some()))
+}
+
+/// phpl_class_info(string $class): array
+///
+/// What a class declares and inherits: its name, parent, interfaces, doc comment,
+/// constants, properties and methods. It is a small part of what ReflectionClass gives.
+/// The members of the class come first, then the ones inherited from its parents.
+pub fn phpl_class_info(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("phpl_class_info", arguments, 1, 1, span)?;
+
+    let name = get_string_argument(evaluator, "phpl_class_info", arguments, 0, "class", span)?;
+
+    let Some(class) = evaluator.env.get_class(&name) else {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "phpl_class_info(): Argument #1 ($class) must be a valid class name, {} given",
+                display_name(&name)
+            ),
+            line: span.line,
+        });
+    };
+
+    let classes: Vec<_> = class.ancestors().collect();
+
+    let mut constants = PhpArray::new();
+    let mut properties = PhpArray::new();
+    let mut methods = PhpArray::new();
+
+    for declaring_class in &classes {
+        for (name, value) in &declaring_class.constants {
+            add_member(&mut constants, name, value.clone());
+        }
+
+        for property in &declaring_class.properties {
+            add_member(
+                &mut properties,
+                &property.name,
+                property_info(declaring_class, property, false),
+            );
+        }
+
+        for property in &declaring_class.static_property_declarations {
+            add_member(
+                &mut properties,
+                &property.name,
+                property_info(declaring_class, property, true),
+            );
+        }
+
+        // order: the methods are stored by name, they are sorted by their position
+        // which gives the declaration order
+        let mut declared_methods: Vec<_> = declaring_class.methods.values().collect();
+
+        declared_methods.sort_by_key(|method| method.function.span.position);
+
+        for method in declared_methods {
+            add_member(&mut methods, &method.function.name, method_info(method));
+        }
+    }
+
+    let mut info = PhpArray::new();
+
+    insert(&mut info, "name", PhpValue::String(class.name.clone()));
+    insert(
+        &mut info,
+        "parent",
+        match &class.parent {
+            Some(parent) => PhpValue::String(parent.name.clone()),
+            None => PhpValue::Null,
+        },
+    );
+    insert(
+        &mut info,
+        "abstract",
+        PhpValue::Bool(class.modifiers.has_abstract()),
+    );
+    insert(
+        &mut info,
+        "final",
+        PhpValue::Bool(class.modifiers.has_final()),
+    );
+
+    let mut interfaces = vec![];
+
+    for interface in classes.iter().flat_map(|class| &class.interfaces) {
+        let interface = PhpValue::String(interface.name.clone());
+
+        if !interfaces.contains(&interface) {
+            interfaces.push(interface);
+        }
+    }
+
+    insert(
+        &mut info,
+        "interfaces",
+        PhpValue::Array(interfaces.into_iter().collect()),
+    );
+    insert(
+        &mut info,
+        "doc_comment",
+        doc_comment_value(&class.doc_comment),
+    );
+    insert(&mut info, "constants", PhpValue::Array(constants));
+    insert(&mut info, "properties", PhpValue::Array(properties));
+    insert(&mut info, "methods", PhpValue::Array(methods));
+
+    Ok(PhpValue::Array(info))
+}
+
+/// spl_object_id(object $object): int
+///
+/// The id of an object does not change while it is alive, once it is destroyed
+/// the id can be given to a new object.
+pub fn spl_object_id(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("spl_object_id", arguments, 1, 1, span)?;
+
+    let id = get_object_id_argument("spl_object_id", arguments, 0, "object", span)?;
+
+    Ok(PhpValue::Int(id as i64))
+}
+
+/// spl_object_hash(object $object): string
+///
+/// The id of the object in hexadecimal on 16 digits, followed by 16 zeros like PHP 8.1 does.
+pub fn spl_object_hash(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("spl_object_hash", arguments, 1, 1, span)?;
+
+    let id = get_object_id_argument("spl_object_hash", arguments, 0, "object", span)?;
+
+    let hash = format!("{:016x}{:016x}", id, 0);
+
+    Ok(PhpValue::String(hash.into()))
+}
+
+fn insert(array: &mut PhpArray, key: &str, value: PhpValue) {
+    array.insert(ArrayKey::String(key.into()), value);
+}
+
+/// Adds a member unless a child class already redeclared it.
+fn add_member(members: &mut PhpArray, name: &ByteString, value: PhpValue) {
+    let key = ArrayKey::String(name.clone());
+
+    if members.get(&key).is_none() {
+        members.insert(key, value);
+    }
+}
+
+/// The doc comment, or false like ReflectionClass::getDocComment() when there is none.
+fn doc_comment_value(doc_comment: &Option<ByteString>) -> PhpValue {
+    match doc_comment {
+        Some(doc_comment) => PhpValue::String(doc_comment.clone()),
+        None => PhpValue::Bool(false),
+    }
+}
+
+fn visibility_name(visibility: Visibility) -> &'static str {
+    match visibility {
+        Visibility::Public => "public",
+        Visibility::Protected => "protected",
+        Visibility::Private => "private",
+    }
+}
+
+fn property_info(class: &PhpClass, property: &ClassProperty, is_static: bool) -> PhpValue {
+    let mut info = PhpArray::new();
+
+    insert(&mut info, "class", PhpValue::String(class.name.clone()));
+    insert(
+        &mut info,
+        "visibility",
+        PhpValue::String(visibility_name(property.modifiers.visibility()).into()),
+    );
+    insert(&mut info, "static", PhpValue::Bool(is_static));
+    insert(
+        &mut info,
+        "readonly",
+        PhpValue::Bool(property.modifiers.has_readonly()),
+    );
+    insert(
+        &mut info,
+        "type",
+        match &property.data_type {
+            Some(data_type) => PhpValue::String(data_type.to_string().into()),
+            None => PhpValue::Null,
+        },
+    );
+    insert(&mut info, "default", property.default_value.clone());
+    insert(
+        &mut info,
+        "doc_comment",
+        doc_comment_value(&property.doc_comment),
+    );
+
+    PhpValue::Array(info)
+}
+
+fn method_info(method: &PhpMethod) -> PhpValue {
+    let mut info = PhpArray::new();
+
+    insert(
+        &mut info,
+        "class",
+        PhpValue::String(method.class_name.clone()),
+    );
+    insert(
+        &mut info,
+        "signature",
+        PhpValue::String(method_signature(method).into()),
+    );
+    insert(
+        &mut info,
+        "doc_comment",
+        doc_comment_value(&method.doc_comment),
+    );
+
+    PhpValue::Array(info)
+}
+
+/// The declaration of a method without its body, like
+/// `public static function create(int $size = 10, ...$items): static`.
+fn method_signature(method: &PhpMethod) -> String {
+    let mut signature = String::new();
+
+    if method.modifiers.has_final() {
+        signature.push_str("final ");
+    }
+
+    if method.modifiers.has_abstract() {
+        signature.push_str("abstract ");
+    }
+
+    signature.push_str(visibility_name(method.modifiers.visibility()));
+    signature.push(' ');
+
+    if method.modifiers.has_static() {
+        signature.push_str("static ");
+    }
+
+    signature.push_str("function ");
+
+    if method.function.return_by_reference {
+        signature.push('&');
+    }
+
+    signature.push_str(&format!(
+        "{}({})",
+        method.function.name,
+        describe_parameters(&method.function.parameters)
+    ));
+
+    if let Some(return_type) = &method.function.return_type {
+        signature.push_str(&format!(": {}", return_type.data_type));
+    }
+
+    signature
+}
@@ -0,0 +1,55 @@
+use std::time::{Duration, SystemTime};
+
+use php_parser_rs::lexer::token::Span;
+
+use crate::evaluator::Evaluator;
+use crate::php_value::{PhpError, PhpValue};
+
+use super::{check_arguments_count, get_bool_argument};
+
+/// The time elapsed since the Unix epoch, 0 for a clock set before it.
+fn unix_time(evaluator: &Evaluator) -> Duration {
+    evaluator
+        .now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default()
+}
+
+/// time(): int
+pub fn time(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("time", arguments, 0, 0, span)?;
+
+    Ok(PhpValue::Int(unix_time(evaluator).as_secs() as i64))
+}
+
+/// microtime(bool $as_float = false): string|float
+///
+/// Without `$as_float`, the microseconds as a fraction of a second come before the
+/// seconds, like "0.25000000 1700000000", so the seconds do not lose precision.
+pub fn microtime(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("microtime", arguments, 0, 1, span)?;
+
+    let as_float = if arguments.is_empty() {
+        false
+    } else {
+        get_bool_argument(evaluator, "microtime", arguments, 0, "as_float", span)?
+    };
+
+    let time = unix_time(evaluator);
+
+    if as_float {
+        return Ok(PhpValue::Float(time.as_secs_f64()));
+    }
+
+    let microtime = format!("0.{:06}00 {}", time.subsec_micros(), time.as_secs());
+
+    Ok(PhpValue::String(microtime.into()))
+}
@@ -0,0 +1,139 @@
+use php_parser_rs::lexer::token::Span;
+
+use crate::evaluator::{ErrorHandler, Evaluator};
+use crate::php_value::{
+    ErrorLevel, PhpError, PhpValue, E_ALL, E_USER_DEPRECATED, E_USER_ERROR, E_USER_NOTICE,
+    E_USER_WARNING,
+};
+
+use super::{
+    check_arguments_count, check_callable_argument, get_int_argument, get_string_argument,
+};
+
+/// set_error_handler(?callable $callback, int $error_levels = E_ALL): ?callable
+pub fn set_error_handler(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    if arguments.is_empty() || arguments.len() > 2 {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "set_error_handler() expects at most 2 arguments, {} given",
+                arguments.len()
+            ),
+            line: span.line,
+        });
+    }
+
+    check_callable_argument(
+        evaluator,
+        "set_error_handler",
+        arguments,
+        0,
+        "callback",
+        true,
+        span,
+    )?;
+
+    let mut arguments = arguments.iter().cloned();
+
+    let callback = arguments.next().unwrap();
+
+    let error_levels = match arguments.next() {
+        Some(PhpValue::Int(error_levels)) => error_levels,
+        Some(value) => {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "set_error_handler(): Argument #2 ($error_levels) must be of type int, {} given",
+                    value.type_name().error_name()
+                ),
+                line: span.line,
+            })
+        }
+        None => E_ALL,
+    };
+
+    let previous_handler = evaluator
+        .error_handlers
+        .last()
+        .map(|handler| handler.callback.clone())
+        .unwrap_or(PhpValue::Null);
+
+    evaluator.error_handlers.push(ErrorHandler {
+        callback,
+        error_levels,
+    });
+
+    Ok(previous_handler)
+}
+
+/// restore_error_handler(): bool
+pub fn restore_error_handler(
+    evaluator: &mut Evaluator,
+    _arguments: &mut [PhpValue],
+    _span: Span,
+) -> Result<PhpValue, PhpError> {
+    evaluator.error_handlers.pop();
+
+    Ok(PhpValue::Bool(true))
+}
+
+/// trigger_error(string $message, int $error_level = E_USER_NOTICE): bool
+///
+/// An E_USER_ERROR that no handler takes stops the script like a fatal error.
+pub fn trigger_error(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("trigger_error", arguments, 1, 2, span)?;
+
+    let message = get_string_argument(evaluator, "trigger_error", arguments, 0, "message", span)?;
+
+    let error_level = match arguments.get(1) {
+        Some(_) => get_int_argument(
+            evaluator,
+            "trigger_error",
+            arguments,
+            1,
+            "error_level",
+            span,
+        )?,
+        None => E_USER_NOTICE,
+    };
+
+    let level = match error_level {
+        E_USER_ERROR => ErrorLevel::UserError,
+        E_USER_WARNING => ErrorLevel::UserWarning,
+        E_USER_NOTICE => ErrorLevel::UserNotice,
+        E_USER_DEPRECATED => ErrorLevel::UserDeprecated,
+        _ => {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("ValueError"),
+                message: "trigger_error(): Argument #2 ($error_level) must be one of E_USER_ERROR, E_USER_WARNING, E_USER_NOTICE, or E_USER_DEPRECATED".to_string(),
+                line: span.line,
+            })
+        }
+    };
+
+    let error = PhpError {
+        level,
+        message: String::from_utf8_lossy(&message).to_string(),
+        line: span.line,
+    };
+
+    if let ErrorLevel::UserError = error.level {
+        if !evaluator.handle_error(&error)? {
+            return Err(error);
+        }
+
+        return Ok(PhpValue::Bool(true));
+    }
+
+    evaluator.raise_error(error)?;
+
+    Ok(PhpValue::Bool(true))
+}
@@ -0,0 +1,44 @@
+use php_parser_rs::lexer::token::Span;
+
+use crate::evaluator::{Evaluator, ShutdownFunction};
+use crate::helpers::display_name;
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+/// register_shutdown_function(callable $callback, mixed ...$args): void
+pub fn register_shutdown_function(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let Some((callback, arguments)) = arguments.split_first() else {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: "register_shutdown_function() expects at least 1 argument, 0 given"
+                .to_string(),
+            line: span.line,
+        });
+    };
+
+    if !evaluator.is_callable(callback) {
+        let callback_name = callback
+            .to_string()
+            .unwrap_or_else(|| callback.type_name().error_name().to_string());
+
+        return Err(PhpError {
+            level: ErrorLevel::EngineError("TypeError"),
+            message: format!(
+                "register_shutdown_function(): Argument #1 ($callback) must be a valid callback, function \"{}\" not found or invalid function name",
+                display_name(callback_name.as_bytes())
+            ),
+            line: span.line,
+        });
+    }
+
+    evaluator.shutdown_functions.push(ShutdownFunction {
+        callback: callback.clone(),
+        arguments: arguments.to_vec(),
+        span,
+    });
+
+    Ok(PhpValue::Null)
+}
@@ -0,0 +1,77 @@
+use php_parser_rs::lexer::token::Span;
+
+use crate::conversion::round_to_decimals;
+use crate::evaluator::Evaluator;
+use crate::php_value::{PhpError, PhpValue};
+
+use super::{check_arguments_count, get_int_argument, get_number_argument, get_string_argument};
+
+/// number_format(float $num, int $decimals = 0, ?string $decimal_separator = ".",
+/// ?string $thousands_separator = ","): string
+///
+/// The number is rounded half up on its decimal digits, so 2.675 gives "2.68".
+/// Negative decimals round to the left of the decimal point, like PHP 8.3 does,
+/// and a number that becomes zero loses its sign.
+pub fn number_format(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("number_format", arguments, 1, 4, span)?;
+
+    let number = get_number_argument(evaluator, "number_format", arguments, 0, "num", span)?;
+
+    let decimals = match arguments.get(1) {
+        Some(_) => get_int_argument(evaluator, "number_format", arguments, 1, "decimals", span)?,
+        None => 0,
+    };
+
+    let separator = |position: usize, parameter: &str, default: &[u8]| match arguments.get(position)
+    {
+        Some(PhpValue::Null) | None => Ok(default.to_vec()),
+        Some(_) => get_string_argument(
+            evaluator,
+            "number_format",
+            arguments,
+            position,
+            parameter,
+            span,
+        ),
+    };
+
+    let decimal_separator = separator(2, "decimal_separator", b".")?;
+    let thousands_separator = separator(3, "thousands_separator", b",")?;
+
+    if let PhpValue::Float(float) = number {
+        if !float.is_finite() {
+            let name = if float.is_nan() { "nan" } else { "inf" };
+
+            return Ok(PhpValue::String(name.into()));
+        }
+    }
+
+    let rounded = round_to_decimals(&number, decimals);
+
+    let mut formatted = vec![];
+
+    if rounded.negative && !rounded.is_zero() {
+        formatted.push(b'-');
+    }
+
+    let integer = rounded.integer.as_bytes();
+
+    for (position, digit) in integer.iter().enumerate() {
+        if position > 0 && (integer.len() - position).is_multiple_of(3) {
+            formatted.extend_from_slice(&thousands_separator);
+        }
+
+        formatted.push(*digit);
+    }
+
+    if !rounded.fraction.is_empty() {
+        formatted.extend_from_slice(&decimal_separator);
+        formatted.extend_from_slice(rounded.fraction.as_bytes());
+    }
+
+    Ok(PhpValue::String(formatted.into()))
+}
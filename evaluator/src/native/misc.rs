@@ -0,0 +1,130 @@
+use php_parser_rs::lexer::token::Span;
+
+use crate::evaluator::Evaluator;
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::{argument_value_error, check_arguments_count, get_bool_argument, get_string_argument};
+
+/// constant(string $name): mixed
+///
+/// The name can be the one of a class constant or an enum case, like "Suit::Hearts".
+pub fn constant(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("constant", arguments, 1, 1, span)?;
+
+    let name = get_string_argument(evaluator, "constant", arguments, 0, "name", span)?;
+
+    let Some(separator) = name.windows(2).position(|window| window == b"::") else {
+        return evaluator
+            .get_constant(&name)
+            .ok_or_else(|| evaluator.undefined_constant(&name, span));
+    };
+
+    let (class_name, constant_name) = (&name[..separator], &name[separator + 2..]);
+
+    let class_name = class_name.strip_prefix(b"\\").unwrap_or(class_name);
+
+    evaluator.get_class_constant(class_name, constant_name, span)
+}
+
+/// define(string $constant_name, mixed $value, bool $case_insensitive = false): bool
+pub fn define(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("define", arguments, 2, 3, span)?;
+
+    let name = get_string_argument(evaluator, "define", arguments, 0, "constant_name", span)?;
+
+    let case_insensitive = arguments.len() == 3
+        && get_bool_argument(evaluator, "define", arguments, 2, "case_insensitive", span)?;
+
+    if case_insensitive {
+        evaluator.raise_error(PhpError {
+            level: ErrorLevel::Warning,
+            message: "define(): Argument #3 ($case_insensitive) is ignored since declaration of case-insensitive constants is no longer supported".to_string(),
+            line: span.line,
+        })?;
+    }
+
+    if name.windows(2).any(|window| window == b"::") {
+        return Err(argument_value_error(
+            "define",
+            0,
+            "constant_name",
+            "cannot be a class constant",
+            span,
+        ));
+    }
+
+    // the constants are kept with the functions, see define_constant()
+    if let PhpValue::Callable(_) = arguments[1] {
+        return Err(argument_value_error(
+            "define",
+            1,
+            "value",
+            "cannot be a Closure",
+            span,
+        ));
+    }
+
+    let defined = evaluator.define_constant(&name, arguments[1].clone(), span)?;
+
+    Ok(PhpValue::Bool(defined))
+}
+
+/// defined(string $constant_name): bool
+pub fn defined(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("defined", arguments, 1, 1, span)?;
+
+    let name = get_string_argument(evaluator, "defined", arguments, 0, "constant_name", span)?;
+
+    Ok(PhpValue::Bool(evaluator.get_constant(&name).is_some()))
+}
+
+/// hrtime(bool $as_number = false): array|int|float
+///
+/// The time is measured with a monotonic clock from the start of the evaluation,
+/// the wall clock of time() does not change it.
+/// Without `$as_number` it is given as [seconds, nanoseconds]. As a number, the
+/// nanoseconds are an int until they do not fit in one, after about 2 seconds,
+/// then they are a float, like an int that overflows.
+pub fn hrtime(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("hrtime", arguments, 0, 1, span)?;
+
+    let as_number = if arguments.is_empty() {
+        false
+    } else {
+        get_bool_argument(evaluator, "hrtime", arguments, 0, "as_number", span)?
+    };
+
+    let elapsed = evaluator.started_at.elapsed();
+
+    if as_number {
+        let nanoseconds = elapsed.as_nanos();
+
+        return Ok(match i64::try_from(nanoseconds) {
+            Ok(nanoseconds) => PhpValue::Int(nanoseconds),
+            Err(_) => PhpValue::Float(nanoseconds as f64),
+        });
+    }
+
+    let time = [
+        PhpValue::Int(elapsed.as_secs() as i64),
+        PhpValue::Int(elapsed.subsec_nanos() as i64),
+    ];
+
+    Ok(PhpValue::Array(time.into_iter().collect()))
+}
@@ -0,0 +1,761 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use php_parser_rs::lexer::token::Span;
+
+use crate::conversion::{parse_numeric_string, scalar_to_string, DEFAULT_PRECISION};
+use crate::evaluator::Evaluator;
+use crate::helpers::display_name;
+use crate::php_array::ArrayKey;
+use crate::php_value::{ErrorLevel, PhpCallable, PhpError, PhpObject, PhpValue};
+
+mod array;
+mod classes;
+mod date;
+mod error_handling;
+mod function_handling;
+mod math;
+mod misc;
+mod options;
+mod output_control;
+mod string;
+mod variable_handling;
+
+pub(crate) use string::LANGINFO_ITEMS;
+
+/// A function implemented in Rust that can be called from PHP code.
+#[derive(Debug)]
+pub struct NativeFunction {
+    pub name: &'static str,
+
+    /// Receives the arguments of the call, the ones passed by reference
+    /// are written back to the variables once the function returns.
+    pub function: fn(&mut Evaluator, &mut [PhpValue], Span) -> Result<PhpValue, PhpError>,
+
+    /// The positions of the parameters that are passed by reference.
+    pub by_reference: &'static [usize],
+}
+
+/// All the native functions.
+const NATIVE_FUNCTIONS: &[NativeFunction] = &[
+    // array
+    NativeFunction {
+        name: "array_key_first",
+        function: array::array_key_first,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_key_last",
+        function: array::array_key_last,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "current",
+        function: array::current,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "pos",
+        function: array::current,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "key",
+        function: array::key,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "next",
+        function: array::next,
+        by_reference: &[0],
+    },
+    NativeFunction {
+        name: "prev",
+        function: array::prev,
+        by_reference: &[0],
+    },
+    NativeFunction {
+        name: "reset",
+        function: array::reset,
+        by_reference: &[0],
+    },
+    NativeFunction {
+        name: "end",
+        function: array::end,
+        by_reference: &[0],
+    },
+    NativeFunction {
+        name: "array_key_exists",
+        function: array::array_key_exists,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "key_exists",
+        function: array::array_key_exists,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_search",
+        function: array::array_search,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "in_array",
+        function: array::in_array,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_keys",
+        function: array::array_keys,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_values",
+        function: array::array_values,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "count",
+        function: array::count,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "sizeof",
+        function: array::count,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_push",
+        function: array::array_push,
+        by_reference: &[0],
+    },
+    NativeFunction {
+        name: "array_merge",
+        function: array::array_merge,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_map",
+        function: array::array_map,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_filter",
+        function: array::array_filter,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "usort",
+        function: array::usort,
+        by_reference: &[0],
+    },
+    NativeFunction {
+        name: "array_flip",
+        function: array::array_flip,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_unique",
+        function: array::array_unique,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_slice",
+        function: array::array_slice,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_splice",
+        function: array::array_splice,
+        by_reference: &[0],
+    },
+    NativeFunction {
+        name: "array_combine",
+        function: array::array_combine,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_fill",
+        function: array::array_fill,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "array_fill_keys",
+        function: array::array_fill_keys,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "range",
+        function: array::range,
+        by_reference: &[],
+    },
+    // classes
+    NativeFunction {
+        name: "class_exists",
+        function: classes::class_exists,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "phpl_class_info",
+        function: classes::phpl_class_info,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "spl_object_hash",
+        function: classes::spl_object_hash,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "spl_object_id",
+        function: classes::spl_object_id,
+        by_reference: &[],
+    },
+    // date
+    NativeFunction {
+        name: "microtime",
+        function: date::microtime,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "time",
+        function: date::time,
+        by_reference: &[],
+    },
+    // error handling
+    NativeFunction {
+        name: "set_error_handler",
+        function: error_handling::set_error_handler,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "restore_error_handler",
+        function: error_handling::restore_error_handler,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "trigger_error",
+        function: error_handling::trigger_error,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "user_error",
+        function: error_handling::trigger_error,
+        by_reference: &[],
+    },
+    // function handling
+    NativeFunction {
+        name: "register_shutdown_function",
+        function: function_handling::register_shutdown_function,
+        by_reference: &[],
+    },
+    // math
+    NativeFunction {
+        name: "number_format",
+        function: math::number_format,
+        by_reference: &[],
+    },
+    // misc
+    NativeFunction {
+        name: "constant",
+        function: misc::constant,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "define",
+        function: misc::define,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "defined",
+        function: misc::defined,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "hrtime",
+        function: misc::hrtime,
+        by_reference: &[],
+    },
+    // options
+    NativeFunction {
+        name: "ini_get",
+        function: options::ini_get,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "ini_set",
+        function: options::ini_set,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "ini_alter",
+        function: options::ini_set,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "ini_restore",
+        function: options::ini_restore,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "get_cfg_var",
+        function: options::get_cfg_var,
+        by_reference: &[],
+    },
+    // output control
+    NativeFunction {
+        name: "flush",
+        function: output_control::flush,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "headers_sent",
+        function: output_control::headers_sent,
+        by_reference: &[0, 1],
+    },
+    NativeFunction {
+        name: "ob_implicit_flush",
+        function: output_control::ob_implicit_flush,
+        by_reference: &[],
+    },
+    // string
+    NativeFunction {
+        name: "localeconv",
+        function: string::localeconv,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "nl_langinfo",
+        function: string::nl_langinfo,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "sprintf",
+        function: string::sprintf,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "str_repeat",
+        function: string::str_repeat,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "str_replace",
+        function: string::str_replace,
+        by_reference: &[3],
+    },
+    NativeFunction {
+        name: "str_word_count",
+        function: string::str_word_count,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "strlen",
+        function: string::strlen,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "strpos",
+        function: string::strpos,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "strtolower",
+        function: string::strtolower,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "strtoupper",
+        function: string::strtoupper,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "substr",
+        function: string::substr,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "trim",
+        function: string::trim,
+        by_reference: &[],
+    },
+    // variable handling
+    NativeFunction {
+        name: "boolval",
+        function: variable_handling::boolval,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "floatval",
+        function: variable_handling::floatval,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "get_defined_vars",
+        function: variable_handling::get_defined_vars,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "gettype",
+        function: variable_handling::gettype,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "intval",
+        function: variable_handling::intval,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "print_r",
+        function: variable_handling::print_r,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "settype",
+        function: variable_handling::settype,
+        by_reference: &[0],
+    },
+    NativeFunction {
+        name: "strval",
+        function: variable_handling::strval,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "var_dump",
+        function: variable_handling::var_dump,
+        by_reference: &[],
+    },
+    NativeFunction {
+        name: "var_export",
+        function: variable_handling::var_export,
+        by_reference: &[],
+    },
+];
+
+/// Returns the native function with the given name.
+///
+/// Function names are case insensitive.
+pub fn get_native_function(name: &[u8]) -> Option<&'static NativeFunction> {
+    NATIVE_FUNCTIONS
+        .iter()
+        .find(|function| function.name.as_bytes().eq_ignore_ascii_case(name))
+}
+
+/// The names of all the native functions.
+pub fn native_function_names() -> impl Iterator<Item = &'static str> {
+    NATIVE_FUNCTIONS.iter().map(|function| function.name)
+}
+
+/// Checks that a native function received between `min` and `max` arguments.
+pub(crate) fn check_arguments_count(
+    function_name: &str,
+    arguments: &[PhpValue],
+    min: usize,
+    max: usize,
+    span: Span,
+) -> Result<(), PhpError> {
+    let (expected, count) = if min == max {
+        ("exactly", min)
+    } else if arguments.len() < min {
+        ("at least", min)
+    } else {
+        ("at most", max)
+    };
+
+    if arguments.len() >= min && arguments.len() <= max {
+        return Ok(());
+    }
+
+    Err(PhpError {
+        level: ErrorLevel::EngineError("ArgumentCountError"),
+        message: format!(
+            "{}() expects {} {} argument{}, {} given",
+            function_name,
+            expected,
+            count,
+            if count == 1 { "" } else { "s" },
+            arguments.len()
+        ),
+        line: span.line,
+    })
+}
+
+/// The error of an argument that does not have the type of its parameter.
+fn argument_type_error(
+    function_name: &str,
+    position: usize,
+    parameter: &str,
+    expected_type: &str,
+    value: &PhpValue,
+    span: Span,
+) -> PhpError {
+    PhpError {
+        level: ErrorLevel::EngineError("TypeError"),
+        message: format!(
+            "{}(): Argument #{} (${}) must be of type {}, {} given",
+            function_name,
+            position + 1,
+            parameter,
+            expected_type,
+            value.type_name().error_name()
+        ),
+        line: span.line,
+    }
+}
+
+/// The error of the ValueError that PHP throws when an argument has the right type but
+/// a value the function does not accept.
+///
+/// The natives make all their ValueErrors here, the evaluator throws them as objects
+/// of the ValueError class.
+fn value_error(message: String, span: Span) -> PhpError {
+    PhpError {
+        level: ErrorLevel::EngineError("ValueError"),
+        message,
+        line: span.line,
+    }
+}
+
+/// The ValueError of the argument at `position`, `requirement` is what its value must
+/// be, like "must be greater than or equal to 0".
+fn argument_value_error(
+    function_name: &str,
+    position: usize,
+    parameter: &str,
+    requirement: &str,
+    span: Span,
+) -> PhpError {
+    value_error(
+        format!(
+            "{}(): Argument #{} (${}) {}",
+            function_name,
+            position + 1,
+            parameter,
+            requirement
+        ),
+        span,
+    )
+}
+
+/// Checks that an int argument, like a count or a length, is not negative.
+fn check_not_negative(
+    function_name: &str,
+    value: i64,
+    position: usize,
+    parameter: &str,
+    span: Span,
+) -> Result<(), PhpError> {
+    if value >= 0 {
+        return Ok(());
+    }
+
+    Err(argument_value_error(
+        function_name,
+        position,
+        parameter,
+        "must be greater than or equal to 0",
+        span,
+    ))
+}
+
+/// Reads an int argument, with the conversions PHP does for int parameters, which
+/// strict_types turns off.
+pub(crate) fn get_int_argument(
+    evaluator: &Evaluator,
+    function_name: &str,
+    arguments: &[PhpValue],
+    position: usize,
+    parameter: &str,
+    span: Span,
+) -> Result<i64, PhpError> {
+    let value = &arguments[position];
+
+    let int = match value {
+        PhpValue::Int(i) => Some(*i),
+        _ if evaluator.is_strict() => None,
+        PhpValue::Bool(b) => Some(*b as i64),
+        PhpValue::Float(f) if f.fract() == 0.0 => Some(*f as i64),
+        PhpValue::String(s) => std::str::from_utf8(s)
+            .ok()
+            .and_then(|s| s.trim().parse().ok()),
+        _ => None,
+    };
+
+    int.ok_or_else(|| argument_type_error(function_name, position, parameter, "int", value, span))
+}
+
+/// Reads an int|float argument, numeric strings are converted to a number without
+/// strict_types.
+fn get_number_argument(
+    evaluator: &Evaluator,
+    function_name: &str,
+    arguments: &[PhpValue],
+    position: usize,
+    parameter: &str,
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let value = &arguments[position];
+
+    let number = match value {
+        PhpValue::Int(_) | PhpValue::Float(_) => Some(value.clone()),
+        _ if evaluator.is_strict() => None,
+        PhpValue::Null => Some(PhpValue::Int(0)),
+        PhpValue::Bool(b) => Some(PhpValue::Int(*b as i64)),
+        PhpValue::String(s) => parse_numeric_string(s),
+        _ => None,
+    };
+
+    number.ok_or_else(|| {
+        argument_type_error(function_name, position, parameter, "float", value, span)
+    })
+}
+
+/// Reads a string argument, scalar values are converted to string without strict_types.
+pub(crate) fn get_string_argument(
+    evaluator: &Evaluator,
+    function_name: &str,
+    arguments: &[PhpValue],
+    position: usize,
+    parameter: &str,
+    span: Span,
+) -> Result<Vec<u8>, PhpError> {
+    match &arguments[position] {
+        PhpValue::String(s) => Ok(s.bytes.clone()),
+        _ if evaluator.is_strict() => Err(argument_type_error(
+            function_name,
+            position,
+            parameter,
+            "string",
+            &arguments[position],
+            span,
+        )),
+        PhpValue::Array(_) | PhpValue::Object(_) | PhpValue::Callable(_) | PhpValue::Resource(_) => {
+            Err(argument_type_error(
+                function_name,
+                position,
+                parameter,
+                "string",
+                &arguments[position],
+                span,
+            ))
+        }
+        value => Ok(scalar_to_string(value, DEFAULT_PRECISION)
+            .unwrap_or_default()
+            .into_bytes()),
+    }
+}
+
+/// Reads a bool argument, scalar values are converted to bool without strict_types.
+fn get_bool_argument(
+    evaluator: &Evaluator,
+    function_name: &str,
+    arguments: &[PhpValue],
+    position: usize,
+    parameter: &str,
+    span: Span,
+) -> Result<bool, PhpError> {
+    match &arguments[position] {
+        PhpValue::Bool(b) => Ok(*b),
+        PhpValue::Array(_) | PhpValue::Object(_) | PhpValue::Callable(_) => Err(
+            argument_type_error(function_name, position, parameter, "bool", &arguments[position], span),
+        ),
+        value if !evaluator.is_strict() => Ok(value.clone().is_true()),
+        value => Err(argument_type_error(
+            function_name,
+            position,
+            parameter,
+            "bool",
+            value,
+            span,
+        )),
+    }
+}
+
+/// Checks that an argument is a callback, `nullable` is for the ?callable parameters.
+fn check_callable_argument(
+    evaluator: &Evaluator,
+    function_name: &str,
+    arguments: &[PhpValue],
+    position: usize,
+    parameter: &str,
+    nullable: bool,
+    span: Span,
+) -> Result<(), PhpError> {
+    let value = &arguments[position];
+
+    if evaluator.is_callable(value) || (nullable && value.is_null()) {
+        return Ok(());
+    }
+
+    let reason = match value {
+        PhpValue::String(name) => format!(
+            "function \"{}\" not found or invalid function name",
+            display_name(name)
+        ),
+        PhpValue::Array(array) if array.len() != 2 => {
+            "array callback must have exactly two members".to_string()
+        }
+        PhpValue::Array(array) => match array.get(&ArrayKey::Int(0)).as_deref() {
+            Some(PhpValue::Object(object)) => format!(
+                "class {} does not have a method \"{}\"",
+                object.borrow().class.name,
+                array
+                    .get(&ArrayKey::Int(1))
+                    .and_then(|method| method.to_string())
+                    .unwrap_or_default()
+            ),
+            _ => "first array member is not a valid class name or object".to_string(),
+        },
+        _ => "no array or string given".to_string(),
+    };
+
+    Err(PhpError {
+        level: ErrorLevel::EngineError("TypeError"),
+        message: format!(
+            "{}(): Argument #{} (${}) must be a valid callback{}, {}",
+            function_name,
+            position + 1,
+            parameter,
+            if nullable { " or null" } else { "" },
+            reason
+        ),
+        line: span.line,
+    })
+}
+
+fn get_object_argument(
+    function_name: &str,
+    arguments: &[PhpValue],
+    position: usize,
+    parameter: &str,
+    span: Span,
+) -> Result<Rc<RefCell<PhpObject>>, PhpError> {
+    match &arguments[position] {
+        PhpValue::Object(object) => Ok(Rc::clone(object)),
+        value => Err(argument_type_error(
+            function_name,
+            position,
+            parameter,
+            "object",
+            value,
+            span,
+        )),
+    }
+}
+
+/// The id of an object argument, the closures are objects with their own id.
+fn get_object_id_argument(
+    function_name: &str,
+    arguments: &[PhpValue],
+    position: usize,
+    parameter: &str,
+    span: Span,
+) -> Result<usize, PhpError> {
+    match &arguments[position] {
+        PhpValue::Callable(PhpCallable {
+            closure: Some(scope),
+            ..
+        }) => Ok(scope.id),
+        _ => get_object_argument(function_name, arguments, position, parameter, span)
+            .map(|object| object.borrow().id),
+    }
+}
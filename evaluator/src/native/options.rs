@@ -0,0 +1,116 @@
+use php_parser_rs::lexer::token::Span;
+
+use crate::conversion::format_float;
+use crate::evaluator::Evaluator;
+use crate::helpers::get_string_from_bytes;
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+/// Returns the name of the setting, which is the first argument of the function.
+fn get_option_argument(
+    function_name: &str,
+    arguments: &[PhpValue],
+    expected_arguments: usize,
+    span: Span,
+) -> Result<String, PhpError> {
+    if arguments.len() != expected_arguments {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "{}() expects exactly {} argument{}, {} given",
+                function_name,
+                expected_arguments,
+                if expected_arguments == 1 { "" } else { "s" },
+                arguments.len()
+            ),
+            line: span.line,
+        });
+    }
+
+    match &arguments[0] {
+        PhpValue::String(option) => Ok(get_string_from_bytes(option)),
+        value => Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "{}(): Argument #1 ($option) must be of type string, {} given",
+                function_name,
+                value.type_name().error_name()
+            ),
+            line: span.line,
+        }),
+    }
+}
+
+/// ini_get(string $option): string|false
+pub fn ini_get(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let option = get_option_argument("ini_get", arguments, 1, span)?;
+
+    Ok(evaluator
+        .ini
+        .get(&option)
+        .map_or(PhpValue::Bool(false), |value| PhpValue::String(value.into())))
+}
+
+/// ini_set(string $option, string|int|float|bool|null $value): string|false
+pub fn ini_set(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let option = get_option_argument("ini_set", arguments, 2, span)?;
+
+    let value = match &arguments[1] {
+        PhpValue::String(value) => get_string_from_bytes(value),
+        PhpValue::Int(i) => i.to_string(),
+        PhpValue::Float(f) => format_float(*f, evaluator.ini.get_int("precision")),
+        PhpValue::Bool(true) => "1".to_string(),
+        PhpValue::Bool(false) | PhpValue::Null => "".to_string(),
+        value => {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!(
+                    "ini_set(): Argument #2 ($value) must be of type string|int|float|bool|null, {} given",
+                    value.type_name().error_name()
+                ),
+                line: span.line,
+            })
+        }
+    };
+
+    Ok(evaluator
+        .ini
+        .set(&option, value)
+        .map_or(PhpValue::Bool(false), |old_value| {
+            PhpValue::String(old_value.into())
+        }))
+}
+
+/// ini_restore(string $option): void
+pub fn ini_restore(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let option = get_option_argument("ini_restore", arguments, 1, span)?;
+
+    evaluator.ini.restore(&option);
+
+    Ok(PhpValue::Null)
+}
+
+/// get_cfg_var(string $option): string|array|false
+pub fn get_cfg_var(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    let option = get_option_argument("get_cfg_var", arguments, 1, span)?;
+
+    Ok(evaluator
+        .ini
+        .get_config(&option)
+        .map_or(PhpValue::Bool(false), |value| PhpValue::String(value.into())))
+}
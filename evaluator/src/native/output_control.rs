@@ -0,0 +1,70 @@
+use php_parser_rs::lexer::token::Span;
+
+use crate::evaluator::Evaluator;
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::{check_arguments_count, get_bool_argument};
+
+/// flush(): void
+pub fn flush(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("flush", arguments, 0, 0, span)?;
+
+    evaluator.flush_output().map_err(|error| PhpError {
+        level: ErrorLevel::Fatal,
+        message: format!("Unable to write the output: {}", error),
+        line: span.line,
+    })?;
+
+    Ok(PhpValue::Null)
+}
+
+/// headers_sent(string &$filename = null, int &$line = null): bool
+///
+/// Whether the program has output something, the file and the line of the first output
+/// are written to the arguments.
+pub fn headers_sent(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("headers_sent", arguments, 0, 2, span)?;
+
+    let (file, line) = match &evaluator.output_start {
+        Some((file, line)) => (PhpValue::String(file.clone().into()), *line as i64),
+        None => (PhpValue::String("".into()), 0),
+    };
+
+    if let Some(filename) = arguments.get_mut(0) {
+        *filename = file;
+    }
+
+    if let Some(argument) = arguments.get_mut(1) {
+        *argument = PhpValue::Int(line);
+    }
+
+    Ok(PhpValue::Bool(evaluator.output_start.is_some()))
+}
+
+/// ob_implicit_flush(bool $enable = true): void
+pub fn ob_implicit_flush(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("ob_implicit_flush", arguments, 0, 1, span)?;
+
+    let enable = match arguments.first() {
+        Some(_) => get_bool_argument(evaluator, "ob_implicit_flush", arguments, 0, "enable", span)?,
+        None => true,
+    };
+
+    evaluator
+        .ini
+        .set("implicit_flush", if enable { "1" } else { "0" }.to_string());
+
+    Ok(PhpValue::Null)
+}
@@ -0,0 +1,930 @@
+use php_parser_rs::lexer::token::Span;
+
+use crate::conversion::{
+    format_general, format_scientific, leading_numeric_value, round_to_decimals, scalar_to_string,
+    DEFAULT_PRECISION,
+};
+use crate::evaluator::Evaluator;
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+use super::{
+    argument_type_error, argument_value_error, check_arguments_count, check_not_negative,
+    get_int_argument, get_string_argument, value_error,
+};
+
+/// The value of the numeric and monetary fields that the C locale does not set.
+const CHAR_MAX: i64 = 127;
+
+/// The items of nl_langinfo() with the names of their constants and their values in the
+/// C locale, the numbers are the glibc ones.
+pub(crate) const LANGINFO_ITEMS: &[(&str, i64, &str)] = &[
+    ("CODESET", 14, "ANSI_X3.4-1968"),
+    ("RADIXCHAR", 65536, "."),
+    ("THOUSEP", 65537, ""),
+    ("ABDAY_1", 131072, "Sun"),
+    ("ABDAY_2", 131073, "Mon"),
+    ("ABDAY_3", 131074, "Tue"),
+    ("ABDAY_4", 131075, "Wed"),
+    ("ABDAY_5", 131076, "Thu"),
+    ("ABDAY_6", 131077, "Fri"),
+    ("ABDAY_7", 131078, "Sat"),
+    ("DAY_1", 131079, "Sunday"),
+    ("DAY_2", 131080, "Monday"),
+    ("DAY_3", 131081, "Tuesday"),
+    ("DAY_4", 131082, "Wednesday"),
+    ("DAY_5", 131083, "Thursday"),
+    ("DAY_6", 131084, "Friday"),
+    ("DAY_7", 131085, "Saturday"),
+    ("ABMON_1", 131086, "Jan"),
+    ("ABMON_2", 131087, "Feb"),
+    ("ABMON_3", 131088, "Mar"),
+    ("ABMON_4", 131089, "Apr"),
+    ("ABMON_5", 131090, "May"),
+    ("ABMON_6", 131091, "Jun"),
+    ("ABMON_7", 131092, "Jul"),
+    ("ABMON_8", 131093, "Aug"),
+    ("ABMON_9", 131094, "Sep"),
+    ("ABMON_10", 131095, "Oct"),
+    ("ABMON_11", 131096, "Nov"),
+    ("ABMON_12", 131097, "Dec"),
+    ("MON_1", 131098, "January"),
+    ("MON_2", 131099, "February"),
+    ("MON_3", 131100, "March"),
+    ("MON_4", 131101, "April"),
+    ("MON_5", 131102, "May"),
+    ("MON_6", 131103, "June"),
+    ("MON_7", 131104, "July"),
+    ("MON_8", 131105, "August"),
+    ("MON_9", 131106, "September"),
+    ("MON_10", 131107, "October"),
+    ("MON_11", 131108, "November"),
+    ("MON_12", 131109, "December"),
+    ("AM_STR", 131110, "AM"),
+    ("PM_STR", 131111, "PM"),
+    ("D_T_FMT", 131112, "%a %b %e %H:%M:%S %Y"),
+    ("D_FMT", 131113, "%m/%d/%y"),
+    ("T_FMT", 131114, "%H:%M:%S"),
+    ("T_FMT_AMPM", 131115, "%I:%M:%S %p"),
+    ("CRNCYSTR", 262159, "-"),
+    ("YESEXPR", 327680, "^[yY]"),
+    ("NOEXPR", 327681, "^[nN]"),
+];
+
+/// localeconv(): array
+///
+/// The evaluator always uses the C locale, whatever the environment of the process is.
+pub fn localeconv(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("localeconv", arguments, 0, 0, span)?;
+
+    let mut array = PhpArray::new();
+
+    let mut insert = |key: &str, value: PhpValue| {
+        array.insert(ArrayKey::String(key.into()), value);
+    };
+
+    insert("decimal_point", PhpValue::String(".".into()));
+
+    for key in [
+        "thousands_sep",
+        "int_curr_symbol",
+        "currency_symbol",
+        "mon_decimal_point",
+        "mon_thousands_sep",
+        "positive_sign",
+        "negative_sign",
+    ] {
+        insert(key, PhpValue::String("".into()));
+    }
+
+    for key in [
+        "int_frac_digits",
+        "frac_digits",
+        "p_cs_precedes",
+        "p_sep_by_space",
+        "n_cs_precedes",
+        "n_sep_by_space",
+        "p_sign_posn",
+        "n_sign_posn",
+    ] {
+        insert(key, PhpValue::Int(CHAR_MAX));
+    }
+
+    insert("grouping", PhpValue::Array(PhpArray::new()));
+    insert("mon_grouping", PhpValue::Array(PhpArray::new()));
+
+    Ok(PhpValue::Array(array))
+}
+
+/// nl_langinfo(int $item): string|false
+pub fn nl_langinfo(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("nl_langinfo", arguments, 1, 1, span)?;
+
+    let item = get_int_argument(evaluator, "nl_langinfo", arguments, 0, "item", span)?;
+
+    let Some((_, _, value)) = LANGINFO_ITEMS.iter().find(|(_, number, _)| *number == item) else {
+        evaluator.raise_error(PhpError {
+            level: ErrorLevel::Warning,
+            message: format!("nl_langinfo(): Item '{}' is not valid", item),
+            line: span.line,
+        })?;
+
+        return Ok(PhpValue::Bool(false));
+    };
+
+    Ok(PhpValue::String((*value).into()))
+}
+
+/// Which bytes are in a character list like "a..z0-", that some string functions use
+/// to add characters to the ones they look for. "x..y" is the range from x to y.
+fn get_character_mask(characters: &[u8]) -> [bool; 256] {
+    let mut mask = [false; 256];
+
+    let mut index = 0;
+
+    while index < characters.len() {
+        let character = characters[index];
+
+        let is_range = characters.get(index + 1..index + 3) == Some(b"..")
+            && characters
+                .get(index + 3)
+                .is_some_and(|last| *last >= character);
+
+        if is_range {
+            for byte in character..=characters[index + 3] {
+                mask[byte as usize] = true;
+            }
+
+            index += 4;
+        } else {
+            mask[character as usize] = true;
+
+            index += 1;
+        }
+    }
+
+    mask
+}
+
+/// str_word_count(string $string, int $format = 0, ?string $characters = null): array|int
+///
+/// A word is made of ASCII letters, "'" and "-", and of the given characters.
+/// The string cannot start with "'" or "-", nor end with "-", unless they are given characters.
+pub fn str_word_count(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("str_word_count", arguments, 1, 3, span)?;
+
+    let string = get_string_argument(evaluator, "str_word_count", arguments, 0, "string", span)?;
+
+    let format = match arguments.get(1) {
+        Some(_) => get_int_argument(evaluator, "str_word_count", arguments, 1, "format", span)?,
+        None => 0,
+    };
+
+    if !(0..=2).contains(&format) {
+        return Err(argument_value_error(
+            "str_word_count",
+            1,
+            "format",
+            "must be a valid format value",
+            span,
+        ));
+    }
+
+    let mask = match arguments.get(2) {
+        Some(PhpValue::Null) | None => [false; 256],
+        Some(_) => get_character_mask(&get_string_argument(
+            evaluator,
+            "str_word_count",
+            arguments,
+            2,
+            "characters",
+            span,
+        )?),
+    };
+
+    let mut start = 0;
+    let mut end = string.len();
+
+    if string.first().is_some_and(|first| {
+        (*first == b'\'' || *first == b'-') && !mask[*first as usize]
+    }) {
+        start += 1;
+    }
+
+    if end > start && string[end - 1] == b'-' && !mask[b'-' as usize] {
+        end -= 1;
+    }
+
+    let is_word_byte =
+        |byte: u8| byte.is_ascii_alphabetic() || byte == b'\'' || byte == b'-' || mask[byte as usize];
+
+    let mut words = PhpArray::new();
+    let mut count = 0;
+
+    let mut position = start;
+
+    while position < end {
+        let word_start = position;
+
+        while position < end && is_word_byte(string[position]) {
+            position += 1;
+        }
+
+        if position > word_start {
+            let word = PhpValue::String(string[word_start..position].to_vec().into());
+
+            match format {
+                1 => words.insert(ArrayKey::Int(words.len() as i64), word),
+                2 => words.insert(ArrayKey::Int(word_start as i64), word),
+                _ => count += 1,
+            }
+        }
+
+        position += 1;
+    }
+
+    if format == 0 {
+        Ok(PhpValue::Int(count))
+    } else {
+        Ok(PhpValue::Array(words))
+    }
+}
+
+/// The most decimals that the float specifiers of sprintf() accept.
+const MAX_FLOAT_PRECISION: usize = 53;
+
+/// A conversion specification of sprintf(), like "%'*-10.2f".
+struct FormatSpecification {
+    /// The position of the argument, when it is given like "%2$s".
+    argument: Option<usize>,
+    left_aligned: bool,
+    always_sign: bool,
+    padding: u8,
+    width: usize,
+    precision: Option<usize>,
+    specifier: u8,
+}
+
+/// Reads the specification that follows a "%", `position` is the byte after it.
+fn parse_format_specification(
+    format: &[u8],
+    position: &mut usize,
+    span: Span,
+) -> Result<FormatSpecification, PhpError> {
+    let read_number = |position: &mut usize| -> Result<Option<usize>, PhpError> {
+        let start = *position;
+
+        while format.get(*position).is_some_and(u8::is_ascii_digit) {
+            *position += 1;
+        }
+
+        if *position == start {
+            return Ok(None);
+        }
+
+        std::str::from_utf8(&format[start..*position])
+            .unwrap()
+            .parse::<i32>()
+            .map(|number| Some(number as usize))
+            .map_err(|_| {
+                value_error(
+                    "Width must be greater than zero and less than 2147483647".to_string(),
+                    span,
+                )
+            })
+    };
+
+    let mut specification = FormatSpecification {
+        argument: None,
+        left_aligned: false,
+        always_sign: false,
+        padding: b' ',
+        width: 0,
+        precision: None,
+        specifier: 0,
+    };
+
+    // "%2$s" uses the second argument
+    let start = *position;
+
+    if let Some(argument) = read_number(position).ok().flatten() {
+        if format.get(*position) == Some(&b'$') {
+            if argument == 0 {
+                return Err(value_error(
+                    "Argument number specifier must be greater than zero and less than 2147483647"
+                        .to_string(),
+                    span,
+                ));
+            }
+
+            specification.argument = Some(argument - 1);
+
+            *position += 1;
+        } else {
+            *position = start;
+        }
+    } else {
+        *position = start;
+    }
+
+    loop {
+        match format.get(*position) {
+            Some(b'-') => specification.left_aligned = true,
+            Some(b'+') => specification.always_sign = true,
+            Some(b' ') => specification.padding = b' ',
+            Some(b'0') => specification.padding = b'0',
+            Some(b'\'') if *position + 1 < format.len() => {
+                *position += 1;
+
+                specification.padding = format[*position];
+            }
+            _ => break,
+        }
+
+        *position += 1;
+    }
+
+    specification.width = read_number(position)?.unwrap_or(0);
+
+    if format.get(*position) == Some(&b'.') {
+        *position += 1;
+
+        specification.precision = Some(read_number(position)?.unwrap_or(0));
+    }
+
+    // the length modifier of C is accepted and ignored
+    if format.get(*position) == Some(&b'l') {
+        *position += 1;
+    }
+
+    let Some(specifier) = format.get(*position) else {
+        return Err(value_error(
+            "Missing format specifier at end of string".to_string(),
+            span,
+        ));
+    };
+
+    specification.specifier = *specifier;
+
+    *position += 1;
+
+    Ok(specification)
+}
+
+/// Pads a formatted value to the width of the specification. When it is padded
+/// with zeros on the left, the sign stays before them: "-0003".
+fn pad_formatted_value(result: &mut Vec<u8>, value: &[u8], specification: &FormatSpecification) {
+    let padding_length = specification.width.saturating_sub(value.len());
+    let padding = std::iter::repeat_n(specification.padding, padding_length);
+
+    if specification.left_aligned {
+        result.extend_from_slice(value);
+        result.extend(padding);
+
+        return;
+    }
+
+    let has_sign = matches!(
+        specification.specifier,
+        b'd' | b'e' | b'E' | b'f' | b'F' | b'g' | b'G'
+    ) && matches!(value.first(), Some(b'-' | b'+'));
+
+    if has_sign && specification.padding == b'0' {
+        result.push(value[0]);
+        result.extend(padding);
+        result.extend_from_slice(&value[1..]);
+    } else {
+        result.extend(padding);
+        result.extend_from_slice(value);
+    }
+}
+
+/// The int that a value of sprintf() becomes, "12abc" is 12.
+fn format_argument_to_int(value: &PhpValue) -> i64 {
+    match value {
+        PhpValue::Int(i) => *i,
+        PhpValue::Float(f) => *f as i64,
+        PhpValue::Bool(b) => *b as i64,
+        PhpValue::Null => 0,
+        PhpValue::String(s) => match leading_numeric_value(s) {
+            PhpValue::Float(f) => f as i64,
+            PhpValue::Int(i) => i,
+            _ => 0,
+        },
+        PhpValue::Array(array) => !array.is_empty() as i64,
+        _ => 1,
+    }
+}
+
+/// The float that a value of sprintf() becomes, "1.5abc" is 1.5.
+fn format_argument_to_float(value: &PhpValue) -> f64 {
+    match value {
+        PhpValue::Float(f) => *f,
+        PhpValue::String(s) => leading_numeric_value(s).to_float().unwrap_or(0.0),
+        value => format_argument_to_int(value) as f64,
+    }
+}
+
+/// Formats a float for the "e", "f" and "g" specifiers, with its sign.
+fn format_float_argument(value: f64, specification: &FormatSpecification) -> Vec<u8> {
+    let sign = if value.is_sign_negative() && !value.is_nan() {
+        "-"
+    } else if specification.always_sign {
+        "+"
+    } else {
+        ""
+    };
+
+    if value.is_nan() {
+        return b"NaN".to_vec();
+    }
+
+    if value.is_infinite() {
+        return format!("{}Inf", sign).into_bytes();
+    }
+
+    let precision = specification
+        .precision
+        .unwrap_or(6)
+        .min(MAX_FLOAT_PRECISION);
+
+    let digits = match specification.specifier {
+        b'e' | b'E' => format_scientific(value, precision, specification.specifier as char),
+        b'g' | b'G' => {
+            let exponent_character = if specification.specifier == b'g' {
+                'e'
+            } else {
+                'E'
+            };
+
+            format_general(value, precision, exponent_character)
+        }
+        _ => {
+            let rounded = round_to_decimals(&PhpValue::Float(value), precision as i64);
+
+            if rounded.fraction.is_empty() {
+                rounded.integer
+            } else {
+                format!("{}.{}", rounded.integer, rounded.fraction)
+            }
+        }
+    };
+
+    format!("{}{}", sign, digits).into_bytes()
+}
+
+/// sprintf(string $format, mixed ...$values): string
+///
+/// Supports the specifiers b, c, d, e, E, f, F, g, G, o, s, u, x and X with their flags,
+/// width, precision and argument numbers, but not "*" widths. Ints are 64 bits, so "%u"
+/// and "%x" of a negative number use 64 bits too. Floats are rounded on their decimal
+/// digits and not on their binary value, so "%.2f" of 2.675 is "2.68" like in PHP.
+/// "%s" converts floats with the "precision" setting, like echo does.
+pub fn sprintf(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("sprintf", arguments, 1, usize::MAX, span)?;
+
+    let format = get_string_argument(evaluator, "sprintf", arguments, 0, "format", span)?;
+
+    let values = &arguments[1..];
+
+    let mut result = vec![];
+    let mut position = 0;
+    let mut next_argument = 0;
+    let mut required_arguments = 0;
+
+    while position < format.len() {
+        if format[position] != b'%' {
+            result.push(format[position]);
+            position += 1;
+
+            continue;
+        }
+
+        position += 1;
+
+        if format.get(position) == Some(&b'%') {
+            result.push(b'%');
+            position += 1;
+
+            continue;
+        }
+
+        let specification = parse_format_specification(&format, &mut position, span)?;
+
+        if !b"bcdeEfFgGosuxX".contains(&specification.specifier) {
+            return Err(value_error(
+                format!(
+                    "Unknown format specifier \"{}\"",
+                    specification.specifier as char
+                ),
+                span,
+            ));
+        }
+
+        let argument = specification.argument.unwrap_or_else(|| {
+            next_argument += 1;
+
+            next_argument - 1
+        });
+
+        let Some(value) = values.get(argument) else {
+            required_arguments = required_arguments.max(argument + 1);
+
+            continue;
+        };
+
+        if let Some(precision) = specification.precision {
+            if precision > MAX_FLOAT_PRECISION && b"eEfFgG".contains(&specification.specifier) {
+                evaluator.raise_error(PhpError {
+                    level: ErrorLevel::Notice,
+                    message: format!(
+                        "Requested precision of {} digits was truncated to PHP maximum of {} digits",
+                        precision, MAX_FLOAT_PRECISION
+                    ),
+                    line: span.line,
+                })?;
+            }
+        }
+
+        let formatted = match specification.specifier {
+            b's' => {
+                let string = match value {
+                    PhpValue::String(string) => string.bytes.clone(),
+                    value => evaluator
+                        .value_to_output_string(value.clone(), span)?
+                        .into_bytes(),
+                };
+
+                match specification.precision {
+                    Some(precision) if precision < string.len() => string[..precision].to_vec(),
+                    _ => string,
+                }
+            }
+            b'd' => {
+                let int = format_argument_to_int(value);
+
+                if specification.always_sign && int >= 0 {
+                    format!("+{}", int).into_bytes()
+                } else {
+                    int.to_string().into_bytes()
+                }
+            }
+            b'u' => (format_argument_to_int(value) as u64)
+                .to_string()
+                .into_bytes(),
+            b'c' => {
+                // a character is never padded
+                result.push(format_argument_to_int(value) as u8);
+
+                continue;
+            }
+            b'b' => format!("{:b}", format_argument_to_int(value) as u64).into_bytes(),
+            b'o' => format!("{:o}", format_argument_to_int(value) as u64).into_bytes(),
+            b'x' => format!("{:x}", format_argument_to_int(value) as u64).into_bytes(),
+            b'X' => format!("{:X}", format_argument_to_int(value) as u64).into_bytes(),
+            _ => format_float_argument(format_argument_to_float(value), &specification),
+        };
+
+        pad_formatted_value(&mut result, &formatted, &specification);
+    }
+
+    if required_arguments > values.len() {
+        return Err(PhpError {
+            level: ErrorLevel::EngineError("ArgumentCountError"),
+            message: format!(
+                "{} arguments are required, {} given",
+                required_arguments + 1,
+                arguments.len()
+            ),
+            line: span.line,
+        });
+    }
+
+    Ok(PhpValue::String(result.into()))
+}
+
+/// The characters that trim() removes when none are given.
+const TRIMMED_CHARACTERS: &[u8] = b" \n\r\t\x0B\0";
+
+/// strlen(string $string): int
+pub fn strlen(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("strlen", arguments, 1, 1, span)?;
+
+    let string = get_string_argument(evaluator, "strlen", arguments, 0, "string", span)?;
+
+    Ok(PhpValue::Int(string.len() as i64))
+}
+
+/// strtoupper(string $string): string
+///
+/// Only the ASCII letters are changed, whatever the locale is, like in PHP 8.2.
+pub fn strtoupper(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("strtoupper", arguments, 1, 1, span)?;
+
+    let string = get_string_argument(evaluator, "strtoupper", arguments, 0, "string", span)?;
+
+    Ok(PhpValue::String(string.to_ascii_uppercase().into()))
+}
+
+/// strtolower(string $string): string
+///
+/// Only the ASCII letters are changed, whatever the locale is, like in PHP 8.2.
+pub fn strtolower(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("strtolower", arguments, 1, 1, span)?;
+
+    let string = get_string_argument(evaluator, "strtolower", arguments, 0, "string", span)?;
+
+    Ok(PhpValue::String(string.to_ascii_lowercase().into()))
+}
+
+/// str_repeat(string $string, int $times): string
+pub fn str_repeat(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("str_repeat", arguments, 2, 2, span)?;
+
+    let string = get_string_argument(evaluator, "str_repeat", arguments, 0, "string", span)?;
+    let times = get_int_argument(evaluator, "str_repeat", arguments, 1, "times", span)?;
+
+    check_not_negative("str_repeat", times, 1, "times", span)?;
+
+    Ok(PhpValue::String(string.repeat(times as usize).into()))
+}
+
+/// substr(string $string, int $offset, ?int $length = null): string
+///
+/// A negative offset counts from the end of the string, and a negative length leaves
+/// out that many bytes at the end. The parts outside of the string are ignored.
+pub fn substr(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("substr", arguments, 2, 3, span)?;
+
+    let string = get_string_argument(evaluator, "substr", arguments, 0, "string", span)?;
+    let offset = get_int_argument(evaluator, "substr", arguments, 1, "offset", span)?;
+
+    let length = match arguments.get(2) {
+        Some(PhpValue::Null) | None => None,
+        Some(_) => Some(get_int_argument(
+            evaluator, "substr", arguments, 2, "length", span,
+        )?),
+    };
+
+    let string_length = string.len() as i64;
+
+    let start = if offset < 0 {
+        (string_length + offset).max(0)
+    } else {
+        offset.min(string_length)
+    };
+
+    let end = match length {
+        None => string_length,
+        Some(length) if length < 0 => (string_length + length).max(start),
+        Some(length) => (start + length).min(string_length),
+    };
+
+    Ok(PhpValue::String(
+        string[start as usize..end as usize].to_vec().into(),
+    ))
+}
+
+/// trim(string $string, string $characters = " \n\r\t\v\x00"): string
+///
+/// The characters can have ranges, like "a..z".
+pub fn trim(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("trim", arguments, 1, 2, span)?;
+
+    let string = get_string_argument(evaluator, "trim", arguments, 0, "string", span)?;
+
+    let mask = match arguments.get(1) {
+        Some(_) => get_character_mask(&get_string_argument(
+            evaluator,
+            "trim",
+            arguments,
+            1,
+            "characters",
+            span,
+        )?),
+        None => get_character_mask(TRIMMED_CHARACTERS),
+    };
+
+    let is_kept = |byte: &u8| !mask[*byte as usize];
+
+    let start = string.iter().position(is_kept).unwrap_or(string.len());
+    let end = string
+        .iter()
+        .rposition(is_kept)
+        .map_or(start, |last| last + 1);
+
+    Ok(PhpValue::String(string[start..end].to_vec().into()))
+}
+
+/// Replaces every occurrence of each search string in turn, so a replacement can be
+/// replaced again by the next search strings. Empty search strings are skipped.
+fn replace_all(subject: &[u8], replacements: &[(Vec<u8>, Vec<u8>)], count: &mut i64) -> Vec<u8> {
+    let mut subject = subject.to_vec();
+
+    for (search, replace) in replacements {
+        if search.is_empty() {
+            continue;
+        }
+
+        let mut result = Vec::with_capacity(subject.len());
+        let mut position = 0;
+
+        while position < subject.len() {
+            if subject[position..].starts_with(search) {
+                result.extend_from_slice(replace);
+                position += search.len();
+                *count += 1;
+            } else {
+                result.push(subject[position]);
+                position += 1;
+            }
+        }
+
+        subject = result;
+    }
+
+    subject
+}
+
+/// The string of an item of the arrays given to str_replace().
+fn item_to_string(value: &PhpValue) -> Vec<u8> {
+    scalar_to_string(value, DEFAULT_PRECISION)
+        .unwrap_or_default()
+        .into_bytes()
+}
+
+/// str_replace(array|string $search, array|string $replace, string|array $subject, int &$count = null): string|array
+///
+/// With an array of search strings, each one is replaced by the string at the same
+/// position of `replace` when it is an array, or by "" when it has no more strings.
+/// With an array of subjects, every string of the array is replaced and its keys are kept.
+pub fn str_replace(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("str_replace", arguments, 3, 4, span)?;
+
+    for (position, parameter) in [(0, "search"), (1, "replace"), (2, "subject")] {
+        if matches!(
+            arguments[position],
+            PhpValue::Object(_) | PhpValue::Callable(_) | PhpValue::Resource(_)
+        ) {
+            return Err(argument_type_error(
+                "str_replace",
+                position,
+                parameter,
+                "array|string",
+                &arguments[position],
+                span,
+            ));
+        }
+    }
+
+    let replacements = match (&arguments[0], &arguments[1]) {
+        (PhpValue::Array(search), PhpValue::Array(replace)) => {
+            let mut replace = replace.iter().map(|(_, value)| item_to_string(&value));
+
+            search
+                .iter()
+                .map(|(_, value)| (item_to_string(&value), replace.next().unwrap_or_default()))
+                .collect()
+        }
+        (PhpValue::Array(search), replace) => {
+            let replace = item_to_string(replace);
+
+            search
+                .iter()
+                .map(|(_, value)| (item_to_string(&value), replace.clone()))
+                .collect()
+        }
+        (_, PhpValue::Array(_)) => {
+            return Err(PhpError {
+                level: ErrorLevel::EngineError("TypeError"),
+                message: "str_replace(): Argument #2 ($replace) must be of type string when argument #1 ($search) is a string".to_string(),
+                line: span.line,
+            })
+        }
+        (search, replace) => vec![(item_to_string(search), item_to_string(replace))],
+    };
+
+    let mut count = 0;
+
+    let result = match &arguments[2] {
+        PhpValue::Array(subjects) => {
+            let mut result = PhpArray::new();
+
+            for (key, value) in subjects.iter() {
+                let value = match &*value {
+                    PhpValue::Array(_) => (*value).clone(),
+                    value => PhpValue::String(
+                        replace_all(&item_to_string(value), &replacements, &mut count).into(),
+                    ),
+                };
+
+                result.insert(key.clone(), value);
+            }
+
+            PhpValue::Array(result)
+        }
+        subject => PhpValue::String(
+            replace_all(&item_to_string(subject), &replacements, &mut count).into(),
+        ),
+    };
+
+    if let Some(argument) = arguments.get_mut(3) {
+        *argument = PhpValue::Int(count);
+    }
+
+    Ok(result)
+}
+
+/// strpos(string $haystack, string $needle, int $offset = 0): int|false
+///
+/// A negative offset counts from the end of the haystack.
+pub fn strpos(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("strpos", arguments, 2, 3, span)?;
+
+    let haystack = get_string_argument(evaluator, "strpos", arguments, 0, "haystack", span)?;
+    let needle = get_string_argument(evaluator, "strpos", arguments, 1, "needle", span)?;
+
+    let offset = match arguments.get(2) {
+        Some(_) => get_int_argument(evaluator, "strpos", arguments, 2, "offset", span)?,
+        None => 0,
+    };
+
+    let start = if offset < 0 {
+        haystack.len() as i64 + offset
+    } else {
+        offset
+    };
+
+    if start < 0 || start > haystack.len() as i64 {
+        return Err(argument_value_error(
+            "strpos",
+            2,
+            "offset",
+            "must be contained in argument #1 ($haystack)",
+            span,
+        ));
+    }
+
+    let start = start as usize;
+
+    let position = if needle.is_empty() {
+        Some(0)
+    } else {
+        haystack[start..]
+            .windows(needle.len())
+            .position(|window| window == needle.as_slice())
+    };
+
+    Ok(match position {
+        Some(position) => PhpValue::Int((start + position) as i64),
+        None => PhpValue::Bool(false),
+    })
+}
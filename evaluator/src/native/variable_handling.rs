@@ -0,0 +1,623 @@
+use std::borrow::Cow;
+
+use php_parser_rs::lexer::byte_string::ByteString;
+use php_parser_rs::lexer::token::Span;
+use php_parser_rs::parser::ast::modifiers::Visibility;
+
+use crate::conversion::{format_float, parse_int_with_base, scalar_to_string};
+use crate::evaluator::Evaluator;
+use crate::helpers::get_string_from_bytes;
+use crate::php_array::{ArrayKey, PhpArray};
+use crate::php_value::{ErrorLevel, PhpError, PhpObject, PhpValue};
+
+use super::{
+    argument_value_error, check_arguments_count, get_bool_argument, get_int_argument,
+    get_string_argument,
+};
+
+/// get_defined_vars(): array
+///
+/// The variables are in the order they were defined.
+pub fn get_defined_vars(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    if !arguments.is_empty() {
+        return Err(PhpError {
+            level: ErrorLevel::Fatal,
+            message: format!(
+                "get_defined_vars() expects exactly 0 arguments, {} given",
+                arguments.len()
+            ),
+            line: span.line,
+        });
+    }
+
+    let mut array = PhpArray::new();
+
+    for (name, value) in evaluator.env.get_vars() {
+        if name.as_slice() != b"this" {
+            array.insert(ArrayKey::String(name.clone().into()), value);
+        }
+    }
+
+    Ok(PhpValue::Array(array))
+}
+
+/// var_dump(mixed $value, mixed ...$values): void
+///
+/// Writes the type and the value of each argument. The items of arrays and the
+/// properties of objects are written below them, indented by two spaces.
+pub fn var_dump(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("var_dump", arguments, 1, usize::MAX, span)?;
+
+    let mut dump = String::new();
+
+    for value in arguments.iter() {
+        dump_value(&mut dump, value, 0, &mut vec![]);
+    }
+
+    evaluator.write_output(&dump, span.line)?;
+
+    Ok(PhpValue::Null)
+}
+
+/// Writes a value like var_dump() does, `indent` is the indentation of its lines.
+///
+/// `objects` are the ids of the objects being written, an object that contains
+/// itself is written as `*RECURSION*` the second time.
+fn dump_value(dump: &mut String, value: &PhpValue, indent: usize, objects: &mut Vec<usize>) {
+    let padding = " ".repeat(indent);
+    let type_name = value.type_name().var_dump_name();
+
+    match value {
+        PhpValue::Null => *dump += &format!("{}{}\n", padding, type_name),
+        PhpValue::Bool(boolean) => *dump += &format!("{}{}({})\n", padding, type_name, boolean),
+        PhpValue::Int(int) => *dump += &format!("{}{}({})\n", padding, type_name, int),
+        PhpValue::Float(float) => {
+            *dump += &format!("{}{}({})\n", padding, type_name, format_float(*float, -1))
+        }
+        PhpValue::String(string) => {
+            *dump += &format!(
+                "{}{}({}) \"{}\"\n",
+                padding,
+                type_name,
+                string.len(),
+                get_string_from_bytes(string)
+            )
+        }
+        PhpValue::Array(array) => {
+            *dump += &format!("{}{}({}) {{\n", padding, type_name, array.len());
+
+            for (key, item) in array.iter() {
+                match key {
+                    ArrayKey::Int(int) => *dump += &format!("{}  [{}]=>\n", padding, int),
+                    ArrayKey::String(string) => {
+                        *dump +=
+                            &format!("{}  [\"{}\"]=>\n", padding, get_string_from_bytes(string))
+                    }
+                }
+
+                dump_value(dump, &item, indent + 2, objects);
+            }
+
+            *dump += &format!("{}}}\n", padding);
+        }
+        PhpValue::Object(object) => {
+            let object = object.borrow();
+
+            if object.class.enum_cases.is_some() {
+                let case = object
+                    .get_property(b"name")
+                    .and_then(|name| name.to_string())
+                    .unwrap_or_default();
+
+                *dump += &format!("{}enum({}::{})\n", padding, object.class.name, case);
+
+                return;
+            }
+
+            if objects.contains(&object.id) {
+                *dump += &format!("{}*RECURSION*\n", padding);
+
+                return;
+            }
+
+            *dump += &format!(
+                "{}{}({})#{} ({}) {{\n",
+                padding,
+                type_name,
+                object.class.name,
+                object.id,
+                object.properties.len()
+            );
+
+            objects.push(object.id);
+
+            for (name, property) in &object.properties {
+                let annotation = match property_visibility(&object, name) {
+                    (Visibility::Public, _) => String::new(),
+                    (Visibility::Protected, _) => ":protected".to_string(),
+                    (Visibility::Private, class) => format!(":\"{}\":private", class),
+                };
+
+                *dump += &format!("{}  [\"{}\"{}]=>\n", padding, name, annotation);
+
+                dump_value(dump, property, indent + 2, objects);
+            }
+
+            objects.pop();
+
+            *dump += &format!("{}}}\n", padding);
+        }
+        // the properties of the closures are not written yet
+        PhpValue::Callable(function) => {
+            let id = function.closure.as_ref().map_or(0, |scope| scope.id);
+
+            *dump += &format!(
+                "{}{}(Closure)#{} (0) {{\n{}}}\n",
+                padding, type_name, id, padding
+            )
+        }
+        PhpValue::Resource(resource) => match *resource {},
+    }
+}
+
+/// print_r(mixed $value, bool $return = false): string|true
+///
+/// Arrays and objects are written with their items between parentheses, each level
+/// indented by eight spaces. The other values are written like echo writes them.
+pub fn print_r(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("print_r", arguments, 1, 2, span)?;
+
+    let return_output = match arguments.get(1) {
+        Some(_) => get_bool_argument(evaluator, "print_r", arguments, 1, "return", span)?,
+        None => false,
+    };
+
+    let mut text = String::new();
+
+    let precision = evaluator.ini.get_int("precision");
+
+    print_value(&mut text, &arguments[0], 0, precision, &mut vec![]);
+
+    if return_output {
+        return Ok(PhpValue::String(text.into()));
+    }
+
+    evaluator.write_output(&text, span.line)?;
+
+    Ok(PhpValue::Bool(true))
+}
+
+/// Writes a value like print_r() does, `indent` is the indentation of the parentheses
+/// of its items. `objects` are the ids of the objects being written, like in var_dump().
+fn print_value(
+    text: &mut String,
+    value: &PhpValue,
+    indent: usize,
+    precision: i64,
+    objects: &mut Vec<usize>,
+) {
+    match value {
+        PhpValue::Array(array) => {
+            *text += "Array\n";
+
+            let items = array.iter().map(|(key, item)| {
+                let key = match key {
+                    ArrayKey::Int(int) => int.to_string(),
+                    ArrayKey::String(string) => get_string_from_bytes(string),
+                };
+
+                (key, item)
+            });
+
+            print_items(text, items, indent, precision, objects);
+        }
+        PhpValue::Object(object) => {
+            let object = object.borrow();
+
+            *text += &object.class.name.to_string();
+
+            match &object.class.enum_cases {
+                Some(enum_cases) => match enum_cases.backing_type {
+                    Some(backing_type) => {
+                        *text += &format!(" Enum:{}\n", backing_type.error_name())
+                    }
+                    None => *text += " Enum\n",
+                },
+                None => *text += " Object\n",
+            }
+
+            if objects.contains(&object.id) {
+                *text += " *RECURSION*";
+
+                return;
+            }
+
+            objects.push(object.id);
+
+            let items = object.properties.iter().map(|(name, property)| {
+                let key = match property_visibility(&object, name) {
+                    (Visibility::Public, _) => name.to_string(),
+                    (Visibility::Protected, _) => format!("{}:protected", name),
+                    (Visibility::Private, class) => format!("{}:{}:private", name, class),
+                };
+
+                (key, Cow::Borrowed(property))
+            });
+
+            print_items(text, items, indent, precision, objects);
+
+            objects.pop();
+        }
+        // the properties of the closures are not written yet
+        PhpValue::Callable(_) => {
+            *text += "Closure Object\n";
+
+            print_items(text, std::iter::empty(), indent, precision, objects);
+        }
+        value => *text += &scalar_to_string(value, precision).unwrap_or_default(),
+    }
+}
+
+/// Writes the items of an array or the properties of an object for print_r().
+fn print_items<'v>(
+    text: &mut String,
+    items: impl Iterator<Item = (String, Cow<'v, PhpValue>)>,
+    indent: usize,
+    precision: i64,
+    objects: &mut Vec<usize>,
+) {
+    let padding = " ".repeat(indent);
+
+    *text += &format!("{}(\n", padding);
+
+    for (key, item) in items {
+        *text += &format!("{}    [{}] => ", padding, key);
+
+        print_value(text, &item, indent + 8, precision, objects);
+
+        *text += "\n";
+    }
+
+    *text += &format!("{})\n", padding);
+}
+
+/// var_export(mixed $value, bool $return = false): ?string
+///
+/// The value is written as the PHP code that gives it back. An object that contains
+/// itself cannot be written, it is written as NULL with a warning.
+pub fn var_export(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("var_export", arguments, 1, 2, span)?;
+
+    let return_output = match arguments.get(1) {
+        Some(_) => get_bool_argument(evaluator, "var_export", arguments, 1, "return", span)?,
+        None => false,
+    };
+
+    let mut code = String::new();
+    let mut circular = false;
+
+    export_value(&mut code, &arguments[0], 1, &mut vec![], &mut circular);
+
+    if circular {
+        evaluator.raise_error(PhpError {
+            level: ErrorLevel::Warning,
+            message: "var_export does not handle circular references".to_string(),
+            line: span.line,
+        })?;
+    }
+
+    if return_output {
+        return Ok(PhpValue::String(code.into()));
+    }
+
+    evaluator.write_output(&code, span.line)?;
+
+    Ok(PhpValue::Null)
+}
+
+/// Writes a value like var_export() does, `level` starts at 1 and grows by two for
+/// the items of each array, like in PHP. `circular` is set if an object contains itself.
+fn export_value(
+    code: &mut String,
+    value: &PhpValue,
+    level: usize,
+    objects: &mut Vec<usize>,
+    circular: &mut bool,
+) {
+    // an array or an object inside of another one starts on its own line
+    let new_line = if level > 1 {
+        format!("\n{}", " ".repeat(level - 1))
+    } else {
+        String::new()
+    };
+
+    let closing_padding = " ".repeat(level.saturating_sub(1));
+
+    match value {
+        PhpValue::Null => *code += "NULL",
+        PhpValue::Bool(boolean) => *code += &boolean.to_string(),
+        PhpValue::Int(int) => *code += &int.to_string(),
+        PhpValue::Float(float) => {
+            let mut number = format_float(*float, -1);
+
+            if float.is_finite() && !number.contains(['.', 'E']) {
+                number += ".0";
+            }
+
+            *code += &number;
+        }
+        PhpValue::String(string) => *code += &export_string(string),
+        PhpValue::Array(array) => {
+            *code += &format!("{}array (\n", new_line);
+
+            for (key, item) in array.iter() {
+                let key = match key {
+                    ArrayKey::Int(int) => int.to_string(),
+                    ArrayKey::String(string) => export_string(string),
+                };
+
+                *code += &format!("{}{} => ", " ".repeat(level + 1), key);
+
+                export_value(code, &item, level + 2, objects, circular);
+
+                *code += ",\n";
+            }
+
+            *code += &format!("{})", closing_padding);
+        }
+        PhpValue::Object(object) => {
+            let object = object.borrow();
+
+            if objects.contains(&object.id) {
+                *circular = true;
+                *code += "NULL";
+
+                return;
+            }
+
+            if object.class.enum_cases.is_some() {
+                let case = object
+                    .get_property(b"name")
+                    .and_then(|name| name.to_string())
+                    .unwrap_or_default();
+
+                *code += &format!("{}\\{}::{}", new_line, object.class.name, case);
+
+                return;
+            }
+
+            *code += &format!("{}\\{}::__set_state(array(\n", new_line, object.class.name);
+
+            objects.push(object.id);
+
+            for (name, property) in &object.properties {
+                *code += &format!("{}{} => ", " ".repeat(level + 2), export_string(name));
+
+                export_value(code, property, level + 2, objects, circular);
+
+                *code += ",\n";
+            }
+
+            objects.pop();
+
+            *code += &format!("{}))", closing_padding);
+        }
+        PhpValue::Callable(_) => {
+            *code += &format!(
+                "{}\\Closure::__set_state(array(\n{}))",
+                new_line, closing_padding
+            )
+        }
+        PhpValue::Resource(resource) => match *resource {},
+    }
+}
+
+/// A string as a literal between single quotes. A single quoted string cannot have
+/// NUL bytes, they are concatenated as "\0".
+fn export_string(string: &[u8]) -> String {
+    let escaped = get_string_from_bytes(string)
+        .replace('\\', "\\\\")
+        .replace('\'', "\\'")
+        .replace('\0', "' . \"\\0\" . '");
+
+    format!("'{}'", escaped)
+}
+
+/// gettype(mixed $value): string
+///
+/// The names are the historical ones, like "integer" and "double".
+pub fn gettype(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("gettype", arguments, 1, 1, span)?;
+
+    let name = arguments[0].type_name().gettype_name();
+
+    Ok(PhpValue::String(name.into()))
+}
+
+/// intval(mixed $value, int $base = 10): int
+///
+/// The base is only used for strings.
+pub fn intval(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("intval", arguments, 1, 2, span)?;
+
+    let base = match arguments.get(1) {
+        Some(_) => get_int_argument(evaluator, "intval", arguments, 1, "base", span)?,
+        None => 10,
+    };
+
+    match &arguments[0] {
+        PhpValue::String(string) if base != 10 => Ok(PhpValue::Int(parse_int_with_base(
+            string,
+            base.max(0) as u32,
+        ))),
+        value => Ok(PhpValue::Int(value.to_php_int())),
+    }
+}
+
+/// floatval(mixed $value): float
+pub fn floatval(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("floatval", arguments, 1, 1, span)?;
+
+    Ok(PhpValue::Float(arguments[0].to_php_float()))
+}
+
+/// boolval(mixed $value): bool
+pub fn boolval(
+    _evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("boolval", arguments, 1, 1, span)?;
+
+    Ok(PhpValue::Bool(arguments[0].to_php_bool()))
+}
+
+/// strval(mixed $value): string
+pub fn strval(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("strval", arguments, 1, 1, span)?;
+
+    convert_to_string(evaluator, arguments[0].clone(), span)
+}
+
+/// A value as a string like strval() gives it, a string keeps its bytes.
+fn convert_to_string(
+    evaluator: &mut Evaluator,
+    value: PhpValue,
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    match value {
+        PhpValue::String(string) => Ok(PhpValue::String(string)),
+        value => Ok(PhpValue::String(
+            evaluator.value_to_output_string(value, span)?.into(),
+        )),
+    }
+}
+
+/// settype(mixed &$var, string $type): bool
+pub fn settype(
+    evaluator: &mut Evaluator,
+    arguments: &mut [PhpValue],
+    span: Span,
+) -> Result<PhpValue, PhpError> {
+    check_arguments_count("settype", arguments, 2, 2, span)?;
+
+    let type_name = get_string_argument(evaluator, "settype", arguments, 1, "type", span)?;
+
+    let value = &arguments[0];
+
+    let converted = match type_name.to_ascii_lowercase().as_slice() {
+        b"bool" | b"boolean" => PhpValue::Bool(value.to_php_bool()),
+        b"int" | b"integer" => PhpValue::Int(value.to_php_int()),
+        b"float" | b"double" => PhpValue::Float(value.to_php_float()),
+        b"string" => convert_to_string(evaluator, value.clone(), span)?,
+        b"array" => PhpValue::Array(convert_to_array(value)),
+        b"null" => PhpValue::Null,
+        b"resource" => {
+            return Err(argument_value_error(
+                "settype",
+                1,
+                "type",
+                "cannot be \"resource\"",
+                span,
+            ))
+        }
+        b"object" => {
+            return Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: "settype(): Converting to an object is not supported".to_string(),
+                line: span.line,
+            })
+        }
+        _ => {
+            return Err(argument_value_error(
+                "settype",
+                1,
+                "type",
+                "must be a valid type",
+                span,
+            ))
+        }
+    };
+
+    arguments[0] = converted;
+
+    Ok(PhpValue::Bool(true))
+}
+
+/// A value as an array like `(array)` gives it. The properties of an object are its
+/// elements, the names of the private and protected ones are prefixed like in PHP.
+fn convert_to_array(value: &PhpValue) -> PhpArray {
+    let mut array = PhpArray::new();
+
+    match value {
+        // a closure is an object without properties
+        PhpValue::Null | PhpValue::Callable(_) => {}
+        PhpValue::Array(elements) => return elements.clone(),
+        PhpValue::Object(object) => {
+            let object = object.borrow();
+
+            for (name, property) in &object.properties {
+                let key = match property_visibility(&object, name) {
+                    (Visibility::Public, _) => name.bytes.clone(),
+                    (Visibility::Protected, _) => [b"\0*\0", name.bytes.as_slice()].concat(),
+                    (Visibility::Private, class) => {
+                        [b"\0", class.bytes.as_slice(), b"\0", name.bytes.as_slice()].concat()
+                    }
+                };
+
+                array.insert(ArrayKey::String(key.into()), property.clone());
+            }
+        }
+        value => array.insert(ArrayKey::Int(0), value.clone()),
+    }
+
+    array
+}
+
+/// The visibility of a property of an object, with the name of the class that declares
+/// it. The properties that no class declares are public.
+fn property_visibility<'o>(object: &'o PhpObject, name: &[u8]) -> (Visibility, &'o ByteString) {
+    object
+        .class
+        .ancestors()
+        .find_map(|class| {
+            class
+                .properties
+                .iter()
+                .find(|property| property.name.bytes == name)
+                .map(|property| (property.modifiers.visibility(), &class.name))
+        })
+        .unwrap_or((Visibility::Public, &object.class.name))
+}
@@ -0,0 +1,88 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// A map that is iterated in insertion order.
+///
+/// Anything that the user can see listed, like the variables of get_defined_vars(),
+/// must come from a container like this one or be sorted, never from the iteration
+/// of a HashMap whose order changes from one run to the other.
+/// A key that is removed and inserted again goes to the end, like in PHP.
+#[derive(Debug, Clone)]
+pub struct OrderedMap<K, V> {
+    entries: Vec<(K, V)>,
+
+    /// The position of every key in `entries`.
+    positions: HashMap<K, usize>,
+}
+
+impl<K: Clone + Eq + Hash, V> OrderedMap<K, V> {
+    pub fn new() -> OrderedMap<K, V> {
+        OrderedMap {
+            entries: vec![],
+            positions: HashMap::new(),
+        }
+    }
+
+    pub fn get<Q: ?Sized + Eq + Hash>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+    {
+        self.positions
+            .get(key)
+            .map(|position| &self.entries[*position].1)
+    }
+
+    /// Inserts or replaces a value, a replaced value keeps its position.
+    pub fn insert(&mut self, key: K, value: V) {
+        match self.positions.get(&key) {
+            Some(position) => self.entries[*position].1 = value,
+            None => {
+                self.positions.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+            }
+        }
+    }
+
+    pub fn remove<Q: ?Sized + Eq + Hash>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+    {
+        let position = self.positions.remove(key)?;
+
+        let (_, value) = self.entries.remove(position);
+
+        // the entries after the removed one moved back
+        for (index, (key, _)) in self.entries.iter().enumerate().skip(position) {
+            self.positions.insert(key.clone(), index);
+        }
+
+        Some(value)
+    }
+
+    /// The entries in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+
+    /// The values in insertion order.
+    pub fn values(&self) -> impl Iterator<Item = &V> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+}
+
+impl<K: Clone + Eq + Hash, V> Default for OrderedMap<K, V> {
+    fn default() -> Self {
+        OrderedMap::new()
+    }
+}
+
+impl<K, V> IntoIterator for OrderedMap<K, V> {
+    type Item = (K, V);
+    type IntoIter = std::vec::IntoIter<(K, V)>;
+
+    /// The entries in insertion order.
+    fn into_iter(self) -> Self::IntoIter {
+        self.entries.into_iter()
+    }
+}
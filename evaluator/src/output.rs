@@ -0,0 +1,117 @@
+use std::io::{self, Write};
+
+/// Where the output of a program goes, see `Output::set_target()`.
+pub trait OutputTarget {
+    /// Receives the output in the order it is produced.
+    fn write_output(&mut self, text: &str) -> io::Result<()>;
+
+    /// Called by flush(), the output written so far must be visible.
+    fn flush_output(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Captures the output, for the tests and the programs that embed the evaluator.
+impl OutputTarget for String {
+    fn write_output(&mut self, text: &str) -> io::Result<()> {
+        self.push_str(text);
+
+        Ok(())
+    }
+}
+
+/// Writes the output to a writer, like stdout or a file.
+pub struct WriterTarget<W: Write>(pub W);
+
+impl<W: Write> OutputTarget for WriterTarget<W> {
+    fn write_output(&mut self, text: &str) -> io::Result<()> {
+        self.0.write_all(text.as_bytes())
+    }
+
+    fn flush_output(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+/// The output of a running program.
+///
+/// The text is kept until it is flushed to the target, which happens after every write
+/// when the "implicit_flush" setting is on and at the end of the program otherwise.
+/// The capture buffers, like the ones of ob_start(), take the text before it reaches
+/// the pending output.
+#[derive(Default)]
+pub struct Output<'w> {
+    target: Option<&'w mut dyn OutputTarget>,
+
+    /// The text that was not given to the target yet.
+    pending: String,
+
+    /// The capture buffers, the last one receives the output.
+    buffers: Vec<String>,
+}
+
+impl<'w> Output<'w> {
+    pub fn new() -> Output<'w> {
+        Output::default()
+    }
+
+    /// Sets where the output is written when it is flushed, without a target the
+    /// output stays pending.
+    pub fn set_target(&mut self, target: &'w mut dyn OutputTarget) {
+        self.target = Some(target);
+    }
+
+    pub fn take_target(&mut self) -> Option<&'w mut dyn OutputTarget> {
+        self.target.take()
+    }
+
+    /// Adds text to the last capture buffer, or to the pending output if there is none.
+    pub fn write(&mut self, text: &str) {
+        match self.buffers.last_mut() {
+            Some(buffer) => buffer.push_str(text),
+            None => self.pending.push_str(text),
+        }
+    }
+
+    /// Starts a capture buffer, the output goes to it until it is ended.
+    pub fn push_buffer(&mut self) {
+        self.buffers.push(String::new());
+    }
+
+    /// Ends the last capture buffer and returns what it captured, None if there is no buffer.
+    pub fn pop_buffer(&mut self) -> Option<String> {
+        self.buffers.pop()
+    }
+
+    /// The number of capture buffers, like ob_get_level().
+    pub fn buffer_level(&self) -> usize {
+        self.buffers.len()
+    }
+
+    /// Ends every capture buffer, what they captured is output.
+    pub fn end_buffers(&mut self) {
+        while let Some(buffer) = self.buffers.pop() {
+            self.write(&buffer);
+        }
+    }
+
+    /// Removes the output that was not written to the target yet and returns it.
+    pub fn take_pending(&mut self) -> String {
+        std::mem::take(&mut self.pending)
+    }
+
+    /// Writes the pending output to the target and flushes the target, the capture
+    /// buffers keep their text.
+    pub fn flush(&mut self) -> io::Result<()> {
+        let Some(target) = &mut self.target else {
+            return Ok(());
+        };
+
+        target.write_output(&self.pending)?;
+        target.flush_output()?;
+
+        self.pending.clear();
+
+        Ok(())
+    }
+}
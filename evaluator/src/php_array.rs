@@ -0,0 +1,328 @@
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use php_parser_rs::lexer::byte_string::ByteString;
+
+use crate::conversion::float_to_int;
+use crate::php_value::{ErrorLevel, PhpError, PhpValue};
+
+/// The key of an element of an array.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ArrayKey {
+    Int(i64),
+    String(ByteString),
+}
+
+/// The value of an element. An element assigned by reference, like `$array[] = &$value`,
+/// shares its value with the variables and the elements that refer to it, also in
+/// the copies of the array, like in PHP. The value is boxed so the elements stay small,
+/// a PhpValue is much bigger than a reference.
+#[derive(Debug, Clone)]
+enum Element {
+    Value(Box<PhpValue>),
+    Reference(Rc<RefCell<PhpValue>>),
+}
+
+impl Element {
+    fn value(&self) -> Cow<'_, PhpValue> {
+        match self {
+            Element::Value(value) => Cow::Borrowed(value),
+            Element::Reference(reference) => Cow::Owned(reference.borrow().clone()),
+        }
+    }
+}
+
+/// An ordered map, which is what PHP arrays are.
+///
+/// It also stores the internal pointer used by current(), key(), next(), prev(), reset()
+/// and end(). The pointer is part of the value, so it is copied along with the array.
+/// Just like in PHP 7 and later, foreach does not move it.
+#[derive(Debug, Clone, Default)]
+pub struct PhpArray {
+    entries: Vec<(ArrayKey, Element)>,
+
+    /// The position of every key in `entries`.
+    positions: HashMap<ArrayKey, usize>,
+
+    /// The biggest integer key the array has had, the next append uses it plus one,
+    /// even if it is negative. It is None, and appends use 0, while there are no
+    /// integer keys. Once it is PHP_INT_MAX the next element is occupied and nothing
+    /// can be appended.
+    biggest_index: Option<i64>,
+
+    /// The internal pointer, it is past the end when it is equal to the length of the array.
+    pointer: usize,
+}
+
+impl ArrayKey {
+    /// Converts a value to the key that PHP would use:
+    ///
+    /// - strings containing a decimal integer in its canonical form ("5", "-1") are integers,
+    ///   any other string ("05", "1.5", "-0", " 5", out of range integers) stays a string
+    /// - floats are truncated (1.9 is 1), see `conversion::float_to_int()` for the ones out
+    ///   of the range of the ints
+    /// - true and false are 1 and 0
+    /// - null is ""
+    ///
+    /// Use `Evaluator::get_array_key()` instead, which also emits the deprecation for floats
+    /// that are not an int.
+    pub fn from_value(value: &PhpValue) -> Result<ArrayKey, PhpError> {
+        match value {
+            PhpValue::Int(i) => Ok(ArrayKey::Int(*i)),
+            PhpValue::Float(f) => Ok(ArrayKey::Int(float_to_int(*f))),
+            PhpValue::Bool(b) => Ok(ArrayKey::Int(*b as i64)),
+            PhpValue::Null => Ok(ArrayKey::String(ByteString::default())),
+            PhpValue::String(s) => Ok(ArrayKey::from_bytes(&s.bytes)),
+            _ => Err(PhpError {
+                level: ErrorLevel::Fatal,
+                message: format!("Illegal offset type {}", value.type_name().error_name()),
+                line: 0,
+            }),
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> ArrayKey {
+        let as_int = std::str::from_utf8(bytes)
+            .ok()
+            .and_then(|string| string.parse::<i64>().ok());
+
+        match as_int {
+            Some(i) if i.to_string().as_bytes() == bytes => ArrayKey::Int(i),
+            _ => ArrayKey::String(ByteString::new(bytes.to_vec())),
+        }
+    }
+
+    pub fn to_value(&self) -> PhpValue {
+        match self {
+            ArrayKey::Int(i) => PhpValue::Int(*i),
+            ArrayKey::String(s) => PhpValue::String(s.clone()),
+        }
+    }
+}
+
+/// A list, the values have the keys 0, 1, 2...
+impl FromIterator<PhpValue> for PhpArray {
+    fn from_iter<T: IntoIterator<Item = PhpValue>>(values: T) -> PhpArray {
+        let mut array = PhpArray::new();
+
+        for (index, value) in values.into_iter().enumerate() {
+            array.insert(ArrayKey::Int(index as i64), value);
+        }
+
+        array
+    }
+}
+
+impl PhpArray {
+    pub fn new() -> PhpArray {
+        PhpArray::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn get(&self, key: &ArrayKey) -> Option<Cow<'_, PhpValue>> {
+        self.positions
+            .get(key)
+            .map(|position| self.entries[*position].1.value())
+    }
+
+    /// The value of an element to change it in place, None if the key does not exist
+    /// or if the element is a reference, which is changed through get_reference().
+    pub fn get_mut(&mut self, key: &ArrayKey) -> Option<&mut PhpValue> {
+        let position = self.positions.get(key)?;
+
+        match &mut self.entries[*position].1 {
+            Element::Value(value) => Some(value),
+            Element::Reference(_) => None,
+        }
+    }
+
+    /// The shared value of an element assigned by reference, None if the element
+    /// does not exist or is not a reference.
+    pub fn get_reference(&self, key: &ArrayKey) -> Option<Rc<RefCell<PhpValue>>> {
+        let position = self.positions.get(key)?;
+
+        match &self.entries[*position].1 {
+            Element::Value(_) => None,
+            Element::Reference(reference) => Some(Rc::clone(reference)),
+        }
+    }
+
+    /// Turns an element into a reference and returns its shared value, like `&$array['key']`
+    /// does. A key that does not exist is added with null.
+    pub fn make_reference(&mut self, key: ArrayKey) -> Rc<RefCell<PhpValue>> {
+        let Some(position) = self.positions.get(&key) else {
+            let reference = Rc::new(RefCell::new(PhpValue::Null));
+
+            self.insert_element(key, Element::Reference(Rc::clone(&reference)));
+
+            return reference;
+        };
+
+        let element = &mut self.entries[*position].1;
+
+        let reference = match element {
+            Element::Value(value) => {
+                let value = std::mem::replace(&mut **value, PhpValue::Null);
+
+                Rc::new(RefCell::new(value))
+            }
+            Element::Reference(reference) => return Rc::clone(reference),
+        };
+
+        *element = Element::Reference(Rc::clone(&reference));
+
+        reference
+    }
+
+    /// Sets the value of a key, keeping its position if it already exists. An element
+    /// that was a reference stops being one.
+    pub fn insert(&mut self, key: ArrayKey, value: PhpValue) {
+        self.insert_element(key, Element::Value(Box::new(value)));
+    }
+
+    /// Makes an element share `reference`, like `$array['key'] = &$value`.
+    pub fn insert_reference(&mut self, key: ArrayKey, reference: Rc<RefCell<PhpValue>>) {
+        self.insert_element(key, Element::Reference(reference));
+    }
+
+    fn insert_element(&mut self, key: ArrayKey, element: Element) {
+        if let Some(position) = self.positions.get(&key) {
+            self.entries[*position].1 = element;
+
+            return;
+        }
+
+        if let ArrayKey::Int(i) = key {
+            if self
+                .biggest_index
+                .is_none_or(|biggest_index| i > biggest_index)
+            {
+                self.biggest_index = Some(i);
+            }
+        }
+
+        self.positions.insert(key.clone(), self.entries.len());
+        self.entries.push((key, element));
+    }
+
+    /// Appends a value with the next integer key, like `$array[] = $value`. It fails
+    /// when the array has the key PHP_INT_MAX, see `next_key()`.
+    pub fn push(&mut self, value: PhpValue) -> Result<(), PhpError> {
+        let key = self.next_key()?;
+
+        self.insert(key, value);
+
+        Ok(())
+    }
+
+    /// The key that the next push() uses, there is none after PHP_INT_MAX.
+    ///
+    /// The error has no line, the caller sets it.
+    pub fn next_key(&self) -> Result<ArrayKey, PhpError> {
+        let Some(biggest_index) = self.biggest_index else {
+            return Ok(ArrayKey::Int(0));
+        };
+
+        match biggest_index.checked_add(1) {
+            Some(index) => Ok(ArrayKey::Int(index)),
+            None => Err(PhpError {
+                level: ErrorLevel::EngineError("Error"),
+                message: "Cannot add element to the array as the next element is already occupied"
+                    .to_string(),
+                line: 0,
+            }),
+        }
+    }
+
+    pub fn remove(&mut self, key: &ArrayKey) -> Option<PhpValue> {
+        let position = self.positions.remove(key)?;
+
+        let (_, element) = self.entries.remove(position);
+
+        // order: every position is updated whatever the order is
+        for (_, entry_position) in self.positions.iter_mut() {
+            if *entry_position > position {
+                *entry_position -= 1;
+            }
+        }
+
+        if self.pointer > position {
+            self.pointer -= 1;
+        }
+
+        match element {
+            Element::Value(value) => Some(*value),
+            Element::Reference(reference) => Some(reference.borrow().clone()),
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&ArrayKey, Cow<'_, PhpValue>)> {
+        self.entries
+            .iter()
+            .map(|(key, element)| (key, element.value()))
+    }
+
+    pub fn first_key(&self) -> Option<&ArrayKey> {
+        self.entries.first().map(|(key, _)| key)
+    }
+
+    pub fn last_key(&self) -> Option<&ArrayKey> {
+        self.entries.last().map(|(key, _)| key)
+    }
+
+    /*
+     * Internal pointer
+     */
+
+    /// The element at the internal pointer, None if the pointer is past the end.
+    pub fn current(&self) -> Option<(&ArrayKey, Cow<'_, PhpValue>)> {
+        self.entries
+            .get(self.pointer)
+            .map(|(key, element)| (key, element.value()))
+    }
+
+    /// Moves the pointer to the next element.
+    pub fn next(&mut self) -> Option<(&ArrayKey, Cow<'_, PhpValue>)> {
+        if self.pointer < self.entries.len() {
+            self.pointer += 1;
+        }
+
+        self.current()
+    }
+
+    /// Moves the pointer to the previous element.
+    ///
+    /// Moving back from the first element leaves the pointer past the end, like PHP does.
+    pub fn prev(&mut self) -> Option<(&ArrayKey, Cow<'_, PhpValue>)> {
+        if self.pointer == 0 || self.pointer >= self.entries.len() {
+            self.pointer = self.entries.len();
+        } else {
+            self.pointer -= 1;
+        }
+
+        self.current()
+    }
+
+    /// Moves the pointer to the first element.
+    pub fn reset(&mut self) -> Option<(&ArrayKey, Cow<'_, PhpValue>)> {
+        self.pointer = 0;
+
+        self.current()
+    }
+
+    /// Moves the pointer to the last element.
+    pub fn end(&mut self) -> Option<(&ArrayKey, Cow<'_, PhpValue>)> {
+        self.pointer = self.entries.len().saturating_sub(1);
+
+        self.current()
+    }
+}
@@ -0,0 +1,362 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use php_parser_rs::lexer::byte_string::ByteString;
+use php_parser_rs::parser::ast::data_type::Type;
+use php_parser_rs::parser::ast::modifiers::{
+    ClassModifierGroup, MethodModifierGroup, PropertyModifierGroup, Visibility,
+};
+
+use crate::php_value::{PhpCallable, PhpObject, PhpValue, TypeName};
+
+/// A declared class.
+#[derive(Debug, Clone)]
+pub struct PhpClass {
+    pub name: ByteString,
+    pub modifiers: ClassModifierGroup,
+    pub parent: Option<Rc<PhpClass>>,
+
+    /// The interfaces that the class implements, in the order they are written.
+    pub interfaces: Vec<Rc<PhpInterface>>,
+
+    /// The `/** */` comment written before the class.
+    pub doc_comment: Option<ByteString>,
+
+    /// The constants declared by the class with their values, in declaration order.
+    pub constants: Vec<(ByteString, PhpValue)>,
+
+    /// The instance properties declared by the class, in declaration order.
+    pub properties: Vec<ClassProperty>,
+
+    /// The methods declared by the class, by their lowercase name.
+    pub methods: HashMap<Vec<u8>, PhpMethod>,
+
+    /// The static properties declared by the class, names are without the `$`.
+    ///
+    /// There is one value per declaring class, so a child class that does not
+    /// redeclare a static property shares it with its parent.
+    pub static_properties: HashMap<Vec<u8>, Rc<RefCell<PhpValue>>>,
+
+    /// The declarations of the static properties, in declaration order.
+    pub static_property_declarations: Vec<ClassProperty>,
+
+    /// The cases of the class when it is an enum.
+    pub enum_cases: Option<EnumCases>,
+}
+
+/// The cases declared by an enum.
+#[derive(Debug, Clone)]
+pub struct EnumCases {
+    /// Int or String for a backed enum, None for a pure enum.
+    pub backing_type: Option<TypeName>,
+
+    /// The names of the cases with their backing value, in declaration order.
+    /// The value of the cases of a pure enum is null.
+    pub cases: Vec<(ByteString, PhpValue)>,
+}
+
+/// A declared interface.
+#[derive(Debug, Clone)]
+pub struct PhpInterface {
+    pub name: ByteString,
+
+    /// The interfaces that the interface extends.
+    pub parents: Vec<Rc<PhpInterface>>,
+
+    /// The constants declared by the interface with their values, in declaration order.
+    pub constants: Vec<(ByteString, PhpValue)>,
+
+    /// The methods declared by the interface, by their lowercase name, they have no body.
+    pub methods: HashMap<Vec<u8>, PhpMethod>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ClassProperty {
+    /// The name without the `$`.
+    pub name: ByteString,
+    pub modifiers: PropertyModifierGroup,
+    pub data_type: Option<Type>,
+    pub default_value: PhpValue,
+    pub doc_comment: Option<ByteString>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PhpMethod {
+    pub function: PhpCallable,
+    pub modifiers: MethodModifierGroup,
+
+    /// The name of the class that declares the method.
+    pub class_name: ByteString,
+
+    /// The constructor parameters that are promoted to properties, without the `$`.
+    pub promoted_parameters: Vec<ByteString>,
+
+    pub doc_comment: Option<ByteString>,
+}
+
+/// A method found by `PhpClass::resolve_method()`, with what the callers need to check before calling it.
+#[derive(Debug, Clone)]
+pub struct ResolvedMethod<'a> {
+    pub method: &'a PhpMethod,
+
+    /// The class that declares the method, it is the class itself or one of its parents.
+    pub class_name: &'a ByteString,
+
+    pub is_static: bool,
+    pub visibility: Visibility,
+}
+
+impl PhpClass {
+    /// Finds a method of the class or of one of its parents, method names are case insensitive.
+    ///
+    /// Every kind of call must find its method here, so they all agree on which one is called.
+    pub fn resolve_method(&self, name: &[u8]) -> Option<ResolvedMethod<'_>> {
+        let name = name.to_ascii_lowercase();
+
+        self.ancestors().find_map(|class| {
+            let method = class.methods.get(&name)?;
+
+            Some(ResolvedMethod {
+                method,
+                class_name: &class.name,
+                is_static: method.modifiers.has_static(),
+                visibility: method.modifiers.visibility(),
+            })
+        })
+    }
+
+    /// Same as `resolve_method()`, when only the method itself is needed.
+    pub fn get_method(&self, name: &[u8]) -> Option<&PhpMethod> {
+        self.resolve_method(name).map(|resolved| resolved.method)
+    }
+
+    /// Finds a constant declared by the class, by one of its parents or by one of the
+    /// interfaces they implement, constant names are case sensitive.
+    pub fn get_constant(&self, name: &[u8]) -> Option<&PhpValue> {
+        let own_constant = self
+            .ancestors()
+            .find_map(|class| find_constant(&class.constants, name));
+
+        own_constant.or_else(|| {
+            self.ancestors()
+                .flat_map(|class| &class.interfaces)
+                .find_map(|interface| interface.get_constant(name))
+        })
+    }
+
+    /// Whether the class, or one of its parents, implements the interface `name` or an
+    /// interface that extends it.
+    pub fn implements(&self, name: &[u8]) -> bool {
+        self.ancestors()
+            .flat_map(|class| &class.interfaces)
+            .any(|interface| interface.extends(name))
+    }
+
+    /// Finds a static property declared by the class or by one of its parents.
+    pub fn get_static_property(&self, name: &[u8]) -> Option<Rc<RefCell<PhpValue>>> {
+        self.ancestors()
+            .find_map(|class| class.static_properties.get(name).map(Rc::clone))
+    }
+
+    /// Whether the class is `name` or extends it, class names are case insensitive.
+    pub fn is_subclass_of(&self, name: &[u8]) -> bool {
+        self.ancestors()
+            .any(|class| class.name.bytes.eq_ignore_ascii_case(name))
+    }
+
+    /// The class followed by its parents, up to the root of the hierarchy.
+    ///
+    /// Every walk up the hierarchy must use it. The parent of a class is resolved when
+    /// the class is declared so the chain cannot loop, but the iterator still stops
+    /// at a class it already returned.
+    pub fn ancestors(&self) -> Ancestors<'_> {
+        Ancestors {
+            next: Some(self),
+            visited: vec![],
+        }
+    }
+
+    /// Creates an object of the class, with the default values of the properties
+    /// declared by the class and its parents.
+    pub fn instantiate(self: &Rc<Self>, id: usize) -> PhpObject {
+        let classes: Vec<_> = self.ancestors().collect();
+
+        let mut object = PhpObject {
+            class: Rc::clone(self),
+            id,
+            properties: vec![],
+        };
+
+        for class in classes.iter().rev() {
+            for property in &class.properties {
+                object.set_property(&property.name, property.default_value.clone());
+            }
+        }
+
+        object
+    }
+}
+
+impl PhpInterface {
+    /// Whether the interface is `name` or extends it, interface names are case insensitive.
+    pub fn extends(&self, name: &[u8]) -> bool {
+        self.name.bytes.eq_ignore_ascii_case(name)
+            || self.parents.iter().any(|parent| parent.extends(name))
+    }
+
+    /// Finds a constant declared by the interface or by one of the interfaces it extends.
+    pub fn get_constant(&self, name: &[u8]) -> Option<&PhpValue> {
+        find_constant(&self.constants, name).or_else(|| {
+            self.parents
+                .iter()
+                .find_map(|parent| parent.get_constant(name))
+        })
+    }
+
+    /// The methods declared by the interface, then the ones of the interfaces it extends.
+    ///
+    /// The parents are declared before the interface, so the interfaces cannot loop.
+    pub fn all_methods(&self) -> Vec<&PhpMethod> {
+        // order: the methods are stored by name, they are sorted by their position
+        // which gives the declaration order
+        let mut methods: Vec<_> = self.methods.values().collect();
+
+        methods.sort_by_key(|method| method.function.span.position);
+
+        for parent in &self.parents {
+            methods.extend(parent.all_methods());
+        }
+
+        methods
+    }
+
+    /// The names of the constants of the interface and of the interfaces it extends, each
+    /// one with the interface that declares it.
+    pub fn all_constants(&self) -> Vec<(&PhpInterface, &ByteString)> {
+        let mut constants: Vec<_> = self
+            .constants
+            .iter()
+            .map(|(name, _)| (self, name))
+            .collect();
+
+        for parent in &self.parents {
+            constants.extend(parent.all_constants());
+        }
+
+        constants
+    }
+}
+
+fn find_constant<'a>(constants: &'a [(ByteString, PhpValue)], name: &[u8]) -> Option<&'a PhpValue> {
+    constants
+        .iter()
+        .find(|(constant, _)| constant.bytes == name)
+        .map(|(_, value)| value)
+}
+
+/// The iterator returned by `PhpClass::ancestors()`.
+pub struct Ancestors<'a> {
+    next: Option<&'a PhpClass>,
+    visited: Vec<*const PhpClass>,
+}
+
+impl<'a> Iterator for Ancestors<'a> {
+    type Item = &'a PhpClass;
+
+    fn next(&mut self) -> Option<&'a PhpClass> {
+        let class = self.next.take()?;
+
+        if self.visited.contains(&(class as *const PhpClass)) {
+            return None;
+        }
+
+        self.visited.push(class);
+        self.next = class.parent.as_deref();
+
+        Some(class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use php_parser_rs::parser;
+    use php_parser_rs::parser::ast::modifiers::Visibility;
+
+    use crate::evaluator::Evaluator;
+
+    use super::PhpClass;
+
+    /// Declares the classes of `code` and returns the one named `name`.
+    fn declare(code: &str, name: &str) -> Rc<PhpClass> {
+        let mut evaluator = Evaluator::new("test.php");
+
+        let ast = parser::parse(&format!("<?php {}", code)).unwrap();
+
+        for statement in evaluator.hoist_declarations(ast).unwrap() {
+            evaluator.eval_statement(statement).unwrap();
+        }
+
+        evaluator.env.get_class(name.as_bytes()).unwrap()
+    }
+
+    #[test]
+    fn methods_are_found_case_insensitively() {
+        let class = declare("class A { public function doSomething() {} }", "A");
+
+        let method = class.resolve_method(b"DOSOMETHING").unwrap();
+
+        assert_eq!(method.class_name.bytes, b"A");
+        assert_eq!(method.visibility, Visibility::Public);
+        assert!(!method.is_static);
+    }
+
+    #[test]
+    fn inherited_methods_belong_to_the_parent() {
+        let class = declare(
+            "class A { final protected static function f() {} } class B extends A {}",
+            "B",
+        );
+
+        let method = class.resolve_method(b"f").unwrap();
+
+        assert_eq!(method.class_name.bytes, b"A");
+        assert_eq!(method.visibility, Visibility::Protected);
+        assert!(method.is_static);
+    }
+
+    #[test]
+    fn overridden_methods_belong_to_the_child() {
+        let class = declare(
+            "class A { public function f() {} } class B extends A { private function f() {} }",
+            "B",
+        );
+
+        let method = class.resolve_method(b"f").unwrap();
+
+        assert_eq!(method.class_name.bytes, b"B");
+        assert_eq!(method.visibility, Visibility::Private);
+    }
+
+    #[test]
+    fn missing_methods_are_not_found() {
+        let class = declare("class A {} class B extends A { function g() {} }", "B");
+
+        assert!(class.resolve_method(b"f").is_none());
+        assert!(class.get_method(b"f").is_none());
+    }
+
+    #[test]
+    fn the_ancestors_start_with_the_class() {
+        let class = declare("class A {} class B extends A {} class C extends B {}", "C");
+
+        let names: Vec<_> = class
+            .ancestors()
+            .map(|class| class.name.to_string())
+            .collect();
+
+        assert_eq!(names, ["C", "B", "A"]);
+    }
+}
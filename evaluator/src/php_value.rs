@@ -1,7 +1,7 @@
+use std::cell::RefCell;
 use std::cmp::Ordering;
-use std::collections::HashMap;
 use std::fmt::Debug;
-use std::ops::{Add, BitAnd, BitOr, BitXor, Div, Mul, Not, Rem, Shl, Shr, Sub};
+use std::rc::Rc;
 
 use php_parser_rs::lexer::byte_string::ByteString;
 use php_parser_rs::lexer::token::Span;
@@ -11,33 +11,91 @@ use php_parser_rs::parser::ast::functions::ReturnType;
 use php_parser_rs::parser::ast::variables::SimpleVariable;
 use php_parser_rs::parser::ast::{Expression, Statement};
 
-use crate::environment::Environment;
-use crate::helpers::get_string_from_bytes;
-
-const NULL: &str = "null";
-const BOOL: &str = "bool";
-const INT: &str = "int";
-const FLOAT: &str = "float";
-const STRING: &str = "string";
-const ARRAY: &str = "array";
-const OBJECT: &str = "object";
-const CALLABLE: &str = "callable";
-const RESOURCE: &str = "resource";
+use crate::conversion::{leading_numeric_value, scalar_to_string, DEFAULT_PRECISION};
+use crate::native::NativeFunction;
+use crate::php_array::PhpArray;
+use crate::php_class::PhpClass;
 
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum PhpValue {
     Null,
     Bool(bool),
-    Int(i32),
-    Float(f32),
+    Int(i64),
+    Float(f64),
     String(ByteString),
-    Array(HashMap<PhpValue, PhpValue>),
-    Object(PhpObject),
+    Array(PhpArray),
+    /// Objects are handles, copying the value does not copy the object.
+    Object(Rc<RefCell<PhpObject>>),
     Callable(PhpCallable),
     Resource(Resource),
 }
 
+/// The type of a value.
+///
+/// PHP writes the same type with different names depending on where it is shown,
+/// so the type is never compared or stored as a string, the name is chosen where
+/// it is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeName {
+    Null,
+    Bool,
+    Int,
+    Float,
+    String,
+    Array,
+    Object,
+    Callable,
+    Resource,
+}
+
+impl TypeName {
+    /// The name used by error messages and type declarations, like "int".
+    pub fn error_name(self) -> &'static str {
+        match self {
+            TypeName::Null => "null",
+            TypeName::Bool => "bool",
+            TypeName::Int => "int",
+            TypeName::Float => "float",
+            TypeName::String => "string",
+            TypeName::Array => "array",
+            TypeName::Object => "object",
+            TypeName::Callable => "callable",
+            TypeName::Resource => "resource",
+        }
+    }
+
+    /// The name returned by gettype(), like "integer".
+    ///
+    /// A callable is a Closure object for gettype().
+    pub fn gettype_name(self) -> &'static str {
+        match self {
+            TypeName::Null => "NULL",
+            TypeName::Bool => "boolean",
+            TypeName::Int => "integer",
+            TypeName::Float => "double",
+            TypeName::String => "string",
+            TypeName::Array => "array",
+            TypeName::Object | TypeName::Callable => "object",
+            TypeName::Resource => "resource",
+        }
+    }
+
+    /// The name that starts a value in the output of var_dump(), like "int" in `int(1)`.
+    pub fn var_dump_name(self) -> &'static str {
+        match self {
+            TypeName::Null => "NULL",
+            TypeName::Bool => "bool",
+            TypeName::Int => "int",
+            TypeName::Float => "float",
+            TypeName::String => "string",
+            TypeName::Array => "array",
+            TypeName::Object | TypeName::Callable => "object",
+            TypeName::Resource => "resource",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct PhpError {
     pub level: ErrorLevel,
@@ -45,30 +103,85 @@ pub struct PhpError {
     pub line: usize,
 }
 
+/// An error with the file where it was raised.
+#[derive(Debug, Clone)]
+pub struct ReportedError {
+    pub error: PhpError,
+    pub file: String,
+
+    /// The include and require expressions that led to the file, the outermost first.
+    pub include_stack: Vec<IncludeSite>,
+}
+
+/// An include or require expression, like `include 'a.php'` on line 3 of index.php.
+#[derive(Debug, Clone)]
+pub struct IncludeSite {
+    pub file: String,
+    pub line: usize,
+
+    /// "include", "include_once", "require" or "require_once".
+    pub function: &'static str,
+}
+
+#[allow(dead_code)]
 #[derive(Debug, Clone)]
 pub enum ErrorLevel {
     Fatal,
     Warning,
     ParseError,
+    Notice,
+    Deprecated,
+
+    /// A thrown object that no catch took yet, the message is the whole report
+    /// of the uncaught exception.
+    Exception,
+
+    /// An Error of the engine, like the DivisionByZeroError of `1 % 0`, with the name of
+    /// its class. The evaluator throws it as an object of the class, whose message is the
+    /// message of the error.
+    EngineError(&'static str),
 
     /// A Raw error should not be formatted with get_message().
     /// And it is for private use.
     Raw,
-    /*	Notice,
+
+    /// The errors raised by trigger_error(), a UserError stops the script like a Fatal.
     UserError,
     UserWarning,
-    UserNotice, */
+    UserNotice,
+    UserDeprecated,
 }
 
+/// The values of the E_* constants.
+pub const E_ERROR: i64 = 1;
+pub const E_WARNING: i64 = 2;
+pub const E_PARSE: i64 = 4;
+pub const E_NOTICE: i64 = 8;
+pub const E_CORE_ERROR: i64 = 16;
+pub const E_CORE_WARNING: i64 = 32;
+pub const E_COMPILE_ERROR: i64 = 64;
+pub const E_COMPILE_WARNING: i64 = 128;
+pub const E_USER_ERROR: i64 = 256;
+pub const E_USER_WARNING: i64 = 512;
+pub const E_USER_NOTICE: i64 = 1024;
+pub const E_STRICT: i64 = 2048;
+pub const E_RECOVERABLE_ERROR: i64 = 4096;
+pub const E_DEPRECATED: i64 = 8192;
+pub const E_USER_DEPRECATED: i64 = 16384;
+pub const E_ALL: i64 = 32767;
+
 #[derive(Debug, Clone)]
 pub enum Resource {}
 
 #[derive(Debug, Clone)]
 pub struct PhpObject {
-    pub name: String,
-    pub properties: Vec<PhpValue>,
-    pub methods: Vec<PhpCallable>,
-    pub parent: Option<Box<PhpObject>>,
+    pub class: Rc<PhpClass>,
+
+    /// The number given by spl_object_id(), unique among the objects that are alive.
+    pub id: usize,
+
+    /// The properties of the object in declaration order, names are without the `$`.
+    pub properties: Vec<(ByteString, PhpValue)>,
 }
 
 #[derive(Debug, Clone)]
@@ -80,6 +193,43 @@ pub struct PhpCallable {
     pub parameters: Vec<CallableArgument>,
     pub return_type: Option<ReturnType>,
     pub body: Vec<Statement>,
+
+    /// The file that declares the function, its body runs in it.
+    pub file: String,
+
+    /// What a closure keeps from the scope that created it, None for a declared function.
+    pub closure: Option<Rc<ClosureScope>>,
+}
+
+/// The scope captured by a closure, an arrow function or a first class callable like
+/// `strlen(...)`.
+#[derive(Debug, Clone, Default)]
+pub struct ClosureScope {
+    /// The variables given by `use`, or every variable of the scope for an arrow function.
+    pub variables: Vec<CapturedVariable>,
+
+    /// `$this` and the classes of `self::` and `static::` when the closure was created
+    /// in a method, a static closure has no `$this`.
+    pub this: Option<Rc<RefCell<PhpObject>>>,
+    pub class: Option<Rc<PhpClass>>,
+    pub static_class: Option<Rc<PhpClass>>,
+
+    /// The native function called by a first class callable.
+    pub native_function: Option<&'static NativeFunction>,
+
+    /// The number given by spl_object_id(), the closures share the ids of the objects.
+    pub id: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct CapturedVariable {
+    /// The name without the `$`.
+    pub name: Vec<u8>,
+
+    /// A variable captured by reference shares the cell of the outer variable, the
+    /// others are copied into the scope of every call.
+    pub value: Rc<RefCell<PhpValue>>,
+    pub by_reference: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -92,95 +242,23 @@ pub struct CallableArgument {
 }
 
 impl PhpValue {
+    /// Converts the value to a string with the default precision, see `scalar_to_string()`.
     pub fn to_string(&self) -> Option<String> {
-        match self {
-            PhpValue::Null => Some("NULL".to_string()),
-            PhpValue::Bool(b) => {
-                if *b {
-                    Some("1".to_string())
-                } else {
-                    Some("".to_string())
-                }
-            }
-            PhpValue::Int(i) => Some(i.to_string()),
-            PhpValue::Float(f) => Some(f.to_string()),
-            PhpValue::String(s) => Some(String::from_utf8_lossy(s).to_string()),
-            PhpValue::Array(_) => None,
-            PhpValue::Object(_) => None,
-            PhpValue::Callable(c) => {
-                let name = get_string_from_bytes(&c.name.bytes);
-
-                Some(name)
-            }
-            PhpValue::Resource(_) => Some("Resource".to_string()),
-        }
+        scalar_to_string(self, DEFAULT_PRECISION)
     }
 
-    /// Performs a power operation on two values.
-    pub fn pow(self, value: PhpValue) -> Result<PhpValue, PhpError> {
-        match (self, value) {
-            (PhpValue::Int(i), PhpValue::Int(j)) => Ok(PhpValue::Int(i.pow(j as u32))),
-            (PhpValue::Float(f), PhpValue::Float(g)) => Ok(PhpValue::Float(f.powf(g))),
-            (PhpValue::Int(i), PhpValue::Float(f)) => {
-                let f = f as f32;
-                let i = i as f32;
-
-                Ok(PhpValue::Float(i.powf(f)))
-            }
-            (PhpValue::Float(f), PhpValue::Int(i)) => {
-                let f = f as f32;
-                let i = i as f32;
-
-                Ok(PhpValue::Float(f.powf(i)))
-            }
-            _ => {
-                let error_message = "Unsupported operation".to_string();
-
-                Err(PhpError {
-                    level: ErrorLevel::Fatal,
-                    message: error_message,
-                    line: 0,
-                })
-            }
-        }
-    }
-
-    pub fn get_type(&self) -> String {
+    pub fn type_name(&self) -> TypeName {
         match self {
-            PhpValue::Null => NULL.to_string(),
-            PhpValue::Bool(_) => BOOL.to_string(),
-            PhpValue::Int(_) => INT.to_string(),
-            PhpValue::Float(_) => FLOAT.to_string(),
-            PhpValue::String(_) => STRING.to_string(),
-            PhpValue::Array(_) => ARRAY.to_string(),
-            PhpValue::Object(_) => OBJECT.to_string(),
-            PhpValue::Callable(_) => CALLABLE.to_string(),
-            PhpValue::Resource(_) => RESOURCE.to_string(),
-        }
-    }
-
-    /// Concatenates two values.
-    pub fn concat(self, value: PhpValue) -> Result<PhpValue, PhpError> {
-        let self_as_string = self.to_string();
-        let value_as_string = value.to_string();
-
-        if self_as_string.is_none() || value_as_string.is_none() {
-            let error_message = format!(
-                "Unsupported operation: {} . {}",
-                self.get_type(),
-                value.get_type()
-            );
-
-            return Err(PhpError {
-                level: ErrorLevel::Fatal,
-                message: error_message,
-                line: 0,
-            });
+            PhpValue::Null => TypeName::Null,
+            PhpValue::Bool(_) => TypeName::Bool,
+            PhpValue::Int(_) => TypeName::Int,
+            PhpValue::Float(_) => TypeName::Float,
+            PhpValue::String(_) => TypeName::String,
+            PhpValue::Array(_) => TypeName::Array,
+            PhpValue::Object(_) => TypeName::Object,
+            PhpValue::Callable(_) => TypeName::Callable,
+            PhpValue::Resource(_) => TypeName::Resource,
         }
-
-        Ok(PhpValue::String(
-            (self_as_string.unwrap() + &value_as_string.unwrap()).into(),
-        ))
     }
 
     pub fn is_null(&self) -> bool {
@@ -190,24 +268,54 @@ impl PhpValue {
         }
     }
 
-    /// Checks if the value is "true" in PHP terms.
+    /// Checks if the value is "true" in PHP terms, see `to_php_bool()`.
     pub fn is_true(self) -> bool {
+        self.to_php_bool()
+    }
+
+    /// Converts the value to a bool like `(bool)` does: "" and "0" are false but "0.0"
+    /// is true, and arrays are true when they have elements.
+    pub fn to_php_bool(&self) -> bool {
         match self {
             PhpValue::Null => false,
-            PhpValue::Bool(b) => b,
-            PhpValue::Int(i) => i != 0,
-            PhpValue::Float(f) => f != 0.0,
-            PhpValue::String(s) => s.len() > 0,
-            PhpValue::Array(a) => a.len() != 0,
+            PhpValue::Bool(b) => *b,
+            PhpValue::Int(i) => *i != 0,
+            PhpValue::Float(f) => *f != 0.0,
+            PhpValue::String(s) => !s.bytes.is_empty() && s.bytes != b"0",
+            PhpValue::Array(a) => !a.is_empty(),
             PhpValue::Object(_) => true,
             PhpValue::Callable(_) => true,
             PhpValue::Resource(_) => true,
         }
     }
 
-    pub fn to_float(&self) -> Option<f32> {
+    /// Converts the value to an int like `(int)` does. A string gives the number it
+    /// starts with, so "12abc" is 12 and "abc" is 0, and floats are truncated.
+    pub fn to_php_int(&self) -> i64 {
         match self {
-            PhpValue::Int(i) => Some(*i as f32),
+            PhpValue::Int(i) => *i,
+            // NaN and the infinities have no int
+            PhpValue::Float(f) if !f.is_finite() => 0,
+            PhpValue::Float(f) => *f as i64,
+            PhpValue::String(s) => leading_numeric_value(s).to_php_int(),
+            value => value.to_php_bool() as i64,
+        }
+    }
+
+    /// Converts the value to a float like `(float)` does, strings are read like in
+    /// `to_php_int()` and can be written with an exponent, like "1.5e3".
+    pub fn to_php_float(&self) -> f64 {
+        match self {
+            PhpValue::Int(i) => *i as f64,
+            PhpValue::Float(f) => *f,
+            PhpValue::String(s) => leading_numeric_value(s).to_php_float(),
+            value => value.to_php_bool() as i64 as f64,
+        }
+    }
+
+    pub fn to_float(&self) -> Option<f64> {
+        match self {
+            PhpValue::Int(i) => Some(*i as f64),
             PhpValue::Float(f) => Some(*f),
             PhpValue::String(s) => {
                 let str_value = std::str::from_utf8(&s.bytes).unwrap();
@@ -224,70 +332,7 @@ impl PhpValue {
         }
     }
 
-    fn perform_arithmetic_operation<F>(
-        &self,
-        operation_sign: &str,
-        rhs: PhpValue,
-        operation: F,
-    ) -> Result<PhpValue, PhpError>
-    where
-        F: Fn(f32, f32) -> f32,
-    {
-        let self_type = self.get_type();
-
-        if self_type != INT && self_type != FLOAT {
-            return Err(PhpError {
-                level: ErrorLevel::Fatal,
-                message: format!(
-                    "Unsupported operation: {} {} {}",
-                    self.get_type(),
-                    operation_sign,
-                    rhs.get_type()
-                ),
-                line: 0,
-            });
-        }
-
-        let left_float = self.to_float();
-        let right_float = rhs.to_float();
-
-        if left_float.is_none() || right_float.is_none() {
-            return Err(PhpError {
-                level: ErrorLevel::Fatal,
-                message: format!(
-                    "Unsupported operation: {} {} {}",
-                    self.get_type(),
-                    operation_sign,
-                    rhs.get_type()
-                ),
-                line: 0,
-            });
-        }
-
-        let left = left_float.unwrap();
-        let right = right_float.unwrap();
-
-        if self_type == INT {
-            return Ok(PhpValue::Int(operation(left, right) as i32));
-        } else {
-            return Ok(PhpValue::Float(operation(left, right)));
-        }
-    }
-
     /// Returns the size of the value.
-    fn get_size(&self) -> usize {
-        match self {
-            PhpValue::Int(i) => *i as usize,
-            PhpValue::Float(f) => *f as usize,
-            PhpValue::Bool(b) => b.to_string().len(),
-            PhpValue::Null => 0,
-            PhpValue::Callable(c) => c.name.bytes.len(),
-            PhpValue::String(s) => s.len(),
-            PhpValue::Array(a) => a.len(),
-            _ => 0,
-        }
-    }
-
     pub fn is_iterable(&self) -> bool {
         match self {
             PhpValue::Array(_) => true,
@@ -298,153 +343,9 @@ impl PhpValue {
 }
 
 /*
- * Implementation of the arithmetic operators (and other traits)
+ * Implementation of the comparison traits, the operators are in arithmetic.rs
  */
 
-impl Add for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn add(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation("+", rhs, |left, right| left + right)
-    }
-}
-
-impl Sub for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn sub(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation("-", rhs, |left, right| left - right)
-    }
-}
-
-impl Mul for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn mul(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation("*", rhs, |left, right| left * right)
-    }
-}
-
-impl Div for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn div(self, rhs: Self) -> Self::Output {
-        let right_to_float = rhs.to_float();
-
-        if right_to_float.is_none() {
-            return Err(PhpError {
-                level: ErrorLevel::Fatal,
-                message: format!(
-                    "Unsupported operation: {} / {}",
-                    self.get_type(),
-                    rhs.get_type()
-                ),
-                line: 0,
-            });
-        }
-
-		if right_to_float.unwrap() == 0.0 {
-			return Err(PhpError {
-				level: ErrorLevel::Fatal,
-				message: format!("Division by zero"),
-				line: 0,
-			});
-		}
-
-        self.perform_arithmetic_operation("/", rhs, |left, right| left / right)
-    }
-}
-
-impl Rem for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn rem(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation("%", rhs, |left, right| left % right)
-    }
-}
-
-impl BitAnd for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn bitand(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation("&", rhs, |left, right| {
-            (left as i32 & right as i32) as f32
-        })
-    }
-}
-
-impl BitOr for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn bitor(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation("|", rhs, |left, right| {
-            (left as i32 | right as i32) as f32
-        })
-    }
-}
-
-impl BitXor for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn bitxor(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation("^", rhs, |left, right| {
-            (left as i32 ^ right as i32) as f32
-        })
-    }
-}
-
-impl Shl for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn shl(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation("<<", rhs, |left, right| {
-            let left_as_int = left as i32;
-            let right_as_int = right as i32;
-
-            (left_as_int << right_as_int) as f32
-        })
-    }
-}
-
-impl Shr for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn shr(self, rhs: Self) -> Self::Output {
-        self.perform_arithmetic_operation(">>", rhs, |left, right| {
-            let left_as_int = left as i32;
-            let right_as_int = right as i32;
-
-            (left_as_int >> right_as_int) as f32
-        })
-    }
-}
-
-impl Not for PhpValue {
-    type Output = Result<PhpValue, PhpError>;
-
-    fn not(self) -> Self::Output {
-        let self_clone = self.clone();
-
-        match self_clone {
-            PhpValue::Bool(b) => Ok(PhpValue::Bool(!b)),
-            PhpValue::Int(i) => Ok(PhpValue::Bool(i == 0)),
-            PhpValue::Float(f) => Ok(PhpValue::Bool(f == 0.0)),
-            PhpValue::String(s) => Ok(PhpValue::Bool(s.len() == 0)),
-            PhpValue::Null => Ok(PhpValue::Bool(true)),
-            PhpValue::Array(a) => Ok(PhpValue::Bool(a.len() == 0)),
-            _ => {
-                let error_message = format!("Unsupported operation: !{}", self.get_type());
-
-                Err(PhpError {
-                    level: ErrorLevel::Fatal,
-                    message: error_message,
-                    line: 0,
-                })
-            }
-        }
-    }
-}
-
 impl PartialEq for PhpValue {
     fn eq(&self, other: &Self) -> bool {
         self.partial_cmp(other) == Some(Ordering::Equal)
@@ -457,45 +358,97 @@ impl PartialEq for PhpValue {
 
 impl PartialOrd for PhpValue {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        let self_size = self.get_size();
-        let other_size = other.get_size();
-
-        Some(self_size.cmp(&other_size))
+        self.compare(other).ok().flatten()
     }
 }
 
 impl PhpObject {
-    pub fn is_instance_of(self, object: PhpValue) -> Result<bool, PhpError> {
-        if let PhpValue::Object(object) = object {
-            if object.name == self.name {
-                return Ok(true);
-            }
+    /// Whether the object is an instance of the class `name`, of a subclass of it,
+    /// or of a class that implements the interface `name`.
+    pub fn is_instance_of(&self, name: &[u8]) -> bool {
+        self.class.is_subclass_of(name) || self.class.implements(name)
+    }
 
-            if self.parent.is_some() && self.parent.unwrap().name == object.name {
-                return Ok(true);
-            }
+    pub fn get_property(&self, name: &[u8]) -> Option<&PhpValue> {
+        self.properties
+            .iter()
+            .find(|(property_name, _)| property_name.bytes == name)
+            .map(|(_, value)| value)
+    }
 
-            Ok(false)
-        } else {
-            Err(PhpError {
-                level: ErrorLevel::Fatal,
-                message: "Right side of instanceof must be an object".to_string(),
-                line: 0,
-            })
+    /// Sets the value of a property, adding it if it does not exist.
+    pub fn set_property(&mut self, name: &[u8], value: PhpValue) {
+        let property = self
+            .properties
+            .iter_mut()
+            .find(|(property_name, _)| property_name.bytes == name);
+
+        match property {
+            Some((_, property_value)) => *property_value = value,
+            None => self.properties.push((ByteString::new(name.to_vec()), value)),
         }
     }
 }
 
+impl ErrorLevel {
+    /// The E_* constant of the level, or 0 for raw errors.
+    pub fn errno(&self) -> i64 {
+        match self {
+            ErrorLevel::Fatal | ErrorLevel::Exception | ErrorLevel::EngineError(_) => E_ERROR,
+            ErrorLevel::Warning => E_WARNING,
+            ErrorLevel::ParseError => E_PARSE,
+            ErrorLevel::Notice => E_NOTICE,
+            ErrorLevel::Deprecated => E_DEPRECATED,
+            ErrorLevel::UserError => E_USER_ERROR,
+            ErrorLevel::UserWarning => E_USER_WARNING,
+            ErrorLevel::UserNotice => E_USER_NOTICE,
+            ErrorLevel::UserDeprecated => E_USER_DEPRECATED,
+            ErrorLevel::Raw => 0,
+        }
+    }
+
+    /// Whether a user error handler can handle errors of this level.
+    ///
+    /// Just like in PHP, fatal and parse errors always stop the script.
+    pub fn is_handleable(&self) -> bool {
+        matches!(
+            self,
+            ErrorLevel::Warning
+                | ErrorLevel::Notice
+                | ErrorLevel::Deprecated
+                | ErrorLevel::UserError
+                | ErrorLevel::UserWarning
+                | ErrorLevel::UserNotice
+                | ErrorLevel::UserDeprecated
+        )
+    }
+}
+
 impl PhpError {
     pub fn get_message(self, input: &str) -> String {
         if let ErrorLevel::Raw = self.level {
             return self.message;
         }
 
+        // the report says where the exception was created
+        if let ErrorLevel::Exception = self.level {
+            return format!("PHP Fatal error: {}", self.message);
+        }
+
+        // an error that was not thrown, like one of a shutdown function
+        if let ErrorLevel::EngineError(class) = self.level {
+            return format!(
+                "PHP Fatal error: Uncaught {}: {} in {} on line {}",
+                class, self.message, input, self.line
+            );
+        }
+
         let level_error = match self.level {
-            ErrorLevel::Fatal => "Fatal error",
-            ErrorLevel::Warning => "Warning",
+            ErrorLevel::Fatal | ErrorLevel::UserError => "Fatal error",
+            ErrorLevel::Warning | ErrorLevel::UserWarning => "Warning",
             ErrorLevel::ParseError => "Parse error",
+            ErrorLevel::Notice | ErrorLevel::UserNotice => "Notice",
+            ErrorLevel::Deprecated | ErrorLevel::UserDeprecated => "Deprecated",
             _ => "",
         };
 
@@ -506,6 +459,34 @@ impl PhpError {
     }
 }
 
+impl ReportedError {
+    pub fn get_message(self) -> String {
+        self.error.get_message(&self.file)
+    }
+
+    /// The message followed by the include sites, the innermost first, like PHP
+    /// writes the stack trace of an uncaught error:
+    ///
+    /// ```text
+    /// PHP Fatal error: Call to undefined function f() in lib.php on line 3
+    /// Stack trace:
+    /// #0 index.php(2): include()
+    /// #1 {main}
+    /// ```
+    pub fn get_message_with_stack_trace(self) -> String {
+        let mut message = format!("{}\nStack trace:", self.error.get_message(&self.file));
+
+        for (depth, site) in self.include_stack.iter().rev().enumerate() {
+            message += &format!(
+                "\n#{} {}({}): {}()",
+                depth, site.file, site.line, site.function
+            );
+        }
+
+        message + &format!("\n#{} {{main}}", self.include_stack.len())
+    }
+}
+
 impl From<String> for PhpError {
     fn from(message: String) -> Self {
         PhpError {
@@ -516,12 +497,57 @@ impl From<String> for PhpError {
     }
 }
 
-impl PhpCallable {
-    pub fn call(
-        self,
-        env: Environment,
-        arguments: HashMap<&str, PhpValue>,
-    ) -> Result<PhpValue, PhpError> {
-        Ok(PhpValue::Null)
+#[cfg(test)]
+mod tests {
+    use super::{PhpValue, TypeName};
+
+    const ALL: [TypeName; 9] = [
+        TypeName::Null,
+        TypeName::Bool,
+        TypeName::Int,
+        TypeName::Float,
+        TypeName::String,
+        TypeName::Array,
+        TypeName::Object,
+        TypeName::Callable,
+        TypeName::Resource,
+    ];
+
+    #[test]
+    fn every_type_has_its_three_names() {
+        let names: Vec<_> = ALL
+            .iter()
+            .map(|type_name| {
+                (
+                    type_name.error_name(),
+                    type_name.gettype_name(),
+                    type_name.var_dump_name(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            names,
+            [
+                ("null", "NULL", "NULL"),
+                ("bool", "boolean", "bool"),
+                ("int", "integer", "int"),
+                ("float", "double", "float"),
+                ("string", "string", "string"),
+                ("array", "array", "array"),
+                ("object", "object", "object"),
+                ("callable", "object", "object"),
+                ("resource", "resource", "resource"),
+            ]
+        );
+    }
+
+    #[test]
+    fn values_give_their_type() {
+        assert_eq!(PhpValue::Null.type_name(), TypeName::Null);
+        assert_eq!(PhpValue::Bool(false).type_name(), TypeName::Bool);
+        assert_eq!(PhpValue::Int(1).type_name(), TypeName::Int);
+        assert_eq!(PhpValue::Float(1.5).type_name(), TypeName::Float);
+        assert_eq!(PhpValue::String("a".into()).type_name(), TypeName::String);
     }
 }
@@ -1,37 +1,192 @@
-use std::io::Result;
+use std::cell::RefCell;
+use std::io::{self, Result, Write};
+use std::rc::Rc;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+use std::time::SystemTime;
 
 use php_parser_rs::parser;
 
-use crate::evaluator::Evaluator;
+use crate::interpreter::Interpreter;
+use crate::output::WriterTarget;
+use crate::trace::TraceSink;
 
-/// Evaluate the program.
-pub fn eval_program(input: &str, content: &str) -> Result<()> {
-    match parser::parse(content) {
-        Ok(ast) => {
-            let mut evaluator = Evaluator::new();
+pub use crate::php_value::{ErrorLevel, PhpError, ReportedError};
 
-            for node in ast {
-                let result = evaluator.eval_statement(node);
+/// The default of `EvalOptions::max_source_size`, 128MB.
+pub const DEFAULT_MAX_SOURCE_SIZE: u64 = 128 * 1024 * 1024;
 
-                if evaluator.die || result.is_err() {
-                    if result.is_err() {
-                        evaluator.output = format!("{}", result.unwrap_err().get_message(input));
-                    }
+/// Receives the errors of a program when they happen, see `EvalOptions::diagnostics`.
+///
+/// The output written before an error reaches the writer before the sink receives the error,
+/// so a warning raised between two echoes arrives between the writes of the two echoes.
+/// A fatal error arrives before its message is added to the output.
+pub trait DiagnosticsSink {
+    /// Called for the warnings, notices and deprecations that are reported, with the file
+    /// where they were raised.
+    fn on_warning(&mut self, warning: &ReportedError);
 
-                    break;
-                }
-            }
+    /// Called when the program, an included file or a shutdown function stops because of an error.
+    fn on_fatal(&mut self, error: &PhpError);
 
-            for warning in evaluator.warnings {
-                println!("{}", warning.get_message(input))
-            }
+    /// Called with the message of a fatal error, with its stack trace, instead of adding it
+    /// to the output when `EvalOptions::fatal_errors_to_diagnostics` is set.
+    fn on_fatal_message(&mut self, _message: &str) {}
+}
+
+/// The wall clock read by time() and microtime(), see `EvalOptions::clock`.
+pub trait Clock {
+    /// The current date and time.
+    fn now(&self) -> SystemTime;
+}
+
+/// The options of an evaluation, set by the program that embeds the evaluator.
+#[derive(Clone)]
+pub struct EvalOptions {
+    /// The ini settings, like the ones of a php.ini file.
+    ///
+    /// They replace the defaults, ini_restore() goes back to them and get_cfg_var() returns them.
+    pub ini: Vec<(String, String)>,
+
+    /// The ini settings that the script cannot change with ini_set().
+    pub locked_ini: Vec<String>,
+
+    /// Where the errors are sent when they happen.
+    ///
+    /// With a sink, the warnings are not written to the `warnings` writer,
+    /// unless `collect_warnings` is true.
+    pub diagnostics: Option<Rc<RefCell<dyn DiagnosticsSink>>>,
+
+    /// Whether the warnings are still collected and written at the end when there is a sink.
+    pub collect_warnings: bool,
+
+    /// Whether the messages of the fatal errors go to the sink instead of the output,
+    /// like the command line does when the output is written to a file.
+    pub fatal_errors_to_diagnostics: bool,
+
+    /// The largest file that include and require read, in bytes, 0 means no limit.
+    ///
+    /// A larger file stops the program with a fatal error instead of being read into memory.
+    pub max_source_size: u64,
+
+    /// Receives every executed statement, call and included file, see `trace::TraceWriter`.
+    pub trace: Option<Rc<RefCell<dyn TraceSink>>>,
+
+    /// Whether an included file that starts with whitespace before its opening tag gets a warning.
+    ///
+    /// The whitespace is output, which is the usual reason of a "headers already sent" error.
+    pub warn_output_before_code: bool,
+
+    /// The wall clock of time() and microtime(), the system clock when there is none.
+    ///
+    /// hrtime() does not read it, it measures the time since the start of the evaluation
+    /// with a monotonic clock, so a fixed clock does not stop the durations a script measures.
+    pub clock: Option<Rc<dyn Clock>>,
+
+    /// The arguments given to the script after its name, they are in $argv and
+    /// $_SERVER['argv'] after the name.
+    pub argv: Vec<String>,
+
+    /// Whether the content is PHP code without an opening tag, like the code given to `php -r`.
+    pub without_opening_tag: bool,
+
+    /// When the flag is set, the program stops before its next statement, the output
+    /// produced so far is still printed.
+    ///
+    /// Setting it only stores a bool, so it can be done from another thread or from a
+    /// signal handler, like the SIGINT handler of the command line.
+    pub interrupted: Option<Arc<AtomicBool>>,
+}
+
+impl Default for EvalOptions {
+    fn default() -> Self {
+        EvalOptions {
+            ini: vec![],
+            locked_ini: vec![],
+            diagnostics: None,
+            collect_warnings: false,
+            fatal_errors_to_diagnostics: false,
+            max_source_size: DEFAULT_MAX_SOURCE_SIZE,
+            trace: None,
+            warn_output_before_code: false,
+            clock: None,
+            argv: vec![],
+            without_opening_tag: false,
+            interrupted: None,
+        }
+    }
+}
+
+/// The exit status of a program stopped by an error, the same one PHP uses.
+pub const ERROR_EXIT_CODE: i32 = 255;
+
+/// Evaluate the program, its output is written to stdout as soon as it is produced.
+///
+/// Like the command line of PHP, the "implicit_flush" setting is on. It returns the exit
+/// status of the program, see `eval_program_with_writers()`.
+pub fn eval_program(input: &str, content: &str) -> Result<i32> {
+    let options = EvalOptions {
+        ini: vec![("implicit_flush".to_string(), "1".to_string())],
+        ..EvalOptions::default()
+    };
+
+    let mut stdout = io::stdout();
+
+    eval_program_with_options(input, content, &options, &mut io::stdout(), &mut stdout)
+}
+
+/// Evaluate the program, writing its output to `output` and the warnings to `warnings`.
+///
+/// It returns the exit status of the program: the int given to exit() or die(), 0 if
+/// there is none, and `ERROR_EXIT_CODE` if the program stopped because of an error.
+pub fn eval_program_with_writers(
+    input: &str,
+    content: &str,
+    output: &mut dyn Write,
+    warnings: &mut dyn Write,
+) -> Result<i32> {
+    eval_program_with_options(input, content, &EvalOptions::default(), output, warnings)
+}
+
+/// Same as `eval_program_with_writers()`, with the given options.
+pub fn eval_program_with_options(
+    input: &str,
+    content: &str,
+    options: &EvalOptions,
+    output: &mut dyn Write,
+    warnings: &mut dyn Write,
+) -> Result<i32> {
+    let mut target = WriterTarget(output);
+
+    let mut interpreter = Interpreter::with_options(options);
 
-            print!("{}", evaluator.output);
+    interpreter.set_output_target(&mut target);
+
+    let result = interpreter.eval(input, content, options.without_opening_tag)?;
+
+    for warning in result.warnings {
+        writeln!(warnings, "{}", warning.get_message())?;
+    }
+
+    Ok(result.exit_status)
+}
+
+/// Checks the syntax of the program without evaluating it, like `php -l`.
+///
+/// Every syntax error is written to `output`, not only the first one that stops
+/// an evaluation. It returns false if there is an error.
+pub fn lint_program(input: &str, content: &str, output: &mut dyn Write) -> Result<bool> {
+    match parser::parse_recovering(content) {
+        Ok(_) => {
+            writeln!(output, "No syntax errors detected in {}", input)?;
+
+            Ok(true)
         }
         Err(err) => {
-            println!("{}", err.report(&content, Some(input), true, false)?);
+            writeln!(output, "{}", err.report(content, Some(input), true, false)?)?;
+            writeln!(output, "Errors parsing {}", input)?;
+
+            Ok(false)
         }
     }
-
-    return Ok(());
 }
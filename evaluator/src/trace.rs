@@ -0,0 +1,159 @@
+use std::io::Write;
+
+use crate::conversion::{scalar_to_string, DEFAULT_PRECISION};
+use crate::helpers::get_string_from_bytes;
+use crate::php_value::PhpValue;
+
+/// What the evaluator is doing, sent to the `EvalOptions::trace` sink.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceEvent<'a> {
+    /// A statement is about to be executed.
+    Statement { file: &'a str, line: usize },
+
+    /// A user defined function or method is called, its scope starts.
+    Call {
+        function: &'a str,
+        /// A short description of every argument, separated by commas.
+        arguments: &'a str,
+        file: &'a str,
+        line: usize,
+    },
+
+    /// A function or method returns, its scope ends.
+    Return { function: &'a str },
+
+    /// An included or required file starts.
+    EnterFile { file: &'a str },
+
+    /// An included or required file ends.
+    LeaveFile { file: &'a str },
+}
+
+/// Receives the execution of a program step by step.
+///
+/// The evaluator only builds the events when a sink is installed,
+/// so tracing costs nothing when it is off.
+pub trait TraceSink {
+    /// `depth` is the number of calls and included files the event is nested in.
+    fn on_event(&mut self, event: &TraceEvent, depth: usize);
+}
+
+/// Writes the events as lines indented by their depth, like:
+///
+/// ```text
+/// file.php:3
+/// call f(1, "a") at file.php:3
+///   file.php:2
+/// return from f
+/// ```
+pub struct TraceWriter<W: Write> {
+    writer: W,
+
+    /// Only the events of the files whose path contains it and of the calls to the
+    /// function with this name are written.
+    filter: Option<String>,
+
+    /// The depth of the call to the filtered function being executed.
+    filtered_call_depth: Option<usize>,
+}
+
+impl<W: Write> TraceWriter<W> {
+    pub fn new(writer: W, filter: Option<String>) -> TraceWriter<W> {
+        TraceWriter {
+            writer,
+            filter,
+            filtered_call_depth: None,
+        }
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    /// Whether the event passes the filter, it also tracks the calls to the filtered function.
+    fn is_shown(&mut self, event: &TraceEvent, depth: usize) -> bool {
+        let Some(filter) = &self.filter else {
+            return true;
+        };
+
+        match event {
+            TraceEvent::Call { function, .. } if function.eq_ignore_ascii_case(filter) => {
+                if self.filtered_call_depth.is_none() {
+                    self.filtered_call_depth = Some(depth);
+                }
+
+                return true;
+            }
+            TraceEvent::Return { .. } if self.filtered_call_depth == Some(depth) => {
+                self.filtered_call_depth = None;
+
+                return true;
+            }
+            _ => {}
+        }
+
+        if self.filtered_call_depth.is_some() {
+            return true;
+        }
+
+        match event {
+            TraceEvent::Statement { file, .. }
+            | TraceEvent::Call { file, .. }
+            | TraceEvent::EnterFile { file }
+            | TraceEvent::LeaveFile { file } => file.contains(filter.as_str()),
+            TraceEvent::Return { .. } => false,
+        }
+    }
+}
+
+impl<W: Write> TraceSink for TraceWriter<W> {
+    fn on_event(&mut self, event: &TraceEvent, depth: usize) {
+        if !self.is_shown(event, depth) {
+            return;
+        }
+
+        let indentation = "  ".repeat(depth);
+
+        // the trace is best effort, it must not stop the program
+        let _ = match event {
+            TraceEvent::Statement { file, line } => {
+                writeln!(self.writer, "{}{}:{}", indentation, file, line)
+            }
+            TraceEvent::Call {
+                function,
+                arguments,
+                file,
+                line,
+            } => writeln!(
+                self.writer,
+                "{}call {}({}) at {}:{}",
+                indentation, function, arguments, file, line
+            ),
+            TraceEvent::Return { function } => {
+                writeln!(self.writer, "{}return from {}", indentation, function)
+            }
+            TraceEvent::EnterFile { file } => writeln!(self.writer, "{}enter {}", indentation, file),
+            TraceEvent::LeaveFile { file } => writeln!(self.writer, "{}leave {}", indentation, file),
+        };
+    }
+}
+
+/// Describes a value in a call of the trace, long strings are cut.
+pub(crate) fn summarize_value(value: &PhpValue) -> String {
+    match value {
+        PhpValue::String(string) => {
+            let string = get_string_from_bytes(string);
+
+            if string.chars().count() > 20 {
+                format!("\"{}...\"", string.chars().take(20).collect::<String>())
+            } else {
+                format!("\"{}\"", string)
+            }
+        }
+        PhpValue::Null => "null".to_string(),
+        PhpValue::Bool(b) => b.to_string(),
+        PhpValue::Array(array) => format!("array({})", array.len()),
+        PhpValue::Object(object) => format!("object({})", object.borrow().class.name),
+        value => scalar_to_string(value, DEFAULT_PRECISION).unwrap_or_default(),
+    }
+}
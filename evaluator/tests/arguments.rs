@@ -0,0 +1,273 @@
+mod common;
+
+/// A function that echoes its name when it gives a value, to see the order of the evaluation.
+const TRACE: &str = "function t($name) { echo $name, ' '; return $name; }";
+
+/// Runs `code` after the opening tag and the trace function, and returns its output.
+fn run(code: &str) -> String {
+    common::run(&format!("{} {}", TRACE, code))
+}
+
+#[test]
+fn positional_arguments_are_evaluated_before_the_defaults() {
+    let code = "function f($a, $b = t('default b'), $c = t('default c')) { echo \"| $a $b $c\"; }
+        f(t('a'));";
+
+    assert_eq!(run(code), "a default b default c | a default b default c");
+}
+
+#[test]
+fn named_arguments_are_evaluated_in_the_order_they_are_written() {
+    let code = "function f($a, $b, $c) { echo \"| $a $b $c\"; }
+        f(t('a'), c: t('c'), b: t('b'));";
+
+    assert_eq!(run(code), "a c b | a b c");
+}
+
+#[test]
+fn skipped_parameters_take_their_default_after_every_argument() {
+    let code = "function f($a = t('default a'), $b = t('default b'), $c = t('default c')) {
+            echo \"| $a $b $c\";
+        }
+        f(c: t('c'), a: t('a'));";
+
+    assert_eq!(run(code), "c a default b | a default b c");
+}
+
+#[test]
+fn methods_and_constructors_bind_their_arguments_the_same_way() {
+    let code = "class Point {
+            public function __construct(public $x = 0, public $y = 0, public $z = 0) {}
+            public function move($x = t('default x'), $y = t('default y')) { echo \"| $x $y\\n\"; }
+        }
+        $point = new Point(z: t('z'), x: t('x'));
+        echo '| ', $point->x, ' ', $point->y, ' ', $point->z, \"\\n\";
+        $point->move(y: t('y'));";
+
+    assert_eq!(run(code), "z x | x 0 z\ny default x | default x y\n");
+}
+
+#[test]
+fn promoted_properties_take_their_default() {
+    let code = "class User { public function __construct(public $name, public $role = 'guest') {} }
+        $user = new User('ana');
+        echo $user->name, ' ', $user->role;";
+
+    assert_eq!(run(code), "ana guest");
+}
+
+#[test]
+fn named_arguments_must_match_a_parameter_that_has_no_value_yet() {
+    let error = |message: &str| common::uncaught(message, 2);
+
+    let function = "function f($a, $b = 2) {}\n";
+
+    assert_eq!(
+        run(&format!("{}f(1, c: 3);", function)),
+        error("Error: Unknown named parameter $c")
+    );
+    assert_eq!(
+        run(&format!("{}f(1, a: 3);", function)),
+        error("Error: Named parameter $a overwrites previous argument")
+    );
+    assert_eq!(
+        run(&format!("{}f(b: 3);", function)),
+        error("ArgumentCountError: f(): Argument #1 ($a) not passed")
+    );
+}
+
+#[test]
+fn the_errors_of_the_named_arguments_can_be_caught() {
+    let code = "function f($a, $b = 2) {}
+        try { f(1, c: 3); } catch (ArgumentCountError $e) { echo 'count'; } catch (Error $e) { echo $e->getMessage(), \"\\n\"; }
+        try { f(b: 3); } catch (ArgumentCountError $e) { echo $e->getMessage(), \"\\n\"; }
+        try { Suit::from(other: 'h'); } catch (Error $e) { echo $e->getMessage(); }
+        enum Suit: string { case Hearts = 'h'; }";
+
+    assert_eq!(
+        run(code),
+        "Unknown named parameter $c\nf(): Argument #1 ($a) not passed\nUnknown named parameter $other"
+    );
+}
+
+#[test]
+fn a_variadic_parameter_collects_the_remaining_arguments() {
+    let code = "function f($first, ...$rest) { echo $first, ':', count($rest), ' '; return $rest; }
+        f(t('a'));
+        echo f(1, 2, 3) === [2, 3], ' ';
+        function options(...$options) { return $options; }
+        echo options(1, size: 2, color: 3) === [1, 'size' => 2, 'color' => 3];";
+
+    assert_eq!(run(code), "a a:0 1:2 1 1");
+}
+
+#[test]
+fn the_arguments_of_a_typed_variadic_parameter_are_checked() {
+    let code = "function ints(int ...$numbers) { return $numbers; }
+        echo ints(1, '2', true) === [1, 2, 1], ' ';
+        ints(1, 'x');";
+
+    assert_eq!(
+        run(code),
+        format!(
+            "1 {}",
+            common::uncaught(
+                "TypeError: ints(): Argument #2 must be of type int, string given",
+                3
+            )
+        )
+    );
+}
+
+#[test]
+fn the_arguments_of_typed_parameters_are_checked() {
+    let code = "function add(int $a, float $b, ?string $c = null, ?array $d = null) { var_dump($a + $b, $c); }
+        add('1', 2, c: 3);
+        function ints(int &$number) {}
+        $number = '4';
+        ints($number);
+        var_dump($number);
+        add(1, 'x');";
+
+    assert_eq!(
+        run(code),
+        format!(
+            "float(3)\nstring(1) \"3\"\nint(4)\n{}",
+            common::uncaught(
+                "TypeError: add(): Argument #2 ($b) must be of type float, string given, called in test.php on line 7",
+                7
+            )
+        )
+    );
+}
+
+#[test]
+fn the_type_of_a_parameter_is_written_like_it_is_declared() {
+    assert_eq!(
+        run("function f(?Foo\\Bar $bar) {}\nf(1);"),
+        common::uncaught(
+            "TypeError: f(): Argument #1 ($bar) must be of type ?Foo\\Bar, int given, called in test.php on line 2",
+            2
+        )
+    );
+    assert_eq!(
+        run("class A { function m(Foo\\Bar|null|array $bar = null) {} }\n(new A())->m(null); (new A())->m(1.5);"),
+        common::uncaught(
+            "TypeError: A::m(): Argument #1 ($bar) must be of type Foo\\Bar|null|array, float given, called in test.php on line 2",
+            2
+        )
+    );
+}
+
+#[test]
+fn arrays_are_unpacked_into_arguments() {
+    let code = "function f($a, $b = 'b', $c = 'c') { echo \"$a$b$c \"; }
+        $array = [1, 2];
+        f(...$array);
+        f(0, ...$array);
+        f(...['c' => 3, 'a' => 1]);
+        f(...[1], ...['c' => 3]);
+        echo count(...[[1, 2, 3]]), ' ', str_repeat(...['ab', 2]);";
+
+    assert_eq!(run(code), "12c 012 1b3 1b3 3 abab");
+}
+
+#[test]
+fn only_arrays_are_unpacked() {
+    let error = |message: &str| common::uncaught(&format!("Error: {}", message), 2);
+
+    assert_eq!(
+        run("function f(...$a) {}\nf(...1);"),
+        error("Only arrays and Traversables can be unpacked")
+    );
+    assert_eq!(
+        run("function f(...$a) {}\nf(...['a' => 1, 2]);"),
+        error("Cannot use positional argument after named argument during unpacking")
+    );
+    assert_eq!(
+        run("function f($a, ...$rest) {}\nf(1, ...['a' => 2]);"),
+        error("Named parameter $a overwrites previous argument")
+    );
+}
+
+#[test]
+fn the_extra_arguments_of_a_user_function_are_ignored() {
+    let code = "function f($a) { echo $a, ' '; }
+        f(t('a'), t('b'));
+        (fn ($x) => print($x))(1, 2);";
+
+    assert_eq!(run(code), "a b a 1");
+}
+
+#[test]
+fn omitted_parameters_take_their_default() {
+    let code = "function f($a = 'a', $b = 'b') { echo \"$a$b \"; }
+        f(); f(1); f(1, 2); f(b: 2);";
+
+    assert_eq!(run(code), "ab 1b 12 a2 ");
+}
+
+#[test]
+fn defaults_can_be_constant_expressions() {
+    let code = "class A {
+            const FOO = 'foo';
+            public static function f($a = self::FOO, $b = PHP_INT_MAX, $c = [1, 2]) {
+                echo $a, ' ', $b, ' ', count($c);
+            }
+        }
+        A::f();";
+
+    assert_eq!(run(code), "foo 9223372036854775807 2");
+}
+
+#[test]
+fn a_new_default_creates_an_object_for_each_call() {
+    let code = "class Logger {}
+        function logger($logger = new Logger()) { return $logger; }
+        $a = logger(); $b = logger();
+        var_dump($a === $b, $a == $b);";
+
+    assert_eq!(run(code), "bool(false)\nbool(true)\n");
+}
+
+#[test]
+fn a_new_default_is_created_when_the_function_is_called() {
+    let code = "function f($a = new Missing()) {}
+        echo 'declared ';
+        f();";
+
+    assert_eq!(
+        run(code),
+        format!(
+            "declared {}",
+            common::uncaught("Error: Class \"Missing\" not found", 3)
+        )
+    );
+}
+
+#[test]
+fn new_is_not_supported_in_properties_and_constants() {
+    let error = common::fatal("New expressions are not supported in this context", 2);
+
+    assert_eq!(run("class B {}\nclass A { public $b = new B(); }"), error);
+    assert_eq!(run("class B {}\nclass A { const C = new B(); }"), error);
+    assert_eq!(run("class B {}\ninterface I { const C = new B(); }"), error);
+}
+
+#[test]
+fn the_parameters_without_a_default_must_be_given() {
+    let error = |message: &str| common::uncaught(&format!("ArgumentCountError: {}", message), 2);
+
+    assert_eq!(
+        run("function f($a, $b) {}\nf(1);"),
+        error("Too few arguments to function f(), 1 passed in test.php on line 2 and exactly 2 expected")
+    );
+    assert_eq!(
+        run("function f($a, $b = 2, ...$c) {}\nf();"),
+        error("Too few arguments to function f(), 0 passed in test.php on line 2 and at least 1 expected")
+    );
+    assert_eq!(
+        run("class A { function m($a) {} }\n(new A)->m();"),
+        error("Too few arguments to function A::m(), 0 passed in test.php on line 2 and exactly 1 expected")
+    );
+}
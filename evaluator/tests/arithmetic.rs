@@ -0,0 +1,163 @@
+mod common;
+
+use common::{run, warnings};
+
+#[test]
+fn ints_stay_ints_until_they_overflow() {
+    let code = "echo PHP_INT_MAX, ' ', PHP_INT_MIN, ' ', PHP_INT_SIZE, ' ';
+        echo gettype(PHP_INT_MAX - 1 + 1), ' ', gettype(PHP_INT_MAX + 1), ' ', gettype(PHP_INT_MIN - 1);
+        echo ' ', gettype(PHP_INT_MAX * 2), ' ', gettype(-PHP_INT_MIN), ' ', gettype(2 ** 63);
+        echo ' ', PHP_INT_MAX + 1, ' ', PHP_INT_MAX + 1 == 2 ** 63;";
+
+    assert_eq!(
+        run(code),
+        "9223372036854775807 -9223372036854775808 8 integer double double double double double 9.2233720368548E+18 1"
+    );
+}
+
+#[test]
+fn floats_have_double_precision() {
+    assert_eq!(
+        run("echo 1 / 3, ' ', 0.1 + 0.2, ' ', 1e15, ' ', 9223372036854775808, ' ', 0x7FFFFFFFFFFFFFFF; var_dump(0.1 + 0.2);"),
+        "0.33333333333333 0.3 1.0E+15 9.2233720368548E+18 9223372036854775807float(0.30000000000000004)\n"
+    );
+}
+
+#[test]
+fn a_float_operand_gives_a_float() {
+    assert_eq!(
+        run("var_dump(1 + 1.5, 2 * 0.5, 3 - 0.5, 2 ** 3, 2 ** -1);"),
+        "float(2.5)\nfloat(1)\nfloat(2.5)\nint(8)\nfloat(0.5)\n"
+    );
+}
+
+#[test]
+fn division_gives_an_int_only_when_it_is_exact() {
+    assert_eq!(
+        run("var_dump(7 / 2, 6 / 3, -9 / 3, 1.0 / 4);"),
+        "float(3.5)\nint(2)\nint(-3)\nfloat(0.25)\n"
+    );
+    assert_eq!(
+        run("echo 7 / 2 == 3.5, ' ', gettype(PHP_INT_MIN / -1);"),
+        "1 double"
+    );
+}
+
+#[test]
+fn modulo_works_on_ints() {
+    assert_eq!(
+        run("var_dump(7 % 3, -7 % 3, 7 % -3, 7.9 % 2.1, PHP_INT_MIN % -1);"),
+        "int(1)\nint(-1)\nint(1)\nint(1)\nint(0)\n"
+    );
+}
+
+#[test]
+fn dividing_by_zero_is_an_error() {
+    assert_eq!(
+        run("echo 1 / 0;"),
+        common::uncaught("DivisionByZeroError: Division by zero", 1)
+    );
+    assert_eq!(
+        run("echo 1 % 0;"),
+        common::uncaught("DivisionByZeroError: Modulo by zero", 1)
+    );
+    assert_eq!(
+        run("$a = 1;\n$a %= 0.5;"),
+        common::uncaught("DivisionByZeroError: Modulo by zero", 2)
+    );
+}
+
+#[test]
+fn bitwise_operators_work_on_the_bits_of_ints() {
+    assert_eq!(
+        run("var_dump(PHP_INT_MAX & -1, PHP_INT_MIN | 1, 6 ^ 3, 5.7 & 3, 1 << 31, 1 << 63, 1 << 64, -8 >> 1, -8 >> 70, PHP_INT_MAX >> 64);"),
+        "int(9223372036854775807)\nint(-9223372036854775807)\nint(5)\nint(1)\nint(2147483648)\nint(-9223372036854775808)\nint(0)\nint(-4)\nint(-1)\nint(0)\n"
+    );
+    assert_eq!(
+        run("echo 1 << -1;"),
+        common::uncaught("ArithmeticError: Bit shift by negative number", 1)
+    );
+}
+
+#[test]
+fn numeric_strings_are_numbers() {
+    let code = "var_dump('5' + 3, ' 1.5e3' * 2, '10' / '4', '7 ' % 4, '3' << 1, -'2');
+        $a = '2'; $a += 1; $a *= '3';
+        var_dump($a, null + 1, true + 1, false * 2);";
+
+    assert_eq!(
+        run(code),
+        "int(8)\nfloat(3000)\nfloat(2.5)\nint(3)\nint(6)\nint(-2)\nint(9)\nint(1)\nint(2)\nint(0)\n"
+    );
+    assert_eq!(warnings(code), "");
+}
+
+#[test]
+fn a_string_that_starts_with_a_number_is_that_number_with_a_warning() {
+    let code = "echo '5 apples' + 3, ' ', 2 * '1.5kg';";
+
+    assert_eq!(run(code), "8 3");
+    assert_eq!(
+        warnings(code),
+        "PHP Warning: A non-numeric value encountered in test.php on line 1\n\
+        PHP Warning: A non-numeric value encountered in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn a_string_that_is_not_a_number_is_a_type_error() {
+    assert_eq!(
+        run("echo 'abc' + 1;"),
+        common::uncaught("TypeError: Unsupported operand types: string + int", 1)
+    );
+    assert_eq!(
+        run("$a = 1;\n$a -= '';"),
+        common::uncaught("TypeError: Unsupported operand types: int - string", 2)
+    );
+    assert_eq!(
+        run("echo [] * 2;"),
+        common::uncaught("TypeError: Unsupported operand types: array * int", 1)
+    );
+}
+
+#[test]
+fn bitwise_not_inverts_ints_and_the_bytes_of_strings() {
+    assert_eq!(
+        run("var_dump(~5, ~-1, ~1.9, ~'a' === \"\\x9e\");"),
+        "int(-6)\nint(0)\nint(-2)\nbool(true)\n"
+    );
+    assert_eq!(
+        run("$a = [];\necho ~$a;"),
+        common::uncaught("TypeError: Cannot perform bitwise not on array", 2)
+    );
+}
+
+#[test]
+fn the_errors_of_the_operators_have_the_line_of_the_operator() {
+    let function = "function divide($a, $b) {\n    return $a / $b;\n}\n";
+
+    assert_eq!(
+        run(&format!("{}\n\necho divide(1, 0);", function)),
+        common::uncaught("DivisionByZeroError: Division by zero", 2)
+    );
+    assert_eq!(
+        run("$a = 1;\n\n$b = [$a,\n    $a % 0];"),
+        common::uncaught("DivisionByZeroError: Modulo by zero", 4)
+    );
+    assert_eq!(
+        run("$a = [];\n$b = 2 + 1;\n$a **= 2;"),
+        common::uncaught("TypeError: Unsupported operand types: array ** int", 3)
+    );
+    assert_eq!(
+        run("$a = 1;\n\necho $a |\n    [];"),
+        common::uncaught("TypeError: Unsupported operand types: int | array", 3)
+    );
+    assert_eq!(
+        run("\n\n\necho 1 >> -1;"),
+        common::uncaught("ArithmeticError: Bit shift by negative number", 4)
+    );
+    assert_eq!(
+        run("$a = 'x';\n\necho -$a;"),
+        common::uncaught("TypeError: Unsupported operand types: string * int", 3)
+    );
+}
@@ -0,0 +1,257 @@
+mod common;
+
+use common::{run, run_with_warnings};
+
+#[test]
+fn count_counts_the_elements_and_the_countable_objects() {
+    let code = "$nested = [1, [2, 3], ['a' => [4]]];
+        echo count([]), ' ', count($nested), ' ', sizeof($nested), ' ', count($nested, COUNT_RECURSIVE);
+        class Basket implements Countable { public function count(): int { return 7; } }
+        echo ' ', count(new Basket());";
+
+    assert_eq!(run(code), "0 3 3 7 7");
+}
+
+#[test]
+fn count_only_accepts_arrays_and_countable_objects() {
+    assert_eq!(
+        run("count(5);"),
+        common::uncaught(
+            "TypeError: count(): Argument #1 ($value) must be of type Countable|array, int given",
+            1
+        )
+    );
+    assert_eq!(
+        run("count('abc');"),
+        common::uncaught("TypeError: count(): Argument #1 ($value) must be of type Countable|array, string given", 1)
+    );
+    assert_eq!(
+        run("count([], 2);"),
+        common::uncaught("ValueError: count(): Argument #2 ($mode) must be either COUNT_NORMAL or COUNT_RECURSIVE", 1)
+    );
+}
+
+#[test]
+fn array_push_appends_to_the_variable() {
+    let code = "$stack = [5 => 'a', 'k' => 'b'];
+        $copy = $stack;
+        echo array_push($stack, 'c', 'd'), ' ';
+        echo $stack === [5 => 'a', 'k' => 'b', 6 => 'c', 7 => 'd'], ' ', count($copy);
+        echo ' ', array_push($stack), ' ', count($stack);";
+
+    assert_eq!(run(code), "4 1 2 4 4");
+}
+
+#[test]
+fn array_merge_renumbers_the_integer_keys_and_replaces_the_string_ones() {
+    let code =
+        "$merged = array_merge([3 => 'a', 'color' => 'red'], ['b', 'color' => 'blue', 9 => 'c']);
+        echo $merged === [0 => 'a', 'color' => 'blue', 1 => 'b', 2 => 'c'];
+        echo ' ', array_merge() === [], ' ', array_merge([5 => 'x']) === ['x'];";
+
+    assert_eq!(run(code), "1 1 1");
+    assert_eq!(
+        run("array_merge([], 'a');"),
+        common::uncaught(
+            "TypeError: array_merge(): Argument #2 must be of type array, string given",
+            1
+        )
+    );
+}
+
+#[test]
+fn array_keys_and_array_values_split_the_elements() {
+    let code = "$array = ['a' => 1, 5 => '1', 'c' => 2];
+        echo array_keys($array) === ['a', 5, 'c'], ' ', array_values($array) === [1, '1', 2];
+        echo ' ', array_keys($array, 1) === ['a', 5], ' ', array_keys($array, 1, true) === ['a'];
+        echo ' ', in_array('2', $array), ' ', in_array('2', $array, true) === false;";
+
+    assert_eq!(run(code), "1 1 1 1 1 1");
+}
+
+#[test]
+fn array_map_keeps_the_keys_of_a_single_array() {
+    let code = "$doubled = array_map(fn ($x) => $x * 2, ['a' => 1, 5 => 2]);
+        echo $doubled === ['a' => 2, 5 => 4];
+        echo ' ', array_map(null, [3 => 'x']) === [3 => 'x'];
+        echo ' ', array_map('strtoupper', ['b' => 'hi'])['b'];";
+
+    assert_eq!(run(code), "1 1 HI");
+}
+
+#[test]
+fn array_map_walks_several_arrays_together() {
+    let code = "$sums = array_map(fn ($a, $b) => ($a ?? 0) + $b, ['x' => 1, 2], [10, 20, 30]);
+        echo $sums === [11, 22, 30];
+        echo ' ', array_map(null, [1, 2], ['a']) === [[1, 'a'], [2, null]];";
+
+    assert_eq!(run(code), "1 1");
+    assert_eq!(
+        run("array_map(null, [], 'a');"),
+        common::uncaught(
+            "TypeError: array_map(): Argument #3 must be of type array, string given",
+            1
+        )
+    );
+}
+
+#[test]
+fn array_filter_keeps_the_keys_of_the_elements_it_keeps() {
+    let code = "$array = ['a' => 1, 'b' => 0, 'c' => 3, 'd' => null, 'e' => ''];
+        echo array_filter($array) === ['a' => 1, 'c' => 3];
+        echo ' ', array_filter([1, 2, 3, 4], fn ($x) => $x % 2 == 0) === [1 => 2, 3 => 4];
+        echo ' ', array_filter($array, fn ($key) => $key != 'a', ARRAY_FILTER_USE_KEY) === ['b' => 0, 'c' => 3, 'd' => null, 'e' => ''];
+        echo ' ', array_filter($array, fn ($value, $key) => $value && $key == 'c', ARRAY_FILTER_USE_BOTH) === ['c' => 3];";
+
+    assert_eq!(run(code), "1 1 1 1");
+}
+
+#[test]
+fn usort_sorts_with_the_callback_and_renumbers_the_keys() {
+    let code = "$array = ['x' => 3, 'y' => 1, 'z' => 2];
+        echo usort($array, fn ($a, $b) => $a <=> $b), ' ', $array === [1, 2, 3];
+        $people = [['ana', 30], ['bob', 25], ['eva', 30], ['dan', 25]];
+        usort($people, fn ($a, $b) => $a[1] - $b[1]);
+        echo ' ', $people[0][0], $people[1][0], $people[2][0], $people[3][0];
+        $floats = [0.5, 0.2];
+        usort($floats, fn ($a, $b) => $a - $b);
+        echo ' ', $floats[0];";
+
+    // the sort is stable, and 0.3 is truncated to 0 so the floats stay in their order
+    assert_eq!(run(code), "1 1 bobdananaeva 0.5");
+}
+
+#[test]
+fn methods_are_callbacks_given_with_their_object() {
+    let code = "class Math {
+            public function square($x) { return $x * $x; }
+            public function isOdd($x) { return $x % 2 == 1; }
+            public function compare($a, $b) { return $b <=> $a; }
+        }
+        $math = new Math();
+        echo array_map([$math, 'square'], [1, 2, 3]) === [1, 4, 9];
+        echo ' ', array_filter([1, 2, 3], [$math, 'isOdd']) === [0 => 1, 2 => 3];
+        $array = [1, 3, 2];
+        usort($array, [$math, 'compare']);
+        echo ' ', $array === [3, 2, 1];
+        function apply(callable $callback) { return $callback(4); }
+        echo ' ', apply([$math, 'square']);";
+
+    assert_eq!(run(code), "1 1 1 16");
+}
+
+#[test]
+fn callbacks_must_be_valid() {
+    assert_eq!(
+        run("array_map('missing', []);"),
+        common::uncaught("TypeError: array_map(): Argument #1 ($callback) must be a valid callback or null, function \"missing\" not found or invalid function name", 1)
+    );
+    assert_eq!(
+        run("class A {}\n$array = [];\nusort($array, [new A(), 'missing']);"),
+        common::uncaught("TypeError: usort(): Argument #2 ($callback) must be a valid callback, class A does not have a method \"missing\"", 3)
+    );
+    assert_eq!(
+        run("array_filter([], [1]);"),
+        common::uncaught("TypeError: array_filter(): Argument #2 ($callback) must be a valid callback or null, array callback must have exactly two members", 1)
+    );
+    assert_eq!(
+        run("array_filter([], 5);"),
+        common::uncaught("TypeError: array_filter(): Argument #2 ($callback) must be a valid callback or null, no array or string given", 1)
+    );
+}
+
+#[test]
+fn array_key_exists_finds_the_null_elements_that_isset_does_not() {
+    assert_eq!(
+        run("$array = ['a' => null, 1 => 0];
+            var_dump(array_key_exists('a', $array), isset($array['a']), array_key_exists('1', $array), array_key_exists('b', $array));"),
+        "bool(true)\nbool(false)\nbool(true)\nbool(false)\n"
+    );
+}
+
+#[test]
+fn array_search_gives_the_key_or_false() {
+    assert_eq!(
+        run("var_dump(array_search('1', [0, 'x' => 1, '1']), array_search('1', [0, 'x' => 1, '1'], true), array_search('missing', [1]));"),
+        "string(1) \"x\"\nint(1)\nbool(false)\n"
+    );
+}
+
+#[test]
+fn array_flip_skips_the_values_that_cannot_be_keys() {
+    let (output, warnings) = run_with_warnings(
+        "echo array_flip(['a', 'b', 'a', 'c' => 1, 1.5, [1]]) === ['a' => 2, 'b' => 1, 1 => 'c'];",
+    );
+
+    assert_eq!(output, "1");
+    assert_eq!(
+        warnings,
+        "PHP Warning: array_flip(): Can only flip string and integer values, entry skipped in test.php on line 1\n".repeat(2)
+    );
+}
+
+#[test]
+fn array_unique_keeps_the_first_of_the_duplicates() {
+    assert_eq!(
+        run("echo array_unique([1, '1', 2, 2.0, 'a', 'A']) === [0 => 1, 2 => 2, 4 => 'a', 5 => 'A'];
+            echo array_unique(['10', '1e1', 10]) === ['10', '1e1'];
+            echo array_unique(['10', '1e1', 10], SORT_NUMERIC) === ['10'];
+            echo array_unique([0, 'a', false], SORT_REGULAR) === [0, 'a'];"),
+        "1111"
+    );
+}
+
+#[test]
+fn array_slice_counts_negative_offsets_from_the_end() {
+    assert_eq!(
+        run("echo array_slice([1, 2, 3, 4, 5], -2) === [4, 5];
+            echo array_slice(['a' => 1, 5 => 2, 3], 1, 2) === [2, 3];
+            echo array_slice([1, 2, 3], 1, -1, true) === [1 => 2];
+            echo array_slice(['a' => 1, 'b' => 2], 1) === ['b' => 2];"),
+        "1111"
+    );
+}
+
+#[test]
+fn array_splice_changes_the_array_and_gives_the_removed_elements() {
+    assert_eq!(
+        run("$array = [1, 2, 3, 4, 5];
+            echo array_splice($array, 1, 2, ['x', 'y', 'z']) === [2, 3], $array === [1, 'x', 'y', 'z', 4, 5];
+            $array = ['a' => 1, 'b' => 2, 3];
+            echo array_splice($array, -1) === [3], $array === ['a' => 1, 'b' => 2];
+            $array = [1, 2];
+            echo array_splice($array, 1, 0, 'inserted') === [], $array === [1, 'inserted', 2];"),
+        "111111"
+    );
+}
+
+#[test]
+fn array_combine_and_array_fill_keys_convert_the_values_to_string_keys() {
+    let (output, warnings) = run_with_warnings(
+        "echo array_combine(['a', '5', 1.5, true], [1, 2, 3, 4]) === ['a' => 1, 5 => 2, '1.5' => 3, 1 => 4];
+        echo array_fill_keys(['a', 5, '07', null], 0) === ['a' => 0, 5 => 0, '07' => 0, '' => 0];",
+    );
+
+    assert_eq!(output, "11");
+    assert_eq!(warnings, "");
+}
+
+#[test]
+fn array_fill_starts_at_the_index() {
+    assert_eq!(
+        run("echo array_fill(5, 2, 'x') === [5 => 'x', 6 => 'x'], array_fill(-3, 2, 0) === [-3 => 0, -2 => 0], array_fill(0, 0, 1) === [];"),
+        "111"
+    );
+}
+
+#[test]
+fn range_gives_ints_floats_and_characters() {
+    assert_eq!(
+        run(
+            "echo range(1, 3) === [1, 2, 3], range(5, 1, 2) === [5, 3, 1];
+            echo range(0, 1, 0.25) === [0.0, 0.25, 0.5, 0.75, 1.0];
+            echo range('a', 'e', 2) === ['a', 'c', 'e'], range('C', 'A') === ['C', 'B', 'A'];"
+        ),
+        "11111"
+    );
+}
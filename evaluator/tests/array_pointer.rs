@@ -0,0 +1,71 @@
+mod common;
+
+use common::run;
+
+/// Walks the array from its pointer with next() until it gives false, there are no loops
+/// yet so it recurses.
+const WALK: &str = "function walk(array &$array) {
+    $value = current($array);
+    if ($value === false) { return ''; }
+    $key = key($array);
+    next($array);
+    return $key . '=' . $value . ' ' . walk($array);
+}
+";
+
+/// Runs `code` after the walk function and returns its output.
+fn run_walk(code: &str) -> String {
+    run(&format!("{}{}", WALK, code))
+}
+
+#[test]
+fn next_walks_the_array_until_it_gives_false() {
+    let code = "$array = ['x' => 1, 'y' => 2, 'z' => 3];
+        echo walk($array), '| ';
+        var_dump(current($array), key($array), next($array));";
+
+    assert_eq!(run_walk(code), "x=1 y=2 z=3 | bool(false)\nNULL\nbool(false)\n");
+}
+
+#[test]
+fn reset_and_end_move_the_pointer_after_a_change() {
+    let code = "$array = [1, 2];
+        end($array);
+        $array[] = 3;
+        echo current($array), ' ';
+        echo end($array), key($array), ' ', prev($array), ' ', reset($array), key($array), ' ';
+        array_splice($array, 0, 1);
+        echo reset($array), key($array), ' ', walk($array);";
+
+    assert_eq!(run_walk(code), "2 32 2 10 20 0=2 1=3 ");
+}
+
+#[test]
+fn the_pointer_is_copied_with_the_array() {
+    let code = "$array = [1, 2, 3];
+        next($array);
+        $copy = $array;
+        next($copy);
+        echo current($array), current($copy);";
+
+    assert_eq!(run_walk(code), "23");
+}
+
+#[test]
+fn the_ends_give_false_and_null() {
+    assert_eq!(
+        run("$empty = []; var_dump(current($empty), key($empty), reset($empty), end($empty));
+            $one = [1]; var_dump(prev($one), key($one));"),
+        "bool(false)\nNULL\nbool(false)\nbool(false)\nbool(false)\nNULL\n"
+    );
+}
+
+#[test]
+fn array_key_first_and_array_key_last_do_not_use_the_pointer() {
+    assert_eq!(
+        run("$array = ['a' => 1, 5 => 2, 'c' => 3];
+            next($array);
+            var_dump(array_key_first($array), array_key_last($array), key($array), array_key_first([]));"),
+        "string(1) \"a\"\nstring(1) \"c\"\nint(5)\nNULL\n"
+    );
+}
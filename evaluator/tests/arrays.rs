@@ -0,0 +1,236 @@
+mod common;
+
+use common::run_with_warnings;
+
+fn output(code: &str) -> String {
+    run_with_warnings(code).0
+}
+
+#[test]
+fn nested_arrays_can_be_read() {
+    assert_eq!(output("$a = [[1, 2], [3]]; echo $a[0][1], $a[1][0];"), "23");
+    assert_eq!(
+        output("$a = array(array(1), 'x' => [2, [3]]); echo $a['x'][1][0];"),
+        "3"
+    );
+}
+
+#[test]
+fn keys_are_converted_like_php() {
+    let code = "echo ['5' => 'a', 'b', 'x' => 'c', '07' => 'd', true => 'e', null => 'f']
+        === [5 => 'a', 6 => 'b', 'x' => 'c', '07' => 'd', 1 => 'e', '' => 'f'];";
+
+    assert_eq!(output(code), "1");
+}
+
+/// The keys written in PHP, the key they give in the array as var_dump() shows it, and
+/// whether the cast is deprecated.
+const KEY_CASTS: &[(&str, &str, bool)] = &[
+    ("1", "1", false),
+    ("'1'", "1", false),
+    ("'01'", "\"01\"", false),
+    ("'-1'", "-1", false),
+    ("'-0'", "\"-0\"", false),
+    ("'1.5'", "\"1.5\"", false),
+    ("' 1'", "\" 1\"", false),
+    ("'9223372036854775807'", "9223372036854775807", false),
+    ("'9223372036854775808'", "\"9223372036854775808\"", false),
+    ("true", "1", false),
+    ("false", "0", false),
+    ("null", "\"\"", false),
+    ("1.0", "1", false),
+    ("1.7", "1", true),
+    ("-1.5", "-1", true),
+    ("1.0E+20", "7766279631452241920", true),
+    ("-1.0E+20", "-7766279631452241920", true),
+];
+
+#[test]
+fn keys_are_cast_like_the_table_of_php() {
+    for &(key, cast, deprecated) in KEY_CASTS {
+        // the element is read back with the cast key, so the reads and the writes agree
+        let code = format!(
+            "$a = [{} => 'v']; var_dump($a); echo $a[{1}], isset($a[{1}]), array_key_exists({1}, $a);",
+            key, cast
+        );
+
+        let (output, warnings) = run_with_warnings(&code);
+
+        assert_eq!(
+            output,
+            format!("array(1) {{\n  [{}]=>\n  string(1) \"v\"\n}}\nv11", cast),
+            "key {}",
+            key
+        );
+
+        let deprecation = format!(
+            "PHP Deprecated: Implicit conversion from float {} to int loses precision in test.php on line 1\n",
+            key
+        );
+
+        assert_eq!(
+            warnings,
+            if deprecated {
+                deprecation
+            } else {
+                String::new()
+            },
+            "key {}",
+            key
+        );
+    }
+}
+
+#[test]
+fn appends_use_the_biggest_integer_key_plus_one() {
+    assert_eq!(
+        output("echo [3 => 'a', 1 => 'b', 'c'] === [3 => 'a', 1 => 'b', 4 => 'c'];"),
+        "1"
+    );
+    assert_eq!(
+        output("echo ['x' => 'a', 'b'] === ['x' => 'a', 0 => 'b'];"),
+        "1"
+    );
+    assert_eq!(
+        output("echo [-5 => 'a', 'b'] === [-5 => 'a', -4 => 'b'];"),
+        "1"
+    );
+}
+
+#[test]
+fn nothing_can_be_appended_after_the_biggest_int_key() {
+    let error = |line: usize| {
+        common::uncaught(
+            "Error: Cannot add element to the array as the next element is already occupied",
+            line,
+        )
+    };
+
+    assert_eq!(output("$a = [PHP_INT_MAX => 'a'];\n$a[] = 'b';"), error(2));
+    assert_eq!(output("$a = [PHP_INT_MAX => 'a', 'b'];"), error(1));
+    assert_eq!(
+        output("$a = [PHP_INT_MAX => 'a'];\narray_push($a, 'b');"),
+        error(2)
+    );
+}
+
+#[test]
+fn only_empty_arrays_are_false() {
+    assert_eq!(output("if ([]) { echo 'a'; } else { echo 'b'; }"), "b");
+    assert_eq!(output("if ([0]) { echo 'a'; }"), "a");
+    assert_eq!(output("if ([[]]) { echo 'a'; }"), "a");
+}
+
+#[test]
+fn arrays_are_written_as_array() {
+    assert_eq!(
+        run_with_warnings("echo [1];"),
+        (
+            "Array".to_string(),
+            "PHP Warning: Array to string conversion in test.php on line 1\n".to_string()
+        )
+    );
+}
+
+#[test]
+fn elements_can_be_written() {
+    assert_eq!(
+        output("$a = []; $a['k'] = 1; $a[0] = 2; $a['k'] = 3; echo $a === ['k' => 3, 0 => 2];"),
+        "1"
+    );
+    assert_eq!(output("$a = [1]; echo $a[0] = 5, $a[0];"), "55");
+}
+
+#[test]
+fn elements_can_be_appended() {
+    assert_eq!(output("$a[] = 'a'; $a[] = 'b'; echo $a === ['a', 'b'];"), "1");
+    assert_eq!(
+        output("$a = [-5 => 'a']; $a[] = 'b'; echo $a === [-5 => 'a', -4 => 'b'];"),
+        "1"
+    );
+}
+
+#[test]
+fn nested_arrays_are_created_when_they_are_written() {
+    let code = "$a = null; $a['x'][] = 1; $a['x'][] = 2; $a['y']['z'] = 3;
+        echo $a === ['x' => [1, 2], 'y' => ['z' => 3]];";
+
+    assert_eq!(run_with_warnings(code), ("1".to_string(), "".to_string()));
+}
+
+#[test]
+fn writing_an_element_does_not_change_the_copies() {
+    assert_eq!(output("$a = [1]; $b = $a; $b[0] = 2; echo $a[0], $b[0];"), "12");
+    assert_eq!(output("$a = [1]; $b = &$a; $b[0] = 2; echo $a[0], $b[0];"), "22");
+}
+
+#[test]
+fn false_is_converted_to_an_array_with_a_deprecation() {
+    assert_eq!(
+        run_with_warnings("$a = false; $a[] = 1; echo $a[0];"),
+        (
+            "1".to_string(),
+            "PHP Deprecated: Automatic conversion of false to array is deprecated in test.php on line 1\n"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn reading_a_missing_key_warns() {
+    assert_eq!(
+        run_with_warnings("$a = ['x' => 1]; echo $a['y'] === null;"),
+        (
+            "1".to_string(),
+            "PHP Warning: Undefined array key \"y\" in test.php on line 1\n".to_string()
+        )
+    );
+}
+
+#[test]
+fn elements_of_properties_can_be_written() {
+    let code = "class A { public $items = []; public static $cache; }
+        $a = new A; $a->items[] = 1; $a->items['k'] = 2; A::$cache['x'][] = 3;
+        echo $a->items === [1, 'k' => 2], A::$cache['x'][0];";
+
+    assert_eq!(output(code), "13");
+}
+
+#[test]
+fn characters_of_strings_can_be_written() {
+    assert_eq!(
+        output("$s = 'abc'; $s[1] = 'X'; $s[-1] = 'Y'; $s[5] = 'Z'; echo $s;"),
+        "aXY  Z"
+    );
+    assert_eq!(
+        run_with_warnings("$s = 'abc'; echo $s[0] = 'def', $s;"),
+        (
+            "ddbc".to_string(),
+            "PHP Warning: Only the first byte will be assigned to the string offset in test.php on line 1\n"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn scalars_and_string_offsets_cannot_be_used_as_arrays() {
+    for (code, error) in [
+        ("$i = 1; $i[0] = 2;", "Cannot use a scalar value as an array"),
+        ("$f = 1.5; $f['x'] = 2;", "Cannot use a scalar value as an array"),
+        ("$s = 'abc'; $s['x'] = 'y';", "Cannot access offset of type string on string"),
+        ("$s = 'abc'; $s[1.5] = 'y';", "Cannot access offset of type float on string"),
+        ("$s = 'abc'; $s[0][0] = 'y';", "Cannot use string offset as an array"),
+    ] {
+        assert_eq!(
+            output(code),
+            format!("PHP Fatal error: {} in test.php on line 1\n", error),
+            "{}",
+            code
+        );
+    }
+
+    assert_eq!(
+        output("$s = 'abc'; $s[] = 'y';"),
+        common::uncaught("Error: [] operator not supported for strings", 1)
+    );
+}
@@ -0,0 +1,84 @@
+mod common;
+
+/// Classes with constants, one of them inherited and one redeclared.
+const CLASSES: &str = "
+class Shape {
+    const SIDES = 0;
+    const NAME = 'shape';
+
+    public function describe() { return self::NAME . ' ' . static::NAME . ' ' . static::SIDES; }
+}
+
+class Square extends Shape {
+    const SIDES = 4;
+    const NAME = 'square';
+
+    public function parentName() { return parent::NAME . ' ' . parent::class . ' ' . self::class; }
+}
+";
+
+/// Runs `code` after the classes and returns its output.
+fn run(code: &str) -> String {
+    common::run(&format!("{}\n{}", CLASSES, code))
+}
+
+#[test]
+fn constants_are_read_from_the_class_or_its_parents() {
+    let code = "class Cube extends Square {}
+        echo Shape::NAME, ' ', Square::SIDES, ' ', Cube::NAME, ' ', cube::SIDES;";
+
+    assert_eq!(run(code), "shape 4 square 4");
+}
+
+#[test]
+fn self_static_and_parent_refer_to_the_class_of_the_method() {
+    assert_eq!(
+        run("echo (new Square())->describe(), ' | ', (new Square())->parentName();"),
+        "shape square 4 | shape Shape Square"
+    );
+}
+
+#[test]
+fn class_gives_the_name_of_the_class() {
+    let code = "$square = new Square();
+        echo Square::class, ' ', Missing::class, ' ', $square::class, ' ', square::CLASS;";
+
+    assert_eq!(run(code), "Square Missing Square square");
+}
+
+#[test]
+fn undefined_constants_are_an_error() {
+    assert_eq!(
+        run("echo Square::COLOR;"),
+        "PHP Fatal error: Uncaught Error: Undefined constant Square::COLOR in test.php:16\nStack trace:\n#0 {main}\n  thrown in test.php on line 16\n"
+    );
+    assert_eq!(
+        run("echo Square::sides;"),
+        "PHP Fatal error: Uncaught Error: Undefined constant Square::sides in test.php:16\nStack trace:\n#0 {main}\n  thrown in test.php on line 16\n"
+    );
+}
+
+#[test]
+fn constants_can_be_used_in_constant_expressions() {
+    let code = "class Limits {
+            const MIN = 1;
+            const MAX = self::MIN + Square::SIDES;
+            const NAMES = [self::MIN => 'min', self::MAX => 'max'];
+
+            public $range = [self::MIN, self::MAX];
+            public static $default = self::MAX;
+        }
+        $names = [Limits::MIN => 'first'];
+        echo Limits::MAX, ' ', Limits::NAMES[5], ' ', $names[1], ' ', (new Limits())->range[1], ' ', Limits::$default;";
+
+    assert_eq!(run(code), "5 max first 5 5");
+}
+
+#[test]
+fn parameter_defaults_read_the_constants_when_the_function_is_called() {
+    let code = "function sides($shape = Square::SIDES) { return $shape; }
+        class Circle { const SIDES = 1; public function sides($sides = self::SIDES) { return $sides; } }
+        echo sides(), ' ', (new Circle())->sides();";
+
+    assert_eq!(run(code), "4 1");
+}
@@ -0,0 +1,106 @@
+mod common;
+
+/// Classes with doc comments and inherited members.
+const CLASSES: &str = "
+/** A collection. */
+abstract class Base implements Countable {
+    const VERSION = 2;
+
+    /** @var int[] */
+    protected array $items = [];
+
+    public static $instances = 0;
+
+    /** Creates it. */
+    public function __construct(public string $name = 'base') {}
+
+    /** Counts the items. */
+    public function count(): int { return 0; }
+}
+
+final class Child extends Base implements Countable, Stringable {
+    const LIMIT = 10;
+
+    public function count(): int { return 1; }
+
+    final protected static function &make(?int $size = null, string ...$names) {}
+}
+";
+
+/// Runs `code` after the classes and returns its output.
+fn run(code: &str) -> String {
+    common::run(&format!("{} {}", CLASSES, code))
+}
+
+#[test]
+fn the_class_is_described() {
+    let code = "$info = phpl_class_info('child');
+        echo $info['name'], ' ', $info['parent'], ' ', $info['final'] === true, ' ', $info['abstract'] === false;
+        echo ' ', $info['interfaces'] === ['Countable', 'Stringable'], ' ', $info['doc_comment'] === false;
+        echo ' ', phpl_class_info('Base')['doc_comment'];";
+
+    assert_eq!(run(code), "Child Base 1 1 1 1 /** A collection. */");
+}
+
+#[test]
+fn inherited_constants_come_after_the_own_ones() {
+    assert_eq!(
+        run("echo phpl_class_info('Child')['constants'] === ['LIMIT' => 10, 'VERSION' => 2];"),
+        "1"
+    );
+}
+
+#[test]
+fn properties_are_described_with_their_declaring_class() {
+    let code = "$properties = phpl_class_info('Child')['properties'];
+        echo $properties['items'] === [
+            'class' => 'Base',
+            'visibility' => 'protected',
+            'static' => false,
+            'readonly' => false,
+            'type' => 'array',
+            'default' => [],
+            'doc_comment' => '/** @var int[] */',
+        ];
+        echo $properties['name']['type'], ' ', $properties['name']['visibility'];
+        echo ' ', $properties['instances']['static'] === true, ' ', $properties['instances']['type'] === null;
+        echo ' ', array_keys($properties) === ['items', 'name', 'instances'];";
+
+    assert_eq!(run(code), "1string public 1 1 1");
+}
+
+#[test]
+fn methods_have_a_signature_and_overridden_ones_are_listed_once() {
+    let code = "$methods = phpl_class_info('Child')['methods'];
+        echo array_keys($methods) === ['count', 'make', '__construct'], \"\\n\";
+        echo $methods['count']['class'], ' ', $methods['count']['doc_comment'] === false, \"\\n\";
+        echo $methods['make']['signature'], \"\\n\";
+        echo $methods['__construct']['signature'], ' ', $methods['__construct']['doc_comment'];";
+
+    assert_eq!(
+        run(code),
+        "1\nChild 1\nfinal protected static function &make(?int $size = null, string ...$names)\n\
+         public function __construct(string $name = 'base') /** Creates it. */"
+    );
+}
+
+#[test]
+fn unknown_classes_are_an_error() {
+    assert_eq!(
+        run("phpl_class_info('Missing');"),
+        "PHP Fatal error: phpl_class_info(): Argument #1 ($class) must be a valid class name, Missing given in test.php on line 25\n"
+    );
+}
+
+#[test]
+fn class_exists_once_the_declaration_has_run() {
+    let code = "var_dump(class_exists('child'), class_exists('\\\\Base'));
+        var_dump(class_exists('Countable'), class_exists('Later'));
+        if (true) { class Later {} }
+        var_dump(class_exists('Later', false));";
+
+    assert_eq!(
+        run(code),
+        "bool(true)\nbool(true)\nbool(false)\nbool(false)\nbool(true)\n"
+    );
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use common::run_with_status;
+
+/// Runs `code` after the opening tag and returns whether it ran without errors and its output.
+fn run(code: &str) -> (bool, String) {
+    let (output, status) = run_with_status(code);
+
+    (status == 0, output)
+}
+
+#[test]
+fn type_names_cannot_be_class_names() {
+    for name in ["int", "Mixed", "never", "iterable", "VOID", "object"] {
+        assert_eq!(
+            run(&format!("class {} {{}} echo 'ok';", name)),
+            (
+                false,
+                format!(
+                    "PHP Fatal error: Cannot use '{}' as class name as it is reserved in test.php on line 1\n",
+                    name
+                )
+            )
+        );
+    }
+}
+
+#[test]
+fn keywords_are_rejected_by_the_parser() {
+    for name in ["List", "fn", "self", "true"] {
+        let (success, _) = run(&format!("class {} {{}}", name));
+
+        assert!(!success, "{}", name);
+    }
+}
+
+#[test]
+fn soft_reserved_and_lookalike_names_are_allowed() {
+    for name in [
+        "resource", "numeric", "enum", "Integer", "Strings", "MixedBag", "voids",
+    ] {
+        assert_eq!(
+            run(&format!("class {} {{}} echo 'ok';", name)),
+            (true, "ok".to_string()),
+            "{}",
+            name
+        );
+    }
+}
@@ -0,0 +1,85 @@
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+use evaluator::program::{Clock, EvalOptions};
+
+mod common;
+
+use common::eval_with_options;
+
+/// A wall clock that never moves.
+struct FixedClock(SystemTime);
+
+impl Clock for FixedClock {
+    fn now(&self) -> SystemTime {
+        self.0
+    }
+}
+
+/// A function that takes some time, to measure it.
+const BUSY: &str = "function busy($n) { if ($n > 0) { range(1, 1000); busy($n - 1); } }";
+
+/// Runs `code` after the opening tag and the busy function, with the wall clock
+/// stopped at `now` if it is given, and returns its output.
+fn run(code: &str, now: Option<SystemTime>) -> String {
+    let content = format!("<?php {} {}", BUSY, code);
+
+    let options = EvalOptions {
+        clock: now.map(|now| Rc::new(FixedClock(now)) as Rc<dyn Clock>),
+        ..EvalOptions::default()
+    };
+
+    eval_with_options(&content, &options).output
+}
+
+/// 2023-11-14 22:13:20.25 UTC, its seconds are exact as a float.
+fn fixed_time() -> SystemTime {
+    SystemTime::UNIX_EPOCH + Duration::from_millis(1_700_000_000_250)
+}
+
+#[test]
+fn the_wall_clock_can_be_fixed() {
+    assert_eq!(
+        run(
+            "echo time(), '|', microtime(), '|', microtime(true);",
+            Some(fixed_time())
+        ),
+        "1700000000|0.25000000 1700000000|1700000000.25"
+    );
+}
+
+#[test]
+fn the_wall_clock_is_the_system_clock_by_default() {
+    assert_eq!(
+        run(
+            "echo time() > 1700000000, ' ', microtime(true) > 1700000000;",
+            None
+        ),
+        "1 1"
+    );
+}
+
+#[test]
+fn hrtime_increases() {
+    let code = "$start = hrtime(true); busy(10); $end = hrtime(true);
+        echo $end > $start, ' ', $end - $end === 0;";
+
+    assert_eq!(run(code, None), "1 1");
+}
+
+#[test]
+fn hrtime_gives_seconds_and_nanoseconds_without_its_argument() {
+    let code = "$time = hrtime();
+        echo array_keys($time) === [0, 1], ' ', $time[0] >= 0, ' ', $time[1] < 1000000000;
+        echo ' ', $time[0] - $time[0] === 0, ' ', $time[1] - $time[1] === 0;";
+
+    assert_eq!(run(code, None), "1 1 1 1 1");
+}
+
+#[test]
+fn a_fixed_wall_clock_does_not_stop_hrtime() {
+    let code = "$start = hrtime(true); $time = time(); busy(10);
+        echo hrtime(true) > $start, ' ', time() === $time;";
+
+    assert_eq!(run(code, Some(fixed_time())), "1 1");
+}
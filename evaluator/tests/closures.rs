@@ -0,0 +1,155 @@
+mod common;
+
+use common::{run, warnings};
+
+#[test]
+fn a_closure_is_called_through_the_variable_that_holds_it() {
+    let code = "$add = function (int $a, int $b = 10): int { return $a + $b; };
+        echo $add(1, 2), ' ', $add(1), ' ', $add(b: 5, a: 2);";
+
+    assert_eq!(run(code), "3 11 7");
+}
+
+#[test]
+fn variables_used_by_value_are_copied_when_the_closure_is_created() {
+    let code = "$count = 1;
+        $get = function () use ($count) { $count++; return $count; };
+        $count = 5;
+        echo $get(), ' ', $get(), ' ', $count;";
+
+    assert_eq!(run(code), "2 2 5");
+}
+
+#[test]
+fn variables_used_by_reference_are_shared_with_the_outer_scope() {
+    let code = "$total = 0;
+        $add = function ($amount) use (&$total) { $total += $amount; };
+        $add(2); $add(3);
+        echo $total, ' ';
+        $total = 10; $add(1);
+        echo $total, ' ';
+        $counter = function () use (&$created) { $created = 'yes'; };
+        $counter();
+        echo $created;";
+
+    assert_eq!(run(code), "5 11 yes");
+}
+
+#[test]
+fn an_arrow_function_reads_the_outer_variables_by_value() {
+    let code = "$factor = 3;
+        $multiply = fn ($x) => $x * $factor;
+        $factor = 100;
+        echo $multiply(2), ' ';
+        $increment = fn () => $factor++;
+        $increment();
+        echo $factor, ' ';
+        $adder = fn ($a) => fn ($b) => $a + $b;
+        echo $adder(1)(2);";
+
+    assert_eq!(run(code), "6 100 3");
+}
+
+#[test]
+fn closures_keep_the_object_of_the_method_that_created_them() {
+    let code = "class Counter {
+            private $count = 0;
+            public function incrementer() { return function () { return ++$this->count; }; }
+            public function reader() { return fn () => $this->count; }
+        }
+        $counter = new Counter();
+        $increment = $counter->incrementer();
+        $increment(); $increment();
+        echo $counter->reader()();";
+
+    assert_eq!(run(code), "2");
+}
+
+#[test]
+fn closures_are_callables() {
+    let code = "set_error_handler(function ($errno, $message, $file, $line) { echo 'handled: ', $message; return true; });
+        echo $undefined;
+        function apply(callable $callback, Closure $other) { return $callback(1) . $other(2); }
+        echo ' ', apply(fn ($x) => $x + 1, fn ($x) => $x * 2);";
+
+    assert_eq!(run(code), "handled: Undefined variable $undefined 24");
+}
+
+#[test]
+fn first_class_callables_give_the_function_as_a_closure() {
+    let code = "function greet($name) { return 'hi ' . $name; }
+        $greet = greet(...);
+        $format = sprintf(...);
+        echo $greet('ana'), ' ', $format('%d-%d', 1, 2);
+        $array = [1, 2, 3];
+        $next = next(...);
+        $next($array);
+        echo ' ', current($array);";
+
+    assert_eq!(run(code), "hi ana 1-2 2");
+}
+
+#[test]
+fn first_class_callables_give_the_methods_as_closures() {
+    let code = "class A {
+            private $name = 'a';
+            public function name($suffix) { return $this->name . $suffix . ' ' . static::class; }
+            public static function make($suffix) { return 'make' . $suffix . ' ' . static::class; }
+            private function secret() { return 'secret'; }
+            public function secrets() { return $this->secret(...); }
+        }
+        class B extends A {}
+        $b = new B();
+        $name = $b->name(...);
+        echo $name(1), ', ', A::make(...)(2), ', ', B::make(...)(3), ', ', $b->secrets()(), \"\\n\";
+        try { $b->secret(...); } catch (Error $e) { echo $e->getMessage(), \"\\n\"; }
+        try { A::name(...); } catch (Error $e) { echo $e->getMessage(), \"\\n\"; }
+        try { $b(); } catch (Error $e) { echo $e->getMessage(); }";
+
+    assert_eq!(
+        run(code),
+        "a1 B, make2 A, make3 B, secret
+Call to private method A::secret() from global scope
+Non-static method A::name() cannot be called statically
+Object of type B is not callable"
+    );
+}
+
+#[test]
+fn closures_are_objects_of_the_closure_class() {
+    let code = "class A {}
+        $a = new A();
+        $closure = fn () => 1;
+        $length = strlen(...);
+        var_dump($closure instanceof Closure, $closure instanceof A, $a instanceof Closure);
+        echo gettype($closure), ' ', spl_object_id($a), ' ', spl_object_id($closure), ' ',
+            spl_object_id($length), ' ', spl_object_hash($closure), \"\\n\";
+        var_dump($length);";
+
+    assert_eq!(
+        run(code),
+        "bool(true)
+bool(false)
+bool(false)
+object 1 2 3 00000000000000020000000000000000
+object(Closure)#3 (0) {
+}
+"
+    );
+}
+
+#[test]
+fn invalid_closures_are_errors() {
+    assert_eq!(
+        run("$f = function ($a) use ($a) {};"),
+        "PHP Fatal error: Cannot use lexical variable $a as a parameter name in test.php on line 1\n"
+    );
+    assert_eq!(
+        run("$f = missing(...);"),
+        "PHP Fatal error: Uncaught Error: Call to undefined function missing() in test.php:1\nStack trace:\n#0 {main}\n  thrown in test.php on line 1\n"
+    );
+    assert_eq!(
+        warnings("$f = function () use ($missing) { return $missing; }; $f();"),
+        "PHP Warning: Undefined variable $missing in test.php on line 1\n"
+    );
+}
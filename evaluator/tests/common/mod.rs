@@ -0,0 +1,81 @@
+//! The helpers shared by the integration tests, each test file uses some of them.
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::PathBuf;
+
+use evaluator::interpreter::{EvalResult, Interpreter};
+use evaluator::program::EvalOptions;
+
+/// Evaluates `content` as the file test.php with the given options.
+pub fn eval_with_options(content: &str, options: &EvalOptions) -> EvalResult {
+    Interpreter::with_options(options)
+        .eval_script("test.php", content)
+        .unwrap()
+}
+
+/// Evaluates `content` as the file test.php.
+pub fn eval(content: &str) -> EvalResult {
+    eval_with_options(content, &EvalOptions::default())
+}
+
+/// Runs `code` after the opening tag and returns its output.
+pub fn run(code: &str) -> String {
+    eval(&format!("<?php {}", code)).output
+}
+
+/// Runs `code` after the opening tag and returns its output and its warnings, one per line.
+pub fn run_with_warnings(code: &str) -> (String, String) {
+    let result = eval(&format!("<?php {}", code));
+
+    let warnings = warnings_of(&result);
+
+    (result.output, warnings)
+}
+
+/// Runs `code` after the opening tag and returns its output and its exit status.
+pub fn run_with_status(code: &str) -> (String, i32) {
+    let result = eval(&format!("<?php {}", code));
+
+    (result.output, result.exit_status)
+}
+
+/// Runs `code` after the opening tag and returns its warnings.
+pub fn warnings(code: &str) -> String {
+    run_with_warnings(code).1
+}
+
+/// The warnings of an evaluation, written like the command line writes them.
+pub fn warnings_of(result: &EvalResult) -> String {
+    result
+        .warnings
+        .iter()
+        .map(|warning| format!("{}\n", warning.clone().get_message()))
+        .collect()
+}
+
+/// The fatal error given on `line` of test.php.
+pub fn fatal(message: &str, line: usize) -> String {
+    format!(
+        "PHP Fatal error: {} in test.php on line {}\n",
+        message, line
+    )
+}
+
+/// The report of the Error thrown on `line` of test.php that no catch took, `error` is
+/// the class and the message, like "DivisionByZeroError: Division by zero".
+pub fn uncaught(error: &str, line: usize) -> String {
+    format!(
+        "PHP Fatal error: Uncaught {} in test.php:{}\nStack trace:\n#0 {{main}}\n  thrown in test.php on line {}\n",
+        error, line, line
+    )
+}
+
+/// Writes a file to the temporary directory and returns its path.
+pub fn write_file(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("phpl-{}-{}", std::process::id(), name));
+
+    fs::write(&path, content).unwrap();
+
+    path
+}
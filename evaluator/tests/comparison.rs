@@ -0,0 +1,202 @@
+mod common;
+
+use common::run;
+
+/// The cases where PHP 8 changed the comparison of strings and numbers,
+/// with whether the values are equal.
+const TRAPS: &[(&str, &str, bool)] = &[
+    ("'abc'", "0", false),
+    ("'1'", "'01'", true),
+    ("'10'", "'1e1'", true),
+    ("100", "'1e2'", true),
+    ("null", "'0'", false),
+    ("'abc'", "'ABC'", false),
+    ("' 1'", "1", true),
+];
+
+/// Writes "1" when the values are equal and "0" otherwise, for every way of comparing them.
+fn compare(left: &str, right: &str) -> String {
+    let code = format!(
+        "if ({left} == {right}) {{ echo '1'; }} else {{ echo '0'; }}
+        if ({left} != {right}) {{ echo '0'; }} else {{ echo '1'; }}
+        if (in_array({left}, [{right}])) {{ echo '1'; }} else {{ echo '0'; }}
+        if (array_search({left}, ['x', {right}]) === 1) {{ echo '1'; }} else {{ echo '0'; }}
+        if (array_keys(['x', {right}], {left}) === [1]) {{ echo '1'; }} else {{ echo '0'; }}",
+    );
+
+    run(&code)
+}
+
+#[test]
+fn operators_and_search_functions_agree_on_the_php_8_rules() {
+    for (left, right, equal) in TRAPS {
+        let expected = if *equal { "11111" } else { "00000" };
+
+        assert_eq!(compare(left, right), expected, "{} == {}", left, right);
+        assert_eq!(compare(right, left), expected, "{} == {}", right, left);
+    }
+}
+
+#[test]
+fn strict_searches_compare_the_types() {
+    assert_eq!(run("echo in_array('1', [1], true) === false;"), "1");
+    assert_eq!(run("echo array_search('1', [1, '1'], true);"), "1");
+    assert_eq!(run("echo array_keys([1, '1', 1.0], 1, true) === [0];"), "1");
+    assert_eq!(run("echo '1' === '01';"), "");
+}
+
+#[test]
+fn array_keys_without_a_filter_value_returns_all_the_keys() {
+    assert_eq!(run("echo array_keys(['a' => 1, 2]) === ['a', 0];"), "1");
+}
+
+#[test]
+fn numeric_strings_are_ordered_as_numbers() {
+    assert_eq!(run("echo '10' < '9';"), "");
+    assert_eq!(run("echo 'abc' < 'abd';"), "1");
+    assert_eq!(run("echo 9 < '10';"), "1");
+}
+
+/// The values of the loose comparison table of the PHP manual.
+const TABLE_VALUES: &[&str] = &[
+    "true", "false", "1", "0", "-1", "'1'", "'0'", "'-1'", "null", "[]", "'php'", "''",
+];
+
+/// The rows of the table: `==` of a value with itself and the values after it, 1 for true.
+const LOOSE_TABLE: &[&str] = &[
+    "101011010010",
+    "10100101101",
+    "1001000000",
+    "100101000",
+    "10010000",
+    "1000000",
+    "100000",
+    "10000",
+    "1101",
+    "100",
+    "10",
+    "1",
+];
+
+#[test]
+fn loose_equality_follows_the_php_8_table() {
+    for (row, (left, expected)) in TABLE_VALUES.iter().zip(LOOSE_TABLE).enumerate() {
+        for (right, equal) in TABLE_VALUES[row..].iter().zip(expected.chars()) {
+            let code = format!(
+                "echo ({left} == {right}) ? 1 : 0, ({right} == {left}) ? 1 : 0, ({left} != {right}) ? 0 : 1;",
+            );
+
+            assert_eq!(
+                run(&code),
+                equal.to_string().repeat(3),
+                "{} == {}",
+                left,
+                right
+            );
+        }
+    }
+}
+
+/// Pairs of values with the result of `<=>` for them.
+const ORDERINGS: &[(&str, &str, i32)] = &[
+    ("null", "-1", -1),
+    ("null", "false", 0),
+    ("true", "2", 0),
+    ("'abc'", "'abd'", -1),
+    ("'10'", "'9'", 1),
+    ("'a'", "0", 1),
+    ("'1e1'", "9", 1),
+    ("[1, 2]", "[3]", 1),
+    ("[1, 2]", "[1, 3]", -1),
+    ("[]", "100", 1),
+    ("'Z'", "'a'", -1),
+    ("1.5", "'1.5'", 0),
+];
+
+#[test]
+fn ordering_operators_follow_the_php_8_rules() {
+    for (left, right, expected) in ORDERINGS {
+        let code = format!(
+            "echo {left} <=> {right}, ' ', {right} <=> {left}, ' ', ({left} < {right}) ? 1 : 0, ({left} <= {right}) ? 1 : 0, ({left} > {right}) ? 1 : 0, ({left} >= {right}) ? 1 : 0;",
+        );
+
+        let expected_operators = match expected {
+            -1 => "1100",
+            0 => "0101",
+            _ => "0011",
+        };
+
+        assert_eq!(
+            run(&code),
+            format!("{} {} {}", expected, -expected, expected_operators),
+            "{} <=> {}",
+            left,
+            right
+        );
+    }
+}
+
+#[test]
+fn comparing_arrays_that_contain_themselves_is_an_error() {
+    let arrays = "$a = [1]; $a[] = &$a;\n$b = [1]; $b[] = &$b;\n";
+
+    let fatal = |line: usize| {
+        format!(
+            "PHP Fatal error: Nesting level too deep - recursive dependency? in test.php on line {}\n",
+            line
+        )
+    };
+
+    assert_eq!(run(&format!("{}\nvar_dump($a == $b);", arrays)), fatal(4));
+    assert_eq!(
+        run(&format!("{}var_dump(\n    $a < $b);", arrays)),
+        fatal(4)
+    );
+    assert_eq!(run(&format!("{}\n\nin_array($a, [$b]);", arrays)), fatal(5));
+}
+
+/// Pairs of arrays with the results of `==` and `===` for them, `==` does not look at the
+/// order of the elements and compares them loosely, `===` does both.
+const ARRAY_EQUALITIES: &[(&str, &str, bool, bool)] = &[
+    ("[1, 2]", "[1, 2]", true, true),
+    ("['a' => 1, 'b' => 2]", "['b' => 2, 'a' => 1]", true, false),
+    ("[0 => 'x', 1 => 'y']", "[1 => 'y', 0 => 'x']", true, false),
+    ("[1, 2]", "['1', '2']", true, false),
+    ("[[1]]", "[['1']]", true, false),
+    ("['a' => 1]", "['a' => 1, 'b' => 2]", false, false),
+    ("['a' => 1]", "['b' => 1]", false, false),
+    ("[]", "[]", true, true),
+    ("[null]", "[false]", true, false),
+];
+
+#[test]
+fn arrays_are_equal_like_the_examples_of_php() {
+    for (left, right, equal, identical) in ARRAY_EQUALITIES {
+        let code = format!(
+            "echo ({left} == {right}) ? 1 : 0, ({right} == {left}) ? 1 : 0, ({left} === {right}) ? 1 : 0, ({left} != {right}) ? 1 : 0, ({left} !== {right}) ? 1 : 0;",
+        );
+
+        let expected = format!(
+            "{0}{0}{1}{2}{3}",
+            u8::from(*equal),
+            u8::from(*identical),
+            u8::from(!equal),
+            u8::from(!identical)
+        );
+
+        assert_eq!(run(&code), expected, "{} == {}", left, right);
+    }
+}
+
+#[test]
+fn arrays_with_different_keys_cannot_be_ordered() {
+    // the other array does not have the key, so each one is bigger than the other
+    assert_eq!(
+        run("echo ['a' => 1] <=> ['b' => 1], ['b' => 1] <=> ['a' => 1], ' ', (['a' => 1] < ['b' => 1]) ? 1 : 0;"),
+        "11 0"
+    );
+    assert_eq!(
+        run("echo ['a' => 1, 'b' => 5] <=> ['b' => 2, 'a' => 1];"),
+        "1"
+    );
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use common::{run, uncaught};
+
+#[test]
+fn only_the_branch_that_runs_declares_its_function() {
+    let code = "$flag = false;
+        if ($flag) { function helper() { return 'a'; } } else { function helper() { return 'b'; } }
+        echo helper();";
+
+    assert_eq!(run(code), "b");
+}
+
+#[test]
+fn a_class_exists_guard_declares_the_class_once() {
+    let code = "if (!class_exists('Config')) { class Config { const VERSION = 1; } }
+        if (!class_exists('Config')) { class Config { const VERSION = 2; } }
+        echo Config::VERSION;";
+
+    assert_eq!(run(code), "1");
+}
+
+#[test]
+fn a_conditional_function_is_not_hoisted() {
+    let code = "echo 'a';
+        later();
+        if (true) { function later() {} }";
+
+    assert_eq!(
+        run(code),
+        format!(
+            "a{}",
+            uncaught("Error: Call to undefined function later()", 2)
+        )
+    );
+    assert_eq!(
+        run("echo 'a';\nnew Later();\nif (true) { class Later {} }"),
+        format!("a{}", uncaught("Error: Class \"Later\" not found", 2))
+    );
+}
+
+#[test]
+fn a_top_level_function_is_hoisted() {
+    assert_eq!(run("echo early(); function early() { return 'early'; }"), "early");
+}
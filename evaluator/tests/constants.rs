@@ -0,0 +1,83 @@
+mod common;
+
+use common::run_with_warnings;
+
+/// The output of `code`, which must not give warnings.
+fn output(code: &str) -> String {
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(warnings, "");
+
+    output
+}
+
+#[test]
+fn constants_are_defined_with_const_and_define() {
+    let code = "const GREETING = 'hi', NAME = GREETING . ' ana';
+        var_dump(define('ANSWER', 6 * 7));
+        function show() { echo NAME, ' ', ANSWER, ' ', \\ANSWER; }
+        show();";
+
+    assert_eq!(output(code), "bool(true)\nhi ana 42 42");
+}
+
+#[test]
+fn defined_and_constant_read_constants_by_name() {
+    let code = "define('LIMIT', 10);
+        var_dump(defined('LIMIT'), defined('limit'), defined('MISSING'), defined('TRUE'));
+        var_dump(constant('LIMIT'), constant('null'));
+        class Box { const SIZE = 3; public static function size() { return constant('self::SIZE'); } }
+        enum Suit { case Hearts; }
+        var_dump(constant('Box::SIZE'), Box::size(), constant('Suit::Hearts') === Suit::Hearts);";
+
+    assert_eq!(
+        output(code),
+        "bool(true)\nbool(false)\nbool(false)\nbool(true)\nint(10)\nNULL\nint(3)\nint(3)\nbool(true)\n"
+    );
+}
+
+#[test]
+fn the_core_constants_are_predefined() {
+    let code = "echo 'a', PHP_EOL, 'b', PHP_EOL;
+        var_dump(PHP_INT_MAX, PHP_INT_MIN, PHP_FLOAT_EPSILON > 0, M_PI > 3.14 && M_PI < 3.15, TRUE, False, nULL);";
+
+    assert_eq!(
+        output(code),
+        "a\nb\nint(9223372036854775807)\nint(-9223372036854775808)\nbool(true)\nbool(true)\nbool(true)\nbool(false)\nNULL\n"
+    );
+}
+
+#[test]
+fn a_constant_cannot_be_redefined() {
+    let code = "const A = 1;
+define('A', 2);
+const A = 3;
+var_dump(define('TRUE', 0), A);";
+
+    assert_eq!(
+        run_with_warnings(code),
+        (
+            "bool(false)\nint(1)\n".to_string(),
+            "PHP Warning: Constant A already defined in test.php on line 2\n\
+            PHP Warning: Constant A already defined in test.php on line 3\n\
+            PHP Warning: Constant TRUE already defined in test.php on line 4\n"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn undefined_constants_are_errors() {
+    assert_eq!(
+        output("$a = 1;\necho MISSING;"),
+        "PHP Fatal error: Uncaught Error: Undefined constant \"MISSING\" in test.php:2\nStack trace:\n#0 {main}\n  thrown in test.php on line 2\n"
+    );
+    assert_eq!(
+        output("echo constant('MISSING');"),
+        "PHP Fatal error: Uncaught Error: Undefined constant \"MISSING\" in test.php:1\nStack trace:\n#0 {main}\n  thrown in test.php on line 1\n"
+    );
+    assert_eq!(
+        output("function f() {}\necho f;"),
+        "PHP Fatal error: Uncaught Error: Undefined constant \"f\" in test.php:2\nStack trace:\n#0 {main}\n  thrown in test.php on line 2\n"
+    );
+}
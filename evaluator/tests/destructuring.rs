@@ -0,0 +1,95 @@
+mod common;
+
+use common::run_with_warnings;
+
+#[test]
+fn the_elements_are_assigned_in_order() {
+    let code = "[$a, , $c] = [1, 2, 3]; list($d, list($e, $f)) = [4, [5, 6]];
+        ['y' => $y, 'x' => $x] = ['x' => 'x', 'y' => 'y'];
+        echo $a, $c, $d, $e, $f, $y, $x;";
+
+    assert_eq!(run_with_warnings(code).0, "13456yx");
+}
+
+#[test]
+fn the_swap_idiom_swaps() {
+    let code = "$a = 1; $b = 2; [$a, $b] = [$b, $a]; echo $a, $b;";
+
+    assert_eq!(run_with_warnings(code).0, "21");
+}
+
+#[test]
+fn the_swap_writes_through_references() {
+    let code = "$c = 1; $a = &$c; $b = 2; [$a, $b] = [$b, $a]; echo $a, $b, $c;";
+
+    assert_eq!(run_with_warnings(code).0, "212");
+}
+
+#[test]
+fn an_array_can_be_destructured_into_itself() {
+    let code = "$x = ['a', 'b']; [$x[0], $x[1]] = [$x[1], $x[0]]; echo $x[0], $x[1];
+        $y = ['a', 'b']; [$y[1], $y[0]] = $y; echo ' ', $y[0], $y[1];
+        $z = [1, [2, 3]]; [$z, [$first]] = $z; echo ' ', $first;";
+
+    assert_eq!(run_with_warnings(code).0, "ba ba 2");
+}
+
+#[test]
+fn the_targets_can_be_properties_and_elements() {
+    let code = "class Point { public $x; public static $y; }
+        $point = new Point(); $array = [];
+        [$point->x, Point::$y, $array['z'][]] = [1, 2, 3];
+        echo $point->x, Point::$y, $array['z'][0];";
+
+    assert_eq!(run_with_warnings(code).0, "123");
+}
+
+#[test]
+fn missing_elements_give_null() {
+    let (output, warnings) =
+        run_with_warnings("[$a, $b] = [1]; [$c] = null; echo $a, $b === null, $c === null;");
+
+    assert_eq!(output, "111");
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined array key 1 in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn keyed_and_unkeyed_entries_cannot_be_mixed() {
+    assert_eq!(
+        run_with_warnings("[$a, 'b' => $b] = [1, 'b' => 2];").0,
+        "PHP Fatal error: Cannot mix keyed and unkeyed array entries in assignments in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn the_entries_by_reference_share_the_elements() {
+    let code = "$array = [1, 2, [3, 4]];
+        [$a, &$b, [, &$d]] = $array;
+        $a = 10; $b = 20; $d = 40;
+        echo implode_array($array);
+        $point = ['x' => 1];
+        ['x' => &$x, 'y' => &$y] = $point;
+        $x = 5; $y = 6;
+        echo ' ', $point['x'], $point['y'];
+        $matrix = ['row' => [1]];
+        [&$cell] = $matrix['row'];
+        $cell = 'c';
+        echo ' ', $matrix['row'][0];
+        function implode_array($array) { return $array[0] . $array[1] . $array[2][0] . $array[2][1]; }";
+
+    assert_eq!(
+        run_with_warnings(code),
+        ("120340 56 c".to_string(), String::new())
+    );
+}
+
+#[test]
+fn only_variables_can_be_destructured_by_reference() {
+    assert_eq!(
+        run_with_warnings("[&$a] = [1];").0,
+        "PHP Fatal error: Cannot assign reference to non referenceable value in test.php on line 1\n"
+    );
+}
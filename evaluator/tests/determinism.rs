@@ -0,0 +1,216 @@
+use std::fs;
+use std::path::Path;
+
+mod common;
+
+use common::{eval, warnings_of};
+
+/// A script that lists variables, classes and members, and emits warnings.
+const FIXTURE: &str = "<?php
+/** A shape. */
+abstract class Shape implements Countable {
+    const SIDES = 0;
+    public static $count = 0;
+    protected $name = 'shape';
+    public function __construct(public int $size = 1) {}
+    public function count(): int { return 0; }
+    abstract public function area();
+    public function describe() {}
+    private function secret() {}
+    protected static function create() {}
+}
+
+final class Square extends Shape {
+    const SIDES = 4;
+    public $color = 'red';
+    public function area() { return 1; }
+    public function perimeter() {}
+}
+
+function locals($first, $second = 2) {
+    $z = 1;
+    $a = 2;
+    $m = [3];
+    unset($a);
+    $a = 4;
+
+    return get_defined_vars();
+}
+
+$zeta = 1;
+$alpha = 2;
+$middle = 3;
+
+echo array_slice(array_keys(get_defined_vars()), 7)
+    === ['zeta', 'alpha', 'middle'], \"\\n\";
+echo array_keys(locals(1)) === ['first', 'second', 'z', 'm', 'a'], \"\\n\";
+
+$info = phpl_class_info('Square');
+
+echo array_keys($info['methods'])
+    === ['area', 'perimeter', '__construct', 'count', 'describe', 'secret', 'create'], \"\\n\";
+echo array_keys($info['properties']) === ['color', 'name', 'size', 'count'], \"\\n\";
+echo array_keys($info['constants']) === ['SIDES'], \"\\n\";
+
+$array = ['x' => 1, 'y' => 2, 3];
+echo $array, $undefined, \"\\n\";
+echo sprintf('%05.1f|%s', 2.675, $array), \"\\n\";
+";
+
+/// Runs the fixture and returns its output and its warnings.
+fn run() -> (String, String) {
+    let result = eval(FIXTURE);
+
+    let warnings = warnings_of(&result);
+
+    (result.output, warnings)
+}
+
+#[test]
+fn running_a_script_twice_gives_the_same_output() {
+    let first = run();
+
+    for _ in 0..10 {
+        assert_eq!(run(), first);
+    }
+
+    assert_eq!(first.0, "1\n1\n1\n1\n1\nArray\n002.7|Array\n");
+}
+
+#[test]
+fn the_variables_are_listed_in_definition_order() {
+    let code = "<?php $b = 1; $a = 2; $c = null; unset($b); $b = 3;
+        echo array_keys(get_defined_vars())
+            === ['_SERVER', '_ENV', '_GET', '_POST', '_COOKIE', 'argv', 'argc', 'a', 'c', 'b'];";
+
+    assert_eq!(eval(code).output, "1");
+}
+
+/// The names of the HashMap fields and variables declared in a source file.
+fn hash_map_names(source: &str) -> Vec<String> {
+    let mut names = vec![];
+
+    for line in source.lines() {
+        let line = line.trim();
+
+        let declaration = line
+            .strip_prefix("pub ")
+            .or_else(|| line.strip_prefix("let mut "))
+            .or_else(|| line.strip_prefix("let "))
+            .unwrap_or(line);
+
+        let Some((name, rest)) = declaration.split_once(':') else {
+            continue;
+        };
+
+        let is_identifier = !name.is_empty()
+            && name
+                .bytes()
+                .all(|byte| byte.is_ascii_alphanumeric() || byte == b'_');
+
+        if is_identifier && rest.trim_start().starts_with("HashMap<") {
+            names.push(name.to_string());
+        }
+    }
+
+    names
+}
+
+/// Whether the line iterates over `name`, e.g. `self.methods.values()` or
+/// `for (key, value) in other.classes {`.
+fn iterates(line: &str, name: &str) -> bool {
+    let methods = [
+        "iter()",
+        "iter_mut()",
+        "keys()",
+        "values()",
+        "values_mut()",
+        "into_iter()",
+        "drain()",
+    ];
+
+    if methods
+        .iter()
+        .any(|method| line.contains(&format!(".{}.{}", name, method)))
+    {
+        return true;
+    }
+
+    let Some((_, iterable)) = line.trim().split_once(" in ") else {
+        return false;
+    };
+
+    line.trim().starts_with("for ") && {
+        let iterable = iterable.trim_end_matches('{').trim();
+
+        iterable == name || iterable.ends_with(&format!(".{}", name))
+    }
+}
+
+/// Every iteration of a HashMap must be marked with a comment that starts with
+/// "// order:" and says why its order cannot be seen, because the order of a HashMap
+/// changes from one run to the other. Anything that is listed to the user must come
+/// from an insertion-ordered container, like PhpArray or OrderedMap, or be sorted.
+#[test]
+fn every_iteration_of_a_hash_map_is_marked() {
+    let sources_directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("src");
+
+    let mut directories = vec![sources_directory];
+    let mut sources = vec![];
+
+    while let Some(directory) = directories.pop() {
+        for entry in fs::read_dir(directory).unwrap() {
+            let path = entry.unwrap().path();
+
+            if path.is_dir() {
+                directories.push(path);
+            } else if path.extension().is_some_and(|extension| extension == "rs") {
+                let source = fs::read_to_string(&path).unwrap();
+
+                sources.push((path, source));
+            }
+        }
+    }
+
+    let mut all_names: Vec<String> = sources
+        .iter()
+        .flat_map(|(_, source)| hash_map_names(source))
+        .collect();
+
+    // a name that is something else in another file is only checked where it is a HashMap
+    all_names.retain(|name| {
+        sources.iter().all(|(_, source)| {
+            !source.contains(&format!("{}: Vec<", name)) || hash_map_names(source).contains(name)
+        })
+    });
+
+    let mut unmarked = vec![];
+
+    for (path, source) in &sources {
+        let own_names = hash_map_names(source);
+        let lines: Vec<_> = source.lines().collect();
+
+        for (index, line) in lines.iter().enumerate() {
+            let names = all_names.iter().chain(own_names.iter());
+
+            let is_iteration = names.into_iter().any(|name| iterates(line, name));
+
+            let is_marked = line.contains("// order:")
+                || lines[..index]
+                    .iter()
+                    .rev()
+                    .take_while(|line| line.trim().starts_with("//"))
+                    .any(|line| line.trim().starts_with("// order:"));
+
+            if is_iteration && !is_marked {
+                unmarked.push(format!("{}:{}: {}", path.display(), index + 1, line.trim()));
+            }
+        }
+    }
+
+    assert!(
+        unmarked.is_empty(),
+        "HashMap iterations without an \"// order:\" comment:\n{}",
+        unmarked.join("\n")
+    );
+}
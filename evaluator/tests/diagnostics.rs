@@ -0,0 +1,100 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use evaluator::program::{
+    eval_program_with_options, DiagnosticsSink, EvalOptions, PhpError, ReportedError,
+};
+
+/// Records the writes of the output and the errors received by the sink, in order.
+#[derive(Default)]
+struct Events(Vec<String>);
+
+struct Output(Rc<RefCell<Events>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.is_empty() {
+            let text = String::from_utf8_lossy(buf);
+
+            self.0.borrow_mut().0.push(format!("write {}", text));
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl DiagnosticsSink for Events {
+    fn on_warning(&mut self, warning: &ReportedError) {
+        self.0.push(format!("warning {}", warning.error.message));
+    }
+
+    fn on_fatal(&mut self, error: &PhpError) {
+        self.0.push(format!("fatal {}", error.message));
+    }
+}
+
+fn run(content: &str, collect_warnings: bool) -> (Vec<String>, String) {
+    let events = Rc::new(RefCell::new(Events::default()));
+
+    let options = EvalOptions {
+        diagnostics: Some(events.clone()),
+        collect_warnings,
+        ..EvalOptions::default()
+    };
+
+    let mut output = Output(events.clone());
+    let mut warnings = vec![];
+
+    eval_program_with_options("test.php", content, &options, &mut output, &mut warnings).unwrap();
+
+    let events = std::mem::take(&mut events.borrow_mut().0);
+
+    (events, String::from_utf8(warnings).unwrap())
+}
+
+#[test]
+fn warning_arrives_between_the_writes_of_the_echoes() {
+    let (events, warnings) = run("<?php echo 'a'; $a = []; $a['x']; echo 'b';", false);
+
+    assert_eq!(
+        events,
+        vec![
+            "write a".to_string(),
+            "warning Undefined array key \"x\"".to_string(),
+            "write b".to_string(),
+        ]
+    );
+    assert_eq!(warnings, "");
+}
+
+#[test]
+fn warnings_are_still_written_when_collected() {
+    let (events, warnings) = run("<?php $a = []; $a['x'];", true);
+
+    assert_eq!(events, vec!["warning Undefined array key \"x\"".to_string()]);
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined array key \"x\" in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn silenced_warnings_do_not_reach_the_sink() {
+    let (events, _) = run("<?php $a = []; @$a['x']; echo 'a';", false);
+
+    assert_eq!(events, vec!["write a".to_string()]);
+}
+
+#[test]
+fn fatal_error_arrives_before_the_error_message() {
+    let (events, _) = run("<?php echo 'a'; undefined_function();", false);
+
+    assert_eq!(events.len(), 2);
+    assert!(events[0].starts_with("fatal "));
+    assert!(events[1].starts_with("write aPHP Fatal error: "));
+}
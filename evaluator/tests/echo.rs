@@ -0,0 +1,96 @@
+mod common;
+
+use common::{run, run_with_warnings};
+
+#[test]
+fn booleans_and_null_are_written_like_php() {
+    assert_eq!(run("echo true, '|', false, '|', null;"), "1||");
+    assert_eq!(run("print true; print false; print null;"), "1");
+}
+
+#[test]
+fn numbers_are_written_like_php() {
+    assert_eq!(
+        run("echo 42, '|', -7, '|', 1.0, '|', 1.5, '|', -0.0, '|', 0.1 + 0.2, '|', 1e20;"),
+        "42|-7|1|1.5|-0|0.3|1.0E+20"
+    );
+}
+
+#[test]
+fn interpolated_scalars_are_written_like_echo_writes_them() {
+    let code = "$t = true; $f = false; $n = null; $i = 3; $fl = 2.0;
+        echo \"[$t][$f][$n][$i][$fl]\";";
+
+    assert_eq!(run(code), "[1][][][3][2]");
+}
+
+#[test]
+fn the_arguments_of_echo_are_written_in_order() {
+    let code = "function loud() { echo '[inner]'; return 'b'; }
+        $a = 'a'; $array = [1];
+        echo $a, loud(), $array, 'c';";
+
+    assert_eq!(
+        run_with_warnings(code),
+        (
+            "a[inner]bArrayc".to_string(),
+            "PHP Warning: Array to string conversion in test.php on line 3\n".to_string()
+        )
+    );
+}
+
+#[test]
+fn an_error_stops_the_remaining_arguments_of_echo() {
+    assert_eq!(
+        run("echo 'a', missing(), 'b';"),
+        "aPHP Fatal error: Uncaught Error: Call to undefined function missing() in test.php:1\nStack trace:\n#0 {main}\n  thrown in test.php on line 1\n"
+    );
+    assert_eq!(
+        run("class A {} echo 'a', new A(), 'b';"),
+        format!(
+            "a{}",
+            common::uncaught(
+                "Error: Object of class A could not be converted to string",
+                1
+            )
+        )
+    );
+}
+
+/// Floats that are easy to write differently, with the string PHP gives for them.
+const TRICKY_FLOATS: &[(&str, &str)] = &[
+    ("1.0", "1"),
+    ("-0.0", "-0"),
+    ("1e20", "1.0E+20"),
+    ("1e-7", "1.0E-7"),
+    ("PHP_INT_MAX + 1", "9.2233720368548E+18"),
+    ("0.1 + 0.2", "0.3"),
+    ("-1.5", "-1.5"),
+    ("0.123456789012345", "0.12345678901234"),
+];
+
+/// The same with another precision setting, they are rounded from the binary value.
+const TRICKY_FLOATS_WITH_PRECISION: &[(i64, &str, &str)] =
+    &[(2, "1.45", "1.4"), (17, "0.1", "0.10000000000000001")];
+
+#[test]
+fn every_conversion_to_string_writes_a_float_the_same_way() {
+    let default_precision = TRICKY_FLOATS
+        .iter()
+        .map(|(float, expected)| (14, float, expected));
+
+    let floats = default_precision.chain(
+        TRICKY_FLOATS_WITH_PRECISION
+            .iter()
+            .map(|(precision, float, expected)| (*precision, float, expected)),
+    );
+
+    for (precision, float, expected) in floats {
+        let code = format!(
+            "ini_set('precision', {}); $v = {}; echo $v, '|', $v . '', '|', \"$v\", '|', sprintf('%s', $v), '|', strval($v), '|', print_r($v, true);",
+            precision, float
+        );
+
+        assert_eq!(run(&code), [*expected; 6].join("|"), "{}", float);
+    }
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use common::{fatal, uncaught};
+
+/// A pure enum and an int-backed enum with methods and a constant.
+const ENUMS: &str = "
+enum Direction {
+    case Up;
+    case Down;
+}
+
+interface HasColor {
+    public function color(): string;
+}
+
+enum Suit: int implements HasColor {
+    case Hearts = 1;
+    case Spades = 2;
+
+    const Wild = self::Spades;
+
+    public function color(): string {
+        if ($this === self::Hearts) {
+            return 'Red';
+        }
+        return 'Black';
+    }
+
+    public static function default(): self {
+        return self::from(1);
+    }
+}
+";
+
+/// Runs `code` after the enums and returns its output.
+fn run(code: &str) -> String {
+    common::run(&format!("{} {}", ENUMS, code))
+}
+
+#[test]
+fn cases_are_the_same_object_every_time() {
+    let code = "$up = Direction::Up;
+        echo $up === Direction::Up, ' ', $up === Direction::Down, ' ', $up == Direction::Down;
+        echo ' ', $up->name, ' ', Direction::Down->name;";
+
+    assert_eq!(run(code), "1   Up Down");
+}
+
+#[test]
+fn cases_are_instances_of_their_enum_and_of_its_interfaces() {
+    let code = "echo Direction::Up instanceof Direction, Direction::Up instanceof UnitEnum;
+        echo ' ', Suit::Hearts instanceof BackedEnum, Suit::Hearts instanceof UnitEnum, Suit::Hearts instanceof HasColor;
+        function paint(HasColor $colored) { return $colored->color(); }
+        echo ' ', paint(Suit::Hearts), ' ', Suit::Spades->color();";
+
+    assert_eq!(run(code), "11 111 Red Black");
+}
+
+#[test]
+fn backed_cases_round_trip_through_from() {
+    let code = "$suit = Suit::from(2);
+        echo $suit === Suit::Spades, ' ', $suit->name, ' ', $suit->value;
+        echo ' ', Suit::from(Suit::Hearts->value) === Suit::Hearts, ' ', Suit::default()->name;
+        echo ' ', Suit::tryFrom(3) === null, ' ', Suit::tryFrom(value: 1)->name, ' ', Suit::from('2')->name;";
+
+    assert_eq!(run(code), "1 Spades 2 1 Hearts 1 Hearts Spades");
+}
+
+#[test]
+fn from_fails_with_a_value_that_has_no_case() {
+    assert_eq!(
+        run("Suit::from(3);"),
+        common::uncaught(
+            "ValueError: 3 is not a valid backing value for enum Suit",
+            28
+        )
+    );
+    assert_eq!(
+        run("enum Size: string { case Small = 's'; }\nSize::from('xl');"),
+        common::uncaught(
+            "ValueError: \"xl\" is not a valid backing value for enum Size",
+            29
+        )
+    );
+    assert_eq!(
+        run("Suit::from([]);"),
+        common::uncaught(
+            "TypeError: Suit::from(): Argument #1 ($value) must be of type int, array given",
+            28
+        )
+    );
+}
+
+#[test]
+fn cases_lists_the_cases_in_declaration_order() {
+    let code = "$cases = Suit::cases();
+        echo $cases[0] === Suit::Hearts, ' ', $cases[1]->name;
+        echo ' ', Direction::cases()[1] === Direction::Down;";
+
+    assert_eq!(run(code), "1 Spades 1");
+}
+
+#[test]
+fn constants_can_refer_to_the_cases() {
+    assert_eq!(
+        run("echo Suit::Wild->name, ' ', Suit::Wild == Suit::Spades;"),
+        "Spades 1"
+    );
+}
+
+#[test]
+fn pure_enums_have_no_value_nor_from() {
+    assert_eq!(
+        run("Direction::from(1);"),
+        uncaught("Error: Call to undefined method Direction::from()", 28)
+    );
+}
+
+#[test]
+fn cases_cannot_be_changed_nor_created() {
+    assert_eq!(
+        run("$up = Direction::Up;\n$up->name = 'Left';"),
+        fatal("Cannot modify readonly property Direction::$name", 29)
+    );
+    assert_eq!(
+        run("Suit::Hearts->extra = 1;"),
+        fatal("Cannot create dynamic property Suit::$extra", 28)
+    );
+    assert_eq!(
+        run("new Direction();"),
+        uncaught("Error: Cannot instantiate enum Direction", 28)
+    );
+}
+
+#[test]
+fn invalid_enum_declarations_are_errors() {
+    assert_eq!(
+        run("enum Size: int { case Small = 's'; }"),
+        fatal(
+            "Enum case type string does not match enum backing type int",
+            28
+        )
+    );
+    assert_eq!(
+        run("enum Size: int { case Small = 1; case Tiny = 1; }"),
+        fatal("Duplicate value in enum Size for cases Small and Tiny", 28)
+    );
+    assert_eq!(
+        run("enum Size { case Small; const Small = 1; }"),
+        fatal("Cannot redefine class constant Size::Small", 28)
+    );
+    assert_eq!(
+        run("enum Size { public static function cases(): array { return []; } }"),
+        fatal("Cannot redeclare Size::cases()", 28)
+    );
+    assert_eq!(
+        run("class Size implements UnitEnum {}"),
+        fatal(
+            "Non-enum class Size cannot implement interface UnitEnum",
+            28
+        )
+    );
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use common::{run, run_with_warnings};
+
+#[test]
+fn the_handler_only_gets_the_arguments_it_declares() {
+    let code = "set_error_handler(function ($errno, $message) { echo 'handled: ', $message; return true; });
+        echo $missing;";
+
+    assert_eq!(run(code), "handled: Undefined variable $missing");
+
+    let code = "function handler($errno) { echo 'errno ', $errno; return true; }
+        set_error_handler('handler');
+        echo $missing;";
+
+    assert_eq!(run(code), "errno 2");
+}
+
+#[test]
+fn a_handler_that_returns_true_swallows_the_warning() {
+    let code = "set_error_handler(function ($errno, $message, $file, $line) {
+            echo $message, ' on line ', $line, \"\\n\";
+            return true;
+        });
+        echo $missing;";
+
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(output, "Undefined variable $missing on line 5\n");
+    assert_eq!(warnings, "");
+}
+
+#[test]
+fn a_handler_that_returns_false_gives_the_warning() {
+    let code = "set_error_handler(function ($errno, $message) { echo 'seen '; return false; });
+        echo $missing;";
+
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(output, "seen ");
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined variable $missing in test.php on line 2\n"
+    );
+}
+
+#[test]
+fn a_warning_of_the_handler_is_not_handled_again() {
+    let code = "set_error_handler(function ($errno, $message) { echo 'handled '; echo $inner; return true; });
+        echo $outer;";
+
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(output, "handled ");
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined variable $inner in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn the_previous_handler_is_restored() {
+    let code = "set_error_handler(function ($errno, $message) { echo 'handled '; return true; });
+        restore_error_handler();
+        echo $missing;";
+
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(output, "");
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined variable $missing in test.php on line 3\n"
+    );
+}
+
+#[test]
+fn trigger_error_raises_the_user_levels() {
+    let code = "trigger_error('careful', E_USER_WARNING);
+        trigger_error('note');
+        trigger_error('old', E_USER_DEPRECATED);
+        var_dump(E_ALL, E_USER_ERROR | E_USER_WARNING);";
+
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(output, "int(32767)\nint(768)\n");
+    assert_eq!(
+        warnings,
+        "PHP Warning: careful in test.php on line 1\n\
+        PHP Notice: note in test.php on line 2\n\
+        PHP Deprecated: old in test.php on line 3\n"
+    );
+}
+
+#[test]
+fn a_user_error_stops_the_script_unless_a_handler_takes_it() {
+    let code = "set_error_handler(function ($errno, $message, $file, $line) {
+            echo \"$errno $message $line\\n\";
+            return true;
+        }, E_USER_ERROR);
+        trigger_error('handled', E_USER_ERROR);
+        restore_error_handler();
+        trigger_error('stop', E_USER_ERROR);
+        echo 'never';";
+
+    assert_eq!(
+        run(code),
+        format!("256 handled 5\n{}", common::fatal("stop", 7))
+    );
+}
+
+#[test]
+fn trigger_error_only_takes_the_user_levels() {
+    assert_eq!(
+        run("trigger_error('x', E_WARNING);"),
+        common::uncaught(
+            "ValueError: trigger_error(): Argument #2 ($error_level) must be one of E_USER_ERROR, E_USER_WARNING, E_USER_NOTICE, or E_USER_DEPRECATED",
+            1
+        )
+    );
+}
+
+#[test]
+fn the_handler_must_be_a_valid_callback() {
+    assert_eq!(
+        run("set_error_handler('nope');"),
+        common::uncaught(
+            "TypeError: set_error_handler(): Argument #1 ($callback) must be a valid callback or null, function \"nope\" not found or invalid function name",
+            1
+        )
+    );
+}
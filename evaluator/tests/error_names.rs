@@ -0,0 +1,47 @@
+mod common;
+
+use common::run_with_warnings;
+
+#[test]
+fn control_characters_in_names_are_escaped() {
+    let (_, warnings) = run_with_warnings("$name = \"my\\nvar\\x1b[31m\"; echo $$name;");
+
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined variable $my\\nvar\\x1b[31m in test.php on line 1\n"
+    );
+    assert!(!warnings.trim_end().contains(|c: char| c.is_control()));
+}
+
+#[test]
+fn names_that_are_not_utf8_are_escaped() {
+    let (output, _) = run_with_warnings("$name = \"caf\\xe9\"; new $name();");
+
+    assert_eq!(
+        output,
+        "PHP Fatal error: Uncaught Error: Class \"caf\\xe9\" not found in test.php:1\nStack trace:\n#0 {main}\n  thrown in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn string_values_are_quoted_and_long_ones_are_cut() {
+    let long = format!("\\\"{}\\n", "x".repeat(2048));
+
+    let code = format!(
+        "class Config {{ const LONG = \"{}\"; }}
+        $key = phpl_class_info('Config')['constants']['LONG'];
+        $values = [];
+        echo $values[$key];",
+        long
+    );
+
+    let (_, warnings) = run_with_warnings(&code);
+
+    assert_eq!(
+        warnings,
+        format!(
+            "PHP Warning: Undefined array key \"\\\"{}...\" in test.php on line 4\n",
+            "x".repeat(127)
+        )
+    );
+}
@@ -0,0 +1,170 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn a_catch_takes_the_exceptions_of_its_class_and_of_the_subclasses() {
+    let code = "class NotFound extends Exception {}
+        function find($key) { if ($key !== 'a') { throw new NotFound('no ' . $key, 404); } return 'found'; }
+        try { echo find('a'), ' '; echo find('b'); echo 'never'; }
+        catch (NotFound $e) { echo $e->getMessage(), ' ', $e->getCode(), ' ', $e instanceof Throwable; }
+        try { throw new TypeError('typed'); } catch (\\Error $e) { echo ' ', $e->getMessage(); }";
+
+    assert_eq!(run(code), "found no b 404 1 typed");
+}
+
+#[test]
+fn the_first_catch_that_matches_takes_the_exception() {
+    let code = "function attempt($exception) {
+            try { throw $exception; }
+            catch (ValueError | TypeError $e) { return 'value or type'; }
+            catch (Error $e) { return 'error'; }
+            catch (Exception) { return 'exception'; }
+        }
+        echo attempt(new TypeError()), ', ', attempt(new ValueError()), ', ';
+        echo attempt(new DivisionByZeroError()), ', ', attempt(new Exception());";
+
+    assert_eq!(run(code), "value or type, value or type, error, exception");
+}
+
+#[test]
+fn an_exception_goes_up_until_a_catch_of_its_class() {
+    let code = "class Inner { public function fail() { throw new Exception('deep'); } }
+        try {
+            try { (new Inner())->fail(); }
+            catch (Error $e) { echo 'not an error'; }
+            echo 'never';
+        } catch (Exception $e) {
+            try { throw new Error('rethrown', 0, $e); }
+            catch (Error $error) { echo $error->getMessage(), ' ', $error->getPrevious()->getMessage(); }
+        }";
+
+    assert_eq!(run(code), "rethrown deep");
+}
+
+#[test]
+fn the_finally_block_always_runs() {
+    let code = "function value() { try { return 'try'; } finally { echo 'finally '; } }
+        function replaced() { try { return 'try'; } finally { return 'finally'; } }
+        function swallowed() { try { throw new Exception(); } finally { return 'swallowed'; } }
+        echo value(), ' ', replaced(), ' ', swallowed();
+        try { try { throw new Exception('up'); } finally { echo ' cleanup'; } }
+        catch (Exception $e) { echo ' ', $e->getMessage(); }";
+
+    assert_eq!(run(code), "finally try finally swallowed cleanup up");
+}
+
+#[test]
+fn an_exception_knows_where_it_was_created() {
+    let code = "function make() {\n    return new Exception('made');\n}\n$e = make();\ntry {\n    throw $e;\n} catch (Exception $e) {\n    echo $e->getLine(), ' ', $e->getFile();\n}";
+
+    assert_eq!(run(code), "2 test.php");
+}
+
+#[test]
+fn an_uncaught_exception_stops_the_program_and_keeps_the_output() {
+    assert_eq!(
+        run("echo 'before ';\nthrow new Exception('failed');\necho 'after';"),
+        "before PHP Fatal error: Uncaught Exception: failed in test.php:2\nStack trace:\n#0 {main}\n  thrown in test.php on line 2\n"
+    );
+    assert_eq!(
+        run("throw new Error();"),
+        "PHP Fatal error: Uncaught Error in test.php:1\nStack trace:\n#0 {main}\n  thrown in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn the_errors_of_the_engine_can_be_caught() {
+    let code = "enum Suit: int { case Hearts = 1; }
+        class Vault { private function open() {} }
+        function pair($a, $b) {}
+        function report($e) { echo $e->getMessage(), \"\\n\"; }
+        try { 1 % 0; } catch (DivisionByZeroError $e) { report($e); }
+        try { str_repeat('a', -1); } catch (ValueError $e) { report($e); }
+        try { array_combine([1], []); } catch (ValueError $e) { report($e); }
+        try { Suit::from(3); } catch (ValueError $e) { report($e); }
+        try { pair(1); } catch (ArgumentCountError $e) { report($e); }
+        try { match (5) { 1 => 'one' }; } catch (UnhandledMatchError $e) { report($e); }
+        try { (new Vault())->open(); } catch (Error $e) { report($e); }
+        try { $a = [PHP_INT_MAX => 1, 2]; } catch (Error $e) { report($e); }
+        try { strlen([]); } catch (TypeError $e) { report($e); }";
+
+    assert_eq!(
+        run(code),
+        "Modulo by zero
+str_repeat(): Argument #2 ($times) must be greater than or equal to 0
+array_combine(): Argument #1 ($keys) and argument #2 ($values) must have the same number of elements
+3 is not a valid backing value for enum Suit
+Too few arguments to function pair(), 1 passed in test.php on line 9 and exactly 2 expected
+Unhandled match case 5
+Call to private method Vault::open() from global scope
+Cannot add element to the array as the next element is already occupied
+strlen(): Argument #1 ($string) must be of type string, array given
+"
+    );
+}
+
+#[test]
+fn the_errors_about_missing_names_and_wrong_uses_can_be_caught() {
+    let code = "interface Shape {}
+        enum Suit { case Hearts; }
+        abstract class Base { public static function make() { return $this; } }
+        class Plain {}
+        function report($e) { echo $e->getMessage(), \"\\n\"; }
+        try { missing(); } catch (Error $e) { report($e); }
+        try { new Missing(); } catch (Throwable $e) { report($e); }
+        try { (new Plain())->missing(); } catch (Error $e) { report($e); }
+        try { Plain::missing(); } catch (Error $e) { report($e); }
+        try { $null = null; $null->m(); } catch (Error $e) { report($e); }
+        try { $null(); } catch (Error $e) { report($e); }
+        try { echo MISSING; } catch (Error $e) { report($e); }
+        try { echo Plain::MISSING; } catch (Error $e) { report($e); }
+        try { echo Plain::$missing; } catch (Error $e) { report($e); }
+        try { new Shape(); } catch (Error $e) { report($e); }
+        try { new Suit(); } catch (Error $e) { report($e); }
+        try { new Base(); } catch (Error $e) { report($e); }
+        try { Base::make(); } catch (Error $e) { report($e); }
+        try { $s = 'abc'; $s[] = 'd'; } catch (Error $e) { report($e); }
+        try { class Child extends MissingParent {} } catch (Error $e) { report($e); }
+        try { class Shaped implements MissingShape {} } catch (Error $e) { report($e); }";
+
+    assert_eq!(
+        run(code),
+        "Call to undefined function missing()
+Class \"Missing\" not found
+Call to undefined method Plain::missing()
+Call to undefined method Plain::missing()
+Call to a member function m() on null
+Value of type null is not callable
+Undefined constant \"MISSING\"
+Undefined constant Plain::MISSING
+Access to undeclared static property Plain::$missing
+Cannot instantiate interface Shape
+Cannot instantiate enum Suit
+Cannot instantiate abstract class Base
+Using $this when not in object context
+[] operator not supported for strings
+Class \"MissingParent\" not found
+Interface \"MissingShape\" not found
+"
+    );
+}
+
+#[test]
+fn an_error_of_the_engine_knows_where_it_was_raised() {
+    let code = "try {\n    echo 1;\n    echo 1 / 0;\n} catch (DivisionByZeroError $e) {\n    echo ' ', $e->getLine(), ' ', $e->getFile();\n}";
+
+    assert_eq!(run(code), "1 3 test.php");
+}
+
+#[test]
+fn only_throwable_objects_can_be_thrown() {
+    assert_eq!(
+        run("throw 'text';"),
+        "PHP Fatal error: Can only throw objects in test.php on line 1\n"
+    );
+    assert_eq!(
+        run("class Plain {}\nthrow new Plain();"),
+        "PHP Fatal error: Cannot throw objects that do not implement Throwable in test.php on line 2\n"
+    );
+}
@@ -0,0 +1,56 @@
+use std::fs;
+
+use evaluator::program::ERROR_EXIT_CODE;
+
+mod common;
+
+use common::{run_with_status, write_file};
+
+#[test]
+fn a_string_argument_is_output() {
+    assert_eq!(
+        run_with_status("echo 'a'; die('error: missing config'); echo 'b';"),
+        ("aerror: missing config".to_string(), 0)
+    );
+    assert_eq!(run_with_status("exit(1.5);"), ("1.5".to_string(), 0));
+}
+
+#[test]
+fn an_int_argument_is_the_exit_status() {
+    assert_eq!(
+        run_with_status("echo 'a'; exit(2); echo 'b';"),
+        ("a".to_string(), 2)
+    );
+    assert_eq!(run_with_status("die;"), ("".to_string(), 0));
+    assert_eq!(run_with_status("exit();"), ("".to_string(), 0));
+}
+
+#[test]
+fn errors_have_their_own_exit_status() {
+    assert_eq!(run_with_status("missing();").1, ERROR_EXIT_CODE);
+    assert_eq!(run_with_status("echo 'done';").1, 0);
+}
+
+#[test]
+fn exit_in_a_function_stops_the_program() {
+    let code = "function stop() { exit(3); }
+        register_shutdown_function(function () { echo 'shutdown'; });
+        stop(); echo 'never';";
+
+    assert_eq!(run_with_status(code), ("shutdown".to_string(), 3));
+}
+
+#[test]
+fn exit_in_an_included_file_stops_the_including_file() {
+    let included = write_file("exits.php", "<?php echo 'inside '; exit(4); echo 'never';");
+
+    let (output, status) = run_with_status(&format!(
+        "echo 'before '; include '{}'; echo 'after';",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(output, "before inside ");
+    assert_eq!(status, 4);
+}
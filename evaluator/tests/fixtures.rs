@@ -0,0 +1,209 @@
+//! Runs the scripts of `tests/fixtures` and compares what they print with their `.out` file.
+//!
+//! Each `name.php` has a `name.out` with the output and the warnings, as `phpl name.php`
+//! prints them. When the `.out` file starts with an `--EXPECTF--` line, the rest of it is
+//! a pattern, like in the .phpt files of PHP:
+//!
+//! - `%s` is one or more characters of a line, `%S` zero or more
+//! - `%a` is one or more characters, new lines included, `%A` zero or more
+//! - `%d` is one or more digits, `%i` an int with an optional sign
+//! - `%f` is a float, `%%` is `%`
+//!
+//! The files included by the fixtures are in `tests/fixtures/included`.
+//!
+//! `PHPL_UPDATE_FIXTURES=1 cargo test --test fixtures` writes the output of every fixture
+//! to its `.out` file, except for the patterns that still match.
+
+use std::cell::RefCell;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::rc::Rc;
+
+use evaluator::interpreter::Interpreter;
+use evaluator::output::OutputTarget;
+use evaluator::program::{DiagnosticsSink, EvalOptions, PhpError, ReportedError};
+
+const FIXTURES_DIRECTORY: &str = "tests/fixtures";
+
+const EXPECTF_HEADER: &str = "--EXPECTF--\n";
+
+/// The output of a fixture, the warnings are written to it when they are raised, like the
+/// command line writes them between the output.
+#[derive(Default)]
+struct FixtureOutput(String);
+
+impl OutputTarget for FixtureOutput {
+    fn write_output(&mut self, text: &str) -> io::Result<()> {
+        self.0.push_str(text);
+
+        Ok(())
+    }
+}
+
+impl DiagnosticsSink for FixtureOutput {
+    fn on_warning(&mut self, warning: &ReportedError) {
+        self.0
+            .push_str(&format!("{}\n", warning.clone().get_message()));
+    }
+
+    fn on_fatal(&mut self, _error: &PhpError) {}
+}
+
+/// Shares the output of a fixture between the interpreter and the diagnostics sink.
+struct SharedOutput(Rc<RefCell<FixtureOutput>>);
+
+impl OutputTarget for SharedOutput {
+    fn write_output(&mut self, text: &str) -> io::Result<()> {
+        self.0.borrow_mut().write_output(text)
+    }
+}
+
+/// Runs the fixture like the command line does: the output is streamed and the warnings
+/// are written when they are raised.
+fn run(file_name: &str) -> String {
+    let content = fs::read_to_string(file_name).unwrap();
+
+    let output = Rc::new(RefCell::new(FixtureOutput::default()));
+
+    let options = EvalOptions {
+        ini: vec![("implicit_flush".to_string(), "1".to_string())],
+        diagnostics: Some(output.clone()),
+        ..EvalOptions::default()
+    };
+
+    let mut target = SharedOutput(output.clone());
+
+    let result = {
+        let mut interpreter = Interpreter::with_options(&options);
+
+        interpreter.set_output_target(&mut target);
+
+        interpreter.eval_script(file_name, &content).unwrap()
+    };
+
+    let mut output = std::mem::take(&mut output.borrow_mut().0);
+
+    output.push_str(&result.output);
+
+    output
+}
+
+/// Whether `text` matches the `--EXPECTF--` pattern.
+fn matches_pattern(pattern: &[char], text: &[char]) -> bool {
+    let Some((&first, rest)) = pattern.split_first() else {
+        return text.is_empty();
+    };
+
+    if first != '%' || rest.is_empty() {
+        return text.first() == Some(&first) && matches_pattern(rest, &text[1..]);
+    }
+
+    let (wildcard, rest) = (rest[0], &rest[1..]);
+
+    // the sign of %i is optional, the digits are not
+    let text = match (wildcard, text.first()) {
+        ('i', Some('+' | '-')) => &text[1..],
+        _ => text,
+    };
+
+    let (accepts, at_least_one): (fn(char) -> bool, bool) = match wildcard {
+        's' => (|c| c != '\n', true),
+        'S' => (|c| c != '\n', false),
+        'a' => (|_| true, true),
+        'A' => (|_| true, false),
+        'd' | 'i' => (|c| c.is_ascii_digit(), true),
+        'f' => (
+            |c| c.is_ascii_digit() || matches!(c, '.' | '-' | '+' | 'e' | 'E'),
+            true,
+        ),
+        '%' => return text.first() == Some(&'%') && matches_pattern(rest, &text[1..]),
+        // not a wildcard, the % is a character of the output
+        _ => return text.first() == Some(&'%') && matches_pattern(&pattern[1..], &text[1..]),
+    };
+
+    let longest = text.iter().take_while(|&&c| accepts(c)).count();
+    let shortest = usize::from(at_least_one);
+
+    (shortest..=longest)
+        .rev()
+        .any(|length| matches_pattern(rest, &text[length..]))
+}
+
+/// Whether the output is the expected one, `expected` is the content of the `.out` file.
+fn is_expected(expected: &str, output: &str) -> bool {
+    match expected.strip_prefix(EXPECTF_HEADER) {
+        Some(pattern) => matches_pattern(
+            &pattern.chars().collect::<Vec<_>>(),
+            &output.chars().collect::<Vec<_>>(),
+        ),
+        None => expected == output,
+    }
+}
+
+#[test]
+fn fixtures_print_their_expected_output() {
+    let update = env::var("PHPL_UPDATE_FIXTURES").is_ok_and(|value| value == "1");
+
+    let mut fixtures: Vec<String> = fs::read_dir(FIXTURES_DIRECTORY)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "php"))
+        .map(|path| path.to_string_lossy().replace('\\', "/"))
+        .collect();
+
+    fixtures.sort();
+
+    assert!(!fixtures.is_empty(), "no fixture in {}", FIXTURES_DIRECTORY);
+
+    let mut failures = vec![];
+
+    for fixture in &fixtures {
+        let output = run(fixture);
+
+        let expected_file = Path::new(fixture).with_extension("out");
+        let expected = fs::read_to_string(&expected_file).unwrap_or_default();
+
+        if is_expected(&expected, &output) {
+            continue;
+        }
+
+        if update {
+            fs::write(&expected_file, &output).unwrap();
+        } else {
+            failures.push(format!(
+                "{}\n--- expected\n{}\n+++ output\n{}",
+                fixture, expected, output
+            ));
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{} of {} fixtures failed, run with PHPL_UPDATE_FIXTURES=1 to update them\n\n{}",
+        failures.len(),
+        fixtures.len(),
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn patterns_have_wildcards() {
+    let matches = |pattern: &str, text: &str| {
+        matches_pattern(
+            &pattern.chars().collect::<Vec<_>>(),
+            &text.chars().collect::<Vec<_>>(),
+        )
+    };
+
+    assert!(matches("in %s on line %d\n", "in /tmp/a.php on line 12\n"));
+    assert!(!matches("in %s on line %d\n", "in  on line 12\n"));
+    assert!(matches("a%Sb", "ab"));
+    assert!(matches("a%ab", "a\n\nb"));
+    assert!(!matches("a%sb", "a\nb"));
+    assert!(matches("%i %i", "-3 4"));
+    assert!(matches("%f", "1.5E+20"));
+    assert!(matches("100%%", "100%"));
+    assert!(matches("%x", "%x"));
+}
@@ -0,0 +1,10 @@
+<?php
+echo 1 + 2 * 3, "\n";
+echo (1 + 2) * 3, "\n";
+echo 7 % 3, "\n";
+echo -7 % 3, "\n";
+echo 2 ** 10, "\n";
+echo 10 / 4, "\n";
+echo 1.5 + 1, "\n";
+echo "5" + 3, "\n";
+echo 7 <=> 3, "\n";
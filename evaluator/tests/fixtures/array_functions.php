@@ -0,0 +1,19 @@
+<?php
+$numbers = range(1, 6);
+$doubled = array_map(function ($n) {
+    return $n * 2;
+}, $numbers);
+print_r($doubled);
+
+$even = array_values(array_filter($numbers, function ($n) {
+    return $n % 2 === 0;
+}));
+print_r($even);
+
+$words = ["pear", "fig", "banana"];
+usort($words, function ($a, $b) {
+    return strlen($a) <=> strlen($b);
+});
+print_r($words);
+print_r(array_slice(array_merge($words, ["kiwi"]), 1, 2));
+echo array_search("fig", $words), "\n";
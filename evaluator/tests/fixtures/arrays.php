@@ -0,0 +1,11 @@
+<?php
+$list = [1, 2, 3];
+$list[] = 4;
+echo count($list), "\n";
+print_r($list);
+
+$map = ["one" => 1, "two" => 2];
+$map["three"] = 3;
+print_r($map);
+var_dump(array_keys($map));
+echo in_array(3, $list) ? "has 3" : "no 3", "\n";
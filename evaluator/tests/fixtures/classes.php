@@ -0,0 +1,23 @@
+<?php
+class Point
+{
+    public $x;
+    public $y;
+
+    public function __construct($x, $y)
+    {
+        $this->x = $x;
+        $this->y = $y;
+    }
+
+    public function describe()
+    {
+        return "($this->x, $this->y)";
+    }
+}
+
+$point = new Point(1, 2);
+echo $point->describe(), "\n";
+$point->x = 5;
+echo $point->describe(), "\n";
+echo $point instanceof Point ? "a point" : "not a point", "\n";
@@ -0,0 +1,14 @@
+<?php
+$factor = 3;
+$multiply = function ($value) use ($factor) {
+    return $value * $factor;
+};
+echo $multiply(2), "\n";
+
+$counter = 0;
+$increment = function () use (&$counter) {
+    $counter++;
+};
+$increment();
+$increment();
+echo $counter, "\n";
@@ -0,0 +1,31 @@
+<?php
+$value = 5;
+
+if ($value > 10) {
+    echo "big\n";
+} elseif ($value > 3) {
+    echo "medium\n";
+} else {
+    echo "small\n";
+}
+
+switch ($value) {
+    case 1:
+        echo "one\n";
+        break;
+    case 5:
+        echo "five\n";
+    case 6:
+        echo "falls through\n";
+        break;
+    default:
+        echo "other\n";
+}
+
+echo match (true) {
+    $value < 3 => "low",
+    $value < 8 => "middle",
+    default => "high",
+}, "\n";
+
+echo $value ?: "empty", "\n";
@@ -0,0 +1,7 @@
+<?php
+echo "Hello, world!\n";
+echo 'single', ' ', "double", "\n";
+print "print returns " . print "";
+echo "\n";
+echo true, '|', false, '|', null, "\n";
+?>Inline text after the closing tag.
@@ -0,0 +1,34 @@
+<?php
+enum Suit: string
+{
+    case Hearts = 'H';
+    case Spades = 'S';
+
+    const Wild = self::Spades;
+
+    public function color(): string
+    {
+        return match ($this) {
+            Suit::Hearts => 'Red',
+            Suit::Spades => 'Black',
+        };
+    }
+
+    public static function fromChar(string $char): self
+    {
+        return self::from($char);
+    }
+}
+
+enum Status
+{
+    case Active;
+    case Inactive;
+}
+
+echo Suit::Hearts->name, ' ', Suit::Hearts->value, ' ', Suit::Hearts->color(), "\n";
+echo Suit::fromChar('S')->name, ' ', Suit::Wild->color(), "\n";
+var_dump(Suit::tryFrom('X'));
+var_dump(Suit::Hearts === Suit::from('H'));
+var_dump(Status::Active instanceof UnitEnum, Suit::Hearts instanceof BackedEnum);
+echo count(Status::cases()), ' ', Status::cases()[1]->name, "\n";
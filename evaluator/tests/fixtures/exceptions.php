@@ -0,0 +1,18 @@
+<?php
+function divide($a, $b)
+{
+    if ($b === 0) {
+        throw new Exception("Division by zero");
+    }
+
+    return $a / $b;
+}
+
+try {
+    echo divide(4, 2), "\n";
+    echo divide(1, 0), "\n";
+} catch (Exception $exception) {
+    echo "Caught: ", $exception->getMessage(), "\n";
+} finally {
+    echo "finally\n";
+}
@@ -0,0 +1,7 @@
+<?php
+register_shutdown_function(function () {
+    echo "shutdown\n";
+});
+echo "before\n";
+exit("bye\n");
+echo "never\n";
@@ -0,0 +1,24 @@
+<?php
+function greet(string $name, string $greeting = "Hello"): string
+{
+    return "$greeting, $name!";
+}
+
+echo greet("world"), "\n";
+echo greet("phpl", "Hi"), "\n";
+
+function add_one(&$value)
+{
+    $value++;
+}
+
+$count = 1;
+add_one($count);
+echo $count, "\n";
+
+echo called_before_its_declaration(), "\n";
+
+function called_before_its_declaration()
+{
+    return "hoisted";
+}
@@ -0,0 +1,11 @@
+<?php
+$who = "heredoc";
+echo <<<TEXT
+Hello from $who
+  keeps the indentation
+TEXT;
+echo "\n";
+echo <<<'RAW'
+Nowdoc does not expand $who
+RAW;
+echo "\n";
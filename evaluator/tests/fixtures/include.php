@@ -0,0 +1,8 @@
+<?php
+$greeting = "set by the including file";
+$returned = include 'included/helper.php';
+echo $returned, "\n";
+echo helper_function(), "\n";
+echo $set_by_include, "\n";
+include_once 'included/helper_once.php';
+include_once 'included/helper_once.php';
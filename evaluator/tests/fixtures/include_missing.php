@@ -0,0 +1,5 @@
+<?php
+$result = include 'included/missing.php';
+var_dump($result);
+require 'included/missing.php';
+echo "never\n";
@@ -0,0 +1,10 @@
+<?php
+echo "included sees: $greeting\n";
+$set_by_include = "set by the included file";
+
+function helper_function()
+{
+    return "declared in the included file";
+}
+
+return "returned by the included file";
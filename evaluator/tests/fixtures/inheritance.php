@@ -0,0 +1,26 @@
+<?php
+abstract class Animal
+{
+    public function __construct(protected $name)
+    {
+    }
+
+    abstract public function sound();
+
+    public function speak()
+    {
+        return $this->name . " says " . $this->sound();
+    }
+}
+
+class Dog extends Animal
+{
+    public function sound()
+    {
+        return "woof";
+    }
+}
+
+$dog = new Dog("Rex");
+echo $dog->speak(), "\n";
+echo $dog instanceof Animal ? "an animal" : "not an animal", "\n";
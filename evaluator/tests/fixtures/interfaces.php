@@ -0,0 +1,21 @@
+<?php
+interface Shape
+{
+    public function area();
+}
+
+class Square implements Shape
+{
+    public function __construct(private $side)
+    {
+    }
+
+    public function area()
+    {
+        return $this->side * $this->side;
+    }
+}
+
+$square = new Square(3);
+echo $square->area(), "\n";
+echo $square instanceof Shape ? "a shape" : "not a shape", "\n";
@@ -0,0 +1,4 @@
+<?php
+echo __LINE__, "\n";
+echo __FILE__, "\n";
+echo __DIR__, "\n";
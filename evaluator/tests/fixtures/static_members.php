@@ -0,0 +1,16 @@
+<?php
+class Counter
+{
+    const START = 10;
+
+    public static $count = 10;
+
+    public static function increment()
+    {
+        return ++Counter::$count;
+    }
+}
+
+Counter::increment();
+echo Counter::increment(), "\n";
+echo Counter::START, "\n";
@@ -0,0 +1,8 @@
+<?php
+$name = "phpl";
+echo "Hello $name\n";
+echo "Length: " . strlen($name) . "\n";
+echo strtoupper($name), "\n";
+echo str_repeat("ab", 3), "\n";
+echo substr("interpreter", 0, 5), "\n";
+echo str_replace("world", "there", "hello world"), "\n";
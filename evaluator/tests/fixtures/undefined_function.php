@@ -0,0 +1,4 @@
+<?php
+echo "before\n";
+missing_function();
+echo "never\n";
@@ -0,0 +1,4 @@
+<?php
+echo "before\n";
+echo $missing;
+echo "after\n";
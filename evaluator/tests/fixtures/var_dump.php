@@ -0,0 +1,7 @@
+<?php
+var_dump(1);
+var_dump(1.5);
+var_dump("text");
+var_dump(true);
+var_dump(null);
+var_dump([1, "a" => [true]]);
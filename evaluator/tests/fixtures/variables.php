@@ -0,0 +1,11 @@
+<?php
+$a = 1;
+$b = &$a;
+$b = 2;
+echo $a, "\n";
+$name = 'a';
+echo $$name, "\n";
+unset($b);
+echo isset($b) ? 'set' : 'unset', "\n";
+echo $a ?? 'default', "\n";
+echo $missing ?? 'default', "\n";
@@ -0,0 +1,202 @@
+mod common;
+
+use common::{run, run_with_warnings};
+
+#[test]
+fn number_format_rounds_the_decimal_digits_half_up() {
+    assert_eq!(run("echo number_format(2.675, 2);"), "2.68");
+    assert_eq!(run("echo number_format(1.005, 2);"), "1.01");
+    assert_eq!(
+        run("echo number_format(2.5), ' ', number_format(3.5);"),
+        "3 4"
+    );
+    assert_eq!(run("echo number_format(-2.675, 2);"), "-2.68");
+    assert_eq!(run("echo number_format(9.995, 2);"), "10.00");
+    assert_eq!(run("echo number_format(0.5);"), "1");
+    assert_eq!(run("echo number_format(1.25, 5);"), "1.25000");
+}
+
+#[test]
+fn number_format_groups_the_thousands() {
+    assert_eq!(run("echo number_format(1234567.891);"), "1,234,568");
+    assert_eq!(run("echo number_format(12345.678, 2);"), "12,345.68");
+    assert_eq!(
+        run("echo number_format(12345.678, 2, ',', '.');"),
+        "12.345,68"
+    );
+    assert_eq!(run("echo number_format(123456789);"), "123,456,789");
+    assert_eq!(run("echo number_format(-1234, 1, null, null);"), "-1,234.0");
+    assert_eq!(run("echo number_format(999.5);"), "1,000");
+    assert_eq!(run("echo number_format(100);"), "100");
+    assert_eq!(
+        run("echo number_format(1234567.5, 1, '٫', ' ');"),
+        "1 234 567٫5"
+    );
+    assert_eq!(run("echo number_format(1234567, 0, '.', '');"), "1234567");
+}
+
+#[test]
+fn number_format_handles_large_magnitudes_and_negative_decimals() {
+    assert_eq!(
+        run("echo number_format(1.0E+25);"),
+        "10,000,000,000,000,000,905,969,664"
+    );
+    assert_eq!(
+        run("echo number_format(1e30), ' ', number_format(0.1, 20);"),
+        "1,000,000,000,000,000,019,884,624,838,656 0.10000000000000000555"
+    );
+    assert_eq!(run("echo number_format(1234.5, -2);"), "1,200");
+    assert_eq!(run("echo number_format(1250, -2);"), "1,300");
+    assert_eq!(run("echo number_format(49, -2);"), "0");
+    assert_eq!(run("echo number_format(0.00001, 3);"), "0.000");
+}
+
+#[test]
+fn number_format_never_returns_a_negative_zero() {
+    assert_eq!(run("echo number_format(-0.01);"), "0");
+    assert_eq!(run("echo number_format(-0.001, 2);"), "0.00");
+    assert_eq!(run("echo number_format(-0.0, 1);"), "0.0");
+    assert_eq!(run("echo number_format(-0.5);"), "-1");
+}
+
+#[test]
+fn number_format_rejects_non_numeric_strings() {
+    assert_eq!(
+        run("echo number_format('abc');"),
+        common::uncaught(
+            "TypeError: number_format(): Argument #1 ($num) must be of type float, string given",
+            1
+        )
+    );
+    assert_eq!(run("echo number_format('1234.5', 1);"), "1,234.5");
+}
+
+#[test]
+fn sprintf_formats_floats_on_their_decimal_digits() {
+    assert_eq!(run("echo sprintf('%.2f', 2.675);"), "2.68");
+    assert_eq!(run("echo sprintf('%.2f', -2.675);"), "-2.68");
+    assert_eq!(run("echo sprintf('%f', 1.5);"), "1.500000");
+    assert_eq!(run("echo sprintf('%.0f', 2.5);"), "3");
+    assert_eq!(
+        run("echo sprintf('%.1f', -0.0), ' ', sprintf('%.2f', -0.001);"),
+        "-0.0 -0.00"
+    );
+    assert_eq!(
+        run("echo sprintf('%.3F', 1.0E+25);"),
+        "10000000000000000905969664.000"
+    );
+    assert_eq!(
+        run("echo sprintf('%+.1f %+.1f', 1.25, -1.25);"),
+        "+1.3 -1.3"
+    );
+}
+
+#[test]
+fn sprintf_formats_the_scientific_and_general_notations() {
+    assert_eq!(run("echo sprintf('%e', 1234.5);"), "1.234500e+3");
+    assert_eq!(run("echo sprintf('%.2E', 0.000123);"), "1.23E-4");
+    assert_eq!(run("echo sprintf('%.0e', 1234);"), "1e+3");
+    assert_eq!(run("echo sprintf('%e', 0);"), "0.000000e+0");
+    assert_eq!(run("echo sprintf('%g', 1234.5);"), "1234.5");
+    assert_eq!(run("echo sprintf('%g', 1234567.0);"), "1.23457e+6");
+    assert_eq!(
+        run("echo sprintf('%g', 100000), ' ', sprintf('%g', 1000000);"),
+        "100000 1.0e+6"
+    );
+    assert_eq!(
+        run("echo sprintf('%g', 0.0001), ' ', sprintf('%G', 0.00001234);"),
+        "0.0001 1.234E-5"
+    );
+    assert_eq!(
+        run("echo sprintf('%.3g', 2.675), ' ', sprintf('%.0g', 2.675);"),
+        "2.68 3"
+    );
+    assert_eq!(
+        run("echo sprintf('%g', 0), ' ', sprintf('%g', -1.5);"),
+        "0 -1.5"
+    );
+}
+
+#[test]
+fn sprintf_converts_floats_to_strings_with_the_precision_setting() {
+    assert_eq!(run("echo sprintf('%s', 3.14159265);"), "3.14159265");
+    assert_eq!(
+        run("ini_set('precision', 3); echo sprintf('%s|%.5f', 2.675, 2.675), '|', 2.675;"),
+        "2.67|2.67500|2.67"
+    );
+}
+
+#[test]
+fn sprintf_formats_integers() {
+    assert_eq!(
+        run("echo sprintf('%d|%+d|%+d', 42, 42, -42);"),
+        "42|+42|-42"
+    );
+    assert_eq!(
+        run("echo sprintf('%d|%d|%d', '12abc', 3.99, true);"),
+        "12|3|1"
+    );
+    assert_eq!(
+        run("echo sprintf('%b|%o|%x|%X', 10, 8, 255, 255);"),
+        "1010|10|ff|FF"
+    );
+    assert_eq!(
+        run("echo sprintf('%u|%x', -1, -1);"),
+        "18446744073709551615|ffffffffffffffff"
+    );
+    assert_eq!(run("echo sprintf('%c%c', 80, 72);"), "PH");
+}
+
+#[test]
+fn sprintf_pads_and_truncates() {
+    assert_eq!(
+        run("echo sprintf('[%5d][%-5d][%05d]', 42, 42, -3);"),
+        "[   42][42   ][-0003]"
+    );
+    assert_eq!(run("echo sprintf('[%08.2f]', -2.675);"), "[-0002.68]");
+    assert_eq!(
+        run("echo sprintf(\"[%'*10s][%-'#6s]\", 'abc', 'ab');"),
+        "[*******abc][ab####]"
+    );
+    assert_eq!(
+        run("echo sprintf('[%.2s][%5.1s]', 'abc', 'xyz');"),
+        "[ab][    x]"
+    );
+    assert_eq!(run("echo sprintf('[%05s]', 'ab');"), "[000ab]");
+}
+
+#[test]
+fn sprintf_uses_argument_numbers() {
+    assert_eq!(
+        run("echo sprintf('%2$s %1$s %2$s, %s', 'a', 'b');"),
+        "b a b, a"
+    );
+    assert_eq!(run("echo sprintf('100%% %s', 'sure');"), "100% sure");
+}
+
+#[test]
+fn sprintf_errors() {
+    assert_eq!(
+        run("echo sprintf('%s %s', 'a');"),
+        common::uncaught("ArgumentCountError: 3 arguments are required, 2 given", 1)
+    );
+    assert_eq!(
+        run("echo sprintf('%s %');"),
+        common::uncaught("ValueError: Missing format specifier at end of string", 1)
+    );
+    assert_eq!(
+        run("echo sprintf('%y', 1);"),
+        common::uncaught("ValueError: Unknown format specifier \"y\"", 1)
+    );
+    assert_eq!(
+        run("echo sprintf('%0$s', 1);"),
+        common::uncaught("ValueError: Argument number specifier must be greater than zero and less than 2147483647", 1)
+    );
+    assert_eq!(
+        run_with_warnings("echo sprintf('%s', []);"),
+        (
+            "Array".to_string(),
+            "PHP Warning: Array to string conversion in test.php on line 1\n".to_string()
+        )
+    );
+}
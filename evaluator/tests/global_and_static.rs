@@ -0,0 +1,53 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn global_binds_the_global_variables() {
+    let code = "$total = 1;
+        function add($amount) { global $total; $total += $amount; }
+        add(2); add(3);
+        function create() { global $created; $created = 'new'; }
+        create();
+        echo $total, ' ', $created;";
+
+    assert_eq!(run(code), "6 new");
+}
+
+#[test]
+fn a_global_variable_is_shared_until_it_is_rebound() {
+    let code = "$name = 'a';
+        function read() { global $name; echo $name; $other = 'local'; $name = &$other; echo $name; }
+        read();
+        echo ' ', $name;";
+
+    assert_eq!(run(code), "alocal a");
+}
+
+#[test]
+fn static_variables_keep_their_value_between_calls() {
+    let code = "function counter() { static $count = 0, $unset; $count++; return $count . gettype($unset); }
+        echo counter(), ' ', counter(), ' ', counter();";
+
+    assert_eq!(run(code), "1NULL 2NULL 3NULL");
+}
+
+#[test]
+fn the_initializer_of_a_static_variable_runs_once() {
+    let code = "function init() { echo 'init '; return 10; }
+        function next_id() { static $id = init(); return $id++; }
+        echo next_id(), ' ', next_id(), ' ';
+        function other() { static $id = 0; return ++$id; }
+        echo other();";
+
+    assert_eq!(run(code), "init 10 11 1");
+}
+
+#[test]
+fn inherited_methods_share_their_static_variables() {
+    let code = "class Base { public function next() { static $n = 0; return ++$n; } }
+        class Child extends Base {}
+        echo (new Base())->next(), (new Child())->next(), (new Base())->next();";
+
+    assert_eq!(run(code), "123");
+}
@@ -0,0 +1,74 @@
+use std::fs;
+
+mod common;
+
+use common::run_with_status;
+
+/// Runs `code` after the opening tag and returns whether it ran without errors and its output.
+fn run(code: &str) -> (bool, String) {
+    let (output, status) = run_with_status(code);
+
+    (status == 0, output)
+}
+
+#[test]
+fn the_data_after_halt_compiler_is_not_executed_nor_output() {
+    assert_eq!(
+        run("echo 'a'; __halt_compiler();\x00\x01\x7f<?php echo 'b'; ?>c"),
+        (true, "a".to_string())
+    );
+    assert_eq!(run("echo 'a'; __halt_compiler();"), (true, "a".to_string()));
+    assert_eq!(
+        run("echo 'a'; __halt_compiler ( ) ; b"),
+        (true, "a".to_string())
+    );
+    assert_eq!(
+        run("echo 'a'; __halt_compiler() ?>\nb"),
+        (true, "a".to_string())
+    );
+}
+
+#[test]
+fn the_offset_is_the_first_byte_after_halt_compiler() {
+    let code = "echo __COMPILER_HALT_OFFSET__; __halt_compiler();\x00data";
+
+    let offset = "<?php ".len() + code.find('\x00').unwrap();
+
+    assert_eq!(run(code), (true, offset.to_string()));
+}
+
+#[test]
+fn the_offset_is_undefined_without_halt_compiler() {
+    assert_eq!(
+        run("echo 'a'; echo __COMPILER_HALT_OFFSET__;"),
+        (
+            false,
+            "aPHP Fatal error: Uncaught Error: Undefined constant \"__COMPILER_HALT_OFFSET__\" in test.php:1\nStack trace:\n#0 {main}\n  thrown in test.php on line 1\n"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn every_file_has_its_own_offset() {
+    let path = std::env::temp_dir().join(format!("phpl-{}-halt.php", std::process::id()));
+
+    let included = "<?php echo __COMPILER_HALT_OFFSET__ . ' ';\n__halt_compiler();";
+
+    fs::write(&path, format!("{}data", included)).unwrap();
+
+    let code = format!(
+        "include '{}'; echo __COMPILER_HALT_OFFSET__; __halt_compiler();",
+        path.display()
+    );
+
+    let (success, output) = run(&code);
+
+    fs::remove_file(&path).unwrap();
+
+    assert!(success);
+    assert_eq!(
+        output,
+        format!("{} {}", included.len(), "<?php ".len() + code.len())
+    );
+}
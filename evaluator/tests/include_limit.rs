@@ -0,0 +1,60 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use evaluator::program::EvalOptions;
+
+mod common;
+
+use common::eval_with_options;
+
+/// Writes a file of `size` bytes in the temporary directory and returns its path.
+fn generated_file(name: &str, size: usize) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("phpl-{}-{}.php", std::process::id(), name));
+
+    let content = format!("<?php echo 'included';{}", " ".repeat(size - 22));
+
+    fs::write(&path, content).unwrap();
+
+    path
+}
+
+fn include(path: &Path, function: &str, max_source_size: u64) -> (bool, String) {
+    let options = EvalOptions {
+        max_source_size,
+        ..EvalOptions::default()
+    };
+
+    let content = format!("<?php echo 'before '; {} '{}';", function, path.display());
+
+    let result = eval_with_options(&content, &options);
+
+    (result.exit_status == 0, result.output)
+}
+
+#[test]
+fn files_larger_than_the_limit_are_not_included() {
+    let path = generated_file("large", 200);
+
+    let (success, output) = include(&path, "include", 100);
+
+    assert!(!success);
+    assert_eq!(
+        output,
+        format!(
+            "before PHP Fatal error: include(): The file \"{}\" is 200 bytes, more than the maximum source size of 100 bytes in test.php on line 1\n",
+            path.display()
+        )
+    );
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn files_within_the_limit_are_included() {
+    let path = generated_file("small", 50);
+
+    assert_eq!(include(&path, "require", 50), (true, "before included".to_string()));
+    assert_eq!(include(&path, "require_once", 0), (true, "before included".to_string()));
+
+    fs::remove_file(path).unwrap();
+}
@@ -0,0 +1,122 @@
+use std::fs;
+use std::path::PathBuf;
+
+mod common;
+
+use common::run;
+
+/// Creates a directory in the temporary directory, with the given files.
+fn directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("phpl-{}-{}", std::process::id(), name));
+
+    fs::create_dir_all(&directory).unwrap();
+
+    for (file, content) in files {
+        fs::write(directory.join(file), content).unwrap();
+    }
+
+    directory
+}
+
+#[test]
+fn the_same_file_is_included_once_whatever_its_path_is() {
+    let directory = directory("spellings", &[("a.php", "<?php echo 'a';")]);
+
+    let spellings = [
+        format!("{}/a.php", directory.display()),
+        format!("{}/./a.php", directory.display()),
+        format!(
+            "{}/../{}/a.php",
+            directory.display(),
+            directory.file_name().unwrap().to_string_lossy()
+        ),
+    ];
+
+    let output = run(&format!(
+        "include_once '{}'; include_once '{}'; require_once '{}'; echo 'end';",
+        spellings[0], spellings[1], spellings[2]
+    ));
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(output, "aend");
+}
+
+#[test]
+fn the_once_variants_skip_files_read_by_any_include() {
+    let directory = directory("kinds", &[("a.php", "<?php echo 'a';")]);
+    let path = directory.join("a.php");
+
+    let output = run(&format!(
+        "include '{0}'; include_once '{0}'; require_once '{0}'; require '{0}';",
+        path.display()
+    ));
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(output, "aa");
+}
+
+#[test]
+fn included_files_share_the_registry() {
+    let directory = directory("nested", &[("a.php", "<?php echo 'a';")]);
+    let path = directory.join("a.php");
+
+    fs::write(
+        directory.join("b.php"),
+        format!("<?php include_once '{}'; echo 'b';", path.display()),
+    )
+    .unwrap();
+
+    let output = run(&format!(
+        "include '{}'; include_once '{}'; echo 'end';",
+        directory.join("b.php").display(),
+        path.display()
+    ));
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(output, "abend");
+}
+
+#[test]
+fn the_separators_are_the_ones_of_the_platform() {
+    let expected = if cfg!(windows) { "\\;" } else { "/:" };
+
+    assert_eq!(run("echo DIRECTORY_SEPARATOR, PATH_SEPARATOR;"), expected);
+}
+
+#[test]
+fn the_include_path_is_split_with_the_path_separator() {
+    let first = directory("path-first", &[]);
+    let second = directory("path-second", &[("lib.php", "<?php echo 'lib';")]);
+
+    let output = run(&format!(
+        "ini_set('include_path', '{}' . PATH_SEPARATOR . '{}'); include 'lib.php';",
+        first.display(),
+        second.display()
+    ));
+
+    fs::remove_dir_all(&first).unwrap();
+    fs::remove_dir_all(&second).unwrap();
+
+    assert_eq!(output, "lib");
+}
+
+#[cfg(windows)]
+#[test]
+fn slashes_and_backslashes_are_the_same_file() {
+    let directory = directory("windows", &[("a.php", "<?php echo 'a';")]);
+
+    let with_backslashes = directory.join("a.php").display().to_string();
+    let with_slashes = with_backslashes.replace('\\', "/");
+
+    let output = run(&format!(
+        "include_once '{}'; include_once '{}'; echo 'end';",
+        with_backslashes, with_slashes
+    ));
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(output, "aend");
+}
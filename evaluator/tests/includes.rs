@@ -0,0 +1,279 @@
+use std::fs;
+
+mod common;
+
+use common::{run_with_warnings, write_file};
+
+#[test]
+fn a_fatal_error_in_an_included_file_keeps_the_output_and_stops_the_program() {
+    let included = write_file(
+        "fails.php",
+        "<?php\necho 'inside ';\n$copy = $undefined;\nmissing();\necho 'never';",
+    );
+
+    let (output, warnings) = run_with_warnings(&format!(
+        "echo 'before ';\n$a = $main;\ninclude '{}';\necho 'after';",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(
+        output,
+        format!(
+            "before inside PHP Fatal error: Uncaught Error: Call to undefined function missing() in {0}:4\nStack trace:\n#0 test.php(3): include()\n#1 {{main}}\n  thrown in {0} on line 4\n",
+            included.display()
+        )
+    );
+    assert_eq!(
+        warnings,
+        format!(
+            "PHP Warning: Undefined variable $main in test.php on line 2\nPHP Warning: Undefined variable $undefined in {} on line 3\n",
+            included.display()
+        )
+    );
+}
+
+#[test]
+fn the_stack_trace_lists_every_include_from_the_innermost() {
+    let inner = write_file("inner.php", "<?php\n\nnew Missing();");
+    let outer = write_file(
+        "outer.php",
+        &format!("<?php\nrequire_once '{}';", inner.display()),
+    );
+
+    let (output, _) = run_with_warnings(&format!("include '{}';", outer.display()));
+
+    fs::remove_file(&inner).unwrap();
+    fs::remove_file(&outer).unwrap();
+
+    assert_eq!(
+        output,
+        format!(
+            "PHP Fatal error: Uncaught Error: Class \"Missing\" not found in {0}:3\nStack trace:\n#0 {1}(2): require_once()\n#1 test.php(1): include()\n#2 {{main}}\n  thrown in {0} on line 3\n",
+            inner.display(),
+            outer.display()
+        )
+    );
+}
+
+#[test]
+fn the_shutdown_functions_run_after_a_fatal_error_in_an_included_file() {
+    let included = write_file("shutdown.php", "<?php\nmissing();");
+
+    let (output, _) = run_with_warnings(&format!(
+        "register_shutdown_function(function () {{ echo 'shutdown'; }});\ninclude '{}';",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert!(output.ends_with(&format!(
+        "#1 {{main}}\n  thrown in {} on line 2\nshutdown",
+        included.display()
+    )));
+}
+
+#[test]
+fn an_exception_thrown_in_an_included_file_can_be_caught_by_the_file_that_includes_it() {
+    let included = write_file(
+        "throws.php",
+        "<?php\n\nthrow new Exception('from the file');",
+    );
+
+    let (caught, _) = run_with_warnings(&format!(
+        "try {{ include '{}'; }} catch (Exception $e) {{ echo $e->getMessage(), ' ', $e->getLine(), ' ', $e->getFile() === '{}'; }}",
+        included.display(),
+        included.display()
+    ));
+
+    let (uncaught, _) = run_with_warnings(&format!(
+        "echo 'before ';\ninclude '{}';",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(caught, "from the file 3 1");
+    assert_eq!(
+        uncaught,
+        format!(
+            "before PHP Fatal error: Uncaught Exception: from the file in {}:3\nStack trace:\n#0 test.php(2): include()\n#1 {{main}}\n  thrown in {} on line 3\n",
+            included.display(),
+            included.display()
+        )
+    );
+}
+
+#[test]
+fn the_return_value_of_the_file_is_the_value_of_the_include() {
+    let config = write_file("config.php", "<?php\nreturn ['name' => 'phpl'];");
+    let without_return = write_file("no_return.php", "<?php\n$a = 1;");
+
+    let (output, _) = run_with_warnings(&format!(
+        "$config = require '{}';\necho $config['name'], ' ';\nvar_dump(include '{}');",
+        config.display(),
+        without_return.display()
+    ));
+
+    fs::remove_file(&config).unwrap();
+    fs::remove_file(&without_return).unwrap();
+
+    assert_eq!(output, "phpl bool(true)\n");
+}
+
+#[test]
+fn the_file_runs_in_the_scope_of_the_include() {
+    let included = write_file(
+        "scope.php",
+        "<?php\n$local .= ' changed';\n$defined = 'defined';\nunset($removed);\n$alias = &$local;",
+    );
+
+    let (output, _) = run_with_warnings(&format!(
+        "function f() {{ $local = 'local'; $removed = 1; include '{}'; $alias = 'through the alias';\n\
+         echo $local, ' ', $defined, ' ', isset($removed) ? 'kept' : 'removed'; }}\n\
+         f(); echo ' ', isset($defined) ? 'global' : 'not global';",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(output, "through the alias defined removed not global");
+}
+
+#[test]
+fn a_file_that_cannot_be_included_only_stops_require() {
+    let missing = std::env::temp_dir().join("phpl-missing-file.php");
+
+    let (output, warnings) = run_with_warnings(&format!(
+        "var_dump(include '{}');\necho 'after';",
+        missing.display()
+    ));
+
+    assert_eq!(output, "bool(false)\nafter");
+    assert_eq!(
+        warnings,
+        format!(
+            "PHP Warning: include({0}): Failed to open stream: No such file or directory in test.php on line 1\n\
+            PHP Warning: include(): Failed opening '{0}' for inclusion (include_path='.') in test.php on line 1\n",
+            missing.display()
+        )
+    );
+
+    let (output, _) = run_with_warnings(&format!(
+        "echo 'before ';\nrequire '{}';\necho 'after';",
+        missing.display()
+    ));
+
+    assert!(output.starts_with("before PHP Fatal error: require("));
+    assert!(!output.contains("after"));
+}
+
+#[test]
+fn a_parse_error_in_an_included_file_keeps_the_output() {
+    let invalid = write_file("invalid.php", "<?php\n$a = ;");
+
+    let (included, warnings) = run_with_warnings(&format!(
+        "echo 'before ';\nvar_dump(include '{}');\necho 'after';",
+        invalid.display()
+    ));
+
+    let (required, _) = run_with_warnings(&format!(
+        "echo 'before ';\nrequire '{}';\necho 'after';",
+        invalid.display()
+    ));
+
+    fs::remove_file(&invalid).unwrap();
+
+    let error = format!("before PHP Parse Error in {}:", invalid.display());
+
+    assert!(included.starts_with(&error));
+    assert!(included.ends_with("bool(false)\nafter"));
+    assert_eq!(
+        warnings,
+        format!(
+            "PHP Warning: include(): Failed opening '{}' for inclusion in test.php on line 2\n",
+            invalid.display()
+        )
+    );
+
+    assert!(required.starts_with(&error));
+    assert!(!required.contains("after"));
+}
+
+#[test]
+fn relative_paths_are_also_looked_for_in_the_directory_of_the_current_file() {
+    let directory = std::env::temp_dir().join(format!("phpl-{}-directory", std::process::id()));
+
+    fs::create_dir_all(&directory).unwrap();
+    fs::write(directory.join("outer.php"), "<?php\ninclude 'inner.php';").unwrap();
+    fs::write(directory.join("inner.php"), "<?php\necho 'inner';").unwrap();
+
+    let (output, warnings) = run_with_warnings(&format!(
+        "include '{}';",
+        directory.join("outer.php").display()
+    ));
+
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(output, "inner");
+    assert_eq!(warnings, "");
+}
+
+#[test]
+fn the_errors_of_a_function_report_the_file_that_declares_it() {
+    let included = write_file(
+        "declares.php",
+        "<?php\nfunction f() {\n    $copy = $undefined;\n    missing();\n}",
+    );
+
+    let (output, warnings) = run_with_warnings(&format!(
+        "include '{}';\necho 'before ';\nf();",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(
+        warnings,
+        format!(
+            "PHP Warning: Undefined variable $undefined in {} on line 3\n",
+            included.display()
+        )
+    );
+    assert_eq!(
+        output,
+        format!(
+            "before PHP Fatal error: Uncaught Error: Call to undefined function missing() in {0}:4\nStack trace:\n#0 {{main}}\n  thrown in {0} on line 4\n",
+            included.display()
+        )
+    );
+}
+
+#[test]
+fn the_magic_constants_give_the_file_and_the_line_where_they_are() {
+    let included = write_file("constants.php", "");
+
+    let canonical = fs::canonicalize(&included).unwrap();
+
+    fs::write(
+        &included,
+        format!(
+            "<?php\necho __LINE__, __FILE__ === '{}', __DIR__ === '{}', ' ';\n\
+             function file() {{ return __FILE__; }}",
+            canonical.display(),
+            canonical.parent().unwrap().display()
+        ),
+    )
+    .unwrap();
+
+    let (output, _) = run_with_warnings(&format!(
+        "include '{}';\necho file() === '{}', ' ', __FILE__, ' ', __DIR__, ' ', __LINE__;",
+        included.display(),
+        canonical.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(output, "211 1 test.php . 2");
+}
@@ -0,0 +1,71 @@
+mod common;
+
+use common::run_with_warnings;
+
+fn increment(string: &str) -> String {
+    run_with_warnings(&format!("$s = '{}'; $s++; echo $s;", string)).0
+}
+
+#[test]
+fn strings_carry_like_perl() {
+    assert_eq!(increment("a"), "b");
+    assert_eq!(increment("a9"), "b0");
+    assert_eq!(increment("Az"), "Ba");
+    assert_eq!(increment("zz"), "aaa");
+    assert_eq!(increment("z"), "aa");
+    assert_eq!(increment("Zz"), "AAa");
+    assert_eq!(increment("a9z"), "b0a");
+}
+
+#[test]
+fn non_alphanumeric_strings_stop_the_carry() {
+    let (output, warnings) =
+        run_with_warnings("$s = 'a!'; $s++; echo $s; $t = 'a-9'; $t++; echo ' ', $t;");
+
+    assert_eq!(output, "a! a-0");
+    assert_eq!(
+        warnings,
+        "PHP Deprecated: Increment on non-alphanumeric string is deprecated in test.php on line 1\n"
+            .repeat(2)
+    );
+}
+
+#[test]
+fn empty_string_and_numeric_strings() {
+    assert_eq!(increment(""), "1");
+    assert_eq!(increment("9"), "10");
+    assert_eq!(increment(" 5"), "6");
+    assert_eq!(increment("1.5"), "2.5");
+
+    let (output, _) = run_with_warnings("$s = '5'; $s--; echo $s; $e = ''; $e--; echo ' ', $e;");
+
+    assert_eq!(output, "4 -1");
+}
+
+#[test]
+fn decrement_does_not_change_other_strings() {
+    let (output, warnings) = run_with_warnings("$s = 'abc'; $s--; echo $s;");
+
+    assert_eq!(output, "abc");
+    assert_eq!(
+        warnings,
+        "PHP Deprecated: Decrement on non-numeric string has no effect and is deprecated in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn prefix_and_postfix_values() {
+    let (output, _) =
+        run_with_warnings("$i = 1; echo $i++, ' ', $i, ' ', ++$i, ' ', $i--, ' ', --$i;");
+
+    assert_eq!(output, "1 2 3 3 1");
+}
+
+#[test]
+fn properties_are_incremented() {
+    let (output, _) = run_with_warnings(
+        "class C { public $c = 0; public static $s = 5; } $o = new C; $o->c++; ++$o->c; C::$s--; echo $o->c, ' ', C::$s;",
+    );
+
+    assert_eq!(output, "2 4");
+}
@@ -0,0 +1,64 @@
+use std::fs;
+
+mod common;
+
+use common::{run, write_file};
+
+#[test]
+fn a_class_cannot_extend_itself() {
+    assert_eq!(
+        run("class A extends a {}"),
+        "PHP Fatal error: Circular inheritance detected: A -> a in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn a_class_declared_again_to_extend_its_child_is_a_redeclaration() {
+    let included = write_file("cycle.php", "<?php\nclass A extends B {}");
+
+    let output = run(&format!(
+        "class A {{}} class B extends A {{}} include '{}';",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert!(output.contains(&format!(
+        "PHP Fatal error: Cannot declare class A, because the name is already in use in {} on line 2",
+        included.display()
+    )));
+}
+
+#[test]
+fn two_files_extending_each_other_end_with_a_fatal_error() {
+    let first = write_file("first.php", "<?php\nclass First extends Second {}");
+    let second = write_file(
+        "second.php",
+        &format!(
+            "<?php\ninclude '{}';\nclass Second extends First {{}}",
+            first.display()
+        ),
+    );
+
+    let output = run(&format!("include '{}';", second.display()));
+
+    fs::remove_file(&first).unwrap();
+    fs::remove_file(&second).unwrap();
+
+    // Second cannot be hoisted before First exists, so first.php fails when it is included
+    assert!(output.contains(&format!(
+        "PHP Fatal error: Uncaught Error: Class \"Second\" not found in {0}:2\nStack trace:\n#0 {1}(2): include()\n#1 test.php(1): include()\n#2 {{main}}\n  thrown in {0} on line 2",
+        first.display(),
+        second.display()
+    )));
+}
+
+#[test]
+fn the_parents_are_walked_in_order() {
+    assert_eq!(
+        run("class A {} class B extends A {} class C extends B {}
+            echo phpl_class_info('C')['parent'], ' ', phpl_class_info('B')['parent'];
+            $c = new C(); echo ' ', $c instanceof A;"),
+        "B A 1"
+    );
+}
@@ -0,0 +1,53 @@
+mod common;
+
+use common::{eval_with_options, run, run_with_warnings, warnings_of};
+use evaluator::program::EvalOptions;
+
+#[test]
+fn a_change_of_precision_is_used_by_the_next_statement() {
+    let code = "echo 1 / 3, ' ';
+        echo ini_set('precision', '4'), ' ', 1 / 3, ' ', ini_get('precision'), ' ';
+        ini_restore('precision');
+        echo 1 / 3;";
+
+    assert_eq!(run(code), "0.33333333333333 14 0.3333 4 0.33333333333333");
+}
+
+#[test]
+fn a_change_of_error_reporting_is_used_by_the_next_warning() {
+    let code = "echo $a;
+        ini_set('error_reporting', 32765);
+        echo $b;
+        ini_set('error_reporting', '32767');
+        echo $c;";
+
+    assert_eq!(
+        run_with_warnings(code).1,
+        "PHP Warning: Undefined variable $a in test.php on line 1\nPHP Warning: Undefined variable $c in test.php on line 5\n"
+    );
+}
+
+#[test]
+fn unknown_settings_are_false() {
+    assert_eq!(
+        run("var_dump(ini_set('no_such_setting', '1'), ini_get('no_such_setting'), get_cfg_var('no_such_setting'));"),
+        "bool(false)\nbool(false)\nbool(false)\n"
+    );
+}
+
+#[test]
+fn the_options_give_the_settings_and_can_lock_them() {
+    let options = EvalOptions {
+        ini: vec![("precision".to_string(), "5".to_string())],
+        locked_ini: vec!["precision".to_string()],
+        ..EvalOptions::default()
+    };
+
+    let result = eval_with_options(
+        "<?php var_dump(ini_set('precision', '10')); echo ini_get('precision'), ' ', get_cfg_var('precision'), ' ', 1 / 3;",
+        &options,
+    );
+
+    assert_eq!(result.output, "bool(false)\n5 5 0.33333");
+    assert_eq!(warnings_of(&result), "");
+}
@@ -0,0 +1,213 @@
+mod common;
+
+use common::{fatal, uncaught};
+
+/// Interfaces that extend each other and classes that implement them.
+const CLASSES: &str = "
+interface HasName {
+    const PREFIX = 'name: ';
+
+    public function name(): string;
+}
+
+interface HasGreeting extends HasName {
+    public function greet(string $greeting = 'hello');
+}
+
+class Person implements HasGreeting {
+    public function __construct(private string $name) {}
+
+    public function name(): string { return self::PREFIX . $this->name; }
+
+    public function greet(string $greeting = 'hello', ...$rest) { return $greeting . ' ' . $this->name; }
+}
+
+class Student extends Person {}
+";
+
+/// Runs `code` after the classes and returns its output.
+fn run(code: &str) -> String {
+    common::run(&format!("{} {}", CLASSES, code))
+}
+
+#[test]
+fn objects_are_instances_of_the_interfaces_of_their_classes() {
+    let code = "$student = new Student('ana');
+        echo $student instanceof HasGreeting, $student instanceof hasname, $student instanceof Person;
+        echo ' ', $student->greet(), ' ', $student->name();";
+
+    assert_eq!(run(code), "111 hello ana name: ana");
+}
+
+#[test]
+fn interface_constants_are_read_through_the_interface_and_the_class() {
+    assert_eq!(
+        run("echo HasName::PREFIX, HasGreeting::PREFIX, Student::PREFIX;"),
+        "name: name: name: "
+    );
+    assert_eq!(
+        run("echo HasName::MISSING;"),
+        uncaught("Error: Undefined constant HasName::MISSING", 21)
+    );
+}
+
+#[test]
+fn type_hints_accept_the_classes_that_implement_the_interface() {
+    let code = "function describe(HasName $named): HasGreeting { echo $named->name(), ' '; return $named; }
+        echo describe(new Student('bob'))->greet('hi');";
+
+    assert_eq!(run(code), "name: bob hi bob");
+}
+
+#[test]
+fn interfaces_can_be_used_before_their_declaration() {
+    let code = "echo (new Later()) instanceof Early;
+        interface Early {}
+        class Later implements Early {}";
+
+    assert_eq!(run(code), "1");
+}
+
+#[test]
+fn interfaces_cannot_be_instantiated() {
+    assert_eq!(
+        run("new HasName();"),
+        uncaught("Error: Cannot instantiate interface HasName", 21)
+    );
+}
+
+#[test]
+fn classes_must_implement_the_methods_of_their_interfaces() {
+    assert_eq!(
+        run("\nclass Nobody implements HasGreeting {}"),
+        fatal("Class Nobody contains 2 abstract methods and must therefore be declared abstract or implement the remaining methods (HasGreeting::greet, HasName::name)", 22)
+    );
+    assert_eq!(
+        run("abstract class Nobody implements HasGreeting {} echo 'ok';"),
+        "ok"
+    );
+}
+
+#[test]
+fn implemented_methods_must_be_compatible() {
+    assert_eq!(
+        run("\nclass Rude implements HasName {\n private function name(): string { return ''; } }"),
+        fatal(
+            "Access level to Rude::name() must be public (as in class HasName)",
+            23
+        )
+    );
+    assert_eq!(
+        run("\nclass Rude implements HasGreeting {\n public function name(): string { return ''; }\n public function greet($greeting, $name) {} }"),
+        fatal("Declaration of Rude::greet($greeting, $name) must be compatible with HasGreeting::greet(string $greeting = 'hello')", 24)
+    );
+}
+
+#[test]
+fn only_interfaces_can_be_implemented() {
+    assert_eq!(
+        run("\nclass Wrong implements Person {}"),
+        fatal("Wrong cannot implement Person - it is not an interface", 22)
+    );
+    assert_eq!(
+        run("\nclass Wrong extends HasName {}"),
+        fatal("Class Wrong cannot extend interface HasName", 22)
+    );
+    assert_eq!(
+        run("\nclass Wrong implements Missing {}"),
+        uncaught("Error: Interface \"Missing\" not found", 22)
+    );
+}
+
+#[test]
+fn a_constant_cannot_come_from_two_interfaces() {
+    let interfaces = "\ninterface Small { const SIZE = 1; }\ninterface Big { const SIZE = 100; }";
+
+    assert_eq!(
+        run(&format!(
+            "{}\nclass Box implements Small, Big {{}}",
+            interfaces
+        )),
+        fatal(
+            "Class Box inherits both Small::SIZE and Big::SIZE, which is ambiguous",
+            24
+        )
+    );
+    assert_eq!(
+        run(&format!(
+            "{}\ninterface Sized extends Small, Big {{}}",
+            interfaces
+        )),
+        fatal(
+            "Interface Sized inherits both Small::SIZE and Big::SIZE, which is ambiguous",
+            24
+        )
+    );
+    assert_eq!(
+        run(&format!(
+            "{}\nclass Parcel implements Small {{}}\nclass Box extends Parcel implements Big {{}}",
+            interfaces
+        )),
+        fatal(
+            "Class Box inherits both Big::SIZE and Small::SIZE, which is ambiguous",
+            25
+        )
+    );
+    assert_eq!(
+        run(&format!(
+            "{}\nenum Size implements Small, Big {{}}",
+            interfaces
+        )),
+        fatal(
+            "Enum Size inherits both Small::SIZE and Big::SIZE, which is ambiguous",
+            24
+        )
+    );
+}
+
+#[test]
+fn a_constant_can_come_from_the_same_interface_twice() {
+    let code = "\ninterface Base { const SIZE = 1; }
+        interface Left extends Base {}
+        interface Right extends Base {}
+        class Both implements Left, Right {}
+        class Own implements Base, Left { const SIZE = 2; }
+        echo Both::SIZE, Own::SIZE;";
+
+    assert_eq!(run(code), "12");
+}
+
+#[test]
+fn a_class_implements_every_interface_of_a_chain() {
+    let code = "interface A { const NAME = 'a'; function a(); }
+        interface B extends A { function b(); }
+        interface D extends B { function d(); }
+        class Impl implements D {
+            function a() { return self::NAME; }
+            function b() { return 'b'; }
+            function d() { return 'd'; }
+        }
+        function f(A $a) { return $a->a() . $a->b() . $a->d(); }
+        $impl = new Impl();
+        echo f($impl), ' ', $impl instanceof A, $impl instanceof B, $impl instanceof D, ' ', D::NAME;";
+
+    assert_eq!(common::run(code), "abd 111 a");
+
+    let code = "interface A { function a(); }
+        interface B extends A { function b(); }
+        interface D extends B { function d(); }
+        class Impl implements D { function b() {} function d() {} }";
+
+    assert_eq!(
+        common::run(code),
+        fatal("Class Impl contains 1 abstract method and must therefore be declared abstract or implement the remaining methods (A::a)", 4)
+    );
+}
+
+#[test]
+fn an_interface_method_cannot_have_a_body() {
+    assert_eq!(
+        common::run("echo 'never';\ninterface I {\n    function f() { return 1; }\n}"),
+        fatal("Interface function I::f() cannot contain body", 3)
+    );
+}
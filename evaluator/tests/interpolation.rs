@@ -0,0 +1,67 @@
+mod common;
+
+use common::{run, run_with_warnings};
+
+/// The values that the interpolated strings read.
+const VALUES: &str = "class Box {
+    public $items = ['k' => 'item'];
+    public $name = 'box';
+
+    public function label() { return 'label'; }
+}
+$box = new Box();
+$array = ['key' => 'value', 2 => 'two'];
+$key = 'key';
+$index = 2;
+";
+
+/// Runs `code` after the values and returns its output.
+fn interpolate(code: &str) -> String {
+    run(&format!("{}{}", VALUES, code))
+}
+
+#[test]
+fn braced_expressions_are_evaluated() {
+    assert_eq!(
+        interpolate("echo \"{$box->label()}|{$array['key']}|{$box->items['k']}|{$array[$key]}\";"),
+        "label|value|item|value"
+    );
+}
+
+#[test]
+fn unbraced_array_keys_are_not_quoted() {
+    assert_eq!(
+        interpolate("echo \"$array[key]|$array[2]|$array[$index]|$box->name\";"),
+        "value|two|two|box"
+    );
+}
+
+#[test]
+fn dollar_brace_expressions_are_deprecated() {
+    let (output, warnings) = run_with_warnings("$name = 'ana'; echo \"hi ${name}\";");
+
+    assert_eq!(output, "hi ana");
+    assert_eq!(
+        warnings,
+        "PHP Deprecated: Using ${var} in strings is deprecated, use {$var} instead in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn heredocs_interpolate_like_double_quotes() {
+    assert_eq!(
+        interpolate("echo <<<TEXT\n{$box->label()} $array[key]\nTEXT;"),
+        "label value"
+    );
+}
+
+#[test]
+fn interpolated_values_are_converted_with_the_usual_warnings() {
+    let (output, warnings) = run_with_warnings("$array = [1]; echo \"[$array[missing]][{$array}]\";");
+
+    assert_eq!(output, "[][Array]");
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined array key \"missing\" in test.php on line 1\nPHP Warning: Array to string conversion in test.php on line 1\n"
+    );
+}
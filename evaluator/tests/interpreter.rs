@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use evaluator::interpreter::{ByteString, ErrorLevel, Interpreter, PhpValue};
+use evaluator::program::{DiagnosticsSink, EvalOptions, PhpError, ReportedError, ERROR_EXIT_CODE};
+
+#[test]
+fn the_output_and_the_value_are_returned() {
+    let result = Interpreter::new()
+        .eval_str("echo 'a', 'b'; 1 + 2;")
+        .unwrap();
+
+    assert_eq!(result.output, "ab");
+    assert_eq!(result.value, PhpValue::Int(3));
+    assert_eq!(result.exit_status, 0);
+    assert!(result.error.is_none());
+}
+
+#[test]
+fn a_return_statement_gives_the_value() {
+    let result = Interpreter::new()
+        .eval_script("test.php", "<?php return 'done'; echo 'never';")
+        .unwrap();
+
+    assert_eq!(result.output, "");
+    assert_eq!(result.value, PhpValue::String(ByteString::from("done")));
+}
+
+#[test]
+fn warnings_are_returned_with_their_file() {
+    let result = Interpreter::new().eval_str("echo $missing;").unwrap();
+
+    assert_eq!(result.warnings.len(), 1);
+    assert!(matches!(
+        result.warnings[0].error.level,
+        ErrorLevel::Warning
+    ));
+    assert_eq!(result.warnings[0].file, "Command line code");
+}
+
+#[test]
+fn the_error_that_stopped_the_program_is_returned() {
+    let result = Interpreter::new().eval_str("echo 'a'; missing();").unwrap();
+
+    assert_eq!(
+        result.output,
+        "aPHP Fatal error: Uncaught Error: Call to undefined function missing() in Command line code:1\nStack trace:\n#0 {main}\n  thrown in Command line code on line 1\n"
+    );
+    assert!(matches!(result.error.unwrap().level, ErrorLevel::Exception));
+    assert_eq!(result.exit_status, ERROR_EXIT_CODE);
+
+    let result = Interpreter::new().eval_str("exit(7);").unwrap();
+
+    assert_eq!(result.exit_status, 7);
+}
+
+#[test]
+fn global_variables_can_be_seeded_and_read() {
+    let mut interpreter = Interpreter::new();
+
+    interpreter.set_variable("name", PhpValue::String(ByteString::from("world")));
+
+    let result = interpreter
+        .eval_str("echo 'hello ' . $name; $length = strlen($name);")
+        .unwrap();
+
+    assert_eq!(result.output, "hello world");
+    assert_eq!(interpreter.get_variable("length"), Some(PhpValue::Int(5)));
+    assert_eq!(interpreter.get_variable("missing"), None);
+}
+
+#[test]
+fn the_state_is_kept_between_evaluations() {
+    let mut interpreter = Interpreter::new();
+
+    interpreter
+        .eval_str("function twice($n) { return $n * 2; } $total = 1;")
+        .unwrap();
+
+    let result = interpreter.eval_str("$total = twice($total + 1);").unwrap();
+
+    assert_eq!(result.value, PhpValue::Int(4));
+    assert_eq!(interpreter.get_variable("total"), Some(PhpValue::Int(4)));
+}
+
+#[test]
+fn the_output_goes_to_the_target() {
+    let mut captured = String::new();
+
+    {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.set_output_target(&mut captured);
+
+        let result = interpreter.eval_str("echo 'streamed';").unwrap();
+
+        assert_eq!(result.output, "");
+    }
+
+    assert_eq!(captured, "streamed");
+}
+
+#[test]
+fn an_unsupported_statement_is_an_error() {
+    let result = Interpreter::new()
+        .eval_str("echo 'a';\nwhile (true) {}")
+        .unwrap();
+
+    assert_eq!(
+        result.output,
+        "aPHP Fatal error: Unsupported statement: While in Command line code on line 2\n"
+    );
+    assert!(matches!(result.error.unwrap().level, ErrorLevel::Fatal));
+}
+
+#[test]
+fn an_unsupported_expression_is_an_error() {
+    for (code, name) in [
+        ("eval('echo 1;');", "Eval"),
+        ("echo (int) '5';", "Cast"),
+        ("function f() {}\n$f = f(...);\nclone $f;", "Clone"),
+    ] {
+        let result = Interpreter::new().eval_str(code).unwrap();
+
+        assert!(
+            result.output.starts_with(&format!(
+                "PHP Fatal error: Unsupported expression: {} in Command line code on line",
+                name
+            )),
+            "{}",
+            result.output
+        );
+        assert!(matches!(result.error.unwrap().level, ErrorLevel::Fatal));
+    }
+}
+
+/// Sets the interrupted flag when it receives a warning.
+struct InterruptOnWarning(Arc<AtomicBool>);
+
+impl DiagnosticsSink for InterruptOnWarning {
+    fn on_warning(&mut self, _warning: &ReportedError) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    fn on_fatal(&mut self, _error: &PhpError) {}
+}
+
+#[test]
+fn an_interrupted_program_stops_before_its_next_statement() {
+    let interrupted = Arc::new(AtomicBool::new(false));
+
+    let options = EvalOptions {
+        diagnostics: Some(Rc::new(RefCell::new(InterruptOnWarning(
+            interrupted.clone(),
+        )))),
+        interrupted: Some(interrupted.clone()),
+        ..EvalOptions::default()
+    };
+
+    let result = Interpreter::with_options(&options)
+        .eval_str("register_shutdown_function(function () { echo ' shutdown'; });\necho 'a', $missing;\necho 'b';")
+        .unwrap();
+
+    assert_eq!(result.output, "a shutdown");
+    assert!(interrupted.load(Ordering::SeqCst));
+
+    let result = Interpreter::with_options(&options)
+        .eval_str("echo 'never';")
+        .unwrap();
+
+    assert_eq!(result.output, "");
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use common::run_with_warnings;
+
+/// The output of `code`, which must not give warnings.
+fn output(code: &str) -> String {
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(warnings, "");
+
+    output
+}
+
+/// Writes 1 for true and 0 for false, so every result shows up in the output.
+const SHOW: &str = "function show($value) { echo $value ? 1 : 0; }";
+
+#[test]
+fn isset_looks_into_array_elements() {
+    let code = "$array = ['a' => 1, 'b' => null, 'c' => [0 => ['d' => false]]];
+        show(isset($array['a'])); show(isset($array['b'])); show(isset($array['missing']));
+        show(isset($array['c'][0]['d'])); show(isset($array['c'][1]['d'])); show(isset($array['a']['x']));
+        show(isset($undefined['a'][0]));";
+
+    assert_eq!(output(&format!("{} {}", SHOW, code)), "1001000");
+}
+
+#[test]
+fn isset_looks_into_properties() {
+    let code = "class Node { public $next = null; public $value = 1; public $items = ['x' => 2]; }
+        $node = new Node();
+        $node->next = new Node();
+        show(isset($node->value)); show(isset($node->next->value)); show(isset($node->next->next->value));
+        show(isset($node->missing)); show(isset($node->items['x'])); show(isset($node?->value));
+        $nothing = null;
+        show(isset($nothing->value)); show(isset($nothing?->value));";
+
+    assert_eq!(output(&format!("{} {}", SHOW, code)), "11001100");
+}
+
+#[test]
+fn isset_looks_into_string_offsets_and_static_properties() {
+    let code = "$string = 'abc';
+        show(isset($string[0])); show(isset($string[-1])); show(isset($string[3])); show(isset($string['1']));
+        show(isset($string['x']));
+        class Config { public static $debug = true; public static $empty = null; }
+        show(isset(Config::$debug)); show(isset(Config::$empty)); show(isset(Config::$missing));";
+
+    assert_eq!(output(&format!("{} {}", SHOW, code)), "11010100");
+}
+
+#[test]
+fn isset_is_true_only_when_every_argument_is_set() {
+    let code = "$array = ['a' => 1];
+        $object = new Item();
+        class Item { public $p = 0; }
+        show(isset($array['a'], $object->p)); show(isset($array['a'], $array['b'])); show(isset($missing['a'], $array['a']));";
+
+    assert_eq!(output(&format!("{} {}", SHOW, code)), "100");
+}
+
+#[test]
+fn isset_stops_at_the_first_missing_argument() {
+    let code = "function key_of($key) { echo $key; return $key; }
+        $array = ['a' => 1];
+        echo isset($array[key_of('b')], $array[key_of('a')]) ? 'set' : 'unset';";
+
+    assert_eq!(output(code), "bunset");
+}
+
+#[test]
+fn empty_is_true_for_missing_links_and_falsy_values() {
+    let code = "$array = ['zero' => '0', 'text' => 'a', 'list' => [], 'nested' => ['x' => 0.0]];
+        show(empty($array['zero'])); show(empty($array['text'])); show(empty($array['list']));
+        show(empty($array['nested']['x'])); show(empty($array['missing']['x'])); show(empty($missing->property));
+        class Box { public $content = 'something'; }
+        $box = new Box();
+        show(empty($box->content)); show(empty($box->other)); show(empty('0.0'));";
+
+    assert_eq!(output(&format!("{} {}", SHOW, code)), "101111010");
+}
+
+#[test]
+fn isset_cannot_read_with_empty_brackets() {
+    assert_eq!(
+        run_with_warnings("$array = []; isset($array[]);").0,
+        "PHP Fatal error: Cannot use [] for reading in test.php on line 1\n"
+    );
+    assert_eq!(
+        run_with_warnings("$array = []; isset($array[[]]);").0,
+        "PHP Fatal error: Cannot access offset of type array in isset or empty in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn a_null_variable_is_defined_but_not_set() {
+    let code = "function f() {
+            $null = null;
+            echo $null;
+            var_dump(isset($null), isset($missing), empty($null), empty($missing));
+            $vars = get_defined_vars();
+            var_dump(array_key_exists('null', $vars), array_key_exists('missing', $vars));
+            echo $missing;
+        }
+        f();";
+
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(
+        output,
+        "bool(false)\nbool(false)\nbool(true)\nbool(true)\nbool(true)\nbool(false)\n"
+    );
+    // only the variable that was never assigned warns when it is read
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined variable $missing in test.php on line 7\n"
+    );
+}
@@ -0,0 +1,17 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn keywords_are_case_insensitive_in_conditions() {
+    assert_eq!(run("if (TRUE) { echo 'a'; }"), "a");
+    assert_eq!(run("if (False) { echo 'a'; } else { echo 'b'; }"), "b");
+    assert_eq!(run("if (NuLl === null) { echo 'a'; }"), "a");
+}
+
+#[test]
+fn keywords_can_be_fully_qualified() {
+    assert_eq!(run("if (\\TRUE === true) { echo 'a'; }"), "a");
+    assert_eq!(run("if (\\false === false) { echo 'a'; }"), "a");
+    assert_eq!(run("if (\\Null === null) { echo 'a'; }"), "a");
+}
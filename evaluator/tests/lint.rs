@@ -0,0 +1,55 @@
+use evaluator::program::{eval_program_with_writers, lint_program, ERROR_EXIT_CODE};
+
+/// Three statements that cannot be parsed, on the lines 2, 4 and 8.
+const INVALID: &str = "<?php
+$a = ;
+function foo() {
+    $b = 1 +;
+    return $b;
+}
+echo 1;
+class {}
+";
+
+/// Checks the syntax of `content` and returns the report and whether it is valid.
+fn lint(content: &str) -> (String, bool) {
+    let mut output = vec![];
+
+    let valid = lint_program("test.php", content, &mut output).unwrap();
+
+    (String::from_utf8(output).unwrap(), valid)
+}
+
+#[test]
+fn every_syntax_error_is_reported() {
+    let (report, valid) = lint(INVALID);
+
+    assert!(!valid);
+    assert!(report.contains("test.php:2:6"));
+    assert!(report.contains("test.php:4:13"));
+    assert!(report.contains("test.php:8:7"));
+    assert!(report.ends_with("Errors parsing test.php\n"));
+}
+
+#[test]
+fn a_valid_program_is_not_evaluated() {
+    assert_eq!(
+        lint("<?php echo 'hello';"),
+        ("No syntax errors detected in test.php\n".to_string(), true)
+    );
+}
+
+#[test]
+fn an_evaluation_still_stops_at_the_first_syntax_error() {
+    let mut output = vec![];
+
+    let status = eval_program_with_writers("test.php", INVALID, &mut output, &mut vec![]).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+
+    assert_eq!(status, ERROR_EXIT_CODE);
+    assert!(output.contains("test.php:2:6"));
+    assert!(!output.contains("test.php:4:13"));
+    assert!(!output.contains("Errors parsing"));
+    assert_eq!(output.matches("Error:").count(), 1);
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use std::sync::Once;
+
+static SET_LOCALE: Once = Once::new();
+
+/// Runs `code` after the opening tag with a host locale that uses a decimal comma.
+///
+/// The environment is changed once, before any test of this file runs its code, so
+/// no thread reads it while it is written.
+fn run(code: &str) -> String {
+    SET_LOCALE.call_once(|| {
+        std::env::set_var("LC_ALL", "de_DE.UTF-8");
+        std::env::set_var("LC_NUMERIC", "de_DE.UTF-8");
+    });
+
+    common::run(code)
+}
+
+#[test]
+fn every_conversion_uses_a_decimal_point() {
+    assert_eq!(run("echo 1.5;"), "1.5");
+    assert_eq!(run("print 1.5;"), "1.5");
+    assert_eq!(run("echo 'x' . 1.5;"), "x1.5");
+    assert_eq!(run("$f = 1.5; echo \"$f {$f}\";"), "1.5 1.5");
+    assert_eq!(run("$s = 'x'; $s .= 1.5; echo $s;"), "x1.5");
+    assert_eq!(
+        run("ini_set('memory_limit', 1.5); echo ini_get('memory_limit');"),
+        "1.5"
+    );
+    assert_eq!(run("echo 1.0E+25, ' ', -0.0001;"), "1.0E+25 -0.0001");
+}
+
+#[test]
+fn the_locale_functions_describe_the_c_locale() {
+    assert_eq!(
+        run("$l = localeconv(); echo $l['decimal_point'], '|', $l['thousands_sep'];"),
+        ".|"
+    );
+    assert_eq!(
+        run("$l = localeconv(); echo $l['frac_digits'], '|', $l['grouping'] === [];"),
+        "127|1"
+    );
+    assert_eq!(
+        run("echo nl_langinfo(RADIXCHAR), '|', nl_langinfo(THOUSEP), '|', nl_langinfo(CODESET);"),
+        ".||ANSI_X3.4-1968"
+    );
+    assert_eq!(
+        run("echo nl_langinfo(ABDAY_1), '|', nl_langinfo(MON_12), '|', nl_langinfo(D_T_FMT);"),
+        "Sun|December|%a %b %e %H:%M:%S %Y"
+    );
+}
+
+#[test]
+fn the_precision_setting_only_changes_the_number_of_digits() {
+    assert_eq!(run("echo 3.14159;"), "3.14159");
+    assert_eq!(run("ini_set('precision', 3); echo 3.14159;"), "3.14");
+    assert_eq!(
+        run("ini_set('precision', 3); echo 'x' . 1234.5;"),
+        "x1.23E+3"
+    );
+    assert_eq!(run("ini_set('precision', -1); echo 0.1;"), "0.1");
+}
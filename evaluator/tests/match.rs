@@ -0,0 +1,59 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn the_subject_is_compared_strictly() {
+    let code = "function kind($value) {
+            return match ($value) { 1 => 'int', '1' => 'string', 1.0 => 'float', true => 'bool', default => 'other' };
+        }
+        echo kind(1), ' ', kind('1'), ' ', kind(1.0), ' ', kind(true), ' ', kind(null), ' ', kind('01');";
+
+    assert_eq!(run(code), "int string float bool other other");
+}
+
+#[test]
+fn an_arm_can_have_several_conditions() {
+    let code = "function size($value) {
+            return match ($value) { 1, 2 => 'low', 3, 'a' => 'mixed', default => 'none' };
+        }
+        echo size(1), ' ', size(2), ' ', size(3), ' ', size('a'), ' ', size('b');";
+
+    assert_eq!(run(code), "low low mixed mixed none");
+}
+
+#[test]
+fn only_the_matched_arm_is_evaluated() {
+    let code = "function say($word) { echo $word, ' '; return $word; }
+        echo match (2) { say(1) => say('one'), say(2) => say('two'), say(3) => say('three') };";
+
+    assert_eq!(run(code), "1 2 two two");
+}
+
+#[test]
+fn the_conditions_can_be_any_expression() {
+    let code = "class Limits { const LOW = 10; }
+        enum Light { case Red; case Green; }
+        $age = 20;
+        echo match (true) { $age < Limits::LOW => 'child', $age < 18 => 'teen', default => 'adult' };
+        echo ' ', match (Light::Green) { Light::Red => 'stop', Light::Green => 'go' };
+        echo ' ', match (10) { Limits::LOW => 'limit', default => 'none' };";
+
+    assert_eq!(run(code), "adult go limit");
+}
+
+#[test]
+fn no_matching_arm_is_an_error() {
+    assert_eq!(
+        run("echo match (5) { 1 => 'a', '5' => 'b' };"),
+        common::uncaught("UnhandledMatchError: Unhandled match case 5", 1)
+    );
+    assert_eq!(
+        run("echo match ('five') { 5 => 'a' };"),
+        common::uncaught("UnhandledMatchError: Unhandled match case \"five\"", 1)
+    );
+    assert_eq!(
+        run("echo match ([]) { 5 => 'a' };"),
+        common::uncaught("UnhandledMatchError: Unhandled match case of type array", 1)
+    );
+}
@@ -0,0 +1,40 @@
+mod common;
+
+/// Runs `code` after the opening tag and a class, and returns its output.
+fn run(code: &str) -> String {
+    common::run(&format!("class Item {{}} {}", code))
+}
+
+#[test]
+fn every_instance_has_its_own_id() {
+    assert_eq!(
+        run("$a = new Item(); $b = new Item(); echo spl_object_id($a), ' ', spl_object_id($b);"),
+        "1 2"
+    );
+}
+
+#[test]
+fn the_same_instance_has_the_same_id_and_hash() {
+    let code = "$a = new Item(); $b = $a;
+        echo spl_object_id($a) === spl_object_id($b), ' ', spl_object_hash($a) === spl_object_hash($b);
+        echo ' ', spl_object_hash($a);";
+
+    assert_eq!(run(code), "1 1 00000000000000010000000000000000");
+}
+
+#[test]
+fn the_id_of_a_destroyed_object_is_reused() {
+    let code = "$a = new Item(); $b = new Item(); unset($a);
+        $c = new Item();
+        echo spl_object_id($c), ' ', spl_object_id(new Item());";
+
+    assert_eq!(run(code), "1 3");
+}
+
+#[test]
+fn the_argument_must_be_an_object() {
+    assert_eq!(
+        run("spl_object_id('Item');"),
+        common::uncaught("TypeError: spl_object_id(): Argument #1 ($object) must be of type object, string given", 1)
+    );
+}
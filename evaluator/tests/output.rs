@@ -0,0 +1,133 @@
+use std::io::{self, Write};
+
+use evaluator::interpreter::Interpreter;
+use evaluator::output::{Output, OutputTarget};
+use evaluator::program::{eval_program_with_options, EvalOptions};
+
+/// Records every write, so the tests can tell when the output arrived.
+#[derive(Default)]
+struct Writes(Vec<String>);
+
+impl Write for Writes {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.is_empty() {
+            self.0.push(String::from_utf8_lossy(buf).to_string());
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Records the writes and the flushes of the output target, in the order they happen.
+#[derive(Default)]
+struct Events(Vec<String>);
+
+impl OutputTarget for Events {
+    fn write_output(&mut self, text: &str) -> io::Result<()> {
+        if !text.is_empty() {
+            self.0.push(format!("write {}", text));
+        }
+
+        Ok(())
+    }
+
+    fn flush_output(&mut self) -> io::Result<()> {
+        self.0.push("flush".to_string());
+
+        Ok(())
+    }
+}
+
+/// Runs `code` after the opening tag and returns the writes of its output.
+fn run(code: &str, implicit_flush: bool) -> Vec<String> {
+    let options = EvalOptions {
+        ini: vec![(
+            "implicit_flush".to_string(),
+            if implicit_flush { "1" } else { "0" }.to_string(),
+        )],
+        ..EvalOptions::default()
+    };
+
+    let mut output = Writes::default();
+
+    let content = format!("<?php {}", code);
+
+    eval_program_with_options("test.php", &content, &options, &mut output, &mut vec![]).unwrap();
+
+    output.0
+}
+
+#[test]
+fn the_output_is_streamed_with_implicit_flush() {
+    assert_eq!(run("echo 'a'; echo 'b';", true), vec!["a", "b"]);
+    assert_eq!(run("echo 'a'; echo 'b';", false), vec!["ab"]);
+}
+
+#[test]
+fn flush_and_implicit_flush_write_the_pending_output_in_order() {
+    let mut events = Events::default();
+
+    {
+        let mut interpreter = Interpreter::new();
+
+        interpreter.set_output_target(&mut events);
+
+        interpreter
+            .eval_str(
+                "echo 'a'; echo 'b'; flush(); echo 'c';
+                ob_implicit_flush(); echo 'd'; echo 'e';
+                ob_implicit_flush(false); echo 'f'; echo 'g';",
+            )
+            .unwrap();
+    }
+
+    assert_eq!(
+        events.0,
+        vec!["write ab", "flush", "write cd", "flush", "write e", "flush", "write fg", "flush"]
+    );
+}
+
+#[test]
+fn the_streamed_output_is_kept_before_a_fatal_error() {
+    let error = "PHP Fatal error: Uncaught Error: Call to undefined function f() in test.php:1\nStack trace:\n#0 {main}\n  thrown in test.php on line 1\n";
+
+    assert_eq!(run("echo 'before'; f();", true), vec!["before", error]);
+    assert_eq!(
+        run("echo 'before'; f();", false),
+        vec![format!("before{}", error)]
+    );
+}
+
+#[test]
+fn capture_buffers_take_the_output_until_they_are_popped() {
+    let mut captured = String::new();
+
+    let mut output = Output::new();
+
+    output.set_target(&mut captured);
+
+    output.write("a");
+    output.push_buffer();
+    output.write("b");
+    output.push_buffer();
+    output.write("c");
+
+    assert_eq!(output.buffer_level(), 2);
+    assert_eq!(output.pop_buffer(), Some("c".to_string()));
+
+    output.flush().unwrap();
+    output.end_buffers();
+    output.write("d");
+
+    assert_eq!(output.pop_buffer(), None);
+
+    output.flush().unwrap();
+
+    drop(output);
+
+    assert_eq!(captured, "abd");
+}
@@ -0,0 +1,91 @@
+use std::fs;
+
+use evaluator::program::EvalOptions;
+
+mod common;
+
+use common::{eval_with_options, warnings_of, write_file};
+
+/// Runs `code` and returns its output and its warnings.
+fn run(code: &str, warn_output_before_code: bool) -> (String, String) {
+    let options = EvalOptions {
+        warn_output_before_code,
+        ..EvalOptions::default()
+    };
+
+    let result = eval_with_options(code, &options);
+
+    let warnings = warnings_of(&result);
+
+    (result.output, warnings)
+}
+
+#[test]
+fn headers_are_not_sent_before_the_first_output() {
+    let code =
+        "<?php\n$sent = headers_sent($file, $line);\necho '';\n$still_not_sent = headers_sent();\n\
+                echo $sent === false, $file === '', $line === 0, $still_not_sent === false;";
+
+    assert_eq!(run(code, false).0, "1111");
+}
+
+#[test]
+fn headers_sent_gives_the_place_of_the_first_output() {
+    let code = "<?php\n\necho 'a';\necho 'b';\nheaders_sent($file, $line);\necho \" $file:$line\";";
+
+    assert_eq!(run(code, false).0, "ab test.php:3");
+    assert_eq!(
+        run(
+            "\n<?php headers_sent($file, $line); echo \" $file:$line\";",
+            false
+        )
+        .0,
+        "\n test.php:1"
+    );
+}
+
+#[test]
+fn the_first_output_can_be_in_an_included_file() {
+    let path = write_file("output-start.php", "\n<?php\n");
+
+    let code = format!(
+        "<?php include '{}'; echo headers_sent($file, $line), ' ', $file === '{}', ' ', $line;",
+        path.display(),
+        path.display()
+    );
+
+    let (output, warnings) = run(&code, false);
+
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(output, "\n1 1 1");
+    assert_eq!(warnings, "");
+}
+
+#[test]
+fn whitespace_before_the_opening_tag_of_an_included_file_is_a_warning() {
+    let with_whitespace = write_file("leading-blank-line.php", "\n<?php echo 'included';");
+    let without_whitespace = write_file("no-blank-line.php", "<?php echo 'included';");
+
+    let code = format!(
+        "<?php include '{}'; include '{}';",
+        with_whitespace.display(),
+        without_whitespace.display()
+    );
+
+    let (output, warnings) = run(&code, true);
+    let (_, warnings_without_the_option) = run(&code, false);
+
+    fs::remove_file(&with_whitespace).unwrap();
+    fs::remove_file(&without_whitespace).unwrap();
+
+    assert_eq!(output, "\nincludedincluded");
+    assert_eq!(
+        warnings,
+        format!(
+            "PHP Warning: 1 bytes of whitespace are output before the opening tag in {} on line 1\n",
+            with_whitespace.display()
+        )
+    );
+    assert_eq!(warnings_without_the_option, "");
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use common::run_with_warnings;
+
+fn fatal(message: &str) -> (String, String) {
+    (common::fatal(message, 1), String::new())
+}
+
+#[test]
+fn variadic_parameters_cannot_have_a_default() {
+    assert_eq!(
+        run_with_warnings("function f(...$a = []) {} echo 'ok';"),
+        fatal("Variadic parameter cannot have a default value")
+    );
+}
+
+#[test]
+fn only_the_last_parameter_can_be_variadic() {
+    assert_eq!(
+        run_with_warnings("function f(...$a, $b) {}"),
+        fatal("Only the last parameter can be variadic")
+    );
+    assert_eq!(
+        run_with_warnings("function f(...$a, ...$b) {}"),
+        fatal("Only the last parameter can be variadic")
+    );
+    assert_eq!(
+        run_with_warnings("class A { function m(...$a, $b) {} }"),
+        fatal("Only the last parameter can be variadic")
+    );
+}
+
+#[test]
+fn parameters_cannot_be_redefined() {
+    assert_eq!(
+        run_with_warnings("function f($a, $b, $a) {}"),
+        fatal("Redefinition of parameter $a")
+    );
+}
+
+#[test]
+fn optional_parameters_before_a_required_one_are_deprecated() {
+    let deprecation = "PHP Deprecated: Optional parameter $a declared before required parameter $b is implicitly treated as a required parameter in test.php on line 1\n";
+
+    assert_eq!(
+        run_with_warnings("function f($a = 1, $b) {} echo 'ok';"),
+        ("ok".to_string(), deprecation.to_string())
+    );
+    assert_eq!(
+        run_with_warnings("class A { function m($a = 1, $b) {} } echo 'ok';"),
+        ("ok".to_string(), deprecation.to_string())
+    );
+    assert_eq!(
+        run_with_warnings("class A { function __construct($a = 1, $b) {} } echo 'ok';"),
+        ("ok".to_string(), deprecation.to_string())
+    );
+}
+
+#[test]
+fn typed_parameters_with_a_null_default_are_only_nullable() {
+    assert_eq!(
+        run_with_warnings("function f(?int $a = null, $b) {} echo 'ok';"),
+        ("ok".to_string(), String::new())
+    );
+}
+
+#[test]
+fn implicitly_required_parameters_lose_their_default() {
+    let (output, _) = run_with_warnings("function f($a = 1, $b) {} f(b: 2);");
+
+    assert_eq!(
+        output,
+        common::uncaught("ArgumentCountError: f(): Argument #1 ($a) not passed", 1)
+    );
+
+    let (output, _) = run_with_warnings("function f($a, $b = 2, ...$c) { echo $a, $b; } f(1);");
+
+    assert_eq!(output, "12");
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn scalars_are_written_like_echo_writes_them() {
+    assert_eq!(
+        run(
+            "print_r(1); print_r(' '); print_r(1.5); print_r(true); print_r(false); print_r(null);"
+        ),
+        "1 1.51"
+    );
+}
+
+#[test]
+fn nested_arrays_are_indented_by_eight_spaces() {
+    let code = "print_r(['a' => 1, 'list' => [true, null], 'empty' => []]);";
+
+    assert_eq!(
+        run(code),
+        "Array
+(
+    [a] => 1
+    [list] => Array
+        (
+            [0] => 1
+            [1] => 
+        )
+
+    [empty] => Array
+        (
+        )
+
+)
+"
+    );
+}
+
+#[test]
+fn objects_show_the_visibility_of_their_properties() {
+    let code = "class Base { private $secret = 1; }
+        class Child extends Base { protected $level = 2; public $self; }
+        enum Suit: string { case Hearts = 'H'; }
+        $child = new Child();
+        $child->self = $child;
+        print_r([$child, Suit::Hearts]);";
+
+    assert_eq!(
+        run(code),
+        "Array
+(
+    [0] => Child Object
+        (
+            [secret:Base:private] => 1
+            [level:protected] => 2
+            [self] => Child Object
+ *RECURSION*
+        )
+
+    [1] => Suit Enum:string
+        (
+            [name] => Hearts
+            [value] => H
+        )
+
+)
+"
+    );
+}
+
+#[test]
+fn the_text_is_returned_instead_of_written_when_asked() {
+    assert_eq!(
+        run("$text = print_r([1], true); echo strlen($text), ' ', print_r('x');"),
+        "23 x1"
+    );
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::{run, run_with_warnings};
+
+#[test]
+fn a_chain_of_references_shares_one_value() {
+    let code = "$a = 1; $b = &$a; $c = &$b;
+        $c = 2; echo $a, $b, $c, ' ';
+        $a = 3; echo $a, $b, $c;";
+
+    assert_eq!(run(code), "222 333");
+}
+
+#[test]
+fn unset_only_breaks_the_link_of_its_variable() {
+    let code = "$a = 1; $b = &$a; $c = &$b;
+        unset($b);
+        $b = 5; echo $a, $b, $c, ' ';
+        $c = 6; echo $a, $b, $c;";
+
+    assert_eq!(run(code), "151 656");
+}
+
+#[test]
+fn a_reference_to_an_undefined_variable_creates_it() {
+    let code = "$b = &$a; var_dump($a); $b = 'set'; echo $a;";
+
+    assert_eq!(run(code), "NULL\nset");
+}
+
+#[test]
+fn array_elements_can_be_references() {
+    let code = "$value = 1;
+        $array = [&$value, 'key' => &$value];
+        $value = 2; echo $array[0], $array['key'], ' ';
+        $array[0] = 3; echo $value, $array['key'], ' ';
+        $other = [];
+        $other['a']['b'] = &$value;
+        $value = 4; echo $other['a']['b'];";
+
+    assert_eq!(run(code), "22 33 4");
+}
+
+#[test]
+fn a_variable_can_refer_to_an_array_element() {
+    let code = "$array = ['x' => 1, 'y' => 2];
+        $x = &$array['x'];
+        $x = 10; echo $array['x'], ' ';
+        $array['x'] = 20; echo $x, ' ';
+        $new = &$array['z'][];
+        $new = 'created'; echo $array['z'][0], ' ';
+        unset($x); $x = 0; echo $array['x'];";
+
+    assert_eq!(run(code), "10 20 created 20");
+}
+
+#[test]
+fn copies_of_an_array_share_its_references() {
+    let code = "$value = 1;
+        $array = [&$value, 2];
+        $copy = $array;
+        $copy[0] = 'shared'; $copy[1] = 'copied';
+        echo $value, ' ', $array[1];";
+
+    assert_eq!(run(code), "shared 2");
+}
+
+#[test]
+fn string_offsets_cannot_be_references() {
+    assert_eq!(
+        run("$string = 'abc';\n$first = &$string[0];"),
+        "PHP Fatal error: Cannot create references to/from string offsets in test.php on line 2\n"
+    );
+}
+
+#[test]
+fn parameters_passed_by_reference_change_the_arguments() {
+    let code = "function set(&$x) { $x = 1; }
+        set($y); echo $y, ' ';
+        $array = ['a' => 0];
+        set($array['a']); set($array['b']); echo $array['a'], $array['b'], ' ';
+        set(x: $named); echo $named, ' ';
+        class Counter {
+            public function add(&$total, $step) { $total += $step; }
+            public static function reset(&$total) { $total = 0; }
+        }
+        $total = 5; (new Counter)->add($total, 2); echo $total, ' ';
+        Counter::reset($total); echo $total;";
+
+    assert_eq!(run(code), "1 11 1 7 0");
+}
+
+#[test]
+fn temporaries_passed_by_reference_give_a_notice() {
+    let code = "function set(&$x) { $x = 1; return 'done'; }
+        function make() { return 0; }
+        echo set(make());";
+
+    assert_eq!(
+        run_with_warnings(code),
+        (
+            "done".to_string(),
+            "PHP Notice: Only variables should be passed by reference in test.php on line 3\n"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn literals_cannot_be_passed_by_reference() {
+    assert_eq!(
+        run("function set(&$x) {}\nset(2);"),
+        "PHP Fatal error: set(): Argument #1 ($x) could not be passed by reference in test.php on line 2\n"
+    );
+}
+
+#[test]
+fn a_callback_can_write_to_the_array_it_is_given() {
+    let code = "$a = [3, 1, 2];
+        usort($a, function ($x, $y) use (&$a) { $a[] = 9; return $x <=> $y; });
+        echo count($a), ':', $a[0], $a[1], $a[2], ' ';
+        $b = [1, 2];
+        $doubled = array_map(function ($x) use (&$b) { $b[] = $x; return $x * 2; }, $b);
+        echo count($b), ' ', $doubled[0] + $doubled[1];";
+
+    // like PHP, usort writes the sorted array when it ends
+    assert_eq!(run(code), "3:123 4 6");
+}
+
+#[test]
+fn a_value_converted_by_its_own_code_can_be_used_by_that_code() {
+    let code = "class Loud {
+            public $count = 0;
+            function __toString() { $this->count++; echo '[', $this->count, ']'; return 'loud'; }
+        }
+        $loud = new Loud();
+        echo 'a', $loud, 'b', $loud->count;
+        $loud->name = $loud . '';
+        echo ' ', $loud->name;";
+
+    assert_eq!(run(code), "a[1]loudb1[2] loud");
+}
@@ -0,0 +1,148 @@
+mod common;
+
+use common::{fatal, run_with_warnings};
+
+fn output(code: &str) -> String {
+    run_with_warnings(code).0
+}
+
+#[test]
+fn a_value_of_the_wrong_type_is_an_error() {
+    assert_eq!(
+        output("function f(): int { return 'abc'; }\necho f();"),
+        common::uncaught(
+            "TypeError: f(): Return value must be of type int, string returned",
+            2
+        )
+    );
+    assert_eq!(
+        output("class Box { function get(): array { return $this; } }\n(new Box())->get();"),
+        common::uncaught(
+            "TypeError: Box::get(): Return value must be of type array, Box returned",
+            2
+        )
+    );
+}
+
+#[test]
+fn nullable_and_union_types_accept_each_of_their_types() {
+    let code = "function f(): ?string { return null; }
+        function g(): int|array { return [1]; }
+        function h($a): Box|false { if ($a) { return new Box(); } return false; }
+        class Box { public $name = 'Box'; }
+        echo f() === null, g() === [1], h(false) === false, h(true)->name;";
+
+    assert_eq!(output(code), "111Box");
+}
+
+#[test]
+fn scalars_are_converted_to_the_return_type() {
+    let code = "function i(): int { return '42'; }
+        function f(): float { return 1; }
+        function s(): string { return 1.5; }
+        function b(): bool { return 'yes'; }
+        echo i() === 42, f() === 1.0, s() === '1.5', b() === true;";
+
+    assert_eq!(output(code), "1111");
+}
+
+#[test]
+fn a_float_loses_its_fractional_part_with_a_deprecation() {
+    assert_eq!(
+        run_with_warnings("function f(): int { return 1.5; }\necho f();"),
+        (
+            "1".to_string(),
+            "PHP Deprecated: Implicit conversion from float 1.5 to int loses precision in test.php on line 2\n"
+                .to_string()
+        )
+    );
+}
+
+#[test]
+fn a_function_with_a_return_type_must_return() {
+    assert_eq!(
+        output("function f(): int {}\nf();"),
+        common::uncaught(
+            "TypeError: f(): Return value must be of type int, none returned",
+            2
+        )
+    );
+    assert_eq!(
+        output("function f(): int {\nreturn;\n}"),
+        fatal("A function with return type must return a value", 2)
+    );
+    assert_eq!(
+        output("function f(): ?int {\nreturn;\n}"),
+        fatal(
+            "A function with return type must return a value (did you mean \"return null;\" instead of \"return;\"?)",
+            2
+        )
+    );
+}
+
+#[test]
+fn void_functions_cannot_return_a_value() {
+    assert_eq!(
+        output("function f(): void { if (true) { return; } } echo f() === null;"),
+        "1"
+    );
+    assert_eq!(
+        output("function f(): void {\nreturn 1;\n}"),
+        fatal("A void function must not return a value", 2)
+    );
+    assert_eq!(
+        output("function f(): void {\nreturn null;\n}"),
+        fatal(
+            "A void function must not return a value (did you mean \"return;\" instead of \"return null;\"?)",
+            2
+        )
+    );
+}
+
+#[test]
+fn never_functions_cannot_return() {
+    assert_eq!(
+        output("function f(): never {\nreturn;\n}"),
+        fatal("A never-returning function must not return", 2)
+    );
+    assert_eq!(
+        output("function f(): never {}\nf();"),
+        common::uncaught(
+            "TypeError: f(): never-returning function must not implicitly return",
+            2
+        )
+    );
+    assert_eq!(
+        output("function f(): never { echo 'bye'; exit; } f(); echo 'after';"),
+        "bye"
+    );
+}
+
+#[test]
+fn the_returns_of_nested_functions_are_not_checked_with_the_outer_type() {
+    let code = "function outer(): void {
+            function inner(): int { return 1; }
+        }
+        outer();
+        echo inner();";
+
+    assert_eq!(output(code), "1");
+}
+
+#[test]
+fn the_type_is_written_like_it_is_declared() {
+    assert_eq!(
+        output("function f(): ?Foo\\Bar { return 1; }\nf();"),
+        common::uncaught(
+            "TypeError: f(): Return value must be of type ?Foo\\Bar, int returned",
+            2
+        )
+    );
+    assert_eq!(
+        output("function f(): Foo\\Bar|null|array { return 1; }\nf();"),
+        common::uncaught(
+            "TypeError: f(): Return value must be of type Foo\\Bar|null|array, int returned",
+            2
+        )
+    );
+}
@@ -0,0 +1,65 @@
+use std::fs;
+
+mod common;
+
+use common::run;
+
+#[test]
+fn functions_give_the_returned_value_to_the_caller() {
+    assert_eq!(run("function f($a) { return $a * 2; } echo f(21);"), "42");
+    assert_eq!(
+        run("function f() { return; } function g() {} echo f() === null, g() === null;"),
+        "11"
+    );
+}
+
+#[test]
+fn return_stops_the_function() {
+    let code = "function f($a) {
+            if ($a) {
+                return 'early';
+            }
+            echo 'not returned ';
+            return 'late';
+            echo 'never';
+        }
+        echo f(true), ' ', f(false);";
+
+    assert_eq!(run(code), "early not returned late");
+}
+
+#[test]
+fn methods_give_the_returned_value_to_the_caller() {
+    let code = "class Counter {
+            public function __construct(public $count = 0) {}
+            public function next() { return new Counter($this->count + 1); }
+        }
+        echo (new Counter())->next()->next()->count;";
+
+    assert_eq!(run(code), "2");
+}
+
+#[test]
+fn return_at_the_top_level_stops_the_script() {
+    assert_eq!(run("echo 'a'; return; echo 'b';"), "a");
+}
+
+#[test]
+fn return_at_the_top_level_of_an_included_file_gives_the_result_of_include() {
+    let path = std::env::temp_dir().join(format!("phpl-{}-return.php", std::process::id()));
+
+    fs::write(
+        &path,
+        "<?php echo 'included '; return 'value'; echo 'never';",
+    )
+    .unwrap();
+
+    let output = run(&format!(
+        "$value = include '{}'; echo $value, ' after';",
+        path.display()
+    ));
+
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(output, "included value after");
+}
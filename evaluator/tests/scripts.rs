@@ -0,0 +1,86 @@
+use std::fs;
+
+use evaluator::program::{eval_program_with_options, EvalOptions};
+
+mod common;
+
+use common::{eval, warnings_of};
+
+fn run(content: &str) -> (bool, String, String) {
+    let result = eval(content);
+
+    let warnings = warnings_of(&result);
+
+    (result.exit_status == 0, result.output, warnings)
+}
+
+#[test]
+fn the_shebang_of_the_main_script_is_skipped() {
+    let (success, output, warnings) = run("#!/usr/bin/env php\n<?php\necho 'a';\necho $b;");
+
+    assert!(success);
+    assert_eq!(output, "a");
+    assert_eq!(
+        warnings,
+        "PHP Warning: Undefined variable $b in test.php on line 4\n"
+    );
+}
+
+#[test]
+fn the_shebang_of_an_included_file_is_output() {
+    let path = std::env::temp_dir().join(format!("phpl-{}-shebang.php", std::process::id()));
+
+    fs::write(&path, "#!/usr/bin/env php\n<?php echo 'included';").unwrap();
+
+    let (success, output, _) = run(&format!("<?php include '{}';", path.display()));
+
+    assert!(success);
+    assert_eq!(output, "#!/usr/bin/env php\nincluded");
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn a_byte_order_mark_is_output_like_inline_html() {
+    let (success, output, _) = run("\u{feff}<?php echo 'a';");
+
+    assert!(success);
+    assert_eq!(output, "\u{feff}a");
+}
+
+#[test]
+fn inline_html_is_not_escaped() {
+    let (_, output, _) = run("héllo <?php echo 'a'; ?>\twörld");
+
+    assert_eq!(output, "héllo a\twörld");
+}
+
+#[test]
+fn parse_errors_point_at_the_character_after_multi_byte_text() {
+    let (success, output, _) = run("<?php\n$a = \"héllo wörld\"; $b = ;\n");
+
+    assert!(!success);
+    assert!(output.contains("test.php:2:26"), "{}", output);
+}
+
+#[test]
+fn code_without_an_opening_tag_can_be_run() {
+    let options = EvalOptions {
+        without_opening_tag: true,
+        ..EvalOptions::default()
+    };
+
+    let mut output = vec![];
+
+    let status = eval_program_with_options(
+        "Command line code",
+        "echo 1 + 1, ' ', __LINE__; ?> html <?php echo 'php';",
+        &options,
+        &mut output,
+        &mut vec![],
+    )
+    .unwrap();
+
+    assert_eq!(status, 0);
+    assert_eq!(String::from_utf8(output).unwrap(), "2 1 html php");
+}
@@ -0,0 +1,75 @@
+use evaluator::program::ERROR_EXIT_CODE;
+
+mod common;
+
+use common::{run, run_with_status};
+
+#[test]
+fn the_shutdown_functions_run_in_order_after_the_script() {
+    let code = "function bye($name) { echo ' bye ', $name; }
+        register_shutdown_function('bye', 'first');
+        register_shutdown_function(function () { echo ' second'; });
+        echo 'end';";
+
+    assert_eq!(run(code), "end bye first second");
+}
+
+#[test]
+fn the_shutdown_functions_run_after_exit() {
+    let code = "register_shutdown_function(function () { echo ' shutdown'; });
+        echo 'a'; exit(5); echo 'never';";
+
+    assert_eq!(run_with_status(code), ("a shutdown".to_string(), 5));
+}
+
+#[test]
+fn the_shutdown_functions_run_after_a_fatal_error() {
+    let code = "register_shutdown_function(function () { echo 'shutdown'; });
+        missing();";
+
+    assert_eq!(
+        run_with_status(code),
+        (
+            format!(
+                "{}shutdown",
+                common::uncaught("Error: Call to undefined function missing()", 2)
+            ),
+            ERROR_EXIT_CODE
+        )
+    );
+}
+
+#[test]
+fn an_error_in_a_shutdown_function_does_not_stop_the_others() {
+    let code = "register_shutdown_function(function () { echo 'first '; missing(); });
+        register_shutdown_function(function () { echo 'second'; });";
+
+    assert_eq!(
+        run_with_status(code),
+        (
+            format!(
+                "first {}second",
+                common::uncaught("Error: Call to undefined function missing()", 1)
+            ),
+            ERROR_EXIT_CODE
+        )
+    );
+}
+
+#[test]
+fn exit_in_a_shutdown_function_stops_the_others() {
+    let code = "register_shutdown_function(function () { echo 'first'; exit(6); });
+        register_shutdown_function(function () { echo 'never'; });";
+
+    assert_eq!(run_with_status(code), ("first".to_string(), 6));
+}
+
+#[test]
+fn a_shutdown_function_can_register_another_one() {
+    let code = "register_shutdown_function(function () {
+            echo 'outer ';
+            register_shutdown_function(function () { echo 'inner'; });
+        });";
+
+    assert_eq!(run(code), "outer inner");
+}
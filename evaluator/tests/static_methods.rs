@@ -0,0 +1,79 @@
+mod common;
+
+/// Classes with static methods and properties.
+const CLASSES: &str = "
+class Model {
+    public static $created = 0;
+
+    public function __construct(public $name = 'model') { self::$created = self::$created + 1; }
+
+    public static function create() { return new static(); }
+    public static function describe() { return 'self ' . self::label() . ', static ' . static::label(); }
+    public static function label() { return 'model'; }
+    public function greet() { return 'hello from ' . $this->name; }
+}
+
+class User extends Model {
+    public function __construct() { parent::__construct('user'); }
+
+    public static function label() { return 'user'; }
+    public static function parentLabel() { return parent::label() . ' ' . parent::describe(); }
+    public function greet() { return parent::greet() . '!'; }
+}
+";
+
+/// Runs `code` after the classes and returns its output.
+fn run(code: &str) -> String {
+    common::run(&format!("{}\n{}", CLASSES, code))
+}
+
+#[test]
+fn static_methods_are_called_on_the_class() {
+    assert_eq!(
+        run("echo Model::label(), ' ', user::LABEL();"),
+        "model user"
+    );
+}
+
+#[test]
+fn static_methods_share_the_static_properties() {
+    let code = "Model::create(); User::create(); new Model();
+        echo Model::$created, ' ', User::$created;";
+
+    assert_eq!(run(code), "3 3");
+}
+
+#[test]
+fn static_refers_to_the_class_of_the_call() {
+    let code = "echo Model::describe(), ' | ', User::describe(), ' | ', User::create()->name;";
+
+    assert_eq!(
+        run(code),
+        "self model, static model | self model, static user | user"
+    );
+}
+
+#[test]
+fn parent_calls_keep_the_class_of_static() {
+    assert_eq!(
+        run("echo User::parentLabel();"),
+        "model self model, static user"
+    );
+}
+
+#[test]
+fn parent_calls_methods_on_this() {
+    assert_eq!(run("echo (new User())->greet();"), "hello from user!");
+}
+
+#[test]
+fn methods_that_are_not_static_cannot_be_called_statically() {
+    assert_eq!(
+        run("User::greet();"),
+        "PHP Fatal error: Non-static method User::greet() cannot be called statically in test.php on line 21\n"
+    );
+    assert_eq!(
+        run("Model::missing();"),
+        "PHP Fatal error: Uncaught Error: Call to undefined method Model::missing() in test.php:21\nStack trace:\n#0 {main}\n  thrown in test.php on line 21\n"
+    );
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{run, uncaught};
+
+/// A class with a static counter, a child that shares it and a child that redeclares it.
+const CLASSES: &str = "class Counter {
+    public static $count = 0;
+
+    public static function up() { return ++static::$count; }
+}
+class Child extends Counter {}
+class Split extends Counter { public static $count = 10; }
+";
+
+#[test]
+fn a_child_shares_the_static_property_of_its_parent() {
+    let code = "Child::$count++; Child::up(); Counter::$count = Counter::$count + 1;
+        echo Counter::$count, Child::$count;";
+
+    assert_eq!(run(&format!("{}{}", CLASSES, code)), "33");
+}
+
+#[test]
+fn a_redeclared_static_property_is_split_from_the_parent() {
+    let code = "Counter::up(); echo Split::up(), ' ', Split::$count, ' ', Counter::$count;";
+
+    assert_eq!(run(&format!("{}{}", CLASSES, code)), "11 11 1");
+}
+
+#[test]
+fn a_static_property_is_not_a_property_of_the_objects() {
+    let code = "$counter = new Counter(); Counter::$count = 5;
+        echo isset($counter->count) ? 'set' : 'not set', ' ', Counter::$count;";
+
+    assert_eq!(run(&format!("{}{}", CLASSES, code)), "not set 5");
+}
+
+#[test]
+fn an_undeclared_static_property_is_an_error() {
+    assert_eq!(
+        run(&format!("{}echo Counter::$missing;", CLASSES)),
+        uncaught(
+            "Error: Access to undeclared static property Counter::$missing",
+            8
+        )
+    );
+}
+
+#[test]
+fn compound_assignments_change_a_static_property() {
+    let code = "Counter::$count += 2; Child::$count *= 5; Split::$count .= 'x';
+        echo Counter::$count, ' ', Split::$count;";
+
+    assert_eq!(run(&format!("{}{}", CLASSES, code)), "10 10x");
+}
+
+#[test]
+fn private_and_protected_static_properties_are_only_visible_from_their_classes() {
+    let code = "class P {
+            private static $secret = 1;
+            protected static $shared = 2;
+            public static function get() { return self::$secret; }
+        }
+        class C extends P { public static function shared() { return ++static::$shared; } }
+        echo P::get(), ' ', C::shared(), ' ';
+        ";
+
+    assert_eq!(
+        run(&format!("{}echo P::$secret;", code)),
+        format!(
+            "1 3 {}",
+            uncaught("Error: Cannot access private property P::$secret", 8)
+        )
+    );
+    assert_eq!(
+        run(&format!("{}C::$shared += 1;", code)),
+        format!(
+            "1 3 {}",
+            uncaught("Error: Cannot access protected property C::$shared", 8)
+        )
+    );
+}
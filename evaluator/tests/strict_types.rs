@@ -0,0 +1,100 @@
+use std::fs;
+
+mod common;
+
+use common::{fatal, run, uncaught, write_file};
+
+#[test]
+fn a_strict_file_gets_type_errors_from_a_function_of_a_non_strict_file() {
+    let included = write_file(
+        "lax.php",
+        "<?php\nfunction lax(int $a) { return strlen($a); }",
+    );
+
+    let output = run(&format!(
+        "declare(strict_types=1);\ninclude '{}';\necho lax(123), ' ';\nlax('3');",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(
+        output,
+        format!(
+            "3 {}",
+            uncaught(
+                "TypeError: lax(): Argument #1 ($a) must be of type int, string given, called in test.php on line 4",
+                4
+            )
+        )
+    );
+}
+
+#[test]
+fn a_non_strict_file_gets_coercion_from_a_function_of_a_strict_file() {
+    let included = write_file(
+        "strict.php",
+        "<?php\ndeclare(strict_types=1);\nfunction strict(int $a): string { return $a; }",
+    );
+
+    let output = run(&format!(
+        "include '{}';\nvar_dump(strict('3'));",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(output, "string(1) \"3\"\n");
+}
+
+#[test]
+fn the_native_functions_follow_the_mode_of_the_file_that_calls_them() {
+    let included = write_file(
+        "strict_natives.php",
+        "<?php\ndeclare(strict_types=1);\nfunction length($a) { return strlen($a); }",
+    );
+
+    let output = run(&format!(
+        "include '{}';\necho strlen(123), ' ', str_repeat('a', '2'), ' ';\nlength(123);",
+        included.display()
+    ));
+
+    fs::remove_file(&included).unwrap();
+
+    assert_eq!(
+        output,
+        format!(
+            "3 aa PHP Fatal error: Uncaught TypeError: strlen(): Argument #1 ($string) must be of type string, int given in {0}:3\n\
+            Stack trace:\n#0 {{main}}\n  thrown in {0} on line 3\n",
+            included.display()
+        )
+    );
+}
+
+#[test]
+fn an_int_is_still_accepted_for_a_float_in_strict_mode() {
+    let code = "declare(strict_types=1);
+        function f(float $x): float { return 1; }
+        var_dump(f(2), number_format(2));";
+
+    assert_eq!(run(code), "float(1)\nstring(1) \"2\"\n");
+}
+
+#[test]
+fn the_strict_types_declaration_must_be_first_and_without_a_block() {
+    assert_eq!(
+        run("echo 1;\ndeclare(strict_types=1);"),
+        fatal(
+            "strict_types declaration must be the very first statement in the script",
+            2
+        )
+    );
+    assert_eq!(
+        run("declare(strict_types=1) { echo 1; }"),
+        fatal("strict_types declaration must not use block mode", 1)
+    );
+    assert_eq!(
+        run("declare(strict_types=2);"),
+        fatal("strict_types declaration must have 0 or 1 as its value", 1)
+    );
+}
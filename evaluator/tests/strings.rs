@@ -0,0 +1,141 @@
+mod common;
+
+use common::run;
+
+const TEXT: &str = "$s = \"Hello fri3nd, you're\n       looking          good today!\";";
+
+#[test]
+fn str_word_count_counts_the_words() {
+    assert_eq!(run(&format!("{} echo str_word_count($s);", TEXT)), "7");
+    assert_eq!(run("echo str_word_count('');"), "0");
+}
+
+#[test]
+fn str_word_count_returns_the_words() {
+    let code = format!(
+        "{} echo str_word_count($s, 1) === ['Hello', 'fri', 'nd', \"you're\", 'looking', 'good', 'today'];",
+        TEXT
+    );
+
+    assert_eq!(run(&code), "1");
+}
+
+#[test]
+fn str_word_count_returns_the_words_by_offset() {
+    let code = format!(
+        "{} echo str_word_count($s, 2) === [0 => 'Hello', 6 => 'fri', 10 => 'nd', 14 => \"you're\", 28 => 'looking', 45 => 'good', 50 => 'today'];",
+        TEXT
+    );
+
+    assert_eq!(run(&code), "1");
+}
+
+#[test]
+fn str_word_count_accepts_extra_characters() {
+    let code = format!(
+        "{} echo str_word_count($s, 1, '0..9') === ['Hello', 'fri3nd', \"you're\", 'looking', 'good', 'today'];",
+        TEXT
+    );
+
+    assert_eq!(run(&code), "1");
+}
+
+#[test]
+fn str_word_count_trims_leading_quotes_and_trailing_dashes() {
+    assert_eq!(run("echo str_word_count(\"'a b-\", 1) === ['a', 'b'];"), "1");
+    assert_eq!(run("echo str_word_count(\"'a b-\", 1, \"'-\") === [\"'a\", 'b-'];"), "1");
+}
+
+#[test]
+fn str_word_count_rejects_unknown_formats() {
+    assert_eq!(
+        run("str_word_count('a', 3);"),
+        common::uncaught(
+            "ValueError: str_word_count(): Argument #2 ($format) must be a valid format value",
+            1
+        )
+    );
+}
+
+#[test]
+fn strlen_and_the_case_functions_work_on_bytes() {
+    let code = "echo strlen('hello'), ' ', strlen(''), ' ', strlen(12.5), ' ', strlen('é');
+        echo ' ', strtoupper('Hello é 1'), ' ', strtolower('HeLLo World');";
+
+    assert_eq!(run(code), "5 0 4 2 HELLO é 1 hello world");
+}
+
+#[test]
+fn substr_counts_negative_offsets_and_lengths_from_the_end() {
+    let code = "echo substr('abcdef', 1), ',', substr('abcdef', 1, 3), ',', substr('abcdef', -2);
+        echo ',', substr('abcdef', 0, -1), ',', substr('abcdef', -3, -1), ',', substr('abcdef', 2, null);
+        echo ',', substr('abc', 5) === '', substr('abc', -10, 1), substr('abc', 2, -5) === '';";
+
+    assert_eq!(run(code), "bcdef,bcd,ef,abcde,de,cdef,1a1");
+}
+
+#[test]
+fn str_repeat_and_trim() {
+    let code = "echo str_repeat('ab', 3), '|', str_repeat('x', 0), '|';
+        echo trim(\"  \\t hi \\n\\0\"), '|', trim('xxhixx', 'x'), '|', trim('abchicba', 'a..c'), '|', trim('   '), '|';";
+
+    assert_eq!(run(code), "ababab||hi|hi|hi||");
+}
+
+#[test]
+fn str_replace_replaces_every_search_string_in_turn() {
+    let code = "echo str_replace('l', 'L', 'hello world', $count), ' ', $count;
+        echo ' ', str_replace(['a', 'b'], ['b', 'c'], 'ab'), ' ', str_replace(['a', 'b'], 'x', 'abc');
+        echo ' ', str_replace(['a', 'b'], ['1'], 'abc'), ' ', str_replace('', 'x', 'abc');
+        echo ' ', str_replace('o', '0', ['k' => 'foo', 'bar']) === ['k' => 'f00', 'bar'];";
+
+    assert_eq!(run(code), "heLLo worLd 3 cc xxc 1c abc 1");
+}
+
+#[test]
+fn strpos_gives_false_when_the_needle_is_not_found() {
+    let code = "echo strpos('hello', 'l'), ' ', strpos('hello', 'l', 3), ' ', strpos('hello', 'l', -2);
+        echo ' ', strpos('hello', 'z') === false, ' ', strpos('hello', ''), ' ', strpos('hello', 'h', 5) === false;";
+
+    assert_eq!(run(code), "2 3 3 1 0 1");
+}
+
+#[test]
+fn string_functions_check_their_arguments() {
+    assert_eq!(
+        run("strlen();"),
+        common::uncaught(
+            "ArgumentCountError: strlen() expects exactly 1 argument, 0 given",
+            1
+        )
+    );
+    assert_eq!(
+        run("substr('a', 1, 2, 3);"),
+        common::uncaught(
+            "ArgumentCountError: substr() expects at most 3 arguments, 4 given",
+            1
+        )
+    );
+    assert_eq!(
+        run("strtoupper([]);"),
+        common::uncaught(
+            "TypeError: strtoupper(): Argument #1 ($string) must be of type string, array given",
+            1
+        )
+    );
+    assert_eq!(
+        run("str_repeat('a', -1);"),
+        common::uncaught(
+            "ValueError: str_repeat(): Argument #2 ($times) must be greater than or equal to 0",
+            1
+        )
+    );
+    assert_eq!(
+        run("strpos('abc', 'a', 4);"),
+        common::uncaught("ValueError: strpos(): Argument #3 ($offset) must be contained in argument #1 ($haystack)", 1)
+    );
+    assert_eq!(
+        run("str_replace('a', ['b'], 'abc');"),
+        common::uncaught("TypeError: str_replace(): Argument #2 ($replace) must be of type string when argument #1 ($search) is a string", 1)
+    );
+}
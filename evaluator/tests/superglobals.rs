@@ -0,0 +1,72 @@
+use evaluator::program::EvalOptions;
+
+mod common;
+
+use common::eval_with_options;
+
+/// Runs `code` after the opening tag with the script arguments `argv`, and returns
+/// its output.
+fn run(code: &str, argv: &[&str]) -> String {
+    let content = format!("<?php {}", code);
+
+    let options = EvalOptions {
+        argv: argv.iter().map(|argument| argument.to_string()).collect(),
+        ..EvalOptions::default()
+    };
+
+    eval_with_options(&content, &options).output
+}
+
+#[test]
+fn the_arguments_of_the_script_are_in_argv() {
+    let code = "echo $argc, ' ', $argv[0], ' ', $argv[1], ' ', $argv[2], ' ';
+        echo $_SERVER['argc'], ' ', $_SERVER['argv'] === $argv;";
+
+    assert_eq!(run(code, &["one", "-o"]), "3 test.php one -o 3 1");
+    assert_eq!(run("echo $argc, count($argv);", &[]), "11");
+}
+
+#[test]
+fn server_describes_the_script() {
+    let code = "echo $_SERVER['SCRIPT_FILENAME'], ' ', $_SERVER['PHP_SELF'], ' ';
+        echo $_SERVER['SCRIPT_NAME'], ' ', gettype($_SERVER['REQUEST_TIME']);";
+
+    assert_eq!(run(code, &[]), "test.php test.php test.php integer");
+}
+
+#[test]
+fn env_has_the_environment_variables() {
+    let path = std::env::var("PATH").unwrap_or_default();
+
+    assert_eq!(run("echo $_ENV['PATH'] ?? '';", &[]), path);
+}
+
+#[test]
+fn the_request_superglobals_are_empty() {
+    assert_eq!(
+        run("var_dump($_GET, $_POST, $_COOKIE);", &[]),
+        "array(0) {\n}\narray(0) {\n}\narray(0) {\n}\n"
+    );
+}
+
+#[test]
+fn superglobals_are_seen_from_every_scope() {
+    let code = "function script() { return $_SERVER['argv'][1]; }
+        function set() { $_GET['page'] = 2; }
+        set();
+        echo script(), ' ', $_GET['page'], ' ', isset($argv) ? 'global' : 'local';
+        function local() { return isset($argv) ? 'global' : 'local'; }
+        echo ' ', local();";
+
+    assert_eq!(run(code, &["first"]), "first 2 global local");
+}
+
+#[test]
+fn globals_gives_the_global_variables() {
+    let code = "$count = 3;
+        function read() { return $GLOBALS['count'] + 1; }
+        echo read(), ' ', isset($GLOBALS['missing']) ? 'yes' : 'no', ' ';
+        echo array_key_exists('count', $GLOBALS), ' ', $GLOBALS['argv'] === $argv;";
+
+    assert_eq!(run(code, &[]), "4 no 1 1");
+}
@@ -0,0 +1,110 @@
+mod common;
+
+use common::{run, warnings};
+
+#[test]
+fn the_cases_are_compared_loosely() {
+    let code = "function name($value) {
+            switch ($value) {
+                case 1: return 'one';
+                case 'two': return 'two';
+                case null: return 'empty';
+            }
+            return 'none';
+        }
+        echo name('1'), ' ', name(1.0), ' ', name('two'), ' ', name(0), ' ', name(''), ' ', name(3);";
+
+    assert_eq!(run(code), "one one two empty empty none");
+}
+
+#[test]
+fn the_cases_fall_through_until_a_break() {
+    let code = "$size = 2;
+        switch ($size) {
+            case 1: echo 'one ';
+            case 2: echo 'two ';
+            case 3: echo 'three ';
+                break;
+            case 4: echo 'four ';
+        }
+        echo 'end';";
+
+    assert_eq!(run(code), "two three end");
+}
+
+#[test]
+fn the_default_case_is_taken_when_no_case_matches_wherever_it_is() {
+    let code = "function pick($value) {
+            switch ($value) {
+                default: echo 'default ';
+                case 'a': echo 'a ';
+                    break;
+                case 'b': echo 'b ';
+            }
+        }
+        pick('b'); pick('a'); pick('z');";
+
+    assert_eq!(run(code), "b a default a ");
+}
+
+#[test]
+fn the_cases_are_evaluated_until_one_matches() {
+    let code = "function say($word) { echo $word, ' '; return $word; }
+        switch ('b') {
+            case say('a'):
+            case say('b'): echo 'matched ';
+            case say('c'): echo 'falls ';
+        }";
+
+    assert_eq!(run(code), "a b matched falls ");
+}
+
+#[test]
+fn break_leaves_nested_switches() {
+    let code = "switch (1) {
+            case 1:
+                switch (2) {
+                    case 2: echo 'inner ';
+                        break 2;
+                }
+                echo 'skipped ';
+        }
+        switch (1) {
+            case 1:
+                switch (2) { case 2: break; }
+                echo 'outer';
+        }";
+
+    assert_eq!(run(code), "inner outer");
+}
+
+#[test]
+fn continue_in_a_switch_is_a_break_with_a_warning() {
+    let code = "switch (1) { case 1: echo 'a'; continue; case 2: echo 'b'; } echo 'c';";
+
+    assert_eq!(run(code), "ac");
+    assert_eq!(
+        warnings(code),
+        "PHP Warning: \"continue\" targeting switch is equivalent to \"break\" in test.php on line 1\n"
+    );
+}
+
+#[test]
+fn break_outside_of_a_switch_is_an_error() {
+    assert_eq!(
+        run("echo 'a'; break;"),
+        "aPHP Fatal error: 'break' not in the 'loop' or 'switch' context in test.php on line 1\n"
+    );
+    assert_eq!(
+        run("switch (1) { case 1: break 2; }"),
+        "PHP Fatal error: Cannot 'break' 2 levels in test.php on line 1\n"
+    );
+    assert_eq!(
+        run("function f() { break; } switch (1) { case 1: f(); }"),
+        "PHP Fatal error: 'break' not in the 'loop' or 'switch' context in test.php on line 1\n"
+    );
+    assert_eq!(
+        run("switch (1) { case 1: break 0; }"),
+        "PHP Fatal error: 'break' operator accepts only positive integers in test.php on line 1\n"
+    );
+}
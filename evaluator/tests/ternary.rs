@@ -0,0 +1,68 @@
+mod common;
+
+use common::{run, warnings};
+
+#[test]
+fn only_the_taken_branch_is_evaluated() {
+    let code = "function say($word) { echo $word, ' '; return $word; }
+        $result = true ? say('then') : say('else');
+        $result .= 0 ? say('then') : say('else');
+        echo $result;";
+
+    assert_eq!(run(code), "then else thenelse");
+}
+
+#[test]
+fn the_short_ternary_gives_the_condition_when_it_is_true() {
+    let code = "function once() { echo 'once '; return 'value'; }
+        echo once() ?: 'default', ' ', 0 ?: 'zero', ' ', '' ?: [] ?: 'empty';";
+
+    assert_eq!(run(code), "once value zero empty");
+}
+
+#[test]
+fn a_ternary_without_a_then_branch_is_a_short_ternary() {
+    assert_eq!(
+        run("echo 'value' ? : 'default', ' ', 0 ? /* none */ : 'zero';"),
+        "value zero"
+    );
+}
+
+#[test]
+fn coalesce_gives_the_first_value_that_is_not_null() {
+    let code = "$set = 'a'; $null = null; $false = false;
+        $array = ['key' => 'b', 'nested' => ['c']];
+        echo $set ?? 'x', $null ?? 'y', ($false ?? 'z') === false, ' ';
+        echo $array['key'] ?? 'x', $array['missing'] ?? 'y', $array['nested'][0] ?? 'z', ' ';
+        echo $undefined ?? $null ?? 'last', ' ', 'abc'[1] ?? 'x', 'abc'[5] ?? 'y';";
+
+    assert_eq!(run(code), "ay1 byc last by");
+}
+
+#[test]
+fn coalesce_reads_properties_of_missing_objects_as_null() {
+    let code = "class Box { public $item = 'pen'; public $empty = null; }
+        $box = new Box();
+        echo $box->item ?? 'x', $box->empty ?? 'y', $box->missing ?? 'z';
+        echo $missing->item ?? 'w', $box?->item ?? 'v';";
+
+    assert_eq!(run(code), "penyzwpen");
+}
+
+#[test]
+fn coalesce_does_not_warn_about_its_left_side() {
+    assert_eq!(
+        warnings(
+            "$array = []; echo $undefined ?? 'd', $array['k']['j'] ?? 'd', $undefined->p ?? 'd';"
+        ),
+        ""
+    );
+    assert_eq!(
+        warnings("echo $undefined ?: 'd';"),
+        "PHP Warning: Undefined variable $undefined in test.php on line 1\n"
+    );
+    assert_eq!(
+        warnings("function f() { return $inside; } echo f() ?? 'd';"),
+        "PHP Warning: Undefined variable $inside in test.php on line 1\n"
+    );
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn the_constructor_writes_the_properties_of_the_new_object() {
+    let code = "class Point {
+            public $x;
+            public function __construct($x) { $this->x = $x; $this->y = $x * 2; }
+            public function move() { $this->x = $this->x + 1; return $this; }
+        }
+        $point = new Point(3);
+        echo $point->x, ' ', $point->y, ' ', $point->move()->move()->x, ' ', $point->x;";
+
+    assert_eq!(run(code), "3 6 5 5");
+}
+
+#[test]
+fn this_is_only_defined_in_the_methods_of_an_object() {
+    let error =
+        |line: usize| common::uncaught("Error: Using $this when not in object context", line);
+
+    assert_eq!(run("echo $this;"), error(1));
+    assert_eq!(run("function f() {\nreturn $this;\n}\nf();"), error(2));
+    assert_eq!(
+        run("class A { public static function f() {\nreturn $this;\n} }\nA::f();"),
+        error(2)
+    );
+}
+
+#[test]
+fn this_cannot_be_assigned() {
+    assert_eq!(
+        run("class A { public function f() { $this = new A(); } }\n(new A())->f();"),
+        "PHP Fatal error: Cannot re-assign $this in test.php on line 1\n"
+    );
+}
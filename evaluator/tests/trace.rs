@@ -0,0 +1,109 @@
+use std::cell::RefCell;
+use std::fs;
+use std::rc::Rc;
+
+use evaluator::program::EvalOptions;
+use evaluator::trace::{TraceEvent, TraceSink, TraceWriter};
+
+mod common;
+
+use common::eval_with_options;
+
+const FIXTURE: &str = "<?php
+function f($n) {
+    if ($n > 1) {
+        echo 'big';
+    } else {
+        echo 'small';
+    }
+}
+f(2);
+f(1);
+";
+
+/// Records the line of every statement, and the calls and returns.
+#[derive(Default)]
+struct Lines(Vec<String>);
+
+impl TraceSink for Lines {
+    fn on_event(&mut self, event: &TraceEvent, depth: usize) {
+        let line = match event {
+            TraceEvent::Statement { line, .. } => line.to_string(),
+            TraceEvent::Call { function, .. } => format!("call {}", function),
+            TraceEvent::Return { function } => format!("return {}", function),
+            TraceEvent::EnterFile { .. } => "enter".to_string(),
+            TraceEvent::LeaveFile { .. } => "leave".to_string(),
+        };
+
+        self.0.push(format!("{}{}", depth, line));
+    }
+}
+
+fn trace(content: &str, sink: Rc<RefCell<dyn TraceSink>>) -> String {
+    let options = EvalOptions {
+        trace: Some(sink),
+        ..EvalOptions::default()
+    };
+
+    eval_with_options(content, &options).output
+}
+
+#[test]
+fn statements_follow_the_control_flow() {
+    let lines = Rc::new(RefCell::new(Lines::default()));
+
+    let output = trace(FIXTURE, lines.clone());
+
+    assert_eq!(output, "bigsmall");
+    assert_eq!(
+        lines.borrow().0,
+        vec![
+            "02", "09", "0call f", "13", "14", "0return f", "010", "0call f", "13", "16",
+            "0return f",
+        ]
+    );
+}
+
+#[test]
+fn included_files_are_one_level_deeper() {
+    let path = std::env::temp_dir().join(format!("phpl-{}-trace.php", std::process::id()));
+
+    fs::write(&path, "<?php\necho 'included';").unwrap();
+
+    let lines = Rc::new(RefCell::new(Lines::default()));
+
+    let content = format!("<?php\ninclude '{}';\necho 'after';", path.display());
+
+    assert_eq!(trace(&content, lines.clone()), "includedafter");
+    assert_eq!(lines.borrow().0, vec!["02", "0enter", "12", "0leave", "03"]);
+
+    fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn the_writer_indents_and_filters() {
+    let writer = Rc::new(RefCell::new(TraceWriter::new(vec![], None)));
+
+    trace("<?php\nfunction f($s) {\n    echo $s;\n}\nf('a');", writer.clone());
+
+    let written = String::from_utf8(writer.borrow().get_ref().clone()).unwrap();
+
+    assert_eq!(
+        written,
+        "test.php:2\ntest.php:5\ncall f(\"a\") at test.php:5\n  test.php:3\nreturn from f\n"
+    );
+
+    let writer = Rc::new(RefCell::new(TraceWriter::new(vec![], Some("g".to_string()))));
+
+    trace(
+        "<?php\nfunction g() {\n    echo 1;\n}\nfunction f() {\n    g();\n}\nf();",
+        writer.clone(),
+    );
+
+    let written = String::from_utf8(writer.borrow().get_ref().clone()).unwrap();
+
+    assert_eq!(
+        written,
+        "  call g() at test.php:6\n    test.php:3\n  return from g\n"
+    );
+}
@@ -0,0 +1,142 @@
+mod common;
+
+use common::run_with_warnings;
+
+/// The output of `code`, which must not give warnings.
+fn output(code: &str) -> String {
+    let (output, warnings) = run_with_warnings(code);
+
+    assert_eq!(warnings, "");
+
+    output
+}
+
+#[test]
+fn gettype_gives_the_historical_names() {
+    let code = "echo gettype(1), ' ', gettype(1.5), ' ', gettype(true), ' ', gettype(null);
+        echo ' ', gettype('a'), ' ', gettype([]), ' ', gettype(fn () => 1);";
+
+    assert_eq!(
+        output(code),
+        "integer double boolean NULL string array object"
+    );
+}
+
+#[test]
+fn intval_reads_the_number_at_the_start_of_a_string() {
+    let code = "echo intval('12abc'), ' ', intval('abc'), ' ', intval('  -7 '), ' ', intval('1e3');
+        echo ' ', intval(4.9), ' ', intval(-4.9), ' ', intval(true), ' ', intval(null), ' ', intval([0]);";
+
+    assert_eq!(output(code), "12 0 -7 1000 4 -4 1 0 1");
+}
+
+#[test]
+fn intval_reads_strings_in_the_given_base() {
+    let code = "echo intval('0x1A', 16), ' ', intval('1A', 16), ' ', intval('42', 8), ' ', intval('0b101', 0);
+        echo ' ', intval('0x1A', 0), ' ', intval('012', 0), ' ', intval('-ff', 16), ' ', intval('42', 1);
+        echo ' ', intval('ffffffffffffffffff', 16), ' ', intval(42, 8);";
+
+    assert_eq!(
+        output(code),
+        "26 26 34 5 26 10 -255 0 9223372036854775807 42"
+    );
+}
+
+#[test]
+fn floatval_boolval_and_strval_convert_like_php() {
+    let code = "echo floatval('1.5e3'), ' ', floatval('-.5abc'), ' ', floatval('abc'), ' ', floatval(2);
+        echo ' ', var_export(boolval('0'), true), ' ', var_export(boolval('0.0'), true), ' ', var_export(boolval(''), true);
+        echo ' ', var_export(boolval([]), true), ' ', var_export(boolval([0]), true), ' ', var_export(boolval(0.0), true);
+        echo ' ', strval(1.0), ' ', strval(true), ' [', strval(null), ']';";
+
+    assert_eq!(
+        output(code),
+        "1500 -0.5 0 2 false true false false true false 1 1 []"
+    );
+    assert_eq!(
+        run_with_warnings("echo strval([1]);"),
+        (
+            "Array".to_string(),
+            "PHP Warning: Array to string conversion in test.php on line 1\n".to_string()
+        )
+    );
+}
+
+#[test]
+fn the_string_zero_is_false_in_conditions() {
+    assert_eq!(
+        output("if ('0') { echo 'a'; } if ('0.0') { echo 'b'; } if ('') { echo 'c'; }"),
+        "b"
+    );
+}
+
+#[test]
+fn settype_changes_the_variable() {
+    let code = "$value = '12 apples';
+        echo settype($value, 'integer'), ' ', gettype($value), ' ', $value;
+        settype($value, 'float'); echo ' ', gettype($value);
+        settype($value, 'string'); echo ' ', gettype($value), ' ', $value;
+        settype($value, 'bool'); echo ' ', var_export($value, true);
+        settype($value, 'array'); echo ' ', $value === [true];
+        settype($value, 'null'); echo ' ', gettype($value);";
+
+    assert_eq!(output(code), "1 integer 12 double string 12 true 1 NULL");
+}
+
+#[test]
+fn settype_gives_the_properties_of_an_object_as_an_array() {
+    let code = "class Point { public $x = 1; protected $y = 2; private $z = 3; }
+        $point = new Point();
+        settype($point, 'array');
+        echo $point === ['x' => 1, \"\\0*\\0y\" => 2, \"\\0Point\\0z\" => 3];";
+
+    assert_eq!(output(code), "1");
+}
+
+#[test]
+fn settype_needs_a_valid_type() {
+    assert_eq!(
+        run_with_warnings("$value = 1; settype($value, 'number');").0,
+        common::uncaught(
+            "ValueError: settype(): Argument #2 ($type) must be a valid type",
+            1
+        )
+    );
+}
+
+#[test]
+fn concatenation_converts_every_scalar_to_a_string() {
+    let code = "echo 'Count: ' . 5, '|', 'pi=' . 3.14, '|', 'a' . true . false . null . 'b';
+        $s = 'x'; $s .= 1; $s .= 2.5; $s .= true; $s .= null;
+        echo '|', $s, '|', 1 . 2, '|', -1.5 . '';";
+
+    assert_eq!(output(code), "Count: 5|pi=3.14|a1b|x12.51|12|-1.5");
+}
+
+#[test]
+fn concatenating_an_array_gives_array_with_a_warning() {
+    assert_eq!(
+        run_with_warnings("echo 'a' . [1];"),
+        (
+            "aArray".to_string(),
+            "PHP Warning: Array to string conversion in test.php on line 1\n".to_string()
+        )
+    );
+}
+
+#[test]
+fn objects_are_converted_with_their_to_string_method() {
+    let code = "class Name { public function __toString(): string { return 'ana'; } }
+        $s = 'hi ';
+        $s .= new Name();
+        echo 'name: ' . new Name(), ', ', $s;";
+
+    assert_eq!(output(code), "name: ana, hi ana");
+    assert_eq!(
+        output("class Plain {}\necho 'a' . new Plain();"),
+        common::uncaught(
+            "Error: Object of class Plain could not be converted to string",
+            2
+        )
+    );
+}
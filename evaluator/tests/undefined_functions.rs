@@ -0,0 +1,40 @@
+mod common;
+
+use common::run;
+
+fn uncaught(message: &str) -> String {
+    common::uncaught(&format!("Error: {}", message), 1)
+}
+
+#[test]
+fn a_typo_in_a_native_function_suggests_it() {
+    assert_eq!(
+        run("sprintff('%d', 1);"),
+        uncaught("Call to undefined function sprintff(), did you mean sprintf()?")
+    );
+    assert_eq!(
+        run("IN_ARARY(1, [1]);"),
+        uncaught("Call to undefined function IN_ARARY(), did you mean in_array()?")
+    );
+}
+
+#[test]
+fn a_typo_in_a_user_function_suggests_it() {
+    assert_eq!(
+        run("function greet() {} gret();"),
+        uncaught("Call to undefined function gret(), did you mean greet()?")
+    );
+    assert_eq!(
+        run("function compute_total() {} $f = compute_totl(...);"),
+        uncaught("Call to undefined function compute_totl(), did you mean compute_total()?")
+    );
+}
+
+#[test]
+fn an_unrelated_name_has_no_suggestion() {
+    assert_eq!(
+        run("function greet() {} frobnicate();"),
+        uncaught("Call to undefined function frobnicate()")
+    );
+    assert_eq!(run("xy();"), uncaught("Call to undefined function xy()"));
+}
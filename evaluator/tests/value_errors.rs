@@ -0,0 +1,73 @@
+mod common;
+
+use common::run;
+
+/// The report of a ValueError thrown on the first line.
+fn value_error(message: &str) -> String {
+    common::uncaught(&format!("ValueError: {}", message), 1)
+}
+
+#[test]
+fn array_functions_reject_invalid_values() {
+    assert_eq!(
+        run("array_fill(0, -1, 'x');"),
+        value_error("array_fill(): Argument #2 ($count) must be greater than or equal to 0")
+    );
+    assert_eq!(run("echo array_fill(5, 0, 'x') === [];"), "1");
+    assert_eq!(
+        run("array_combine([1, 2], [1]);"),
+        value_error("array_combine(): Argument #1 ($keys) and argument #2 ($values) must have the same number of elements")
+    );
+}
+
+#[test]
+fn range_rejects_an_invalid_step() {
+    assert_eq!(
+        run("range(1, 5, 0);"),
+        value_error("range(): Argument #3 ($step) cannot be 0")
+    );
+    assert_eq!(
+        run("range(1, 5, 0.0);"),
+        value_error("range(): Argument #3 ($step) cannot be 0")
+    );
+    assert_eq!(
+        run("range(1, 5, 10);"),
+        value_error("range(): Argument #3 ($step) must not exceed the specified range")
+    );
+    assert_eq!(
+        run("range('a', 'c', 5);"),
+        value_error("range(): Argument #3 ($step) must not exceed the specified range")
+    );
+}
+
+#[test]
+fn string_functions_reject_invalid_values() {
+    assert_eq!(
+        run("str_word_count('a b', 3);"),
+        value_error("str_word_count(): Argument #2 ($format) must be a valid format value")
+    );
+    assert_eq!(
+        run("str_word_count('a b', -1);"),
+        value_error("str_word_count(): Argument #2 ($format) must be a valid format value")
+    );
+}
+
+#[test]
+fn sprintf_rejects_invalid_formats() {
+    assert_eq!(
+        run("sprintf('%');"),
+        value_error("Missing format specifier at end of string")
+    );
+    assert_eq!(
+        run("sprintf('%y', 1);"),
+        value_error("Unknown format specifier \"y\"")
+    );
+    assert_eq!(
+        run("sprintf('%0$s', 1);"),
+        value_error("Argument number specifier must be greater than zero and less than 2147483647")
+    );
+    assert_eq!(
+        run("sprintf('%99999999999d', 1);"),
+        value_error("Width must be greater than zero and less than 2147483647")
+    );
+}
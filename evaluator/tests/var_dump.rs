@@ -0,0 +1,90 @@
+mod common;
+
+use common::run;
+
+#[test]
+fn scalars_are_written_with_their_type() {
+    assert_eq!(
+        run("var_dump(5, -3, 1.5, 2.0, 1e25, true, false, null, 'abc', '');"),
+        "int(5)\nint(-3)\nfloat(1.5)\nfloat(2)\nfloat(1.0E+25)\nbool(true)\nbool(false)\nNULL\nstring(3) \"abc\"\nstring(0) \"\"\n"
+    );
+}
+
+/// The output of PHP 8.2 for the same array.
+#[test]
+fn nested_arrays_are_indented_like_php_does() {
+    let code = "var_dump([1, 'key' => ['nested' => [true, null]], 5 => 'five', 'empty' => []]);";
+
+    assert_eq!(
+        run(code),
+        r#"array(4) {
+  [0]=>
+  int(1)
+  ["key"]=>
+  array(1) {
+    ["nested"]=>
+    array(2) {
+      [0]=>
+      bool(true)
+      [1]=>
+      NULL
+    }
+  }
+  [5]=>
+  string(4) "five"
+  ["empty"]=>
+  array(0) {
+  }
+}
+"#
+    );
+}
+
+#[test]
+fn objects_show_the_visibility_of_their_properties() {
+    let code = "class Base { private $secret = 1; }
+        class Child extends Base { public $name = 'child'; protected $level = 2; public $self; }
+        $child = new Child();
+        $child->self = $child;
+        var_dump($child);";
+
+    assert_eq!(
+        run(code),
+        r#"object(Child)#1 (4) {
+  ["secret":"Base":private]=>
+  int(1)
+  ["name"]=>
+  string(5) "child"
+  ["level":protected]=>
+  int(2)
+  ["self"]=>
+  *RECURSION*
+}
+"#
+    );
+}
+
+#[test]
+fn references_are_written_as_the_value_they_refer_to() {
+    let code = "enum Status { case Active; }
+        $value = 1;
+        $reference = &$value;
+        $reference = [Status::Active];
+        var_dump($value);";
+
+    assert_eq!(
+        run(code),
+        "array(1) {\n  [0]=>\n  enum(Status::Active)\n}\n"
+    );
+}
+
+#[test]
+fn var_dump_needs_a_value() {
+    assert_eq!(
+        run("var_dump();"),
+        common::uncaught(
+            "ArgumentCountError: var_dump() expects at least 1 argument, 0 given",
+            1
+        )
+    );
+}
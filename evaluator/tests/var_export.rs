@@ -0,0 +1,76 @@
+mod common;
+
+use common::run_with_warnings;
+
+#[test]
+fn scalars_are_written_as_php_literals() {
+    let code = "var_export(null); echo ' '; var_export(false); echo ' '; var_export(-3);
+        echo ' '; var_export(2.0); echo ' '; var_export(0.5); echo ' '; var_export(\"it's \\\\ \\0\");";
+
+    assert_eq!(
+        run_with_warnings(code).0,
+        "NULL false -3 2.0 0.5 'it\\'s \\\\ ' . \"\\0\" . ''"
+    );
+}
+
+#[test]
+fn nested_arrays_start_on_their_own_line() {
+    let code = "var_export([1, 'key' => ['nested' => true, 'empty' => []]]);";
+
+    assert_eq!(
+        run_with_warnings(code).0,
+        "array (
+  0 => 1,
+  'key' => 
+  array (
+    'nested' => true,
+    'empty' => 
+    array (
+    ),
+  ),
+)"
+    );
+}
+
+#[test]
+fn objects_are_written_with_set_state() {
+    let code = "class Point { public $x = 1; protected $tags = ['a']; }
+        enum Suit { case Hearts; }
+        var_export([new Point(), Suit::Hearts]);";
+
+    assert_eq!(
+        run_with_warnings(code).0,
+        "array (
+  0 => 
+  \\Point::__set_state(array(
+     'x' => 1,
+     'tags' => 
+    array (
+      0 => 'a',
+    ),
+  )),
+  1 => 
+  \\Suit::Hearts,
+)"
+    );
+}
+
+#[test]
+fn the_code_is_returned_instead_of_written_when_asked() {
+    assert_eq!(
+        run_with_warnings("$code = var_export(['a' => null], true); echo strlen($code);").0,
+        "24"
+    );
+}
+
+#[test]
+fn an_object_that_contains_itself_is_written_as_null() {
+    let (output, warnings) =
+        run_with_warnings("class Node { public $next; }\n$node = new Node();\n$node->next = $node;\nvar_export($node);");
+
+    assert_eq!(output, "\\Node::__set_state(array(\n   'next' => NULL,\n))");
+    assert_eq!(
+        warnings,
+        "PHP Warning: var_export does not handle circular references in test.php on line 4\n"
+    );
+}
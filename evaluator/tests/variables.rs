@@ -0,0 +1,73 @@
+mod common;
+
+use common::{run, run_with_warnings};
+
+#[test]
+fn variable_variables_are_the_same_variables_as_the_named_ones() {
+    let code = "$name = 'a'; $$name = 1; echo $a, ' ', isset($a);
+        $b = 2; echo ' ', $$name, ${'b'};
+        ${'c'} = 3; $c++; echo ' ', $c;
+        unset($$name); echo ' ', isset($a) === false;";
+
+    assert_eq!(run(code), "1 1 12 4 1");
+}
+
+#[test]
+fn a_variable_variable_can_have_a_dollar_in_its_name() {
+    let code = "${'$a'} = 'dollar'; $a = 'plain';
+        echo ${'$a'}, ' ', $a, ' ', array_slice(array_keys(get_defined_vars()), 7) === ['$a', 'a'];";
+
+    assert_eq!(run(code), "dollar plain 1");
+}
+
+#[test]
+fn named_arguments_are_read_as_the_parameters() {
+    let code = "class Point {
+            public function __construct(public $x = 0, public $y = 0) {
+                echo $x, ' ', $y, ' ', isset($y), ' ';
+            }
+        }
+        $point = new Point(y: 2);
+        echo $point->x, $point->y;";
+
+    assert_eq!(run(code), "0 2 1 02");
+}
+
+#[test]
+fn get_defined_vars_lists_the_names_without_the_dollar() {
+    let code = "function f($first) { $name = 'second'; $$name = 2; return get_defined_vars(); }
+        echo f(1) === ['first' => 1, 'name' => 'second', 'second' => 2];";
+
+    assert_eq!(run(code), "1");
+}
+
+#[test]
+fn the_warnings_after_comments_and_attributes_have_their_line() {
+    let code = "
+# a comment
+#[Attribute]
+#[
+    First,
+    Second(1, 2)
+]
+# between the attribute and the function
+function f(
+    $a, # inside the parameters
+    $b
+) {
+    return $a . $b;
+}
+echo f('a', 'b');
+echo $missing;
+$text = \"multi
+line\";
+echo $missing_too; # at the end";
+
+    assert_eq!(
+        run_with_warnings(code),
+        (
+            "ab".to_string(),
+            "PHP Warning: Undefined variable $missing in test.php on line 16\nPHP Warning: Undefined variable $missing_too in test.php on line 19\n".to_string()
+        )
+    );
+}
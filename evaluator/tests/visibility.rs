@@ -0,0 +1,113 @@
+mod common;
+
+/// A class with a method of each visibility, a subclass and an unrelated class.
+const CLASSES: &str = "
+class Account {
+    public function open() { return 'public'; }
+    protected function audit() { return 'protected'; }
+    private function secret() { return 'private'; }
+
+    public function callAll() { return $this->open() . ' ' . $this->audit() . ' ' . $this->secret(); }
+}
+
+class SavingsAccount extends Account {
+    public function callProtected() { return $this->audit(); }
+    public function callPrivate() { return $this->secret(); }
+}
+
+class Bank {
+    public function callProtected($account) { return $account->audit(); }
+}
+
+function call_secret($account) { return $account->secret(); }
+";
+
+/// Runs `code` after the classes and returns its output.
+fn run(code: &str) -> String {
+    common::run(&format!("{}\n{}", CLASSES, code))
+}
+
+/// The report of the Error thrown on `line`.
+fn error(message: &str, line: usize) -> String {
+    common::uncaught(&format!("Error: {}", message), line)
+}
+
+#[test]
+fn only_public_methods_can_be_called_from_the_global_scope() {
+    assert_eq!(run("echo (new Account())->open();"), "public");
+    assert_eq!(
+        run("(new Account())->audit();"),
+        error(
+            "Call to protected method Account::audit() from global scope",
+            21
+        )
+    );
+    assert_eq!(
+        run("(new Account())->secret();"),
+        error(
+            "Call to private method Account::secret() from global scope",
+            21
+        )
+    );
+}
+
+#[test]
+fn the_class_can_call_all_its_methods() {
+    assert_eq!(
+        run("echo (new Account())->callAll();"),
+        "public protected private"
+    );
+}
+
+#[test]
+fn a_subclass_can_call_the_protected_methods_but_not_the_private_ones() {
+    assert_eq!(
+        run("echo (new SavingsAccount())->callProtected();"),
+        "protected"
+    );
+    assert_eq!(
+        run("echo (new SavingsAccount())->callAll();"),
+        "public protected private"
+    );
+    assert_eq!(
+        run("(new SavingsAccount())->callPrivate();"),
+        error(
+            "Call to private method Account::secret() from scope SavingsAccount",
+            12
+        )
+    );
+}
+
+#[test]
+fn other_classes_and_functions_cannot_call_the_protected_and_private_methods() {
+    assert_eq!(
+        run("(new Bank())->callProtected(new Account());"),
+        error(
+            "Call to protected method Account::audit() from scope Bank",
+            16
+        )
+    );
+    assert_eq!(
+        run("class Teller { function help() { return call_secret(new Account()); } }\n(new Teller())->help();"),
+        error(
+            "Call to private method Account::secret() from global scope",
+            19
+        )
+    );
+}
+
+#[test]
+fn private_constructors_can_only_be_called_from_the_class() {
+    let code = "class Singleton {
+            private function __construct() { echo 'created'; }
+        }
+        new Singleton();";
+
+    assert_eq!(
+        run(code),
+        error(
+            "Call to private Singleton::__construct() from global scope",
+            24
+        )
+    );
+}
@@ -30,7 +30,43 @@ impl Lexer {
     }
 
     pub fn tokenize<B: ?Sized + AsRef<[u8]>>(&self, input: &B) -> SyntaxResult<Vec<Token>> {
+        self.tokenize_source(State::new(Source::new(input.as_ref())))
+    }
+
+    /// Same as `tokenize()` for the main script of a program, a shebang line
+    /// at the start of the input (`#!/usr/bin/env php`) is skipped like php-cli does.
+    ///
+    /// The following tokens keep their real line and position.
+    pub fn tokenize_script<B: ?Sized + AsRef<[u8]>>(
+        &self,
+        input: &B,
+    ) -> SyntaxResult<Vec<Token>> {
+        let mut state = State::new(Source::new(input.as_ref()));
+
+        if state.source.at(b"#!", 2) {
+            while let Some(char) = state.source.current() {
+                state.source.next();
+
+                if *char == b'\n' {
+                    break;
+                }
+            }
+        }
+
+        self.tokenize_source(state)
+    }
+
+    /// Same as `tokenize()` for code given without an opening tag, like the code
+    /// of `php -r`, the input is PHP code from the start.
+    pub fn tokenize_code<B: ?Sized + AsRef<[u8]>>(&self, input: &B) -> SyntaxResult<Vec<Token>> {
         let mut state = State::new(Source::new(input.as_ref()));
+
+        state.replace(StackFrame::Scripting);
+
+        self.tokenize_source(state)
+    }
+
+    fn tokenize_source(&self, mut state: State) -> SyntaxResult<Vec<Token>> {
         let mut tokens = Vec::new();
 
         while !state.source.eof() {
@@ -115,6 +151,39 @@ impl Lexer {
         }
     }
 
+    /// Skips the `();` after `__halt_compiler`, there can be whitespace between its tokens,
+    /// and a closing tag can take the place of the `;`.
+    fn halt_compiler(&self, state: &mut State) -> SyntaxResult<()> {
+        for parenthesis in [b'(', b')'] {
+            self.skip_whitespace(state);
+
+            if state.source.current() != Some(&parenthesis) {
+                return Err(SyntaxError::InvalidHaltCompiler(state.source.span()));
+            }
+
+            state.source.next();
+        }
+
+        self.skip_whitespace(state);
+
+        match state.source.read(2) {
+            [b';', ..] => state.source.next(),
+            [b'?', b'>'] => {
+                state.source.skip(2);
+
+                // like PHP, the closing tag ends with the newline that follows it
+                if state.source.current() == Some(&b'\n') {
+                    state.source.next();
+                }
+            }
+            _ => return Err(SyntaxError::InvalidHaltCompiler(state.source.span())),
+        }
+
+        state.replace(StackFrame::Halted);
+
+        Ok(())
+    }
+
     fn read_and_skip_whitespace(&self, state: &mut State) -> Vec<u8> {
         let mut buffer = Vec::new();
         while let Some(true) = state.source.current().map(|u: &u8| u.is_ascii_whitespace()) {
@@ -813,13 +882,7 @@ impl Lexer {
                     let kind = identifier_to_keyword(&buffer).unwrap_or(TokenKind::Identifier);
 
                     if kind == TokenKind::HaltCompiler {
-                        match state.source.read(3) {
-                            [b'(', b')', b';'] => {
-                                state.source.skip(3);
-                                state.replace(StackFrame::Halted);
-                            }
-                            _ => return Err(SyntaxError::InvalidHaltCompiler(state.source.span())),
-                        }
+                        self.halt_compiler(state)?;
                     }
 
                     (kind, buffer.into())
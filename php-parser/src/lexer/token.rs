@@ -24,6 +24,35 @@ impl Span {
             position,
         }
     }
+
+    /// Computes the line and the column of a byte position of the source, both start at 1.
+    ///
+    /// Unlike the `column` of the lexer, which counts bytes, the column counts characters,
+    /// so it stays right after multi-byte UTF-8 text.
+    pub fn line_and_column(source: &[u8], position: usize) -> (usize, usize) {
+        let before = &source[..position.min(source.len())];
+
+        let line_start = before
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |newline| newline + 1);
+
+        let line = before.iter().filter(|byte| **byte == b'\n').count() + 1;
+
+        (
+            line,
+            character_offset(&before[line_start..], before.len() - line_start) + 1,
+        )
+    }
+}
+
+/// Converts a byte position of the source to the number of characters before it.
+pub fn character_offset(source: &[u8], position: usize) -> usize {
+    source[..position.min(source.len())]
+        .iter()
+        // the bytes that continue a multi-byte character start with 0b10
+        .filter(|byte| **byte & 0xC0 != 0x80)
+        .count()
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -4,6 +4,7 @@ pub mod node;
 pub mod parser;
 pub mod printer;
 pub mod traverser;
+pub mod visitor;
 
 pub use lexer::stream::TokenStream;
 pub use parser::{construct, parse};
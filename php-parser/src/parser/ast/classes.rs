@@ -9,6 +9,7 @@ use self::serde::Serialize;
 use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::comments::CommentGroup;
 use crate::parser::ast::constant::ClassishConstant;
 use crate::parser::ast::functions::AbstractConstructor;
 use crate::parser::ast::functions::AbstractMethod;
@@ -56,6 +57,7 @@ impl Node for ClassBody {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct ClassStatement {
+    pub comments: CommentGroup,
     pub attributes: Vec<AttributeGroup>, // `#[Qux]`
     #[serde(flatten)]
     pub modifiers: ClassModifierGroup, // `abstract`, `final`
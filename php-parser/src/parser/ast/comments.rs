@@ -39,6 +39,13 @@ impl CommentGroup {
     pub fn iter(&self) -> Iter<'_, Comment> {
         self.comments.iter()
     }
+
+    /// The `/** */` comment of the declaration the group comes before, it must be the last comment.
+    pub fn doc_comment(&self) -> Option<&Comment> {
+        self.comments
+            .last()
+            .filter(|comment| comment.format == CommentFormat::Document)
+    }
 }
 
 impl IntoIterator for CommentGroup {
@@ -10,6 +10,7 @@ use crate::parser::ast::attributes::AttributeGroup;
 use crate::parser::ast::constant::ClassishConstant;
 use crate::parser::ast::functions::AbstractConstructor;
 use crate::parser::ast::functions::AbstractMethod;
+use crate::parser::ast::functions::ConcreteMethod;
 use crate::parser::ast::identifiers::SimpleIdentifier;
 use crate::parser::ast::utils::CommaSeparated;
 
@@ -19,6 +20,7 @@ pub enum InterfaceMember {
     Constant(ClassishConstant),       // `public const FOO = 123;`
     Constructor(AbstractConstructor), // `public function __construct(): void;`
     Method(AbstractMethod),           // `public function foo(): void;`
+    ConcreteMethod(ConcreteMethod),   // `public function foo(): void {}`, which cannot be declared
 }
 
 impl Node for InterfaceMember {
@@ -27,6 +29,7 @@ impl Node for InterfaceMember {
             InterfaceMember::Constant(constant) => vec![constant],
             InterfaceMember::Constructor(constructor) => vec![constructor],
             InterfaceMember::Method(method) => vec![method],
+            InterfaceMember::ConcreteMethod(method) => vec![method],
         }
     }
 }
@@ -16,6 +16,8 @@ use crate::parser::ast::classes::ClassStatement;
 use crate::parser::ast::comments::Comment;
 use crate::parser::ast::constant::ConstantStatement;
 use crate::parser::ast::control_flow::IfStatement;
+use crate::parser::ast::control_flow::IfStatementBody;
+use crate::parser::ast::declares::DeclareBody;
 use crate::parser::ast::declares::DeclareStatement;
 use crate::parser::ast::enums::BackedEnumStatement;
 use crate::parser::ast::enums::UnitEnumStatement;
@@ -32,8 +34,11 @@ use crate::parser::ast::loops::BreakStatement;
 use crate::parser::ast::loops::ContinueStatement;
 use crate::parser::ast::loops::DoWhileStatement;
 use crate::parser::ast::loops::ForStatement;
+use crate::parser::ast::loops::ForStatementBody;
 use crate::parser::ast::loops::ForeachStatement;
+use crate::parser::ast::loops::ForeachStatementBody;
 use crate::parser::ast::loops::WhileStatement;
+use crate::parser::ast::loops::WhileStatementBody;
 use crate::parser::ast::namespaces::NamespaceStatement;
 use crate::parser::ast::operators::ArithmeticOperationExpression;
 use crate::parser::ast::operators::AssignmentOperationExpression;
@@ -111,10 +116,21 @@ pub enum Ending {
     CloseTag(Span),
 }
 
+impl Ending {
+    pub fn span(&self) -> Span {
+        match self {
+            Ending::Semicolon(span) => *span,
+            Ending::CloseTag(span) => *span,
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 #[serde(tag = "type")]
 pub struct HaltCompilerStatement {
     pub content: Option<ByteString>,
+    /// The position of the first byte after `__halt_compiler();`, the value of `__COMPILER_HALT_OFFSET__`.
+    pub offset: usize,
 }
 
 impl Node for HaltCompilerStatement {}
@@ -281,6 +297,167 @@ pub struct ClosingTagStatement {
     pub span: Span,
 }
 
+impl Statement {
+    /// Returns the span of the first token of the statement, if the statement has one.
+    pub fn first_span(&self) -> Option<Span> {
+        match self {
+            Statement::FullOpeningTag(statement) => Some(statement.span),
+            Statement::ShortOpeningTag(statement) => Some(statement.span),
+            Statement::EchoOpeningTag(statement) => Some(statement.span),
+            Statement::ClosingTag(statement) => Some(statement.span),
+            Statement::InlineHtml(_) => None,
+            Statement::Label(statement) => Some(statement.label.span),
+            Statement::Goto(statement) => Some(statement.keyword),
+            Statement::HaltCompiler(_) => None,
+            Statement::Static(statement) => statement
+                .vars
+                .first()
+                .map(|static_var| static_var.var.first_span()),
+            Statement::DoWhile(statement) => Some(statement.r#do),
+            Statement::While(statement) => Some(statement.r#while),
+            Statement::For(statement) => Some(statement.r#for),
+            Statement::Foreach(statement) => Some(statement.foreach),
+            Statement::Break(statement) => Some(statement.r#break),
+            Statement::Continue(statement) => Some(statement.r#continue),
+            Statement::Constant(statement) => Some(statement.r#const),
+            Statement::Function(statement) => Some(
+                statement
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .unwrap_or(statement.function),
+            ),
+            Statement::Class(statement) => Some(
+                statement
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .unwrap_or(statement.class),
+            ),
+            Statement::Trait(statement) => Some(
+                statement
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .unwrap_or(statement.r#trait),
+            ),
+            Statement::Interface(statement) => Some(
+                statement
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .unwrap_or(statement.interface),
+            ),
+            Statement::If(statement) => Some(statement.r#if),
+            Statement::Switch(statement) => Some(statement.switch),
+            Statement::Echo(statement) => Some(statement.echo),
+            Statement::Expression(statement) => statement.expression.first_span(),
+            Statement::Return(statement) => Some(statement.r#return),
+            Statement::Namespace(statement) => match statement {
+                NamespaceStatement::Unbraced(namespace) => Some(namespace.start),
+                NamespaceStatement::Braced(namespace) => Some(namespace.namespace),
+            },
+            Statement::Use(statement) => statement.uses.first().map(|u| u.name.span),
+            Statement::GroupUse(statement) => Some(statement.prefix.span),
+            Statement::Comment(statement) => Some(statement.span),
+            Statement::Try(statement) => Some(statement.start),
+            Statement::UnitEnum(statement) => Some(
+                statement
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .unwrap_or(statement.r#enum),
+            ),
+            Statement::BackedEnum(statement) => Some(
+                statement
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .unwrap_or(statement.r#enum),
+            ),
+            Statement::Block(statement) => Some(statement.left_brace),
+            Statement::Global(statement) => Some(statement.global),
+            Statement::Declare(statement) => Some(statement.declare),
+            Statement::Noop(span) => Some(*span),
+        }
+    }
+
+    /// Returns the span of the last token of the statement, usually its `;`, `}` or `?>`,
+    /// if the parser keeps it. The statements whose ending is dropped, like `global $a;`, have none.
+    pub fn last_span(&self) -> Option<Span> {
+        match self {
+            Statement::FullOpeningTag(statement) => Some(statement.span),
+            Statement::ShortOpeningTag(statement) => Some(statement.span),
+            Statement::EchoOpeningTag(statement) => Some(statement.span),
+            Statement::ClosingTag(statement) => Some(statement.span),
+            Statement::InlineHtml(_) => None,
+            Statement::Label(statement) => Some(statement.colon),
+            Statement::Goto(statement) => Some(statement.semicolon),
+            Statement::HaltCompiler(_) => None,
+            Statement::Static(_) => None,
+            Statement::DoWhile(statement) => Some(statement.semicolon),
+            Statement::While(statement) => match &statement.body {
+                WhileStatementBody::Statement { statement } => statement.last_span(),
+                WhileStatementBody::Block { ending, .. } => Some(ending.span()),
+            },
+            Statement::For(statement) => match &statement.body {
+                ForStatementBody::Statement { statement } => statement.last_span(),
+                ForStatementBody::Block { ending, .. } => Some(ending.span()),
+            },
+            Statement::Foreach(statement) => match &statement.body {
+                ForeachStatementBody::Statement { statement } => statement.last_span(),
+                ForeachStatementBody::Block { ending, .. } => Some(ending.span()),
+            },
+            Statement::Break(statement) => Some(statement.ending.span()),
+            Statement::Continue(statement) => Some(statement.ending.span()),
+            Statement::Constant(statement) => Some(statement.semicolon),
+            Statement::Function(statement) => Some(statement.body.right_brace),
+            Statement::Class(statement) => Some(statement.body.right_brace),
+            Statement::Trait(statement) => Some(statement.body.right_brace),
+            Statement::Interface(statement) => Some(statement.body.right_brace),
+            Statement::If(statement) => match &statement.body {
+                IfStatementBody::Statement {
+                    statement,
+                    elseifs,
+                    r#else,
+                } => match (r#else, elseifs.last()) {
+                    (Some(r#else), _) => r#else.statement.last_span(),
+                    (None, Some(elseif)) => elseif.statement.last_span(),
+                    (None, None) => statement.last_span(),
+                },
+                IfStatementBody::Block { ending, .. } => Some(ending.span()),
+            },
+            Statement::Switch(_) => None,
+            Statement::Echo(statement) => Some(statement.ending.span()),
+            Statement::Expression(statement) => Some(statement.ending.span()),
+            Statement::Return(statement) => Some(statement.ending.span()),
+            Statement::Namespace(statement) => match statement {
+                NamespaceStatement::Unbraced(namespace) => namespace
+                    .statements
+                    .last()
+                    .and_then(|statement| statement.last_span())
+                    .or(Some(namespace.end)),
+                NamespaceStatement::Braced(namespace) => Some(namespace.body.end),
+            },
+            Statement::Use(_) => None,
+            Statement::GroupUse(_) => None,
+            Statement::Comment(statement) => Some(statement.span),
+            Statement::Try(statement) => Some(statement.end),
+            Statement::UnitEnum(statement) => Some(statement.body.right_brace),
+            Statement::BackedEnum(statement) => Some(statement.body.right_brace),
+            Statement::Block(statement) => Some(statement.right_brace),
+            Statement::Global(_) => None,
+            Statement::Declare(statement) => match &statement.body {
+                DeclareBody::Noop { semicolon } => Some(*semicolon),
+                DeclareBody::Braced { right_brace, .. } => Some(*right_brace),
+                DeclareBody::Expression { semicolon, .. } => Some(*semicolon),
+                DeclareBody::Block { end, .. } => Some(end.1),
+            },
+            Statement::Noop(span) => Some(*span),
+        }
+    }
+}
+
 impl Node for Statement {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         match self {
@@ -472,7 +649,7 @@ pub struct ExitExpression {
 pub struct IssetExpression {
     pub isset: Span,
     // isset
-    pub variables: Vec<Variable>, // `($a, ...)`
+    pub variables: Vec<Expression>, // `($a, $b['key'], $c->d, ...)`
 }
 
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
@@ -1178,6 +1355,178 @@ pub enum Expression {
     Noop,
 }
 
+impl Expression {
+    /// Returns the span of the first token of the expression, if the expression has one.
+    pub fn first_span(&self) -> Option<Span> {
+        match self {
+            Expression::Eval(expression) => Some(expression.eval),
+            Expression::Empty(expression) => Some(expression.empty),
+            Expression::Die(expression) => Some(expression.die),
+            Expression::Exit(expression) => Some(expression.exit),
+            Expression::Isset(expression) => Some(expression.isset),
+            Expression::Unset(expression) => Some(expression.unset),
+            Expression::Print(expression) => Some(expression.print),
+            Expression::Literal(literal) => match literal {
+                Literal::String(literal) => Some(literal.span),
+                Literal::Integer(literal) => Some(literal.span),
+                Literal::Float(literal) => Some(literal.span),
+            },
+            Expression::ArithmeticOperation(operation) => match operation {
+                ArithmeticOperationExpression::Negative { minus, .. } => Some(*minus),
+                ArithmeticOperationExpression::Positive { plus, .. } => Some(*plus),
+                ArithmeticOperationExpression::PreIncrement { increment, .. } => Some(*increment),
+                ArithmeticOperationExpression::PreDecrement { decrement, .. } => Some(*decrement),
+                ArithmeticOperationExpression::Addition { left, .. }
+                | ArithmeticOperationExpression::Subtraction { left, .. }
+                | ArithmeticOperationExpression::Multiplication { left, .. }
+                | ArithmeticOperationExpression::Division { left, .. }
+                | ArithmeticOperationExpression::Modulo { left, .. }
+                | ArithmeticOperationExpression::Exponentiation { left, .. }
+                | ArithmeticOperationExpression::PostIncrement { left, .. }
+                | ArithmeticOperationExpression::PostDecrement { left, .. } => left.first_span(),
+            },
+            Expression::AssignmentOperation(operation) => match operation {
+                AssignmentOperationExpression::Assign { left, .. }
+                | AssignmentOperationExpression::Addition { left, .. }
+                | AssignmentOperationExpression::Subtraction { left, .. }
+                | AssignmentOperationExpression::Multiplication { left, .. }
+                | AssignmentOperationExpression::Division { left, .. }
+                | AssignmentOperationExpression::Modulo { left, .. }
+                | AssignmentOperationExpression::Exponentiation { left, .. }
+                | AssignmentOperationExpression::Concat { left, .. }
+                | AssignmentOperationExpression::BitwiseAnd { left, .. }
+                | AssignmentOperationExpression::BitwiseOr { left, .. }
+                | AssignmentOperationExpression::BitwiseXor { left, .. }
+                | AssignmentOperationExpression::LeftShift { left, .. }
+                | AssignmentOperationExpression::RightShift { left, .. }
+                | AssignmentOperationExpression::Coalesce { left, .. } => left.first_span(),
+            },
+            Expression::BitwiseOperation(operation) => match operation {
+                BitwiseOperationExpression::Not { not, .. } => Some(*not),
+                BitwiseOperationExpression::And { left, .. }
+                | BitwiseOperationExpression::Or { left, .. }
+                | BitwiseOperationExpression::Xor { left, .. }
+                | BitwiseOperationExpression::LeftShift { left, .. }
+                | BitwiseOperationExpression::RightShift { left, .. } => left.first_span(),
+            },
+            Expression::ComparisonOperation(operation) => match operation {
+                ComparisonOperationExpression::Equal { left, .. }
+                | ComparisonOperationExpression::Identical { left, .. }
+                | ComparisonOperationExpression::NotEqual { left, .. }
+                | ComparisonOperationExpression::AngledNotEqual { left, .. }
+                | ComparisonOperationExpression::NotIdentical { left, .. }
+                | ComparisonOperationExpression::LessThan { left, .. }
+                | ComparisonOperationExpression::GreaterThan { left, .. }
+                | ComparisonOperationExpression::LessThanOrEqual { left, .. }
+                | ComparisonOperationExpression::GreaterThanOrEqual { left, .. }
+                | ComparisonOperationExpression::Spaceship { left, .. } => left.first_span(),
+            },
+            Expression::LogicalOperation(operation) => match operation {
+                LogicalOperationExpression::Not { bang, .. } => Some(*bang),
+                LogicalOperationExpression::And { left, .. }
+                | LogicalOperationExpression::Or { left, .. }
+                | LogicalOperationExpression::LogicalAnd { left, .. }
+                | LogicalOperationExpression::LogicalOr { left, .. }
+                | LogicalOperationExpression::LogicalXor { left, .. } => left.first_span(),
+            },
+            Expression::Concat(expression) => expression.left.first_span(),
+            Expression::Instanceof(expression) => expression.left.first_span(),
+            Expression::Reference(expression) => Some(expression.ampersand),
+            Expression::Parenthesized(expression) => Some(expression.start),
+            Expression::ErrorSuppress(expression) => Some(expression.at),
+            Expression::Identifier(identifier) => match identifier {
+                Identifier::SimpleIdentifier(identifier) => Some(identifier.span),
+                Identifier::DynamicIdentifier(identifier) => Some(identifier.start),
+            },
+            Expression::Variable(variable) => Some(variable.first_span()),
+            Expression::Include(expression) => Some(expression.include),
+            Expression::IncludeOnce(expression) => Some(expression.include_once),
+            Expression::Require(expression) => Some(expression.require),
+            Expression::RequireOnce(expression) => Some(expression.require_once),
+            Expression::FunctionCall(expression) => expression.target.first_span(),
+            Expression::FunctionClosureCreation(expression) => expression.target.first_span(),
+            Expression::MethodCall(expression) => expression.target.first_span(),
+            Expression::MethodClosureCreation(expression) => expression.target.first_span(),
+            Expression::NullsafeMethodCall(expression) => expression.target.first_span(),
+            Expression::StaticMethodCall(expression) => expression.target.first_span(),
+            Expression::StaticVariableMethodCall(expression) => expression.target.first_span(),
+            Expression::StaticMethodClosureCreation(expression) => expression.target.first_span(),
+            Expression::StaticVariableMethodClosureCreation(expression) => {
+                expression.target.first_span()
+            }
+            Expression::PropertyFetch(expression) => expression.target.first_span(),
+            Expression::NullsafePropertyFetch(expression) => expression.target.first_span(),
+            Expression::StaticPropertyFetch(expression) => expression.target.first_span(),
+            Expression::ConstantFetch(expression) => expression.target.first_span(),
+            Expression::Static => None,
+            Expression::Self_ => None,
+            Expression::Parent => None,
+            Expression::ShortArray(expression) => Some(expression.start),
+            Expression::Array(expression) => Some(expression.array),
+            Expression::List(expression) => Some(expression.list),
+            Expression::Closure(expression) => Some(
+                expression
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .or(expression.r#static)
+                    .unwrap_or(expression.function),
+            ),
+            Expression::ArrowFunction(expression) => Some(
+                expression
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .or(expression.r#static)
+                    .unwrap_or(expression.r#fn),
+            ),
+            Expression::New(expression) => Some(expression.new),
+            Expression::InterpolatedString(_) => None,
+            Expression::Heredoc(_) => None,
+            Expression::Nowdoc(_) => None,
+            Expression::ShellExec(_) => None,
+            Expression::AnonymousClass(expression) => Some(
+                expression
+                    .attributes
+                    .first()
+                    .map(|a| a.start)
+                    .unwrap_or(expression.class),
+            ),
+            Expression::Bool(_) => None,
+            Expression::ArrayIndex(expression) => expression
+                .array
+                .first_span()
+                .or(Some(expression.left_bracket)),
+            Expression::Null => None,
+            Expression::MagicConstant(constant) => match constant {
+                MagicConstantExpression::Directory(span)
+                | MagicConstantExpression::File(span)
+                | MagicConstantExpression::Line(span)
+                | MagicConstantExpression::Class(span)
+                | MagicConstantExpression::Function(span)
+                | MagicConstantExpression::Method(span)
+                | MagicConstantExpression::Namespace(span)
+                | MagicConstantExpression::Trait(span)
+                | MagicConstantExpression::CompilerHaltOffset(span) => Some(*span),
+            },
+            Expression::ShortTernary(expression) => expression.condition.first_span(),
+            Expression::Ternary(expression) => expression.condition.first_span(),
+            Expression::Coalesce(expression) => expression.lhs.first_span(),
+            Expression::Clone(expression) => expression.target.first_span(),
+            Expression::Match(expression) => Some(expression.keyword),
+            Expression::Throw(expression) => expression.value.first_span(),
+            Expression::Yield(expression) => expression
+                .key
+                .as_ref()
+                .or(expression.value.as_ref())
+                .and_then(|e| e.first_span()),
+            Expression::YieldFrom(expression) => expression.value.first_span(),
+            Expression::Cast(expression) => Some(expression.cast),
+            Expression::Noop => None,
+        }
+    }
+}
+
 impl Node for EvalExpression {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         vec![self.argument.as_mut()]
@@ -7,6 +7,7 @@ use self::serde::Serialize;
 use crate::lexer::token::Span;
 use crate::node::Node;
 use crate::parser::ast::attributes::AttributeGroup;
+use crate::parser::ast::comments::CommentGroup;
 use crate::parser::ast::data_type::Type;
 use crate::parser::ast::modifiers::PropertyModifierGroup;
 use crate::parser::ast::variables::SimpleVariable;
@@ -15,6 +16,7 @@ use crate::parser::ast::Expression;
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct Property {
+    pub comments: CommentGroup,
     pub attributes: Vec<AttributeGroup>,
     #[serde(flatten)]
     pub modifiers: PropertyModifierGroup,
@@ -42,6 +44,7 @@ impl Node for Property {
 #[derive(Debug, PartialEq, Eq, Clone, Deserialize, Serialize, JsonSchema)]
 
 pub struct VariableProperty {
+    pub comments: CommentGroup,
     pub attributes: Vec<AttributeGroup>,
     pub r#type: Option<Type>,
     pub entries: Vec<PropertyEntry>,
@@ -19,6 +19,16 @@ pub enum Variable {
     BracedVariableVariable(BracedVariableVariable),
 }
 
+impl Variable {
+    pub fn first_span(&self) -> Span {
+        match self {
+            Variable::SimpleVariable(variable) => variable.span,
+            Variable::VariableVariable(variable) => variable.span,
+            Variable::BracedVariableVariable(variable) => variable.start,
+        }
+    }
+}
+
 impl Node for Variable {
     fn children(&mut self) -> Vec<&mut dyn Node> {
         match self {
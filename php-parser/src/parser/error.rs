@@ -10,7 +10,7 @@ use self::schemars::JsonSchema;
 use self::serde::Deserialize;
 use self::serde::Serialize;
 use crate::lexer::error::SyntaxError;
-use crate::lexer::token::{Span, Token, TokenKind};
+use crate::lexer::token::{character_offset, Span, Token, TokenKind};
 use crate::parser::ast::attributes::AttributeGroup;
 use crate::parser::ast::data_type::Type;
 use crate::parser::ast::modifiers::PromotedPropertyModifier;
@@ -119,7 +119,10 @@ impl ParseError {
     ) -> std::io::Result<String> {
         let origin = origin.unwrap_or("input");
 
-        let mut report = Report::build(ReportKind::Error, origin, self.span.position)
+        // the spans count bytes and ariadne counts characters
+        let offset = |position: usize| character_offset(source.as_bytes(), position);
+
+        let mut report = Report::build(ReportKind::Error, origin, offset(self.span.position))
             .with_code(&self.id)
             .with_message(&self.message)
             .with_config(
@@ -135,7 +138,7 @@ impl ParseError {
         for (order, annotation) in self.annotations.iter().enumerate() {
             let mut label = Label::new((
                 origin,
-                annotation.position..annotation.position + annotation.length,
+                offset(annotation.position)..offset(annotation.position + annotation.length),
             ))
             .with_order(order.try_into().unwrap());
 
@@ -681,11 +681,19 @@ expressions! {
         for argument in arguments.arguments {
             match argument {
                 Argument::Positional(arg) => {
-                    let Expression::Variable(variable) = arg.value else {
+                    // variables, array elements and properties
+                    if !matches!(
+                        arg.value,
+                        Expression::Variable(_)
+                            | Expression::ArrayIndex(_)
+                            | Expression::PropertyFetch(_)
+                            | Expression::NullsafePropertyFetch(_)
+                            | Expression::StaticPropertyFetch(_)
+                    ) {
                         return Err(error::cannot_use_isset_on_expression_result(isset, state.stream.current().span));
-                    };
+                    }
 
-                    variables.push(variable);
+                    variables.push(arg.value);
                 }
                 _ => unreachable!(),
             }
@@ -27,6 +27,7 @@ use crate::parser::internal::utils;
 use crate::parser::state::State;
 
 pub fn parse(state: &mut State) -> ParseResult<Statement> {
+    let comments = state.stream.comments();
     let attributes = state.get_attributes();
 
     let modifiers = modifiers::class_group(modifiers::collect(state)?)?;
@@ -81,6 +82,7 @@ pub fn parse(state: &mut State) -> ParseResult<Statement> {
     };
 
     Ok(Statement::Class(ClassStatement {
+        comments,
         class,
         name,
         modifiers,
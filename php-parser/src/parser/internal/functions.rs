@@ -301,6 +301,11 @@ pub fn method(
         None
     };
 
+    // the body of an interface method is an error of the compiler, not of the syntax
+    let has_body = has_body
+        || (matches!(r#type, MethodType::Abstract)
+            && state.stream.current().kind == TokenKind::LeftBrace);
+
     if has_body {
         Ok(Method::Concrete(ConcreteMethod {
             comments,
@@ -83,7 +83,8 @@ fn member(state: &mut State, interface_name: &SimpleIdentifier) -> ParseResult<I
         match method {
             Method::Abstract(method) => Ok(InterfaceMember::Method(method)),
             Method::AbstractConstructor(ctor) => Ok(InterfaceMember::Constructor(ctor)),
-            Method::ConcreteConstructor(_) | Method::Concrete(_) => unreachable!(),
+            Method::Concrete(method) => Ok(InterfaceMember::ConcreteMethod(method)),
+            Method::ConcreteConstructor(_) => unreachable!(),
         }
     }
 }
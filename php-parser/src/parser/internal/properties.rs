@@ -17,6 +17,7 @@ pub fn parse(
     class_name: Option<&SimpleIdentifier>,
     modifiers: PropertyModifierGroup,
 ) -> ParseResult<Property> {
+    let comments = state.stream.comments();
     let ty = data_type::optional_data_type(state)?;
 
     let mut entries = vec![];
@@ -102,6 +103,7 @@ pub fn parse(
     let end = utils::skip_semicolon(state)?;
 
     Ok(Property {
+        comments,
         r#type: ty,
         modifiers,
         attributes: state.get_attributes(),
@@ -114,6 +116,7 @@ pub fn parse_var(
     state: &mut State,
     class_name: Option<&SimpleIdentifier>,
 ) -> ParseResult<VariableProperty> {
+    let comments = state.stream.comments();
     utils::skip(state, TokenKind::Var)?;
 
     let ty = data_type::optional_data_type(state)?;
@@ -165,6 +168,7 @@ pub fn parse_var(
     let end = utils::skip_semicolon(state)?;
 
     Ok(VariableProperty {
+        comments,
         r#type: ty,
         attributes: state.get_attributes(),
         entries,
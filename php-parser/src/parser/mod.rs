@@ -68,6 +68,125 @@ pub fn parse<B: ?Sized + AsRef<[u8]>>(input: &B) -> Result<Program, ParseErrorSt
     construct(&tokens)
 }
 
+/// Same as `parse()` for the main script of a program, see `Lexer::tokenize_script()`.
+pub fn parse_script<B: ?Sized + AsRef<[u8]>>(input: &B) -> Result<Program, ParseErrorStack> {
+    let lexer = Lexer::new();
+    let tokens = match lexer.tokenize_script(input) {
+        Ok(tokens) => tokens,
+        Err(error) => {
+            return Err(ParseErrorStack {
+                errors: vec![error.into()],
+                partial: Vec::new(),
+            })
+        }
+    };
+
+    construct(&tokens)
+}
+
+/// Same as `parse()` for code without an opening tag, see `Lexer::tokenize_code()`.
+pub fn parse_code<B: ?Sized + AsRef<[u8]>>(input: &B) -> Result<Program, ParseErrorStack> {
+    let lexer = Lexer::new();
+    let tokens = match lexer.tokenize_code(input) {
+        Ok(tokens) => tokens,
+        Err(error) => {
+            return Err(ParseErrorStack {
+                errors: vec![error.into()],
+                partial: Vec::new(),
+            })
+        }
+    };
+
+    construct(&tokens)
+}
+
+/// Same as `parse()`, but a statement that cannot be parsed does not stop the parser.
+///
+/// The error is kept and the parser starts again after the statement, so every syntax
+/// error of the input is reported at once. The errors are returned with the statements
+/// that could be parsed, which are not a complete program.
+pub fn parse_recovering<B: ?Sized + AsRef<[u8]>>(input: &B) -> Result<Program, ParseErrorStack> {
+    let lexer = Lexer::new();
+    let tokens = match lexer.tokenize(input) {
+        Ok(tokens) => tokens,
+        Err(error) => {
+            return Err(ParseErrorStack {
+                errors: vec![error.into()],
+                partial: Vec::new(),
+            })
+        }
+    };
+
+    construct_recovering(&tokens)
+}
+
+/// Same as `construct()`, see `parse_recovering()`.
+pub fn construct_recovering(tokens: &[Token]) -> Result<Program, ParseErrorStack> {
+    let mut stream = TokenStream::new(tokens);
+    let mut state = State::new(&mut stream);
+
+    let mut program = Program::new();
+    let mut errors = Vec::new();
+
+    while !state.stream.is_eof() {
+        let start = state.stream.current().span.position;
+
+        match top_level_statement(&mut state) {
+            Ok(statement) => program.push(statement),
+            Err(error) => {
+                errors.push(error);
+
+                resynchronize(&mut state, tokens, start);
+            }
+        }
+    }
+
+    errors.append(&mut state.errors);
+
+    if !errors.is_empty() {
+        errors.sort_by_key(|error| error.span.position);
+
+        return Err(ParseErrorStack {
+            errors,
+            partial: program,
+        });
+    }
+
+    Ok(program.to_vec())
+}
+
+/// Skips the rest of a statement that failed to parse, the statement started at the
+/// `start` position.
+///
+/// The parser stops after the next `;` outside of braces, or after the `}` that closes
+/// the braces opened by the statement, like the body of a function.
+fn resynchronize(state: &mut State, tokens: &[Token], start: usize) {
+    let current = state.stream.current().span.position;
+
+    let mut depth = tokens
+        .iter()
+        .filter(|token| token.span.position >= start && token.span.position < current)
+        .fold(0usize, |depth, token| match token.kind {
+            TokenKind::LeftBrace | TokenKind::DollarLeftBrace => depth + 1,
+            TokenKind::RightBrace => depth.saturating_sub(1),
+            _ => depth,
+        });
+
+    while !state.stream.is_eof() {
+        let kind = &state.stream.current().kind;
+
+        state.stream.next();
+
+        match kind {
+            TokenKind::LeftBrace | TokenKind::DollarLeftBrace => depth += 1,
+            TokenKind::RightBrace if depth <= 1 => return,
+            TokenKind::RightBrace => depth -= 1,
+            TokenKind::SemiColon if depth == 0 => return,
+            _ => {}
+        }
+    }
+}
+
 pub fn construct(tokens: &[Token]) -> Result<Program, ParseErrorStack> {
     let mut stream = TokenStream::new(tokens);
     let mut state = State::new(&mut stream);
@@ -110,6 +229,9 @@ fn top_level_statement(state: &mut State) -> ParseResult<Statement> {
         TokenKind::HaltCompiler => {
             state.stream.next();
 
+            // the lexer stops right after `__halt_compiler();`, the rest of the file is one token
+            let offset = state.stream.current().span.position;
+
             let content = if let TokenKind::InlineHtml = state.stream.current().kind.clone() {
                 let content = state.stream.current().value.clone();
                 state.stream.next();
@@ -118,7 +240,7 @@ fn top_level_statement(state: &mut State) -> ParseResult<Statement> {
                 None
             };
 
-            Statement::HaltCompiler(HaltCompilerStatement { content })
+            Statement::HaltCompiler(HaltCompilerStatement { content, offset })
         }
         _ => statement(state)?,
     };
@@ -443,3 +565,275 @@ fn statement(state: &mut State) -> ParseResult<Statement> {
 
     Ok(statement)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lexer::token::Span;
+    use crate::parser::ast::functions::FunctionStatement;
+
+    /// The line and column of the first token of every top level statement.
+    fn statement_positions(source: &str) -> Vec<(usize, usize)> {
+        parse(source)
+            .unwrap()
+            .iter()
+            .filter_map(Statement::first_span)
+            .map(|span| (span.line, span.column))
+            .collect()
+    }
+
+    fn parse_function(source: &str) -> FunctionStatement {
+        parse(source)
+            .unwrap()
+            .into_iter()
+            .find_map(|statement| match statement {
+                Statement::Function(function) => Some(function),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[test]
+    fn test_hash_comment_at_end_of_file() {
+        let source = "<?php\necho 1;\n$a = 2;\n# no newline after this comment";
+
+        assert_eq!(statement_positions(source), vec![(1, 1), (2, 1), (3, 1)]);
+    }
+
+    #[test]
+    fn test_multiline_attribute_group() {
+        let source = "<?php\n#[Foo(\n    1,\n    2\n)]\nfunction foo() {}\necho $a;\n";
+
+        let function = parse_function(source);
+
+        assert_eq!(function.attributes[0].start.line, 2);
+        assert_eq!(function.attributes[0].end.line, 5);
+        assert_eq!(function.function.line, 6);
+        assert_eq!(statement_positions(source)[2], (7, 1));
+    }
+
+    #[test]
+    fn test_comment_between_attribute_and_function() {
+        let source = "<?php\n#[Foo]\n# a comment\n// another one\nfunction foo() {}\necho $a;\n";
+
+        let function = parse_function(source);
+
+        assert_eq!(function.attributes[0].start.line, 2);
+        assert_eq!((function.function.line, function.function.column), (5, 1));
+        assert_eq!(function.name.span.line, 5);
+        assert_eq!(statement_positions(source)[2], (6, 1));
+    }
+
+    #[test]
+    fn test_comment_inside_parameter_list() {
+        let source = "<?php\nfunction foo(\n    $a, // first\n    # second\n    $b\n) {}\necho $a;\n";
+
+        let function = parse_function(source);
+
+        let lines: Vec<(usize, usize)> = function
+            .parameters
+            .parameters
+            .inner
+            .iter()
+            .map(|parameter| (parameter.name.span.line, parameter.name.span.column))
+            .collect();
+
+        assert_eq!(lines, vec![(3, 5), (5, 5)]);
+        assert_eq!(function.parameters.right_parenthesis.line, 6);
+        assert_eq!(statement_positions(source)[2], (7, 1));
+    }
+
+    #[test]
+    fn test_statements_after_heredoc_and_comments() {
+        let source =
+            "<?php\n$a = <<<EOT\none\ntwo\nEOT; # comment\n#[Foo] #[Bar]\nfunction foo() {}\necho $b;\n";
+
+        assert_eq!(
+            statement_positions(source),
+            vec![(1, 1), (2, 1), (6, 1), (8, 1)]
+        );
+    }
+
+    #[test]
+    fn test_script_shebang_is_skipped() {
+        let source = "#!/usr/bin/env php\n<?php\necho 1;\n";
+
+        let program = parse_script(source).unwrap();
+
+        assert!(matches!(program[0], Statement::FullOpeningTag(_)));
+        let lines: Vec<usize> = program
+            .iter()
+            .filter_map(Statement::first_span)
+            .map(|span| span.line)
+            .collect();
+
+        assert_eq!(lines, vec![2, 3]);
+    }
+
+    #[test]
+    fn test_shebang_is_inline_html_outside_scripts() {
+        let source = "#!/usr/bin/env php\n<?php\necho 1;\n";
+
+        let program = parse(source).unwrap();
+
+        assert!(matches!(program[0], Statement::InlineHtml(_)));
+        assert_eq!(parse_script("<?php #!not a shebang\n").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_multiline_function_spans() {
+        let source = "<?php\nfunction foo(\n    $a\n) {\n    return $a;\n}\necho 1;\n";
+
+        let program = parse(source).unwrap();
+
+        let spans: Vec<((usize, usize), (usize, usize))> = program[1..]
+            .iter()
+            .map(|statement| {
+                let first = statement.first_span().unwrap();
+                let last = statement.last_span().unwrap();
+
+                ((first.line, first.column), (last.line, last.column))
+            })
+            .collect();
+
+        assert_eq!(spans, vec![((2, 1), (6, 1)), ((7, 1), (7, 7))]);
+    }
+
+    #[test]
+    fn test_multi_byte_statement_spans() {
+        let source = "<?php\n$a = \"héllo wörld\"; echo 'ü';\n";
+
+        let program = parse(source).unwrap();
+
+        let assignment = program[1].last_span().unwrap();
+        let echo = program[2].first_span().unwrap();
+
+        // the lexer counts bytes, the helper counts characters
+        assert_eq!((assignment.line, assignment.column), (2, 21));
+        assert_eq!(echo.column, 23);
+        assert_eq!(Span::line_and_column(source.as_bytes(), assignment.position), (2, 19));
+        assert_eq!(Span::line_and_column(source.as_bytes(), echo.position), (2, 21));
+        assert_eq!(program[2].last_span().unwrap().position, source.len() - 2);
+    }
+
+    #[test]
+    fn test_try_statement_ends_at_its_last_brace() {
+        let source = "<?php\ntry {\n} catch (E $e) {\n}\necho 1;\n";
+
+        let program = parse(source).unwrap();
+
+        assert_eq!(program[1].last_span().unwrap().line, 4);
+        assert_eq!(program[1].last_span().unwrap().column, 1);
+    }
+
+    #[test]
+    fn test_halt_compiler_offset() {
+        let source = "<?php\necho 1;\n__halt_compiler();\x00data\n<?php echo 2;";
+
+        let program = parse(source).unwrap();
+
+        let Some(Statement::HaltCompiler(halt)) = program.last() else {
+            panic!("the last statement is not __halt_compiler()");
+        };
+
+        assert_eq!(halt.offset, source.find('\x00').unwrap());
+        assert_eq!(halt.content.as_ref().unwrap().bytes, b"\x00data\n<?php echo 2;");
+    }
+
+    #[test]
+    fn test_halt_compiler_at_end_of_file() {
+        let source = "<?php\n__halt_compiler();";
+
+        let program = parse(source).unwrap();
+
+        let Some(Statement::HaltCompiler(halt)) = program.last() else {
+            panic!("the last statement is not __halt_compiler()");
+        };
+
+        assert_eq!(halt.offset, source.len());
+    }
+
+    #[test]
+    fn test_halt_compiler_with_whitespace_or_a_closing_tag() {
+        for source in [
+            "<?php\n__halt_compiler ( ) ;\x00data",
+            "<?php\n__halt_compiler()\n;\x00data",
+            "<?php\n__halt_compiler() ?>\x00data",
+            "<?php\n__halt_compiler() ?>\n\x00data",
+        ] {
+            let program = parse(source).unwrap();
+
+            let Some(Statement::HaltCompiler(halt)) = program.last() else {
+                panic!("the last statement is not __halt_compiler()");
+            };
+
+            assert_eq!(halt.offset, source.find('\x00').unwrap());
+            assert_eq!(halt.content.as_ref().unwrap().bytes, b"\x00data");
+        }
+
+        assert!(parse("<?php\n__halt_compiler(;").is_err());
+        assert!(parse("<?php\n__halt_compiler() echo 1;").is_err());
+    }
+
+    #[test]
+    fn test_doc_comments_of_class_members() {
+        use crate::parser::ast::classes::ClassMember;
+
+        let source = "<?php\n// not a doc comment\n/** A */\nclass A {\n    /** The x */\n    public int $x = 1;\n\n    // only a comment\n    public $y;\n\n    /** The f */\n    public function f() {}\n}\n";
+
+        let program = parse(source).unwrap();
+
+        let Some(Statement::Class(class)) = program.last() else {
+            panic!("the last statement is not a class");
+        };
+
+        let doc = |comments: &ast::comments::CommentGroup| {
+            comments.doc_comment().map(|comment| comment.content.to_string())
+        };
+
+        assert_eq!(doc(&class.comments), Some("/** A */".to_string()));
+
+        let docs: Vec<_> = class
+            .body
+            .iter()
+            .map(|member| match member {
+                ClassMember::Property(property) => doc(&property.comments),
+                ClassMember::ConcreteMethod(method) => doc(&method.comments),
+                _ => panic!("unexpected member"),
+            })
+            .collect();
+
+        assert_eq!(
+            docs,
+            vec![
+                Some("/** The x */".to_string()),
+                None,
+                Some("/** The f */".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recovering_parse_reports_every_error() {
+        let source = "<?php\n$a = ;\nfunction foo() {\n    $b = 1 +;\n    return $b;\n}\necho 1;\nclass {}\necho 2;\n";
+
+        let errors = parse_recovering(source).unwrap_err();
+
+        let lines: Vec<usize> = errors.errors.iter().map(|error| error.span.line).collect();
+
+        assert_eq!(lines, vec![2, 4, 8]);
+
+        let echoes = errors
+            .partial
+            .iter()
+            .filter(|statement| matches!(statement, Statement::Echo(_)))
+            .count();
+
+        assert_eq!(echoes, 2);
+        assert_eq!(parse(source).unwrap_err().errors.len(), 1);
+        assert_eq!(
+            parse_recovering("<?php echo 1;").unwrap(),
+            parse("<?php echo 1;").unwrap()
+        );
+    }
+}
@@ -0,0 +1,225 @@
+use crate::downcast::downcast_mut;
+use crate::lexer::token::Span;
+use crate::node::Node;
+use crate::parser::ast::data_type::Type;
+use crate::parser::ast::identifiers::Identifier;
+use crate::parser::ast::identifiers::SimpleIdentifier;
+use crate::parser::ast::{Expression, Statement};
+
+/// Hooks called while walking a program with [`walk`].
+///
+/// Every hook has an empty default implementation, so a visitor only needs
+/// to implement the node kinds it is interested in. `enter_*` hooks are called
+/// before the children of the node are visited and `leave_*` hooks after.
+pub trait Visitor {
+    fn enter_statement(&mut self, _statement: &mut Statement, _span: Option<Span>) {}
+
+    fn leave_statement(&mut self, _statement: &mut Statement, _span: Option<Span>) {}
+
+    fn enter_expression(&mut self, _expression: &mut Expression, _span: Option<Span>) {}
+
+    fn leave_expression(&mut self, _expression: &mut Expression, _span: Option<Span>) {}
+
+    fn enter_type(&mut self, _data_type: &mut Type, _span: Span) {}
+
+    fn leave_type(&mut self, _data_type: &mut Type, _span: Span) {}
+
+    fn enter_identifier(&mut self, _identifier: &mut SimpleIdentifier, _span: Span) {}
+
+    fn leave_identifier(&mut self, _identifier: &mut SimpleIdentifier, _span: Span) {}
+
+    /// Called for every node, including the ones that have a dedicated hook.
+    fn enter_node(&mut self, _node: &mut dyn Node) {}
+
+    /// Called for every node, including the ones that have a dedicated hook.
+    fn leave_node(&mut self, _node: &mut dyn Node) {}
+}
+
+/// Walks all the statements of a program, depth first and in source order.
+pub fn walk(visitor: &mut dyn Visitor, program: &mut [Statement]) {
+    for statement in program.iter_mut() {
+        walk_node(visitor, statement);
+    }
+}
+
+/// Walks a single node and all of its children.
+pub fn walk_node(visitor: &mut dyn Visitor, node: &mut dyn Node) {
+    enter(visitor, node);
+
+    for child in node.children() {
+        walk_node(visitor, child);
+    }
+
+    leave(visitor, node);
+}
+
+fn enter(visitor: &mut dyn Visitor, node: &mut dyn Node) {
+    visitor.enter_node(node);
+
+    if let Some(statement) = downcast_mut::<Statement>(node) {
+        let span = statement.first_span();
+
+        visitor.enter_statement(statement, span);
+    } else if let Some(expression) = downcast_mut::<Expression>(node) {
+        let span = expression.first_span();
+
+        visitor.enter_expression(expression, span);
+    } else if let Some(data_type) = downcast_mut::<Type>(node) {
+        let span = data_type.first_span();
+
+        visitor.enter_type(data_type, span);
+    } else if let Some(identifier) = simple_identifier(node) {
+        let span = identifier.span;
+
+        visitor.enter_identifier(identifier, span);
+    }
+}
+
+fn leave(visitor: &mut dyn Visitor, node: &mut dyn Node) {
+    if let Some(statement) = downcast_mut::<Statement>(node) {
+        let span = statement.first_span();
+
+        visitor.leave_statement(statement, span);
+    } else if let Some(expression) = downcast_mut::<Expression>(node) {
+        let span = expression.first_span();
+
+        visitor.leave_expression(expression, span);
+    } else if let Some(data_type) = downcast_mut::<Type>(node) {
+        let span = data_type.first_span();
+
+        visitor.leave_type(data_type, span);
+    } else if let Some(identifier) = simple_identifier(node) {
+        let span = identifier.span;
+
+        visitor.leave_identifier(identifier, span);
+    }
+
+    visitor.leave_node(node);
+}
+
+/// Simple identifiers are reached either directly (function names, class names...)
+/// or wrapped in an `Identifier` (identifier expressions), both are reported the same way.
+fn simple_identifier(node: &mut dyn Node) -> Option<&mut SimpleIdentifier> {
+    if downcast_mut::<SimpleIdentifier>(node).is_some() {
+        return downcast_mut::<SimpleIdentifier>(node);
+    }
+
+    match downcast_mut::<Identifier>(node) {
+        Some(Identifier::SimpleIdentifier(identifier)) => Some(identifier),
+        _ => None,
+    }
+}
+
+/// Counts the visited nodes by kind.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct NodeCounter {
+    pub statements: usize,
+    pub expressions: usize,
+    pub types: usize,
+    pub identifiers: usize,
+    pub nodes: usize,
+}
+
+impl NodeCounter {
+    pub fn count(program: &mut [Statement]) -> NodeCounter {
+        let mut counter = NodeCounter::default();
+
+        walk(&mut counter, program);
+
+        counter
+    }
+}
+
+impl Visitor for NodeCounter {
+    fn enter_statement(&mut self, _statement: &mut Statement, _span: Option<Span>) {
+        self.statements += 1;
+    }
+
+    fn enter_expression(&mut self, _expression: &mut Expression, _span: Option<Span>) {
+        self.expressions += 1;
+    }
+
+    fn enter_type(&mut self, _data_type: &mut Type, _span: Span) {
+        self.types += 1;
+    }
+
+    fn enter_identifier(&mut self, _identifier: &mut SimpleIdentifier, _span: Span) {
+        self.identifiers += 1;
+    }
+
+    fn enter_node(&mut self, _node: &mut dyn Node) {
+        self.nodes += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser;
+
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+    }
+
+    impl Visitor for Recorder {
+        fn enter_statement(&mut self, _statement: &mut Statement, span: Option<Span>) {
+            self.events
+                .push(format!("enter statement {}", span.map_or(0, |s| s.line)));
+        }
+
+        fn leave_statement(&mut self, _statement: &mut Statement, span: Option<Span>) {
+            self.events
+                .push(format!("leave statement {}", span.map_or(0, |s| s.line)));
+        }
+
+        fn enter_identifier(&mut self, identifier: &mut SimpleIdentifier, _span: Span) {
+            self.events.push(format!("identifier {}", identifier.value));
+        }
+    }
+
+    const SOURCE: &str = r#"<?php
+class Foo {
+    public function bar(int $a): string {
+        return "x";
+    }
+}
+$closure = function ($x) use ($y) {
+    return $x;
+};
+$doc = <<<EOT
+hello {$name}
+EOT;
+"#;
+
+    #[test]
+    fn test_walk_counts_nodes() {
+        let mut program = parser::parse(SOURCE).unwrap();
+
+        let counter = NodeCounter::count(&mut program);
+
+        assert_eq!(counter.types, 2);
+        assert_eq!(counter.identifiers, 2);
+        assert!(counter.statements >= 4);
+        assert!(counter.expressions > counter.statements);
+        assert!(counter.nodes > counter.expressions);
+    }
+
+    #[test]
+    fn test_walk_order() {
+        let mut program = parser::parse(SOURCE).unwrap();
+
+        let mut recorder = Recorder::default();
+
+        walk(&mut recorder, &mut program);
+
+        let position = |event: &str| recorder.events.iter().position(|e| e == event).unwrap();
+
+        assert_eq!(recorder.events[0], "enter statement 1");
+        assert!(position("enter statement 2") < position("identifier Foo"));
+        assert!(position("identifier Foo") < position("identifier bar"));
+        assert!(position("identifier bar") < position("leave statement 2"));
+        assert!(position("leave statement 2") < position("enter statement 7"));
+        assert!(position("enter statement 7") < position("enter statement 10"));
+    }
+}
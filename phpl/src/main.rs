@@ -1,21 +1,244 @@
-use std::{env, fs, io::Result};
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::{self, BufWriter, Result, Write};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::{env, fs, process};
 
-use evaluator::program::eval_program;
+use evaluator::interpreter::{Interpreter, COMMAND_LINE_CODE};
+use evaluator::output::WriterTarget;
+use evaluator::program::{
+    lint_program, DiagnosticsSink, EvalOptions, PhpError, ReportedError, ERROR_EXIT_CODE,
+};
+use evaluator::trace::TraceWriter;
+
+/// The exit status of a process terminated by SIGINT.
+const SIGINT_EXIT_CODE: i32 = 130;
+
+/// The exit status when the arguments are invalid, the usage is printed.
+const USAGE_EXIT_CODE: i32 = 1;
+
+const USAGE: &str = "Usage: phpl [options] <filename> [arguments...]
+       phpl [options] -r <code> [arguments...]
+
+Options:
+  -r <code>                          Runs the code, which has no opening tag
+  -l                                 Checks the syntax of the file without running it
+  -o <output file>                   Writes the output to the file and the warnings to stderr
+  --quiet                            Runs the script without writing anything
+  --max-source-size <bytes>          The largest file that include and require read, 0 is no limit
+  --trace                            Writes the statements, calls and included files to stderr
+  --trace-filter=<function or file>  Only traces the matching calls and files
+  --warn-output-before-code          Warns about whitespace before the opening tag of included files";
+
+/// Where the output of the program goes.
+enum OutputMode {
+    Stdout,
+    /// The output is written to a file and the warnings to stderr.
+    File(String),
+    /// The program is still evaluated but nothing is written.
+    Quiet,
+}
 
 fn main() -> Result<()> {
     let args: Vec<String> = env::args().collect();
 
-    if args.len() < 2 {
-        println!("Usage: {} <filename>", args[0]);
+    let mut output_mode = OutputMode::Stdout;
+    let mut file_name = None;
+    let mut code = None;
+    let mut options = EvalOptions::default();
+    let mut trace = false;
+    let mut trace_filter = None;
+    let mut lint = false;
+
+    let mut args_iter = args.iter().skip(1);
+
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "-o" => {
+                let Some(output_file) = args_iter.next() else {
+                    exit_with_usage();
+                };
+
+                output_mode = OutputMode::File(output_file.clone());
+            }
+            "-l" => lint = true,
+            // like the file name, the arguments after the code are given to the script
+            "-r" => {
+                let Some(command_line_code) = args_iter.next() else {
+                    exit_with_usage();
+                };
+
+                code = Some(command_line_code.clone());
+                options.argv = args_iter.by_ref().cloned().collect();
+            }
+            "--quiet" => output_mode = OutputMode::Quiet,
+            "--max-source-size" => {
+                let Some(size) = args_iter.next().and_then(|size| size.parse().ok()) else {
+                    exit_with_usage();
+                };
+
+                options.max_source_size = size;
+            }
+            "--trace" => trace = true,
+            "--warn-output-before-code" => options.warn_output_before_code = true,
+            _ if arg.starts_with("--trace-filter=") => {
+                trace = true;
+                trace_filter = Some(arg["--trace-filter=".len()..].to_string());
+            }
+            _ if arg.starts_with('-') => {
+                eprintln!("Unknown option: {}", arg);
+
+                exit_with_usage();
+            }
+            // the arguments after the file name are given to the script
+            _ => {
+                file_name = Some(arg);
+                options.argv = args_iter.by_ref().cloned().collect();
+            }
+        }
+    }
+
+    let (file_name, content) = match (file_name, code) {
+        (Some(file_name), None) => (file_name.as_str(), fs::read_to_string(file_name)?),
+        // -l only checks files
+        (None, Some(code)) if !lint => (COMMAND_LINE_CODE, code),
+        _ => exit_with_usage(),
+    };
+
+    if lint {
+        if !lint_program(file_name, &content, &mut io::stdout())? {
+            process::exit(ERROR_EXIT_CODE);
+        }
 
         return Ok(());
     }
 
-    let file_name = &args[1];
+    let interrupted = Arc::new(AtomicBool::new(false));
+
+    options.interrupted = Some(Arc::clone(&interrupted));
+
+    install_sigint_handler(interrupted.clone());
+
+    if trace {
+        let tracer = TraceWriter::new(io::stderr(), trace_filter);
+
+        options.trace = Some(Rc::new(RefCell::new(tracer)));
+    }
+
+    let status = match output_mode {
+        OutputMode::Stdout => {
+            // like the command line of PHP, the output is shown as soon as it is produced
+            options
+                .ini
+                .push(("implicit_flush".to_string(), "1".to_string()));
 
-    let content = fs::read_to_string(file_name)?;
+            run(file_name, &content, options, io::stdout(), io::stdout())?
+        }
+        OutputMode::File(output_file) => {
+            let file = BufWriter::new(File::create(output_file)?);
 
-    eval_program(&file_name, &content)?;
+            // the file only gets the output of the script
+            options.fatal_errors_to_diagnostics = true;
+
+            run(file_name, &content, options, file, io::stderr())?
+        }
+        OutputMode::Quiet => run(file_name, &content, options, io::sink(), io::sink())?,
+    };
+
+    if interrupted.load(Ordering::SeqCst) {
+        eprintln!("\nTerminated by signal SIGINT");
+
+        process::exit(SIGINT_EXIT_CODE);
+    }
+
+    if status != 0 {
+        process::exit(status);
+    }
 
     Ok(())
 }
+
+/// Writes the warnings to a writer as soon as they are reported, so they come between
+/// the output written before and after them.
+struct WarningWriter<W: Write>(W);
+
+impl<W: Write> DiagnosticsSink for WarningWriter<W> {
+    fn on_warning(&mut self, warning: &ReportedError) {
+        // like the output, a warning that cannot be written does not stop the program
+        let _ = writeln!(self.0, "{}", warning.clone().get_message());
+    }
+
+    // the message is given by on_fatal_message() with its stack trace
+    fn on_fatal(&mut self, _error: &PhpError) {}
+
+    // only called with -o, otherwise the message is written with the output
+    fn on_fatal_message(&mut self, message: &str) {
+        let _ = writeln!(self.0, "{}", message);
+    }
+}
+
+/// Runs the file, or the code of -r when `file_name` is `COMMAND_LINE_CODE`, and returns
+/// its exit status. The warnings are written to `warnings` when they are reported.
+fn run(
+    file_name: &str,
+    content: &str,
+    mut options: EvalOptions,
+    output: impl Write,
+    warnings: impl Write + 'static,
+) -> Result<i32> {
+    let mut target = WriterTarget(output);
+
+    options.diagnostics = Some(Rc::new(RefCell::new(WarningWriter(warnings))));
+
+    let mut interpreter = Interpreter::with_options(&options);
+
+    interpreter.set_output_target(&mut target);
+
+    let result = if file_name == COMMAND_LINE_CODE {
+        interpreter.eval_str(content)?
+    } else {
+        interpreter.eval_script(file_name, content)?
+    };
+
+    Ok(result.exit_status)
+}
+
+/// Prints how to use phpl and stops with `USAGE_EXIT_CODE`.
+fn exit_with_usage() -> ! {
+    eprintln!("{}", USAGE);
+
+    process::exit(USAGE_EXIT_CODE);
+}
+
+/// Makes Ctrl-C set `interrupted`, which stops the program between two statements instead
+/// of killing the process, so the output produced so far is not lost.
+#[cfg(unix)]
+fn install_sigint_handler(interrupted: Arc<AtomicBool>) {
+    use std::sync::OnceLock;
+
+    const SIGINT: i32 = 2;
+
+    // the handler cannot capture the flag
+    static SIGINT_FLAG: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+    extern "C" {
+        fn signal(signum: i32, handler: extern "C" fn(i32)) -> usize;
+    }
+
+    extern "C" fn on_sigint(_signum: i32) {
+        if let Some(interrupted) = SIGINT_FLAG.get() {
+            interrupted.store(true, Ordering::SeqCst);
+        }
+    }
+
+    let _ = SIGINT_FLAG.set(interrupted);
+
+    unsafe {
+        signal(SIGINT, on_sigint);
+    }
+}
+
+#[cfg(not(unix))]
+fn install_sigint_handler(_interrupted: Arc<AtomicBool>) {}
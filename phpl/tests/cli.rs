@@ -0,0 +1,138 @@
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+/// Runs phpl with the arguments.
+fn phpl(arguments: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_phpl"))
+        .args(arguments)
+        .output()
+        .unwrap()
+}
+
+/// Writes a script to the temporary directory and returns its path.
+fn write_script(name: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("phpl-cli-{}-{}", std::process::id(), name));
+
+    fs::write(&path, content).unwrap();
+
+    path
+}
+
+#[test]
+fn the_warnings_are_written_when_they_are_raised() {
+    let script = write_script(
+        "warnings.php",
+        "<?php\necho \"a\\n\";\n$a = [];\n$a['k'];\nmissing();\n",
+    );
+
+    let output = phpl(&[script.to_str().unwrap()]);
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        format!(
+            "a\nPHP Warning: Undefined array key \"k\" in {0} on line 4\nPHP Fatal error: Uncaught Error: Call to undefined function missing() in {0}:5\nStack trace:\n#0 {{main}}\n  thrown in {0} on line 5\n",
+            script.display()
+        )
+    );
+    assert_eq!(output.status.code(), Some(255));
+}
+
+#[test]
+fn an_unsupported_expression_is_a_fatal_error_and_not_a_panic() {
+    let output = phpl(&["-r", "echo 'a'; eval('echo 1;');"]);
+
+    assert_eq!(
+        String::from_utf8(output.stdout).unwrap(),
+        "aPHP Fatal error: Unsupported expression: Eval in Command line code on line 1\n"
+    );
+    assert_eq!(output.stderr, b"");
+    assert_eq!(output.status.code(), Some(255));
+}
+
+#[test]
+fn an_unknown_option_prints_the_usage_to_stderr() {
+    let output = phpl(&["--unknown", "script.php"]);
+
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(output.stdout, b"");
+    assert!(stderr.starts_with("Unknown option: --unknown\nUsage: phpl"));
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = phpl(&["-o"]);
+
+    assert_eq!(output.stdout, b"");
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .starts_with("Usage: phpl"));
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn the_output_is_written_to_the_file_of_o() {
+    let script = write_script(
+        "template.php",
+        "<p>\n<?php echo 'a', $missing; ?></p>\n<?php echo 'b';",
+    );
+    let output_file =
+        std::env::temp_dir().join(format!("phpl-cli-{}-out.html", std::process::id()));
+
+    let output = phpl(&[
+        "-o",
+        output_file.to_str().unwrap(),
+        script.to_str().unwrap(),
+    ]);
+
+    assert_eq!(fs::read_to_string(&output_file).unwrap(), "<p>\na</p>\nb");
+    assert_eq!(output.stdout, b"");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "PHP Warning: Undefined variable $missing in {} on line 2\n",
+            script.display()
+        )
+    );
+    assert_eq!(output.status.code(), Some(0));
+}
+
+#[test]
+fn the_fatal_errors_are_written_to_stderr_and_not_to_the_file_of_o() {
+    let script = write_script(
+        "fatal.php",
+        "<?php\nregister_shutdown_function(function () { missing_too(); });\necho 'a';\nmissing();",
+    );
+    let output_file =
+        std::env::temp_dir().join(format!("phpl-cli-{}-fatal.html", std::process::id()));
+
+    let output = phpl(&[
+        "-o",
+        output_file.to_str().unwrap(),
+        script.to_str().unwrap(),
+    ]);
+
+    assert_eq!(fs::read_to_string(&output_file).unwrap(), "a");
+    assert_eq!(output.stdout, b"");
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        format!(
+            "PHP Fatal error: Uncaught Error: Call to undefined function missing() in {0}:4\n\
+            Stack trace:\n#0 {{main}}\n  thrown in {0} on line 4\n\
+            PHP Fatal error: Uncaught Error: Call to undefined function missing_too() in {0}:2\n\
+            Stack trace:\n#0 {{main}}\n  thrown in {0} on line 2\n",
+            script.display()
+        )
+    );
+    assert_eq!(output.status.code(), Some(255));
+}
+
+#[test]
+fn quiet_runs_the_script_without_writing_anything() {
+    let script = write_script("quiet.php", "<p>\n<?php echo $missing; exit(3);");
+
+    let output = phpl(&["--quiet", script.to_str().unwrap()]);
+
+    assert_eq!(output.stdout, b"");
+    assert_eq!(output.stderr, b"");
+    assert_eq!(output.status.code(), Some(3));
+}